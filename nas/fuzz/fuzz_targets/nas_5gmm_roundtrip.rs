@@ -0,0 +1,14 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use nas::messages::Nas5gMmMessage;
+
+// Any message that is successfully decoded, should decode to the same message after encoding it.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(message) = Nas5gMmMessage::decode(data) {
+        let encoded = message.encode();
+        let decoded = Nas5gMmMessage::decode(&encoded).expect("Encoded message failed to decode.");
+        assert_eq!(decoded, message);
+    }
+});
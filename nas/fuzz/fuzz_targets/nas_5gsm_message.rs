@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use nas::messages::Nas5gSmMessage;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Nas5gSmMessage::decode(data);
+});
@@ -0,0 +1,517 @@
+//! `nas-decode`: Decode a NAS PDU (eg. from the gNB logs) and print the decoded header, message and
+//! IEs as a tree or JSON.
+//!
+//! A security protected PDU is deciphered if the K_NASenc is given and it's MAC is verified if the
+//! K_NASint is given.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::process::ExitCode;
+
+use clap::{Parser, ValueEnum};
+use serde::Serialize;
+use serde_json::Value;
+
+use nas::messages::headers::{Nas5gSecurityHeader, NasMessageHeader, SecurityProtectedHeader};
+use nas::messages::{Nas5gMmMessage, Nas5gSmMessage};
+use nas::security::{
+    nas_calculate_mac, nas_decrypt_payload, NasAccessType, NasCount, NasEncryptionAlgoIdentity,
+    NasIntegrityAlgoIdentity, NasKey,
+};
+use nas::NasError;
+
+// Length of the Security Protected NAS Message Header. (Section 9.1.1 24.501)
+const SECURITY_PROTECTED_HEADER_LENGTH: usize = 7;
+
+// Offset of the Sequence Number in the Security Protected NAS Message.
+const SEQUENCE_NUMBER_OFFSET: usize = 6;
+
+// Payload Container Type: N1 SM information (Section 9.11.3.40 24.501)
+const PAYLOAD_CONTAINER_TYPE_N1_SM_INFORMATION: u8 = 0x01;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about="Decode 5G NAS PDUs (24.501 Release 17)", long_about = None)]
+struct Cli {
+    #[arg(help = "NAS PDU as hex. Read from the standard input if not given.")]
+    pdu: Option<String>,
+
+    #[arg(
+        short,
+        long,
+        help = "Read the NAS PDU from a file.",
+        conflicts_with = "pdu"
+    )]
+    file: Option<String>,
+
+    #[arg(
+        short,
+        long,
+        help = "The input file or the standard input is binary and not hex."
+    )]
+    binary: bool,
+
+    #[arg(long, help = "K_NASenc (hex) to decipher the PDU.", value_parser = parse_key)]
+    k_nas_enc: Option<NasKey>,
+
+    #[arg(long, help = "K_NASint (hex) to verify the MAC of the PDU.", value_parser = parse_key)]
+    k_nas_int: Option<NasKey>,
+
+    #[arg(
+        long,
+        help = "NAS Encryption Algorithm: 0 to 3 for NEA0 to NEA3.",
+        default_value_t = 2
+    )]
+    nea: u8,
+
+    #[arg(
+        long,
+        help = "NAS Integrity Algorithm: 0 to 3 for NIA0 to NIA3.",
+        default_value_t = 2
+    )]
+    nia: u8,
+
+    #[arg(
+        long,
+        help = "NAS COUNT of the PDU. By default the NAS Overflow is 0 and the Sequence Number is the one in the PDU."
+    )]
+    count: Option<u32>,
+
+    #[arg(
+        long,
+        help = "The PDU is a Downlink PDU. By default the PDU is an Uplink PDU."
+    )]
+    downlink: bool,
+
+    #[arg(long, help = "The PDU is sent over the non-3GPP access.")]
+    non_3gpp: bool,
+
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Tree)]
+    output: OutputFormat,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum OutputFormat {
+    Tree,
+    Json,
+}
+
+/// Inputs of the NAS Security algorithms to verify and decipher the PDU.
+#[derive(Debug)]
+struct SecurityParams {
+    k_nas_enc: Option<NasKey>,
+    k_nas_int: Option<NasKey>,
+    encryption_algo: NasEncryptionAlgoIdentity,
+    integrity_algo: NasIntegrityAlgoIdentity,
+    count: Option<u32>,
+    downlink: bool,
+    access_type: NasAccessType,
+}
+
+/// A plain 5GMM or 5GSM message.
+#[derive(Debug, Serialize)]
+enum NasMessage {
+    Nas5gMm(Nas5gMmMessage),
+    Nas5gSm(Nas5gSmMessage),
+}
+
+/// Decoded PDU: As much of it as could be decoded, followed by the error, if any.
+#[derive(Debug, Default, Serialize)]
+struct DecodedPdu {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    security_protected_header: Option<SecurityProtectedHeader>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    security: Option<Security>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ciphered_payload: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<NasMessage>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    contained_message: Option<ContainedMessage>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Result of the security processing of a security protected PDU.
+#[derive(Debug, Serialize)]
+struct Security {
+    count: u32,
+    mac: MacVerification,
+    deciphered: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+enum MacVerification {
+    Verified,
+    Failed { calculated: String },
+    NotVerified,
+}
+
+/// A plain NAS message contained in an IE of the decoded message, eg. the 5GSM message in the
+/// Payload Container of the UL NAS Transport.
+#[derive(Debug, Serialize)]
+struct ContainedMessage {
+    ie: &'static str,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<NasMessage>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn main() -> io::Result<ExitCode> {
+    let cli = Cli::parse();
+
+    let params = SecurityParams {
+        k_nas_enc: cli.k_nas_enc,
+        k_nas_int: cli.k_nas_int,
+        encryption_algo: NasEncryptionAlgoIdentity::try_from(cli.nea)?,
+        integrity_algo: NasIntegrityAlgoIdentity::try_from(cli.nia)?,
+        count: cli.count,
+        downlink: cli.downlink,
+        access_type: if cli.non_3gpp {
+            NasAccessType::NonThreeGpp
+        } else {
+            NasAccessType::ThreeGpp
+        },
+    };
+
+    let mut input = vec![];
+    match (&cli.pdu, &cli.file) {
+        (Some(pdu), _) => input.extend(pdu.as_bytes()),
+        (None, Some(file)) => {
+            File::open(file)?.read_to_end(&mut input)?;
+        }
+        (None, None) => {
+            io::stdin().read_to_end(&mut input)?;
+        }
+    }
+    let data = if cli.binary && cli.pdu.is_none() {
+        input
+    } else {
+        parse_hex(&String::from_utf8_lossy(&input))?
+    };
+
+    let decoded = decode_pdu(&data, &params);
+    let value = serde_json::to_value(&decoded)?;
+    match cli.output {
+        OutputFormat::Tree => print!("{}", format_tree(&value)),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&value)?),
+    }
+
+    if decoded.error.is_some() {
+        Ok(ExitCode::FAILURE)
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+// Decode the PDU, verifying and deciphering it first if it is security protected.
+fn decode_pdu(data: &[u8], params: &SecurityParams) -> DecodedPdu {
+    let mut decoded = DecodedPdu::default();
+
+    let header = match NasMessageHeader::decode(data) {
+        Ok((header, _)) => header,
+        Err(error) => {
+            decoded.error = Some(error.to_string());
+            return decoded;
+        }
+    };
+
+    let plain = match header {
+        NasMessageHeader::SecurityProtected(header) => {
+            let (plain, security) = unprotect(data, &header, params);
+            decoded.security_protected_header = Some(header);
+            decoded.security = Some(security);
+            match plain {
+                Ok(plain) => plain,
+                Err(payload) => {
+                    decoded.ciphered_payload = Some(hex::encode(payload));
+                    return decoded;
+                }
+            }
+        }
+        _ => data.to_vec(),
+    };
+
+    match decode_message(&plain) {
+        Ok(message) => {
+            decoded.contained_message = contained_message(&message);
+            decoded.message = Some(message);
+        }
+        Err(error) => decoded.error = Some(error.to_string()),
+    }
+
+    decoded
+}
+
+// Verify the MAC (if K_NASint is available) and decipher (if K_NASenc is available) a security
+// protected PDU. Returns the ciphered payload as the error if it cannot be deciphered.
+fn unprotect<'a>(
+    data: &'a [u8],
+    header: &SecurityProtectedHeader,
+    params: &SecurityParams,
+) -> (Result<Vec<u8>, &'a [u8]>, Security) {
+    let count = params
+        .count
+        .unwrap_or_else(|| NasCount::new(0, header.seq_no).value());
+
+    let mac = match params.k_nas_int {
+        Some(key) => {
+            let mac = nas_calculate_mac(
+                key,
+                params.integrity_algo,
+                count,
+                params.access_type as u8,
+                params.downlink,
+                &data[SEQUENCE_NUMBER_OFFSET..],
+            );
+            if u32::from_be_bytes(mac) == header.mac {
+                MacVerification::Verified
+            } else {
+                MacVerification::Failed {
+                    calculated: hex::encode(mac),
+                }
+            }
+        }
+        None => MacVerification::NotVerified,
+    };
+
+    let payload = &data[SECURITY_PROTECTED_HEADER_LENGTH..];
+    let ciphered = matches!(
+        header.sec_header_type,
+        Nas5gSecurityHeader::IntegrityProtectedAndCiphered
+            | Nas5gSecurityHeader::IntegrityProtectedSecurityModeComplete
+    );
+    let plain = match (ciphered, params.k_nas_enc) {
+        (false, _) => Ok(payload.to_vec()),
+        (true, Some(key)) => Ok(nas_decrypt_payload(
+            key,
+            params.encryption_algo,
+            count,
+            params.access_type as u8,
+            params.downlink,
+            payload,
+            payload.len() as u32 * 8,
+        )),
+        (true, None) => Err(payload),
+    };
+
+    let security = Security {
+        count,
+        mac,
+        deciphered: ciphered && plain.is_ok(),
+    };
+
+    (plain, security)
+}
+
+fn decode_message(data: &[u8]) -> Result<NasMessage, NasError> {
+    let (header, _) = NasMessageHeader::decode(data)?;
+
+    match header {
+        NasMessageHeader::Nas5gMm(_) => Ok(NasMessage::Nas5gMm(Nas5gMmMessage::decode(data)?)),
+        NasMessageHeader::Nas5gSm(_) => Ok(NasMessage::Nas5gSm(Nas5gSmMessage::decode(data)?)),
+        // A security protected message is never contained in another one.
+        NasMessageHeader::SecurityProtected(header) => Err(NasError::InvalidSecurityHeaderType(
+            header.sec_header_type as u8,
+        )),
+    }
+}
+
+// Decode the plain NAS message in the NAS Message Container or in the Payload Container (for N1
+// SM information) of a 5GMM message.
+fn contained_message(message: &NasMessage) -> Option<ContainedMessage> {
+    let NasMessage::Nas5gMm(message) = message else {
+        return None;
+    };
+
+    let (ie, value) = match message {
+        Nas5gMmMessage::RegistrationRequest(message) => (
+            "nas_message_container",
+            &message.nas_message_container.as_ref()?.value,
+        ),
+        Nas5gMmMessage::ServiceRequest(message) => (
+            "nas_message_container",
+            &message.nas_message_container.as_ref()?.value,
+        ),
+        Nas5gMmMessage::SecurityModeComplete(message) => (
+            "nas_message_container",
+            &message.nas_message_container.as_ref()?.value,
+        ),
+        Nas5gMmMessage::UlNasTransport(message)
+            if message.payload_container_type.value == PAYLOAD_CONTAINER_TYPE_N1_SM_INFORMATION =>
+        {
+            ("payload_container", &message.payload_container.value)
+        }
+        Nas5gMmMessage::DlNasTransport(message)
+            if message.payload_container_type.value == PAYLOAD_CONTAINER_TYPE_N1_SM_INFORMATION =>
+        {
+            ("payload_container", &message.payload_container.value)
+        }
+        _ => return None,
+    };
+
+    let (message, error) = match decode_message(value) {
+        Ok(message) => (Some(message), None),
+        Err(error) => (None, Some(error.to_string())),
+    };
+
+    Some(ContainedMessage { ie, message, error })
+}
+
+// Format the decoded PDU as a tree: one line for each field, with the fields of a structure
+// indented below it. Absent optional fields are not shown.
+fn format_tree(value: &Value) -> String {
+    let mut output = String::new();
+    if let Value::Object(fields) = value {
+        for (name, value) in fields {
+            format_tree_node(name, value, 0, &mut output);
+        }
+    }
+    output
+}
+
+fn format_tree_node(name: &str, value: &Value, depth: usize, output: &mut String) {
+    let indent = "  ".repeat(depth);
+    match value {
+        Value::Null => {}
+        Value::Object(fields) => {
+            output.push_str(&format!("{}{}\n", indent, name));
+            for (name, value) in fields {
+                format_tree_node(name, value, depth + 1, output);
+            }
+        }
+        Value::Array(values) if values.iter().any(|v| v.is_object() || v.is_array()) => {
+            output.push_str(&format!("{}{}\n", indent, name));
+            for (index, value) in values.iter().enumerate() {
+                format_tree_node(&format!("[{}]", index), value, depth + 1, output);
+            }
+        }
+        Value::String(value) => output.push_str(&format!("{}{}: {}\n", indent, name, value)),
+        value => output.push_str(&format!("{}{}: {}\n", indent, name, value)),
+    }
+}
+
+// Parse a hex dump, ignoring the whitespace, ':' separators and a leading '0x'.
+fn parse_hex(input: &str) -> Result<Vec<u8>, io::Error> {
+    let input = input.trim();
+    let digits: String = input
+        .strip_prefix("0x")
+        .unwrap_or(input)
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != ':')
+        .collect();
+
+    hex::decode(digits).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+
+fn parse_key(key: &str) -> Result<NasKey, String> {
+    parse_hex(key)
+        .map_err(|e| e.to_string())?
+        .try_into()
+        .map_err(|key: Vec<u8>| format!("Key should be 16 octets, not {}.", key.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use nas::security::{
+        nas_encryption_algorithm_key, nas_integrity_algorithm_key, NasSecurityContext,
+    };
+
+    // A Registration Request with SUCI (Null Scheme)
+    const REGISTRATION_REQUEST: &str = "7e004179000d0109f1070000000000000000102e04f0f0f0f0";
+
+    fn params(k_nas_enc: Option<NasKey>, k_nas_int: Option<NasKey>) -> SecurityParams {
+        SecurityParams {
+            k_nas_enc,
+            k_nas_int,
+            encryption_algo: NasEncryptionAlgoIdentity::Nea2,
+            integrity_algo: NasIntegrityAlgoIdentity::Nia2,
+            count: None,
+            downlink: true,
+            access_type: NasAccessType::ThreeGpp,
+        }
+    }
+
+    #[test]
+    fn decode_plain_pdu() {
+        let data = parse_hex(REGISTRATION_REQUEST).unwrap();
+        let decoded = decode_pdu(&data, &params(None, None));
+        assert!(decoded.error.is_none(), "{:?}", decoded.error);
+        assert!(matches!(
+            decoded.message,
+            Some(NasMessage::Nas5gMm(Nas5gMmMessage::RegistrationRequest(_)))
+        ));
+
+        let tree = format_tree(&serde_json::to_value(&decoded).unwrap());
+        assert!(tree.starts_with("message\n  Nas5gMm\n    RegistrationRequest\n"));
+        assert!(tree.contains("plmn: 901-70\n"));
+        // Absent optional IEs are not shown.
+        assert!(!tree.contains("nas_message_container"));
+
+        let decoded = decode_pdu(&data[..10], &params(None, None));
+        assert!(decoded.message.is_none());
+        assert!(decoded.error.is_some());
+    }
+
+    #[test]
+    fn decode_security_protected_pdu() {
+        let kamf = [0x5A_u8; 32];
+        let mut context = NasSecurityContext::new(
+            1,
+            kamf,
+            NasEncryptionAlgoIdentity::Nea2,
+            NasIntegrityAlgoIdentity::Nia2,
+            NasAccessType::ThreeGpp,
+        );
+        let message = parse_hex(REGISTRATION_REQUEST).unwrap();
+        let data = context
+            .protect(&message, Nas5gSecurityHeader::IntegrityProtectedAndCiphered)
+            .unwrap();
+
+        let k_nas_enc = nas_encryption_algorithm_key(kamf, NasEncryptionAlgoIdentity::Nea2);
+        let k_nas_int = nas_integrity_algorithm_key(kamf, NasIntegrityAlgoIdentity::Nia2);
+
+        let decoded = decode_pdu(&data, &params(Some(k_nas_enc), Some(k_nas_int)));
+        assert!(decoded.error.is_none(), "{:?}", decoded.error);
+        let security = decoded.security.unwrap();
+        assert_eq!(security.mac, MacVerification::Verified);
+        assert!(security.deciphered);
+        assert!(matches!(
+            decoded.message,
+            Some(NasMessage::Nas5gMm(Nas5gMmMessage::RegistrationRequest(_)))
+        ));
+
+        // Without the keys only the Security Protected Header is decoded.
+        let decoded = decode_pdu(&data, &params(None, None));
+        assert_eq!(decoded.security.unwrap().mac, MacVerification::NotVerified);
+        assert!(decoded.message.is_none());
+        assert_eq!(
+            decoded.ciphered_payload,
+            Some(hex::encode(&data[SECURITY_PROTECTED_HEADER_LENGTH..]))
+        );
+
+        // Wrong K_NASint
+        let decoded = decode_pdu(&data, &params(Some(k_nas_enc), Some([0; 16])));
+        assert!(matches!(
+            decoded.security.unwrap().mac,
+            MacVerification::Failed { .. }
+        ));
+    }
+
+    #[test]
+    fn parse_hex_and_key() {
+        assert_eq!(parse_hex("0x7e 00:41\n").unwrap(), vec![0x7e, 0x00, 0x41]);
+        assert!(parse_hex("7e0").is_err());
+        assert_eq!(parse_key(&"ab".repeat(16)), Ok([0xab; 16]));
+        assert!(parse_key("abcd").is_err());
+    }
+}
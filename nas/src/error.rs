@@ -0,0 +1,174 @@
+//! Error type for the NAS Messages and the NAS Security.
+
+/// Semantically incorrect message. 24.501 (Release 17) Section 9.11.3.2 and 9.11.4.2
+pub const CAUSE_SEMANTICALLY_INCORRECT_MESSAGE: u8 = 95;
+
+/// Invalid mandatory information. 24.501 (Release 17) Section 9.11.3.2 and 9.11.4.2
+pub const CAUSE_INVALID_MANDATORY_INFORMATION: u8 = 96;
+
+/// Message type non-existent or not implemented. 24.501 (Release 17) Section 9.11.3.2 and
+/// 9.11.4.2
+pub const CAUSE_MESSAGE_TYPE_NON_EXISTENT: u8 = 97;
+
+/// Message type not compatible with the protocol state. 24.501 (Release 17) Section 9.11.3.2 and
+/// 9.11.4.2
+pub const CAUSE_MESSAGE_TYPE_NOT_COMPATIBLE: u8 = 98;
+
+/// Information element non-existent or not implemented. 24.501 (Release 17) Section 9.11.3.2 and
+/// 9.11.4.2
+pub const CAUSE_IE_NON_EXISTENT: u8 = 99;
+
+/// Protocol error, unspecified. 24.501 (Release 17) Section 9.11.3.2 and 9.11.4.2
+pub const CAUSE_PROTOCOL_ERROR_UNSPECIFIED: u8 = 111;
+
+/// Errors during decoding or security processing of the NAS Messages.
+///
+/// The cause values for the protocol errors are common to 5GMM and 5GSM, so [`NasError::cause`]
+/// can be used for the 5GMM Status or the 5GSM Status message sent to the UE. (24.501 Section 7)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NasError {
+    /// Message or an IE is shorter than expected.
+    Truncated { expected: usize, available: usize },
+
+    /// Extended Protocol Discriminator is not known. (24.007 Section 11.2.3.1A)
+    UnknownProtocolDiscriminator(u8),
+
+    /// Security Header Type is not known. (24.501 Section 9.3)
+    UnknownSecurityHeaderType(u8),
+
+    /// Message Type is not known or not implemented.
+    UnknownMessageType(u8),
+
+    /// Message Type is not the one expected by the decoder.
+    UnexpectedMessageType { expected: u8, received: u8 },
+
+    /// IEI that is not known and is encoded as 'comprehension required'. (24.007 Section 11.2.4)
+    InvalidIei(u8),
+
+    /// Length of the value of an IE is not valid for the IE.
+    InvalidLength { ie: &'static str, length: usize },
+
+    /// Octets remaining after decoding the message.
+    UndecodedOctets { decoded: usize, length: usize },
+
+    /// Message is syntactically correct, but the contents are not valid. The `cause` is the cause
+    /// value to be returned for the message.
+    SemanticallyIncorrect { cause: u8, reason: String },
+
+    /// Security Header Type is not valid for the operation.
+    InvalidSecurityHeaderType(u8),
+
+    /// NAS COUNT wrapped around.
+    CountWrapAround,
+
+    /// Integrity verification of the message failed.
+    MacVerificationFailed { count: u32 },
+}
+
+impl NasError {
+    /// Cause value to be returned to the UE for this error. (24.501 Section 7 and Annex A)
+    pub fn cause(&self) -> u8 {
+        match self {
+            Self::Truncated { .. } | Self::InvalidIei(_) | Self::InvalidLength { .. } => {
+                CAUSE_INVALID_MANDATORY_INFORMATION
+            }
+            Self::UnknownMessageType(_) => CAUSE_MESSAGE_TYPE_NON_EXISTENT,
+            Self::UnexpectedMessageType { .. } => CAUSE_MESSAGE_TYPE_NOT_COMPATIBLE,
+            Self::UndecodedOctets { .. } => CAUSE_SEMANTICALLY_INCORRECT_MESSAGE,
+            Self::SemanticallyIncorrect { cause, .. } => *cause,
+            Self::UnknownProtocolDiscriminator(_)
+            | Self::UnknownSecurityHeaderType(_)
+            | Self::InvalidSecurityHeaderType(_)
+            | Self::CountWrapAround
+            | Self::MacVerificationFailed { .. } => CAUSE_PROTOCOL_ERROR_UNSPECIFIED,
+        }
+    }
+
+    pub(crate) fn semantically_incorrect(reason: impl Into<String>) -> Self {
+        Self::SemanticallyIncorrect {
+            cause: CAUSE_SEMANTICALLY_INCORRECT_MESSAGE,
+            reason: reason.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for NasError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Truncated {
+                expected,
+                available,
+            } => write!(
+                f,
+                "Truncated. Expected: {} octets, Available: {} octets",
+                expected, available
+            ),
+            Self::UnknownProtocolDiscriminator(value) => {
+                write!(f, "Unknown Extended Protocol Discriminator: {:#x}", value)
+            }
+            Self::UnknownSecurityHeaderType(value) => {
+                write!(f, "Unknown Security Header Type: {}", value)
+            }
+            Self::UnknownMessageType(value) => write!(f, "Unknown Message Type: {:#x}", value),
+            Self::UnexpectedMessageType { expected, received } => write!(
+                f,
+                "Unexpected Message Type: {:#x}, expected: {:#x}",
+                received, expected
+            ),
+            Self::InvalidIei(iei) => write!(f, "Invalid IEI: {:#x}", iei),
+            Self::InvalidLength { ie, length } => {
+                write!(f, "Invalid length for {}: {}", ie, length)
+            }
+            Self::UndecodedOctets { decoded, length } => write!(
+                f,
+                "Undecoded octets. Decoded: {}, length: {}",
+                decoded, length
+            ),
+            Self::SemanticallyIncorrect { cause, reason } => {
+                write!(f, "Semantically incorrect (cause: {}): {}", cause, reason)
+            }
+            Self::InvalidSecurityHeaderType(value) => {
+                write!(f, "Invalid Security Header Type: {}", value)
+            }
+            Self::CountWrapAround => write!(f, "NAS COUNT wrapped around"),
+            Self::MacVerificationFailed { count } => {
+                write!(f, "NAS MAC verification failed for NAS COUNT: {}", count)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NasError {}
+
+impl From<NasError> for std::io::Error {
+    fn from(error: NasError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cause() {
+        assert_eq!(NasError::UnknownMessageType(0x40).cause(), 97);
+        assert_eq!(
+            NasError::Truncated {
+                expected: 3,
+                available: 2
+            }
+            .cause(),
+            96
+        );
+        assert_eq!(
+            NasError::SemanticallyIncorrect {
+                cause: 83,
+                reason: "QoS Rule".to_string()
+            }
+            .cause(),
+            83
+        );
+        assert_eq!(NasError::MacVerificationFailed { count: 0 }.cause(), 111);
+    }
+}
@@ -1,3 +1,9 @@
+pub mod error;
+pub use error::NasError;
+
 pub mod messages;
 
+pub mod plmn;
+pub use plmn::Plmn;
+
 pub mod security;
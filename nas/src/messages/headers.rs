@@ -1,3 +1,5 @@
+use crate::NasError;
+
 mod mm;
 pub use mm::Nas5gMmMessageHeader;
 
@@ -7,7 +9,8 @@ pub use sm::Nas5gSmMessageHeader;
 /// An Enum representing Extended Protocol Discriminator
 /// See 24.007 (Release 17) Section 11.2.3.1A
 #[repr(u8)]
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ExtProtoDiscriminator {
     /// 5GS Session Management Messages
     FivegNasSessionManagementType = 0x2E,
@@ -16,12 +19,14 @@ pub enum ExtProtoDiscriminator {
     FivegNasMobilityManagementType = 0x7E,
 }
 
-impl From<u8> for ExtProtoDiscriminator {
-    fn from(val: u8) -> Self {
+impl TryFrom<u8> for ExtProtoDiscriminator {
+    type Error = NasError;
+
+    fn try_from(val: u8) -> Result<Self, Self::Error> {
         match val {
-            0x7E => Self::FivegNasMobilityManagementType,
-            0x2E => Self::FivegNasSessionManagementType,
-            _ => unreachable!(),
+            0x7E => Ok(Self::FivegNasMobilityManagementType),
+            0x2E => Ok(Self::FivegNasSessionManagementType),
+            _ => Err(NasError::UnknownProtocolDiscriminator(val)),
         }
     }
 }
@@ -29,6 +34,7 @@ impl From<u8> for ExtProtoDiscriminator {
 /// NAS Message Header: A common structure representing NAS Message Header
 /// 24.501 (Release 17) Section 9 General message format and information elements coding
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum NasMessageHeader {
     SecurityProtected(SecurityProtectedHeader),
     Nas5gMm(Nas5gMmMessageHeader),
@@ -36,6 +42,7 @@ pub enum NasMessageHeader {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct SecurityProtectedHeader {
     pub extended_protocol_disc: ExtProtoDiscriminator,
     pub sec_header_type: Nas5gSecurityHeader,
@@ -45,7 +52,8 @@ pub struct SecurityProtectedHeader {
 
 /// An Enum representing NAS Security Header Type
 /// See 24.501 (Release 17) Section 9.3
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Nas5gSecurityHeader {
     /// No Security and Integrity protection for NAS Messages.
     PlainText = 0x00,
@@ -63,60 +71,62 @@ pub enum Nas5gSecurityHeader {
     IntegrityProtectedSecurityModeComplete = 0x04,
 }
 
+impl TryFrom<u8> for Nas5gSecurityHeader {
+    type Error = NasError;
+
+    fn try_from(val: u8) -> Result<Self, Self::Error> {
+        match val {
+            0 => Ok(Self::PlainText),
+            1 => Ok(Self::IntegrityProtected),
+            2 => Ok(Self::IntegrityProtectedAndCiphered),
+            3 => Ok(Self::IntegrityProtectedSecurityModeCommand),
+            4 => Ok(Self::IntegrityProtectedSecurityModeComplete),
+            _ => Err(NasError::UnknownSecurityHeaderType(val)),
+        }
+    }
+}
+
 impl NasMessageHeader {
-    pub fn decode(data: &[u8]) -> std::io::Result<(Self, usize)> {
-        let mut decoded = 0;
-        let extended_protocol_disc = match data[decoded] {
-            0x2E => ExtProtoDiscriminator::FivegNasSessionManagementType,
-            0x7E => ExtProtoDiscriminator::FivegNasMobilityManagementType,
-            _ => unreachable!(),
-        };
-        decoded += 1;
+    pub fn encode(&self) -> Vec<u8> {
+        match self {
+            Self::SecurityProtected(header) => header.encode(),
+            Self::Nas5gMm(header) => header.encode(),
+            Self::Nas5gSm(header) => header.encode(),
+        }
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        let extended_protocol_disc = ExtProtoDiscriminator::try_from(header_octet(data, 0)?)?;
 
         match extended_protocol_disc {
             // Protocol Discriminator is 5G MM. The message could either be a 5G MM Message or a
             // security protected (with security header) 5G MM or 5G SM message. Section 9.2 24.501
             ExtProtoDiscriminator::FivegNasMobilityManagementType => {
-                let sec_header_type = match data[decoded] & 0x0F {
-                    0 => Nas5gSecurityHeader::PlainText,
-                    1 => Nas5gSecurityHeader::IntegrityProtected,
-                    2 => Nas5gSecurityHeader::IntegrityProtectedAndCiphered,
-                    3 => Nas5gSecurityHeader::IntegrityProtectedSecurityModeCommand,
-                    4 => Nas5gSecurityHeader::IntegrityProtectedSecurityModeComplete,
-                    _ => todo!(),
-                };
-                decoded += 1;
+                // The upper half octet is spare.
+                let sec_header_type = Nas5gSecurityHeader::try_from(header_octet(data, 1)? & 0x0F)?;
 
                 match sec_header_type {
                     // Not Security Protected: a 5GMM Message
                     Nas5gSecurityHeader::PlainText => {
-                        let message_type = data[decoded];
-                        decoded += 1;
-                        Ok((
-                            Self::Nas5gMm(Nas5gMmMessageHeader {
-                                extended_protocol_disc,
-                                sec_header_type,
-                                message_type,
-                            }),
-                            decoded,
-                        ))
+                        let (header, decoded) = Nas5gMmMessageHeader::decode(data)?;
+                        Ok((Self::Nas5gMm(header), decoded))
                     }
                     // Security Protected Header with NAS Container containing the actual message.
                     _ => {
-                        let mac =
-                            u32::from_be_bytes(data[decoded..decoded + 4].try_into().unwrap());
-                        decoded += 4;
+                        let mut mac = [0_u8; 4];
+                        for (index, octet) in mac.iter_mut().enumerate() {
+                            *octet = header_octet(data, 2 + index)?;
+                        }
+                        let seq_no = header_octet(data, 6)?;
 
-                        let seq_no = data[decoded];
-                        decoded += 1;
                         Ok((
                             Self::SecurityProtected(SecurityProtectedHeader {
                                 extended_protocol_disc,
                                 sec_header_type,
-                                mac,
+                                mac: u32::from_be_bytes(mac),
                                 seq_no,
                             }),
-                            decoded,
+                            7,
                         ))
                     }
                 }
@@ -125,21 +135,138 @@ impl NasMessageHeader {
             // security protected 5G SM Message (eg. Service Request) or a 5G SM message contained
             // in the NAS container of a security protected 5G NAS message.
             ExtProtoDiscriminator::FivegNasSessionManagementType => {
-                let pdu_session_identity = data[decoded];
-                decoded += 1;
-
-                let proc_transaction_identity = data[decoded];
-                decoded += 1;
-
-                Ok((
-                    Self::Nas5gSm(Nas5gSmMessageHeader {
-                        extended_protocol_disc,
-                        pdu_session_identity,
-                        proc_transaction_identity,
-                    }),
-                    decoded,
-                ))
+                let (header, decoded) = Nas5gSmMessageHeader::decode(data)?;
+                Ok((Self::Nas5gSm(header), decoded))
             }
         }
     }
 }
+
+// Get the octet at `index` of a header.
+fn header_octet(data: &[u8], index: usize) -> Result<u8, NasError> {
+    data.get(index).copied().ok_or(NasError::Truncated {
+        expected: index + 1,
+        available: data.len(),
+    })
+}
+
+impl SecurityProtectedHeader {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output = vec![
+            self.extended_protocol_disc as u8,
+            self.sec_header_type as u8,
+        ];
+        output.extend(self.mac.to_be_bytes());
+        output.push(self.seq_no);
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        let (header, decoded) = NasMessageHeader::decode(data)?;
+        match header {
+            NasMessageHeader::SecurityProtected(header) => Ok((header, decoded)),
+            _ => Err(NasError::InvalidSecurityHeaderType(
+                Nas5gSecurityHeader::PlainText as u8,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    fn sec_header_type() -> impl Strategy<Value = Nas5gSecurityHeader> {
+        prop_oneof![
+            Just(Nas5gSecurityHeader::PlainText),
+            Just(Nas5gSecurityHeader::IntegrityProtected),
+            Just(Nas5gSecurityHeader::IntegrityProtectedAndCiphered),
+            Just(Nas5gSecurityHeader::IntegrityProtectedSecurityModeCommand),
+            Just(Nas5gSecurityHeader::IntegrityProtectedSecurityModeComplete),
+        ]
+    }
+
+    prop_compose! {
+        fn security_protected_header()
+            (sec_header_type in sec_header_type()
+                .prop_filter("security protected", |h| *h != Nas5gSecurityHeader::PlainText),
+             mac in any::<u32>(),
+             seq_no in any::<u8>()) -> SecurityProtectedHeader {
+            SecurityProtectedHeader {
+                extended_protocol_disc: ExtProtoDiscriminator::FivegNasMobilityManagementType,
+                sec_header_type,
+                mac,
+                seq_no,
+            }
+        }
+    }
+
+    prop_compose! {
+        fn mm_header()(message_type in any::<u8>()) -> Nas5gMmMessageHeader {
+            Nas5gMmMessageHeader {
+                extended_protocol_disc: ExtProtoDiscriminator::FivegNasMobilityManagementType,
+                sec_header_type: Nas5gSecurityHeader::PlainText,
+                message_type,
+            }
+        }
+    }
+
+    prop_compose! {
+        fn sm_header()
+            (pdu_session_identity in any::<u8>(),
+             proc_transaction_identity in any::<u8>(),
+             message_type in any::<u8>()) -> Nas5gSmMessageHeader {
+            Nas5gSmMessageHeader {
+                extended_protocol_disc: ExtProtoDiscriminator::FivegNasSessionManagementType,
+                pdu_session_identity,
+                proc_transaction_identity,
+                message_type,
+            }
+        }
+    }
+
+    fn nas_message_header() -> impl Strategy<Value = NasMessageHeader> {
+        prop_oneof![
+            security_protected_header().prop_map(NasMessageHeader::SecurityProtected),
+            mm_header().prop_map(NasMessageHeader::Nas5gMm),
+            sm_header().prop_map(NasMessageHeader::Nas5gSm),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn roundtrip_nas_message_header(header in nas_message_header()) {
+            let encoded = header.encode();
+            let (decoded, length) = NasMessageHeader::decode(&encoded).unwrap();
+            prop_assert_eq!(length, encoded.len());
+            prop_assert_eq!(decoded, header);
+        }
+
+        #[test]
+        fn roundtrip_security_protected_header(header in security_protected_header()) {
+            let encoded = header.encode();
+            let (decoded, length) = SecurityProtectedHeader::decode(&encoded).unwrap();
+            prop_assert_eq!(length, encoded.len());
+            prop_assert_eq!(decoded, header);
+        }
+
+        #[test]
+        fn roundtrip_mm_header(header in mm_header()) {
+            let encoded = header.encode();
+            let (decoded, length) = Nas5gMmMessageHeader::decode(&encoded).unwrap();
+            prop_assert_eq!(length, encoded.len());
+            prop_assert_eq!(decoded, header);
+        }
+
+        #[test]
+        fn roundtrip_sm_header(header in sm_header()) {
+            let encoded = header.encode();
+            let (decoded, length) = Nas5gSmMessageHeader::decode(&encoded).unwrap();
+            prop_assert_eq!(length, encoded.len());
+            prop_assert_eq!(decoded, header);
+        }
+    }
+}
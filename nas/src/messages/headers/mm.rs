@@ -1,8 +1,10 @@
 /// NAS 5GS Memory Management common header. This header will be part of all NAS Messages from the
 /// 24.501 (Release 17) Section 8.2 5 GS mobility management messages.
-use super::{ExtProtoDiscriminator, Nas5gSecurityHeader};
+use super::{header_octet, ExtProtoDiscriminator, Nas5gSecurityHeader};
+use crate::NasError;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Nas5gMmMessageHeader {
     pub extended_protocol_disc: ExtProtoDiscriminator,
     pub sec_header_type: Nas5gSecurityHeader,
@@ -11,30 +13,17 @@ pub struct Nas5gMmMessageHeader {
 
 impl Nas5gMmMessageHeader {
     pub fn encode(&self) -> Vec<u8> {
-        vec![]
+        vec![
+            self.extended_protocol_disc as u8,
+            self.sec_header_type as u8,
+            self.message_type,
+        ]
     }
 
-    pub fn decode(data: &[u8]) -> std::io::Result<(Self, usize)> {
-        let mut decoded = 0;
-        let extended_protocol_disc = match data[decoded] {
-            0x2E => ExtProtoDiscriminator::FivegNasSessionManagementType,
-            0x7E => ExtProtoDiscriminator::FivegNasMobilityManagementType,
-            _ => unreachable!(),
-        };
-        decoded += 1;
-
-        let sec_header_type = match data[decoded] & 0x0F {
-            0 => Nas5gSecurityHeader::PlainText,
-            1 => Nas5gSecurityHeader::IntegrityProtected,
-            2 => Nas5gSecurityHeader::IntegrityProtectedAndCiphered,
-            3 => Nas5gSecurityHeader::IntegrityProtectedSecurityModeCommand,
-            4 => Nas5gSecurityHeader::IntegrityProtectedSecurityModeComplete,
-            _ => todo!(),
-        };
-        decoded += 1;
-
-        let message_type = data[decoded];
-        decoded += 1;
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        let extended_protocol_disc = ExtProtoDiscriminator::try_from(header_octet(data, 0)?)?;
+        let sec_header_type = Nas5gSecurityHeader::try_from(header_octet(data, 1)? & 0x0F)?;
+        let message_type = header_octet(data, 2)?;
 
         Ok((
             Self {
@@ -42,7 +31,7 @@ impl Nas5gMmMessageHeader {
                 sec_header_type,
                 message_type,
             },
-            decoded,
+            3,
         ))
     }
 }
@@ -1,9 +1,41 @@
 /// NAS 5GS Session Management header.
-use super::ExtProtoDiscriminator;
+/// 24.501 (Release 17) Section 8.3 5GS session management messages.
+use super::{header_octet, ExtProtoDiscriminator};
+use crate::NasError;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Nas5gSmMessageHeader {
     pub extended_protocol_disc: ExtProtoDiscriminator,
     pub pdu_session_identity: u8,
     pub proc_transaction_identity: u8,
+    pub message_type: u8,
+}
+
+impl Nas5gSmMessageHeader {
+    pub fn encode(&self) -> Vec<u8> {
+        vec![
+            self.extended_protocol_disc as u8,
+            self.pdu_session_identity,
+            self.proc_transaction_identity,
+            self.message_type,
+        ]
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        let extended_protocol_disc = ExtProtoDiscriminator::try_from(header_octet(data, 0)?)?;
+        let pdu_session_identity = header_octet(data, 1)?;
+        let proc_transaction_identity = header_octet(data, 2)?;
+        let message_type = header_octet(data, 3)?;
+
+        Ok((
+            Self {
+                extended_protocol_disc,
+                pdu_session_identity,
+                proc_transaction_identity,
+                message_type,
+            },
+            4,
+        ))
+    }
 }
@@ -0,0 +1,319 @@
+//! Common Information Elements for 5GS Mobility Management and 5GS Session Management.
+//! 24.501 (Release 17) Section 9.11.2
+
+use super::{
+    decode_fixed, decode_half_octet, decode_tlv, decode_tlve, encode_fixed, encode_half_octet,
+    encode_tlv, encode_tlve, invalid_length,
+};
+use crate::NasError;
+
+half_octet_ie!(
+    /// Access Type: 24.501 (Release 17) Section 9.11.2.1A
+    AccessType
+);
+
+opaque_ie!(
+    /// Additional Information: 24.501 (Release 17) Section 9.11.2.1
+    AdditionalInformation,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// DNN: 24.501 (Release 17) Section 9.11.2.1B
+    Dnn,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// EAP Message: 24.501 (Release 17) Section 9.11.2.2
+    EapMessage,
+    encode_tlve,
+    decode_tlve
+);
+
+opaque_ie!(
+    /// Service-level-AA Container: 24.501 (Release 17) Section 9.11.2.10
+    ServiceLevelAaContainer,
+    encode_tlve,
+    decode_tlve
+);
+
+/// GPRS Timer: 24.501 (Release 17) Section 9.11.2.3 (See 24.008 Section 10.5.7.3)
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct GprsTimer {
+    pub iei: Option<u8>,
+    pub unit: GprsTimerUnit,
+    pub value: u8,
+}
+
+/// Unit of the Timer value for [`GprsTimer`]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum GprsTimerUnit {
+    TwoSeconds = 0x00,
+    OneMinute = 0x01,
+    Decihours = 0x02,
+    Deactivated = 0x07,
+}
+
+/// GPRS Timer 2: 24.501 (Release 17) Section 9.11.2.4 (See 24.008 Section 10.5.7.4)
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct GprsTimer2 {
+    pub iei: Option<u8>,
+    pub value: u8,
+}
+
+/// GPRS Timer 3: 24.501 (Release 17) Section 9.11.2.5 (See 24.008 Section 10.5.7.4a)
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct GprsTimer3 {
+    pub iei: Option<u8>,
+    pub unit: GprsTimer3Unit,
+    pub value: u8,
+}
+
+/// Unit of the Timer value for [`GprsTimer3`]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum GprsTimer3Unit {
+    TenMinutes = 0x00,
+    OneHour = 0x01,
+    TenHours = 0x02,
+    TwoSeconds = 0x03,
+    ThirtySeconds = 0x04,
+    OneMinute = 0x05,
+    ThreeHundredTwentyHours = 0x06,
+    Deactivated = 0x07,
+}
+
+/// S-NSSAI: 24.501 (Release 17) Section 9.11.2.8
+///
+/// The mapped HPLMN SD is encoded only if both `sd` and `mapped_hplmn_sst` are present.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SNssai {
+    pub iei: Option<u8>,
+    pub sst: u8,
+    pub sd: Option<[u8; 3]>,
+    pub mapped_hplmn_sst: Option<u8>,
+    pub mapped_hplmn_sd: Option<[u8; 3]>,
+}
+
+impl GprsTimer {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        encode_fixed(
+            self.iei,
+            encode_iei,
+            &[(self.unit as u8) << 5 | (self.value & 0x1F)],
+        )
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("GprsTimer decode");
+
+        let (iei, value, decoded) = decode_fixed(data, decode_iei, 1)?;
+
+        // Other values are interpreted as multiples of 1 minute. (24.008 Section 10.5.7.3)
+        let unit = match value[0] >> 5 {
+            0 => GprsTimerUnit::TwoSeconds,
+            2 => GprsTimerUnit::Decihours,
+            7 => GprsTimerUnit::Deactivated,
+            _ => GprsTimerUnit::OneMinute,
+        };
+
+        Ok((
+            Self {
+                iei,
+                unit,
+                value: value[0] & 0x1F,
+            },
+            decoded,
+        ))
+    }
+}
+
+impl GprsTimer2 {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        encode_tlv(self.iei, encode_iei, &[self.value])
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("GprsTimer2 decode");
+
+        let (iei, value, decoded) = decode_tlv(data, decode_iei)?;
+        let value = *value
+            .first()
+            .ok_or_else(|| invalid_length("GPRS Timer 2", 0))?;
+
+        Ok((Self { iei, value }, decoded))
+    }
+}
+
+impl GprsTimer3 {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        encode_tlv(
+            self.iei,
+            encode_iei,
+            &[(self.unit as u8) << 5 | (self.value & 0x1F)],
+        )
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("GprsTimer3 decode");
+
+        let (iei, value, decoded) = decode_tlv(data, decode_iei)?;
+        let value = *value
+            .first()
+            .ok_or_else(|| invalid_length("GPRS Timer 3", 0))?;
+
+        let unit = match value >> 5 {
+            0 => GprsTimer3Unit::TenMinutes,
+            1 => GprsTimer3Unit::OneHour,
+            2 => GprsTimer3Unit::TenHours,
+            3 => GprsTimer3Unit::TwoSeconds,
+            4 => GprsTimer3Unit::ThirtySeconds,
+            5 => GprsTimer3Unit::OneMinute,
+            6 => GprsTimer3Unit::ThreeHundredTwentyHours,
+            _ => GprsTimer3Unit::Deactivated,
+        };
+
+        Ok((
+            Self {
+                iei,
+                unit,
+                value: value & 0x1F,
+            },
+            decoded,
+        ))
+    }
+}
+
+impl SNssai {
+    /// S-NSSAI from the SST and an optional SD, without the mapped HPLMN values.
+    pub fn new(sst: u8, sd: Option<[u8; 3]>) -> Self {
+        Self {
+            iei: None,
+            sst,
+            sd,
+            mapped_hplmn_sst: None,
+            mapped_hplmn_sd: None,
+        }
+    }
+
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        let mut value = vec![self.sst];
+        if let Some(sd) = self.sd {
+            value.extend(sd);
+        }
+        if let Some(mapped_hplmn_sst) = self.mapped_hplmn_sst {
+            value.push(mapped_hplmn_sst);
+            if let (Some(_), Some(mapped_hplmn_sd)) = (self.sd, self.mapped_hplmn_sd) {
+                value.extend(mapped_hplmn_sd);
+            }
+        }
+        encode_tlv(self.iei, encode_iei, &value)
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("SNssai decode");
+
+        let (iei, value, decoded) = decode_tlv(data, decode_iei)?;
+
+        // The contents present are given by the length of the value.
+        let sd = |idx: usize| Some(value[idx..idx + 3].try_into().unwrap());
+        let (sd, mapped_hplmn_sst, mapped_hplmn_sd) = match value.len() {
+            1 => (None, None, None),
+            2 => (None, Some(value[1]), None),
+            4 => (sd(1), None, None),
+            5 => (sd(1), Some(value[4]), None),
+            8 => (sd(1), Some(value[4]), sd(5)),
+            length => return Err(invalid_length("S-NSSAI", length)),
+        };
+
+        Ok((
+            Self {
+                iei,
+                sst: value[0],
+                sd,
+                mapped_hplmn_sst,
+                mapped_hplmn_sd,
+            },
+            decoded,
+        ))
+    }
+}
+
+#[cfg(feature = "ngap")]
+impl From<&SNssai> for ngap::messages::r17::S_NSSAI {
+    fn from(s_nssai: &SNssai) -> Self {
+        use ngap::messages::r17::{SD, SST};
+
+        Self {
+            sst: SST(vec![s_nssai.sst]),
+            sd: s_nssai.sd.map(|sd| SD(sd.to_vec())),
+            ie_extensions: None,
+        }
+    }
+}
+
+#[cfg(feature = "ngap")]
+impl TryFrom<&ngap::messages::r17::S_NSSAI> for SNssai {
+    type Error = NasError;
+
+    fn try_from(s_nssai: &ngap::messages::r17::S_NSSAI) -> Result<Self, Self::Error> {
+        let sst = match s_nssai.sst.0.as_slice() {
+            [sst] => *sst,
+            sst => return Err(invalid_length("SST", sst.len())),
+        };
+        let sd = s_nssai
+            .sd
+            .as_ref()
+            .map(|sd| {
+                sd.0.as_slice()
+                    .try_into()
+                    .map_err(|_| invalid_length("SD", sd.0.len()))
+            })
+            .transpose()?;
+
+        Ok(Self::new(sst, sd))
+    }
+}
+
+#[cfg(feature = "sbi")]
+impl From<&SNssai> for sbi5g::models::Snssai {
+    fn from(s_nssai: &SNssai) -> Self {
+        Self {
+            sst: s_nssai.sst.into(),
+            sd: s_nssai.sd.map(hex::encode),
+        }
+    }
+}
+
+#[cfg(feature = "sbi")]
+impl TryFrom<&sbi5g::models::Snssai> for SNssai {
+    type Error = NasError;
+
+    fn try_from(s_nssai: &sbi5g::models::Snssai) -> Result<Self, Self::Error> {
+        let sst = u8::try_from(s_nssai.sst).map_err(|_| {
+            NasError::semantically_incorrect(format!("Invalid SST: {}", s_nssai.sst))
+        })?;
+        let sd = s_nssai
+            .sd
+            .as_ref()
+            .map(|sd| {
+                hex::decode(sd)
+                    .ok()
+                    .and_then(|sd| sd.try_into().ok())
+                    .ok_or_else(|| NasError::semantically_incorrect(format!("Invalid SD: {}", sd)))
+            })
+            .transpose()?;
+
+        Ok(Self::new(sst, sd))
+    }
+}
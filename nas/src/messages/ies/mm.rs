@@ -0,0 +1,1139 @@
+//! Information Elements for 5GS Mobility Management. 24.501 (Release 17) Section 9.11.3
+
+use super::{
+    decode_fixed, decode_half_octet, decode_tlv, decode_tlve, encode_fixed, encode_half_octet,
+    encode_tlv, encode_tlve, invalid_length, SNssai,
+};
+use crate::{NasError, Plmn};
+
+opaque_ie!(
+    /// ABBA: 24.501 (Release 17) Section 9.11.3.10
+    Abba,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// Additional Information Requested: 24.501 (Release 17) Section 9.11.3.12A
+    AdditionalInformationRequested,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// Allowed PDU Session Status: 24.501 (Release 17) Section 9.11.3.13
+    AllowedPduSessionStatus,
+    encode_tlv,
+    decode_tlv
+);
+
+half_octet_ie!(
+    /// Configuration Update Indication: 24.501 (Release 17) Section 9.11.3.18
+    ConfigurationUpdateIndication
+);
+
+opaque_ie!(
+    /// Daylight Saving Time: 24.501 (Release 17) Section 9.11.3.19
+    DaylightSavingTime,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// Emergency Number List: 24.501 (Release 17) Section 9.11.3.23
+    EmergencyNumberList,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// EPS Bearer Context Status: 24.501 (Release 17) Section 9.11.3.23A
+    EpsBearerContextStatus,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// EPS NAS Message Container: 24.501 (Release 17) Section 9.11.3.24
+    EpsNasMessageContainer,
+    encode_tlve,
+    decode_tlve
+);
+
+opaque_ie!(
+    /// Extended DRX Parameters: 24.501 (Release 17) Section 9.11.3.26A
+    ExtendedDrxParameters,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// Extended Emergency Number List: 24.501 (Release 17) Section 9.11.3.26
+    ExtendedEmergencyNumberList,
+    encode_tlve,
+    decode_tlve
+);
+
+opaque_ie!(
+    /// 5GMM Capability: 24.501 (Release 17) Section 9.11.3.1
+    FivegmmCapability,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// 5GS DRX Parameters: 24.501 (Release 17) Section 9.11.3.2A
+    FivegsDrxParameters,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// 5GS Network Feature Support: 24.501 (Release 17) Section 9.11.3.5
+    FivegsNetworkFeatureSupport,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// 5GS Update Type: 24.501 (Release 17) Section 9.11.3.9A
+    FivegsUpdateType,
+    encode_tlv,
+    decode_tlv
+);
+
+half_octet_ie!(
+    /// 5GS Identity Type: 24.501 (Release 17) Section 9.11.3.3
+    IdentityType
+);
+
+half_octet_ie!(
+    /// IMEISV Request: 24.501 (Release 17) Section 9.11.3.28
+    ImeisvRequest
+);
+
+opaque_ie!(
+    /// LADN Indication: 24.501 (Release 17) Section 9.11.3.29
+    LadnIndication,
+    encode_tlve,
+    decode_tlve
+);
+
+opaque_ie!(
+    /// LADN Information: 24.501 (Release 17) Section 9.11.3.30
+    LadnInformation,
+    encode_tlve,
+    decode_tlve
+);
+
+half_octet_ie!(
+    /// MICO Indication: 24.501 (Release 17) Section 9.11.3.31
+    MicoIndication
+);
+
+opaque_ie!(
+    /// Mobile Station Classmark 2: 24.501 (Release 17) Section 9.11.3.31C
+    MobileStationClassmark2,
+    encode_tlv,
+    decode_tlv
+);
+
+half_octet_ie!(
+    /// N5GC Indication: 24.501 (Release 17) Section 9.11.3.72
+    N5gcIndication
+);
+
+opaque_ie!(
+    /// NAS Message Container: 24.501 (Release 17) Section 9.11.3.33
+    NasMessageContainer,
+    encode_tlve,
+    decode_tlve
+);
+
+opaque_ie!(
+    /// NB-N1 Mode DRX Parameters: 24.501 (Release 17) Section 9.11.3.73
+    NbN1ModeDrxParameters,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// Network Name: 24.501 (Release 17) Section 9.11.3.35
+    NetworkName,
+    encode_tlv,
+    decode_tlv
+);
+
+half_octet_ie!(
+    /// Network Slicing Indication: 24.501 (Release 17) Section 9.11.3.36
+    NetworkSlicingIndication
+);
+
+opaque_ie!(
+    /// NID: 24.501 (Release 17) Section 9.11.3.79
+    Nid,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// Operator-defined Access Category Definitions: 24.501 (Release 17) Section 9.11.3.38
+    OperatorDefinedAccessCategoryDefinitions,
+    encode_tlve,
+    decode_tlve
+);
+
+opaque_ie!(
+    /// Paging Restriction: 24.501 (Release 17) Section 9.11.3.77
+    PagingRestriction,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// Payload Container: 24.501 (Release 17) Section 9.11.3.39
+    PayloadContainer,
+    encode_tlve,
+    decode_tlve
+);
+
+half_octet_ie!(
+    /// Payload Container Type: 24.501 (Release 17) Section 9.11.3.40
+    PayloadContainerType
+);
+
+opaque_ie!(
+    /// PDU Session Reactivation Result: 24.501 (Release 17) Section 9.11.3.42
+    PduSessionReactivationResult,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// PDU Session Reactivation Result Error Cause: 24.501 (Release 17) Section 9.11.3.43
+    PduSessionReactivationResultErrorCause,
+    encode_tlve,
+    decode_tlve
+);
+
+opaque_ie!(
+    /// PDU Session Status: 24.501 (Release 17) Section 9.11.3.44
+    PduSessionStatus,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// PLMN List (Equivalent PLMNs): 24.501 (Release 17) Section 9.11.3.45
+    EquivalentPlmns,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// PEIPS Assistance Information: 24.501 (Release 17) Section 9.11.3.80
+    PeipsAssistanceInformation,
+    encode_tlv,
+    decode_tlv
+);
+
+half_octet_ie!(
+    /// Request Type: 24.501 (Release 17) Section 9.11.3.47
+    RequestType
+);
+
+opaque_ie!(
+    /// S1 UE Network Capability: 24.501 (Release 17) Section 9.11.3.48
+    S1UeNetworkCapability,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// Service Area List: 24.501 (Release 17) Section 9.11.3.49
+    ServiceAreaList,
+    encode_tlv,
+    decode_tlv
+);
+
+half_octet_ie!(
+    /// Service Type: 24.501 (Release 17) Section 9.11.3.50
+    ServiceType
+);
+
+opaque_ie!(
+    /// SOR Transparent Container: 24.501 (Release 17) Section 9.11.3.51
+    SorTransparentContainer,
+    encode_tlve,
+    decode_tlve
+);
+
+opaque_ie!(
+    /// Supported Codec List: 24.501 (Release 17) Section 9.11.3.51A
+    SupportedCodecList,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// UE Radio Capability ID: 24.501 (Release 17) Section 9.11.3.68
+    UeRadioCapabilityId,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// UE Request Type: 24.501 (Release 17) Section 9.11.3.76
+    UeRequestType,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// UE Status: 24.501 (Release 17) Section 9.11.3.56
+    UeStatus,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// UE's Usage Setting: 24.501 (Release 17) Section 9.11.3.55
+    UesUsageSetting,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// Uplink Data Status: 24.501 (Release 17) Section 9.11.3.57
+    UplinkDataStatus,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// WUS Assistance Information: 24.501 (Release 17) Section 9.11.3.71
+    WusAssistanceInformation,
+    encode_tlv,
+    decode_tlv
+);
+
+/// 5GMM Cause: 24.501 (Release 17) Section 9.11.3.2
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FivegmmCause {
+    pub iei: Option<u8>,
+    pub cause: u8,
+}
+
+/// 5GS Registration Result: 24.501 (Release 17) Section 9.11.3.6
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FivegsRegistrationResult {
+    pub iei: Option<u8>,
+    pub disaster_roaming_registration_result: bool,
+    pub emergency_registered: bool,
+    pub nssaa_to_be_performed: bool,
+    pub sms_allowed: bool,
+    pub result: u8,
+}
+
+/// Additional 5G Security Information: 24.501 (Release 17) Section 9.11.3.12
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Additional5gSecurityInformation {
+    pub iei: Option<u8>,
+    /// Retransmission of Initial NAS Message Request
+    pub rinmr: bool,
+    /// Horizontal Derivation Parameter
+    pub hdp: bool,
+}
+
+/// Authentication Failure Parameter: 24.501 (Release 17) Section 9.11.3.14
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AuthenticationFailureParameter {
+    pub iei: Option<u8>,
+    pub auts: [u8; 14],
+}
+
+/// Authentication Parameter AUTN: 24.501 (Release 17) Section 9.11.3.15
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AuthenticationParameterAutn {
+    pub iei: Option<u8>,
+    pub autn: [u8; 16],
+}
+
+/// Authentication Parameter RAND: 24.501 (Release 17) Section 9.11.3.16
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AuthenticationParameterRand {
+    pub iei: Option<u8>,
+    pub rand: [u8; 16],
+}
+
+/// Authentication Response Parameter: 24.501 (Release 17) Section 9.11.3.17
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AuthenticationResponseParameter {
+    pub iei: Option<u8>,
+    pub res_star: [u8; 16],
+}
+
+/// De-registration Type: 24.501 (Release 17) Section 9.11.3.20
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DeregistrationType {
+    pub iei: Option<u8>,
+    pub switch_off: bool,
+    pub re_registration_required: bool,
+    pub access_type: u8,
+}
+
+/// Mapped NSSAI: 24.501 (Release 17) Section 9.11.3.31B
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MappedNssai {
+    pub iei: Option<u8>,
+    pub s_nssais: Vec<SNssai>,
+}
+
+/// NAS Security Algorithms: 24.501 (Release 17) Section 9.11.3.34
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct NasSecurityAlgorithms {
+    pub iei: Option<u8>,
+    pub ciphering: u8,
+    pub integrity: u8,
+}
+
+/// NSSAI: 24.501 (Release 17) Section 9.11.3.37
+///
+/// Used for the Requested, Allowed and Configured NSSAI.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Nssai {
+    pub iei: Option<u8>,
+    pub s_nssais: Vec<SNssai>,
+}
+
+/// NSSAI Inclusion Mode: 24.501 (Release 17) Section 9.11.3.37A
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct NssaiInclusionMode {
+    pub iei: Option<u8>,
+    pub value: NssaiInclusionModeValue,
+}
+
+/// Value of the [`NssaiInclusionMode`]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum NssaiInclusionModeValue {
+    A = 0x00,
+    B = 0x01,
+    C = 0x02,
+    D = 0x03,
+}
+
+/// PDU Session Identity 2: 24.501 (Release 17) Section 9.11.3.41
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PduSessionIdentity2 {
+    pub iei: Option<u8>,
+    pub value: u8,
+}
+
+/// PLMN Identity: 24.501 (Release 17) Section 9.11.3.85
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PlmnIdentity {
+    pub iei: Option<u8>,
+    pub plmn: Plmn,
+}
+
+/// Rejected NSSAI: 24.501 (Release 17) Section 9.11.3.46
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RejectedNssai {
+    pub iei: Option<u8>,
+    pub rejected_s_nssais: Vec<RejectedSNssai>,
+}
+
+/// Rejected S-NSSAI of the [`RejectedNssai`]
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RejectedSNssai {
+    pub cause: RejectedSNssaiCause,
+    pub sst: u8,
+    pub sd: Option<[u8; 3]>,
+}
+
+/// Cause of rejection of a [`RejectedSNssai`]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum RejectedSNssaiCause {
+    NotAvailableInCurrentPlmn = 0x00,
+    NotAvailableInCurrentRegistrationArea = 0x01,
+    NssaaFailedOrRevoked = 0x02,
+    MaximumNumberOfUesReached = 0x03,
+}
+
+/// 5GS Tracking Area Identity: 24.501 (Release 17) Section 9.11.3.8
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Tai {
+    pub iei: Option<u8>,
+    pub plmn: Plmn,
+    pub tac: u32,
+}
+
+/// 5GS Tracking Area Identity List: 24.501 (Release 17) Section 9.11.3.9
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TaiList {
+    pub iei: Option<u8>,
+    pub partial_lists: Vec<PartialTaiList>,
+}
+
+/// Partial Tracking Area Identity List of the [`TaiList`]. Each of the lists has 1 to 16 elements.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum PartialTaiList {
+    /// Type of list '00': Non-consecutive TACs belonging to one PLMN.
+    NonConsecutiveTacs { plmn: Plmn, tacs: Vec<u32> },
+
+    /// Type of list '01': `count` consecutive TACs starting with `tac`, belonging to one PLMN.
+    ConsecutiveTacs { plmn: Plmn, tac: u32, count: u8 },
+
+    /// Type of list '10': TAIs belonging to different PLMNs. The `iei` of the TAIs is not used.
+    Tais(Vec<Tai>),
+}
+
+/// Time Zone: 24.501 (Release 17) Section 9.11.3.52
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TimeZone {
+    pub iei: Option<u8>,
+    pub value: u8,
+}
+
+/// Time Zone and Time: 24.501 (Release 17) Section 9.11.3.53
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TimeZoneAndTime {
+    pub iei: Option<u8>,
+    pub value: [u8; 7],
+}
+
+impl FivegmmCause {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        encode_fixed(self.iei, encode_iei, &[self.cause])
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("FivegmmCause decode");
+
+        let (iei, value, decoded) = decode_fixed(data, decode_iei, 1)?;
+
+        Ok((
+            Self {
+                iei,
+                cause: value[0],
+            },
+            decoded,
+        ))
+    }
+}
+
+impl FivegsRegistrationResult {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        let mut value = self.result & 0x07;
+        if self.sms_allowed {
+            value |= 0x08;
+        }
+        if self.nssaa_to_be_performed {
+            value |= 0x10;
+        }
+        if self.emergency_registered {
+            value |= 0x20;
+        }
+        if self.disaster_roaming_registration_result {
+            value |= 0x40;
+        }
+
+        encode_tlv(self.iei, encode_iei, &[value])
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("FivegsRegistrationResult decode");
+
+        let (iei, value, decoded) = decode_tlv(data, decode_iei)?;
+        let value = *value
+            .first()
+            .ok_or_else(|| invalid_length("5GS Registration Result", 0))?;
+
+        Ok((
+            Self {
+                iei,
+                disaster_roaming_registration_result: value & 0x40 == 0x40,
+                emergency_registered: value & 0x20 == 0x20,
+                nssaa_to_be_performed: value & 0x10 == 0x10,
+                sms_allowed: value & 0x08 == 0x08,
+                result: value & 0x07,
+            },
+            decoded,
+        ))
+    }
+}
+
+impl Additional5gSecurityInformation {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        let mut value = 0;
+        if self.rinmr {
+            value |= 0x02;
+        }
+        if self.hdp {
+            value |= 0x01;
+        }
+
+        encode_tlv(self.iei, encode_iei, &[value])
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("Additional5gSecurityInformation decode");
+
+        let (iei, value, decoded) = decode_tlv(data, decode_iei)?;
+        let value = *value
+            .first()
+            .ok_or_else(|| invalid_length("Additional 5G Security Information", 0))?;
+
+        Ok((
+            Self {
+                iei,
+                rinmr: value & 0x02 == 0x02,
+                hdp: value & 0x01 == 0x01,
+            },
+            decoded,
+        ))
+    }
+}
+
+impl AuthenticationFailureParameter {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        encode_tlv(self.iei, encode_iei, &self.auts)
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("AuthenticationFailureParameter decode");
+
+        let (iei, value, decoded) = decode_tlv(data, decode_iei)?;
+        let auts = value
+            .try_into()
+            .map_err(|_| invalid_length("Authentication Failure Parameter", value.len()))?;
+
+        Ok((Self { iei, auts }, decoded))
+    }
+}
+
+impl AuthenticationParameterAutn {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        encode_tlv(self.iei, encode_iei, &self.autn)
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("AuthenticationParameterAutn decode");
+
+        let (iei, value, decoded) = decode_tlv(data, decode_iei)?;
+        let autn = value
+            .try_into()
+            .map_err(|_| invalid_length("Authentication Parameter AUTN", value.len()))?;
+
+        Ok((Self { iei, autn }, decoded))
+    }
+}
+
+impl AuthenticationParameterRand {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        encode_fixed(self.iei, encode_iei, &self.rand)
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("AuthenticationParameterRand decode");
+
+        let (iei, value, decoded) = decode_fixed(data, decode_iei, 16)?;
+
+        Ok((
+            Self {
+                iei,
+                rand: value.try_into().unwrap(),
+            },
+            decoded,
+        ))
+    }
+}
+
+impl AuthenticationResponseParameter {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        encode_tlv(self.iei, encode_iei, &self.res_star)
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("AuthenticationResponseParameter decode");
+
+        let (iei, value, decoded) = decode_tlv(data, decode_iei)?;
+        let res_star = value
+            .try_into()
+            .map_err(|_| invalid_length("Authentication Response Parameter", value.len()))?;
+
+        Ok((Self { iei, res_star }, decoded))
+    }
+}
+
+impl DeregistrationType {
+    pub(crate) fn encode(&self, encode_iei: bool, upper: bool) -> Vec<u8> {
+        let mut value = self.access_type & 0x03;
+        if self.switch_off {
+            value |= 0x08;
+        }
+        if self.re_registration_required {
+            value |= 0x04;
+        }
+
+        vec![encode_half_octet(self.iei, value, encode_iei, upper)]
+    }
+
+    pub(crate) fn decode(
+        data: &[u8],
+        decode_iei: bool,
+        upper: bool,
+    ) -> Result<(Self, usize), NasError> {
+        log::trace!("DeregistrationType decode");
+
+        let (iei, value) = decode_half_octet(data, decode_iei, upper)?;
+
+        Ok((
+            Self {
+                iei,
+                switch_off: value & 0x08 == 0x08,
+                re_registration_required: value & 0x04 == 0x04,
+                access_type: value & 0x03,
+            },
+            0,
+        ))
+    }
+}
+
+impl MappedNssai {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        encode_tlv(self.iei, encode_iei, &encode_s_nssai_list(&self.s_nssais))
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("MappedNssai decode");
+
+        let (iei, value, decoded) = decode_tlv(data, decode_iei)?;
+        let s_nssais = decode_s_nssai_list(value)?;
+
+        Ok((Self { iei, s_nssais }, decoded))
+    }
+}
+
+impl NasSecurityAlgorithms {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        encode_fixed(
+            self.iei,
+            encode_iei,
+            &[(self.ciphering & 0x0F) << 4 | (self.integrity & 0x0F)],
+        )
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("NasSecurityAlgorithms decode");
+
+        let (iei, value, decoded) = decode_fixed(data, decode_iei, 1)?;
+
+        Ok((
+            Self {
+                iei,
+                ciphering: value[0] >> 4,
+                integrity: value[0] & 0x0F,
+            },
+            decoded,
+        ))
+    }
+}
+
+impl Nssai {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        encode_tlv(self.iei, encode_iei, &encode_s_nssai_list(&self.s_nssais))
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("Nssai decode");
+
+        let (iei, value, decoded) = decode_tlv(data, decode_iei)?;
+        let s_nssais = decode_s_nssai_list(value)?;
+
+        Ok((Self { iei, s_nssais }, decoded))
+    }
+}
+
+impl NssaiInclusionMode {
+    pub(crate) fn encode(&self, encode_iei: bool, upper: bool) -> Vec<u8> {
+        vec![encode_half_octet(
+            self.iei,
+            self.value as u8,
+            encode_iei,
+            upper,
+        )]
+    }
+
+    pub(crate) fn decode(
+        data: &[u8],
+        decode_iei: bool,
+        upper: bool,
+    ) -> Result<(Self, usize), NasError> {
+        let (iei, value) = decode_half_octet(data, decode_iei, upper)?;
+
+        // Bit 3 is spare.
+        let value = match value & 0x03 {
+            0 => NssaiInclusionModeValue::A,
+            1 => NssaiInclusionModeValue::B,
+            2 => NssaiInclusionModeValue::C,
+            _ => NssaiInclusionModeValue::D,
+        };
+
+        Ok((Self { iei, value }, 0))
+    }
+}
+
+impl PduSessionIdentity2 {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        encode_fixed(self.iei, encode_iei, &[self.value])
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("PduSessionIdentity2 decode");
+
+        let (iei, value, decoded) = decode_fixed(data, decode_iei, 1)?;
+
+        Ok((
+            Self {
+                iei,
+                value: value[0],
+            },
+            decoded,
+        ))
+    }
+}
+
+impl PlmnIdentity {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        encode_tlv(self.iei, encode_iei, &self.plmn.encode())
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("PlmnIdentity decode");
+
+        let (iei, value, decoded) = decode_tlv(data, decode_iei)?;
+        if value.len() != 3 {
+            return Err(invalid_length("PLMN Identity", value.len()));
+        }
+
+        Ok((
+            Self {
+                iei,
+                plmn: Plmn::decode(value)?,
+            },
+            decoded,
+        ))
+    }
+}
+
+impl RejectedNssai {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        let mut value = vec![];
+        for rejected in &self.rejected_s_nssais {
+            let length = if rejected.sd.is_some() { 4 } else { 1 };
+            value.push(length << 4 | rejected.cause as u8);
+            value.push(rejected.sst);
+            if let Some(sd) = rejected.sd {
+                value.extend(sd);
+            }
+        }
+        encode_tlv(self.iei, encode_iei, &value)
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("RejectedNssai decode");
+
+        let (iei, value, decoded) = decode_tlv(data, decode_iei)?;
+
+        let mut rejected_s_nssais = vec![];
+        let mut remaining = value;
+        while let Some(first) = remaining.first() {
+            let length = (first >> 4) as usize;
+            if length != 1 && length != 4 {
+                return Err(invalid_length("Rejected S-NSSAI", length));
+            }
+            if remaining.len() < length + 1 {
+                return Err(invalid_length("Rejected NSSAI", value.len()));
+            }
+
+            let cause = match first & 0x0F {
+                0 => RejectedSNssaiCause::NotAvailableInCurrentPlmn,
+                1 => RejectedSNssaiCause::NotAvailableInCurrentRegistrationArea,
+                2 => RejectedSNssaiCause::NssaaFailedOrRevoked,
+                3 => RejectedSNssaiCause::MaximumNumberOfUesReached,
+                cause => {
+                    return Err(NasError::semantically_incorrect(format!(
+                        "Invalid Rejected S-NSSAI Cause: {}",
+                        cause
+                    )))
+                }
+            };
+            let sd = if length == 4 {
+                Some(remaining[2..5].try_into().unwrap())
+            } else {
+                None
+            };
+            rejected_s_nssais.push(RejectedSNssai {
+                cause,
+                sst: remaining[1],
+                sd,
+            });
+
+            remaining = &remaining[length + 1..];
+        }
+
+        Ok((
+            Self {
+                iei,
+                rejected_s_nssais,
+            },
+            decoded,
+        ))
+    }
+}
+
+impl Tai {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        encode_fixed(
+            self.iei,
+            encode_iei,
+            &encode_tai_value(&self.plmn, self.tac),
+        )
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("Tai decode");
+
+        let (iei, value, decoded) = decode_fixed(data, decode_iei, 6)?;
+        let plmn = Plmn::decode(value)?;
+
+        Ok((
+            Self {
+                iei,
+                plmn,
+                tac: decode_tac(&value[3..]),
+            },
+            decoded,
+        ))
+    }
+}
+
+#[cfg(feature = "ngap")]
+impl From<&Tai> for ngap::messages::r17::TAI {
+    fn from(tai: &Tai) -> Self {
+        use ngap::messages::r17::TAC;
+
+        Self {
+            plmn_identity: (&tai.plmn).into(),
+            tac: TAC::from_u32(tai.tac),
+            ie_extensions: None,
+        }
+    }
+}
+
+#[cfg(feature = "ngap")]
+impl TryFrom<&ngap::messages::r17::TAI> for Tai {
+    type Error = NasError;
+
+    fn try_from(tai: &ngap::messages::r17::TAI) -> Result<Self, Self::Error> {
+        if tai.tac.0.len() != 3 {
+            return Err(invalid_length("TAC", tai.tac.0.len()));
+        }
+
+        Ok(Self {
+            iei: None,
+            plmn: Plmn::try_from(&tai.plmn_identity)?,
+            tac: tai.tac.to_u32(),
+        })
+    }
+}
+
+impl TaiList {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        let mut value = vec![];
+        for partial_list in &self.partial_lists {
+            // Number of elements is encoded as one less than the number of elements.
+            let (list_type, elements) = match partial_list {
+                PartialTaiList::NonConsecutiveTacs { tacs, .. } => (0x00, tacs.len()),
+                PartialTaiList::ConsecutiveTacs { count, .. } => (0x01, *count as usize),
+                PartialTaiList::Tais(tais) => (0x02, tais.len()),
+            };
+            value.push(list_type << 5 | (elements.saturating_sub(1) as u8 & 0x1F));
+
+            match partial_list {
+                PartialTaiList::NonConsecutiveTacs { plmn, tacs } => {
+                    value.extend(plmn.encode());
+                    for tac in tacs {
+                        value.extend(&tac.to_be_bytes()[1..]);
+                    }
+                }
+                PartialTaiList::ConsecutiveTacs { plmn, tac, .. } => {
+                    value.extend(encode_tai_value(plmn, *tac));
+                }
+                PartialTaiList::Tais(tais) => {
+                    for tai in tais {
+                        value.extend(encode_tai_value(&tai.plmn, tai.tac));
+                    }
+                }
+            }
+        }
+        encode_tlv(self.iei, encode_iei, &value)
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("TaiList decode");
+
+        let (iei, value, decoded) = decode_tlv(data, decode_iei)?;
+
+        let mut partial_lists = vec![];
+        let mut remaining = value;
+        while let Some(first) = remaining.first() {
+            let list_type = (first >> 5) & 0x03;
+            let elements = (first & 0x1F) as usize + 1;
+            if elements > 16 {
+                return Err(NasError::semantically_incorrect(format!(
+                    "Invalid number of elements in Partial TAI List: {}",
+                    elements
+                )));
+            }
+
+            let length = match list_type {
+                0x00 => 1 + 3 + 3 * elements,
+                0x01 => 1 + 6,
+                0x02 => 1 + 6 * elements,
+                _ => {
+                    return Err(NasError::semantically_incorrect(
+                        "Invalid Type of Partial TAI List: 3",
+                    ))
+                }
+            };
+            if remaining.len() < length {
+                return Err(invalid_length("TAI List", value.len()));
+            }
+            let list = &remaining[1..length];
+
+            let partial_list = match list_type {
+                0x00 => PartialTaiList::NonConsecutiveTacs {
+                    plmn: Plmn::decode(list)?,
+                    tacs: list[3..].chunks(3).map(decode_tac).collect(),
+                },
+                0x01 => PartialTaiList::ConsecutiveTacs {
+                    plmn: Plmn::decode(list)?,
+                    tac: decode_tac(&list[3..]),
+                    count: elements as u8,
+                },
+                _ => {
+                    let mut tais = vec![];
+                    for tai in list.chunks(6) {
+                        tais.push(Tai {
+                            iei: None,
+                            plmn: Plmn::decode(tai)?,
+                            tac: decode_tac(&tai[3..]),
+                        });
+                    }
+                    PartialTaiList::Tais(tais)
+                }
+            };
+            partial_lists.push(partial_list);
+
+            remaining = &remaining[length..];
+        }
+
+        Ok((Self { iei, partial_lists }, decoded))
+    }
+}
+
+impl TimeZone {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        encode_fixed(self.iei, encode_iei, &[self.value])
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("TimeZone decode");
+
+        let (iei, value, decoded) = decode_fixed(data, decode_iei, 1)?;
+
+        Ok((
+            Self {
+                iei,
+                value: value[0],
+            },
+            decoded,
+        ))
+    }
+}
+
+impl TimeZoneAndTime {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        encode_fixed(self.iei, encode_iei, &self.value)
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("TimeZoneAndTime decode");
+
+        let (iei, value, decoded) = decode_fixed(data, decode_iei, 7)?;
+
+        Ok((
+            Self {
+                iei,
+                value: value.try_into().unwrap(),
+            },
+            decoded,
+        ))
+    }
+}
+
+// The S-NSSAIs of the NSSAI IEs are S-NSSAI IEs without the IEI.
+fn encode_s_nssai_list(s_nssais: &[SNssai]) -> Vec<u8> {
+    s_nssais
+        .iter()
+        .flat_map(|s_nssai| s_nssai.encode(false))
+        .collect()
+}
+
+fn decode_s_nssai_list(mut data: &[u8]) -> Result<Vec<SNssai>, NasError> {
+    let mut s_nssais = vec![];
+    while !data.is_empty() {
+        let (s_nssai, decoded) = SNssai::decode(data, false)?;
+        s_nssais.push(s_nssai);
+        data = &data[decoded..];
+    }
+    Ok(s_nssais)
+}
+
+fn encode_tai_value(plmn: &Plmn, tac: u32) -> Vec<u8> {
+    let mut value = plmn.encode().to_vec();
+    value.extend(&tac.to_be_bytes()[1..]);
+    value
+}
+
+// TAC is 3 octets.
+fn decode_tac(data: &[u8]) -> u32 {
+    u32::from_be_bytes([0, data[0], data[1], data[2]])
+}
@@ -1,14 +1,123 @@
+//! Information Elements used in the NAS Messages.
+
+/// Defines an IE whose value is not decoded further and is kept as octets. `$encode` and `$decode`
+/// are the functions used for the format (LV/TLV or LV-E/TLV-E) of the IE.
+macro_rules! opaque_ie {
+    ($(#[$meta:meta])* $name:ident, $encode:ident, $decode:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Eq, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize))]
+        pub struct $name {
+            pub iei: Option<u8>,
+            #[cfg_attr(feature = "serde", serde(serialize_with = "super::serialize_hex"))]
+            pub value: Vec<u8>,
+        }
+
+        impl $name {
+            pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+                $encode(self.iei, encode_iei, &self.value)
+            }
+
+            pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+                let (iei, value, decoded) = $decode(data, decode_iei)?;
+                Ok((
+                    Self {
+                        iei,
+                        value: value.to_vec(),
+                    },
+                    decoded,
+                ))
+            }
+        }
+    };
+}
+
+/// Defines a Type 1 IE (half octet) whose value is kept as it is.
+macro_rules! half_octet_ie {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Eq, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize))]
+        pub struct $name {
+            pub iei: Option<u8>,
+            pub value: u8,
+        }
+
+        impl $name {
+            pub(crate) fn encode(&self, encode_iei: bool, upper: bool) -> Vec<u8> {
+                vec![encode_half_octet(self.iei, self.value, encode_iei, upper)]
+            }
+
+            pub(crate) fn decode(
+                data: &[u8],
+                decode_iei: bool,
+                upper: bool,
+            ) -> Result<(Self, usize), NasError> {
+                let (iei, value) = decode_half_octet(data, decode_iei, upper)?;
+                Ok((Self { iei, value }, 0))
+            }
+        }
+    };
+}
+
+use crate::security::{NasEncryptionAlgoIdentity, NasIntegrityAlgoIdentity};
+use crate::{NasError, Plmn};
+
+use security_3gpp::{HomeNetworkKeyStore, SidfError};
+
+mod common;
+pub use common::{
+    AccessType, AdditionalInformation, Dnn, EapMessage, GprsTimer, GprsTimer2, GprsTimer3,
+    GprsTimer3Unit, GprsTimerUnit, SNssai, ServiceLevelAaContainer,
+};
+
+mod mm;
+pub use mm::{
+    Abba, Additional5gSecurityInformation, AdditionalInformationRequested, AllowedPduSessionStatus,
+    AuthenticationFailureParameter, AuthenticationParameterAutn, AuthenticationParameterRand,
+    AuthenticationResponseParameter, ConfigurationUpdateIndication, DaylightSavingTime,
+    DeregistrationType, EmergencyNumberList, EpsBearerContextStatus, EpsNasMessageContainer,
+    EquivalentPlmns, ExtendedDrxParameters, ExtendedEmergencyNumberList, FivegmmCapability,
+    FivegmmCause, FivegsDrxParameters, FivegsNetworkFeatureSupport, FivegsRegistrationResult,
+    FivegsUpdateType, IdentityType, ImeisvRequest, LadnIndication, LadnInformation, MappedNssai,
+    MicoIndication, MobileStationClassmark2, N5gcIndication, NasMessageContainer,
+    NasSecurityAlgorithms, NbN1ModeDrxParameters, NetworkName, NetworkSlicingIndication, Nid,
+    Nssai, NssaiInclusionMode, NssaiInclusionModeValue, OperatorDefinedAccessCategoryDefinitions,
+    PagingRestriction, PartialTaiList, PayloadContainer, PayloadContainerType, PduSessionIdentity2,
+    PduSessionReactivationResult, PduSessionReactivationResultErrorCause, PduSessionStatus,
+    PeipsAssistanceInformation, PlmnIdentity, RejectedNssai, RejectedSNssai, RejectedSNssaiCause,
+    RequestType, S1UeNetworkCapability, ServiceAreaList, ServiceType, SorTransparentContainer,
+    SupportedCodecList, Tai, TaiList, TimeZone, TimeZoneAndTime, UeRadioCapabilityId,
+    UeRequestType, UeStatus, UesUsageSetting, UplinkDataStatus, WusAssistanceInformation,
+};
+
+mod sm;
+pub use sm::{
+    AllowedSscMode, AlwaysOnPduSessionIndication, AlwaysOnPduSessionRequested, AtsssContainer,
+    ControlPlaneOnlyIndication, DsTtEthernetPortMacAddress, EthernetHeaderCompressionConfiguration,
+    ExtendedProtocolConfigurationOptions, FivegsmCapability, FivegsmCause,
+    FivegsmCongestionReAttemptIndicator, FivegsmNetworkFeatureSupport,
+    IntegrityProtectionMaximumDataRate, IpHeaderCompressionConfiguration, MappedEpsBearerContexts,
+    MaximumNumberOfSupportedPacketFilters, PacketFilter, PduAddress, PduSessionType,
+    PduSessionTypeValue, PortManagementInformationContainer, ProtocolConfigurationOption,
+    QosFlowDescription, QosFlowDescriptions, QosFlowOperationCode, QosFlowParameter, QosRule,
+    QosRuleOperationCode, QosRules, ReAttemptIndicator, ServingPlmnRateControl, SessionAmbr,
+    SmPduDnRequestContainer, SscMode, SscModeValue, UeDsTtResidenceTime,
+};
+
 /// 5G Registration Type :  24.501 (Release 17) Section: 9.11.3.7
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct FivegRegistrationType {
-    iei: Option<u8>,
-    follow_on_req_pending: bool,
-    reg_type: RegistrationType,
+    pub iei: Option<u8>,
+    pub follow_on_req_pending: bool,
+    pub reg_type: RegistrationType,
 }
 
 /// Registration Type of `FivegRegistrationType` See also: [`FivegRegistrationType`]
 #[repr(u8)]
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum RegistrationType {
     Initial = 0x01,
 
@@ -26,88 +135,175 @@ pub enum RegistrationType {
 }
 
 /// NAS Keyset Encoding. 24.501 (Release 17) Section: 9.11.3.32
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct NasKeySetId {
-    iei: Option<u8>,
-    sec_context: SecurityContextType,
-    identifier: u8,
+    pub iei: Option<u8>,
+    pub sec_context: SecurityContextType,
+    pub identifier: u8,
 }
 
 /// Security Context Type for `NasKeySetId` See also: [`NasKeySetId`]
 #[repr(u8)]
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum SecurityContextType {
     Native = 0x00,
     Mapped = 0x01,
 }
 
 /// 5GS Mobile Identity: 24.501 (Release 17) Section 9.11.3.4
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct FivegsMobileIdentity {
-    iei: Option<u8>,
-    length: u16,
-    identity: MobileIdentity,
+    pub iei: Option<u8>,
+    pub length: u16,
+    pub identity: MobileIdentity,
 }
 
 /// 5G-GUTI Mobile Identity: 24.501 (Release 17) Figure 9.11.3.4.1
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct FivegGuti {
-    mcc: u16,
-    mnc: u16,
-    amf_region_id: u8,
-    amf_set_id: u16,
-    amf_pointer: u8,
-    tmsi: u32,
+    pub plmn: Plmn,
+    pub amf_region_id: u8,
+    pub amf_set_id: u16,
+    pub amf_pointer: u8,
+    pub tmsi: u32,
 }
 
 /// SUCI Mobile Identity: 24.501 (Release 17) Figure 9.11.3.4.3-3A/9.11.3.4.4
-#[derive(Debug, Eq, PartialEq)]
+///
+/// The Routing Indicator is kept as the string of it's 1 to 4 digits, as "0" and "0000" are
+/// different Routing Indicators.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Suci {
-    supi_format: u8, // TODO: Make Enum
-    mcc: u16,
-    mnc: u16,
-    routing_indicator: u16,
-    protection_scheme: u8, // TODO: Make Enum
-    home_network_pki: u8,
-    scheme_output: Vec<u8>,
+    pub supi_format: SupiFormat,
+    pub plmn: Plmn,
+    pub routing_indicator: String,
+    pub protection_scheme: ProtectionScheme,
+    pub home_network_pki: u8,
+    pub scheme_output: Vec<u8>,
+}
+
+/// SUCI Mobile Identity for the SUPI formats other than IMSI: 24.501 (Release 17) Section 9.11.3.4
+/// The SUCI is in the NAI format. (See 23.003 Section 28.7.3)
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SuciNai {
+    pub supi_format: SupiFormat,
+    pub nai: String,
 }
 
-/// Enum representing Mobile Identity: See also [`FivegsmobileIdentity`]
+/// SUPI Format of the SUCI: 24.501 (Release 17) Section 9.11.3.4
 #[repr(u8)]
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SupiFormat {
+    Imsi = 0x00,
+
+    /// Network Specific Identifier (NAI)
+    Nai = 0x01,
+
+    /// Global Cable Identifier
+    Gci = 0x02,
+
+    /// Global Line Identifier
+    Gli = 0x03,
+}
+
+/// Protection Scheme of the SUCI: 24.501 (Release 17) Section 9.11.3.4 (See 33.501 Annex C.1)
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ProtectionScheme {
+    Null,
+    EciesProfileA,
+    EciesProfileB,
+
+    /// Operator specific Protection Scheme with the identifier (0xC - 0xF).
+    OperatorSpecific(u8),
+}
+
+/// 5G-S-TMSI Mobile Identity: 24.501 (Release 17) Section 9.11.3.4
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FivegSTmsi {
+    pub amf_set_id: u16,
+    pub amf_pointer: u8,
+    pub tmsi: u32,
+}
+
+/// IMEI: 15 decimal digits. (23.003 Section 6.2.1)
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Imei(String);
+
+/// IMEISV: 16 decimal digits. (23.003 Section 6.2.2)
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Imeisv(String);
+
+/// MAC Address Mobile Identity: 24.501 (Release 17) Section 9.11.3.4
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MacAddress {
+    /// MAC address usage restriction indication (MAURI).
+    pub usage_restriction: bool,
+    pub address: [u8; 6],
+}
+
+/// Enum representing Mobile Identity: See also [`FivegsMobileIdentity`]
+///
+/// IMEI and IMEISV are kept as the (validated) string of their decimal digits.
+#[repr(u8)]
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum MobileIdentity {
+    NoIdentity,
     Suci(Suci),
+    SuciNai(SuciNai),
     FivegGuti(FivegGuti),
-    // TODO: Other variant support
+    Imei(Imei),
+    FivegSTmsi(FivegSTmsi),
+    Imeisv(Imeisv),
+    MacAddress(MacAddress),
+    Eui64([u8; 8]),
 }
 
 /// UE Security Capability : 24.501 (Release 17) Section 9.11.3.54
-#[derive(Debug, Eq, PartialEq)]
+///
+/// The supported algorithms are bitmaps with algorithm 0 (eg. 5G-EA0) in the most significant bit.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct UeSecurityCapability {
-    capabilities: Vec<u8>,
+    pub iei: Option<u8>,
+    pub fiveg_ea: u8,
+    pub fiveg_ia: u8,
+    pub eea: Option<u8>,
+    pub eia: Option<u8>,
+    /// Spare octets following the EPS algorithms, replayed as received.
+    pub spare: Vec<u8>,
 }
 
 impl FivegRegistrationType {
-    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
-        vec![]
+    pub(crate) fn encode(&self, encode_iei: bool, upper: bool) -> Vec<u8> {
+        let mut value = self.reg_type as u8;
+        if self.follow_on_req_pending {
+            value |= 0x08;
+        }
+
+        vec![encode_half_octet(self.iei, value, encode_iei, upper)]
     }
 
     pub(crate) fn decode(
         data: &[u8],
         decode_iei: bool,
         upper: bool,
-    ) -> std::io::Result<(Self, usize)> {
-        let value = data[0];
-
-        let iei = if decode_iei {
-            Some((value & 0xF0) >> 4)
-        } else {
-            None
-        };
-
-        let for_reg_type = if upper { value >> 4 } else { value & 0x0F };
+    ) -> Result<(Self, usize), NasError> {
+        let (iei, for_reg_type) = decode_half_octet(data, decode_iei, upper)?;
 
-        let follow_on_req_pending = (for_reg_type & 0x08) == 0;
+        let follow_on_req_pending = (for_reg_type & 0x08) == 0x08;
 
         let reg_type = match for_reg_type & 0x07 {
             1 => RegistrationType::Initial,
@@ -132,29 +328,27 @@ impl FivegRegistrationType {
 }
 
 impl NasKeySetId {
-    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
-        vec![]
+    pub(crate) fn encode(&self, encode_iei: bool, upper: bool) -> Vec<u8> {
+        let mut value = self.identifier & 0x07;
+        if self.sec_context == SecurityContextType::Mapped {
+            value |= 0x08;
+        }
+
+        vec![encode_half_octet(self.iei, value, encode_iei, upper)]
     }
 
     pub(crate) fn decode(
         data: &[u8],
         decode_iei: bool,
         upper: bool,
-    ) -> std::io::Result<(Self, usize)> {
+    ) -> Result<(Self, usize), NasError> {
         log::trace!("NasKeySetId decode");
 
-        let value = data[0];
-        let iei = if decode_iei {
-            Some((value & 0xF0) >> 4)
+        let (iei, sec_context_and_id) = decode_half_octet(data, decode_iei, upper)?;
+        let sec_context = if sec_context_and_id & 0x08 == 0x08 {
+            SecurityContextType::Mapped
         } else {
-            None
-        };
-
-        let sec_context_and_id = if upper { value >> 4 } else { value & 0x0F };
-        let sec_context = match sec_context_and_id & 0x08 {
-            0 => SecurityContextType::Native,
-            0x08 => SecurityContextType::Mapped,
-            _ => unreachable!(),
+            SecurityContextType::Native
         };
 
         let identifier = sec_context_and_id & 0x07;
@@ -172,30 +366,28 @@ impl NasKeySetId {
 
 impl FivegsMobileIdentity {
     pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
-        vec![]
-    }
+        let mut output = vec![];
+        if encode_iei {
+            output.push(self.iei.unwrap_or_default());
+        }
 
-    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> std::io::Result<(Self, usize)> {
-        log::trace!("FivegsMobileIdentity decode");
+        let identity = self.identity.encode();
+        output.extend((identity.len() as u16).to_be_bytes());
+        output.extend(identity);
 
-        let mut decoded = 0;
-        let iei = if decode_iei {
-            decoded += 1;
-            Some(data[0] & 0xF0)
-        } else {
-            None
-        };
+        output
+    }
 
-        let length = u16::from_be_bytes(data[decoded..decoded + 2].try_into().unwrap());
-        decoded += 2;
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("FivegsMobileIdentity decode");
 
-        let (identity, identity_decoded) = MobileIdentity::decode(&data[decoded..], length)?;
-        decoded += identity_decoded;
+        let (iei, value, decoded) = decode_tlve(data, decode_iei)?;
+        let identity = MobileIdentity::decode(value)?;
 
         Ok((
             Self {
                 iei,
-                length,
+                length: value.len() as u16,
                 identity,
             },
             decoded,
@@ -204,168 +396,1145 @@ impl FivegsMobileIdentity {
 }
 
 impl FivegGuti {
-    pub(crate) fn decode(data: &[u8]) -> std::io::Result<(Self, usize)> {
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut output = vec![0xF0 | MobileIdentity::FIVEG_GUTI_IDENTITY_TYPE];
+        output.extend(self.plmn.encode());
+        output.push(self.amf_region_id);
+        output.push((self.amf_set_id >> 2) as u8);
+        output.push(((self.amf_set_id & 0x03) as u8) << 6 | (self.amf_pointer & 0x3F));
+        output.extend(self.tmsi.to_be_bytes());
+
+        output
+    }
+
+    pub(crate) fn decode(value: &[u8]) -> Result<Self, NasError> {
         log::trace!("FivegGuti decode");
 
-        let mut decoded = 0;
+        if value.len() != 11 {
+            return Err(invalid_length("5G-GUTI", value.len()));
+        }
 
-        let (mcc, mnc, mcc_mnc_decoded) = decode_mcc_mnc(&data[decoded..])?;
-        decoded += mcc_mnc_decoded;
+        // Identity Type Octet.
+        let mut decoded = 1;
 
-        let amf_region_id = data[decoded];
-        decoded += 1;
+        let plmn = Plmn::decode(&value[decoded..])?;
+        decoded += 3;
 
-        let amf_set_id = data[decoded] as u16;
+        let amf_region_id = value[decoded];
         decoded += 1;
 
-        let amf_set_id_remaining = (data[decoded] & 0xC0) >> 6;
-        let amf_set_id = (amf_set_id << 2) | amf_set_id_remaining as u16;
-
-        let amf_pointer = data[decoded] & 0x3F;
+        let amf_set_id = (value[decoded] as u16) << 2 | (value[decoded + 1] >> 6) as u16;
         decoded += 1;
 
-        let tmsi = u32::from_be_bytes(data[decoded..decoded + 4].try_into().unwrap());
-        decoded += 4;
+        let amf_pointer = value[decoded] & 0x3F;
+        decoded += 1;
 
-        Ok((
-            Self {
-                mcc,
-                mnc: mnc.into(),
-                amf_region_id,
-                amf_set_id,
-                amf_pointer,
-                tmsi,
-            },
-            decoded,
-        ))
+        let tmsi = u32::from_be_bytes([
+            value[decoded],
+            value[decoded + 1],
+            value[decoded + 2],
+            value[decoded + 3],
+        ]);
+
+        Ok(Self {
+            plmn,
+            amf_region_id,
+            amf_set_id,
+            amf_pointer,
+            tmsi,
+        })
     }
 }
 
 impl MobileIdentity {
+    const NO_IDENTITY_TYPE: u8 = 0x00;
+    const SUCI_IDENTITY_TYPE: u8 = 0x01;
+    const FIVEG_GUTI_IDENTITY_TYPE: u8 = 0x02;
+    const IMEI_IDENTITY_TYPE: u8 = 0x03;
+    const FIVEG_S_TMSI_IDENTITY_TYPE: u8 = 0x04;
+    const IMEISV_IDENTITY_TYPE: u8 = 0x05;
+    const MAC_ADDRESS_IDENTITY_TYPE: u8 = 0x06;
+    const EUI64_IDENTITY_TYPE: u8 = 0x07;
+
+    const SUPI_FORMAT_IMSI: u8 = 0x00;
+
     pub(crate) fn encode(&self) -> Vec<u8> {
-        todo!();
+        match self {
+            Self::NoIdentity => vec![Self::NO_IDENTITY_TYPE],
+            Self::Suci(suci) => suci.encode(),
+            Self::SuciNai(suci) => suci.encode(),
+            Self::FivegGuti(guti) => guti.encode(),
+            Self::Imei(imei) => encode_identity_digits(Self::IMEI_IDENTITY_TYPE, &imei.0),
+            Self::FivegSTmsi(s_tmsi) => s_tmsi.encode(),
+            Self::Imeisv(imeisv) => encode_identity_digits(Self::IMEISV_IDENTITY_TYPE, &imeisv.0),
+            Self::MacAddress(address) => address.encode(),
+            Self::Eui64(eui64) => {
+                let mut output = vec![Self::EUI64_IDENTITY_TYPE];
+                output.extend(eui64);
+                output
+            }
+        }
     }
 
-    pub(crate) fn decode(data: &[u8], length: u16) -> std::io::Result<(Self, usize)> {
+    // Decode the value of the 5GS Mobility Identity IE.
+    pub(crate) fn decode(value: &[u8]) -> Result<Self, NasError> {
         log::trace!("MobileIdentity decode");
 
-        let identity_type_byte = data[0];
+        let identity_type_byte = *value
+            .first()
+            .ok_or_else(|| invalid_length("5GS Mobile Identity", 0))?;
         match identity_type_byte & 0x07 {
-            1 => {
-                let (suci, decoded) = Suci::decode(data, length)?;
-                Ok((Self::Suci(suci), decoded))
+            Self::NO_IDENTITY_TYPE if value.len() == 1 => Ok(Self::NoIdentity),
+            Self::NO_IDENTITY_TYPE => Err(invalid_length("No Identity", value.len())),
+            Self::SUCI_IDENTITY_TYPE => {
+                if (identity_type_byte & 0x70) >> 4 == Self::SUPI_FORMAT_IMSI {
+                    Ok(Self::Suci(Suci::decode(value)?))
+                } else {
+                    Ok(Self::SuciNai(SuciNai::decode(value)?))
+                }
             }
+            Self::FIVEG_GUTI_IDENTITY_TYPE => Ok(Self::FivegGuti(FivegGuti::decode(value)?)),
+            Self::IMEI_IDENTITY_TYPE => Ok(Self::Imei(Imei::new(&decode_identity_digits(
+                value, "IMEI",
+            )?)?)),
+            Self::FIVEG_S_TMSI_IDENTITY_TYPE => Ok(Self::FivegSTmsi(FivegSTmsi::decode(value)?)),
+            Self::IMEISV_IDENTITY_TYPE => Ok(Self::Imeisv(Imeisv::new(&decode_identity_digits(
+                value, "IMEISV",
+            )?)?)),
+            Self::MAC_ADDRESS_IDENTITY_TYPE => Ok(Self::MacAddress(MacAddress::decode(value)?)),
+            // Self::EUI64_IDENTITY_TYPE
             _ => {
-                log::error!(
-                    "identity type decode not supported yet: {:?}",
-                    identity_type_byte
-                );
-                todo!();
+                let eui64 = value[1..]
+                    .try_into()
+                    .map_err(|_| invalid_length("EUI-64", value.len()))?;
+                Ok(Self::Eui64(eui64))
             }
         }
     }
 }
 
+impl TryFrom<u8> for SupiFormat {
+    type Error = NasError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(Self::Imsi),
+            0x01 => Ok(Self::Nai),
+            0x02 => Ok(Self::Gci),
+            0x03 => Ok(Self::Gli),
+            _ => Err(NasError::semantically_incorrect(format!(
+                "Reserved SUPI Format: {}",
+                value
+            ))),
+        }
+    }
+}
+
+impl From<ProtectionScheme> for u8 {
+    fn from(scheme: ProtectionScheme) -> Self {
+        match scheme {
+            ProtectionScheme::Null => 0x00,
+            ProtectionScheme::EciesProfileA => 0x01,
+            ProtectionScheme::EciesProfileB => 0x02,
+            ProtectionScheme::OperatorSpecific(value) => value & 0x0F,
+        }
+    }
+}
+
+impl TryFrom<u8> for ProtectionScheme {
+    type Error = NasError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(Self::Null),
+            0x01 => Ok(Self::EciesProfileA),
+            0x02 => Ok(Self::EciesProfileB),
+            0x0C..=0x0F => Ok(Self::OperatorSpecific(value)),
+            _ => Err(NasError::semantically_incorrect(format!(
+                "Reserved Protection Scheme: {}",
+                value
+            ))),
+        }
+    }
+}
+
+impl SuciNai {
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut output = vec![(self.supi_format as u8) << 4 | MobileIdentity::SUCI_IDENTITY_TYPE];
+        output.extend(self.nai.as_bytes());
+
+        output
+    }
+
+    pub(crate) fn decode(value: &[u8]) -> Result<Self, NasError> {
+        log::trace!("SuciNai decode");
+
+        let supi_format = SupiFormat::try_from((value[0] & 0x70) >> 4)?;
+        let nai = String::from_utf8(value[1..].to_vec()).map_err(|_| {
+            NasError::semantically_incorrect("SUCI NAI is not a valid UTF-8 string")
+        })?;
+
+        Ok(Self { supi_format, nai })
+    }
+}
+
+impl FivegSTmsi {
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut output = vec![0xF0 | MobileIdentity::FIVEG_S_TMSI_IDENTITY_TYPE];
+        output.push((self.amf_set_id >> 2) as u8);
+        output.push(((self.amf_set_id & 0x03) as u8) << 6 | (self.amf_pointer & 0x3F));
+        output.extend(self.tmsi.to_be_bytes());
+
+        output
+    }
+
+    pub(crate) fn decode(value: &[u8]) -> Result<Self, NasError> {
+        log::trace!("FivegSTmsi decode");
+
+        if value.len() != 7 {
+            return Err(invalid_length("5G-S-TMSI", value.len()));
+        }
+
+        Ok(Self {
+            amf_set_id: (value[1] as u16) << 2 | (value[2] >> 6) as u16,
+            amf_pointer: value[2] & 0x3F,
+            tmsi: u32::from_be_bytes([value[3], value[4], value[5], value[6]]),
+        })
+    }
+}
+
+impl MacAddress {
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut output = vec![MobileIdentity::MAC_ADDRESS_IDENTITY_TYPE];
+        if self.usage_restriction {
+            output[0] |= 0x08;
+        }
+        output.extend(self.address);
+
+        output
+    }
+
+    pub(crate) fn decode(value: &[u8]) -> Result<Self, NasError> {
+        log::trace!("MacAddress decode");
+
+        let address = value[1..]
+            .try_into()
+            .map_err(|_| invalid_length("MAC Address", value.len()))?;
+
+        Ok(Self {
+            usage_restriction: value[0] & 0x08 == 0x08,
+            address,
+        })
+    }
+}
+
+impl Imei {
+    const DIGITS: usize = 15;
+
+    /// IMEI from its 15 decimal digits.
+    pub fn new(digits: &str) -> Result<Self, NasError> {
+        validate_identity_digits(digits, Self::DIGITS, "IMEI")?;
+        Ok(Self(digits.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Imei {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Imeisv {
+    const DIGITS: usize = 16;
+
+    /// IMEISV from its 16 decimal digits.
+    pub fn new(digits: &str) -> Result<Self, NasError> {
+        validate_identity_digits(digits, Self::DIGITS, "IMEISV")?;
+        Ok(Self(digits.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Imeisv {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+// The IMEI or IMEISV should have exactly `length` decimal digits.
+fn validate_identity_digits(digits: &str, length: usize, ie: &str) -> Result<(), NasError> {
+    if digits.len() != length || !digits.bytes().all(|digit| digit.is_ascii_digit()) {
+        return Err(NasError::semantically_incorrect(format!(
+            "Invalid {}: {}, should be {} decimal digits",
+            ie, digits, length
+        )));
+    }
+
+    Ok(())
+}
+
+// Encode the digits as BCD, the first digit in the lower half of the octet. For an odd number of
+// digits the upper half of the last octet is coded as "1111".
+fn encode_bcd_digits(digits: &str) -> Vec<u8> {
+    digits
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let low = pair[0].wrapping_sub(b'0') & 0x0F;
+            let high = pair
+                .get(1)
+                .map_or(0x0F, |digit| digit.wrapping_sub(b'0') & 0x0F);
+            high << 4 | low
+        })
+        .collect()
+}
+
+// Decode the BCD digits. See `encode_bcd_digits` for the format. Any "1111" digit should only be
+// followed by the "1111" digits, `None` if the digits are not valid.
+fn decode_bcd_digits(data: &[u8]) -> Option<String> {
+    let mut digits = String::new();
+    let mut filler = false;
+    for digit in data.iter().flat_map(|octet| [octet & 0x0F, octet >> 4]) {
+        match digit {
+            0..=9 if !filler => digits.push((b'0' + digit) as char),
+            0x0F => filler = true,
+            _ => return None,
+        }
+    }
+
+    Some(digits)
+}
+
+// Encode the IMEI or IMEISV digits. The first digit shares the octet with the Identity Type and the
+// unused half of the last octet for an even number of digits is coded as "1111". The digits are
+// validated when the `Imei` or the `Imeisv` is created.
+fn encode_identity_digits(identity_type: u8, digits: &str) -> Vec<u8> {
+    let digits = digits
+        .bytes()
+        .map(|digit| digit - b'0')
+        .collect::<Vec<u8>>();
+
+    let odd_indication = if digits.len() % 2 == 1 { 0x08 } else { 0x00 };
+    let first = digits.first().copied().unwrap_or(0x0F);
+    let mut output = vec![first << 4 | odd_indication | identity_type];
+    for pair in digits.get(1..).unwrap_or_default().chunks(2) {
+        output.push(pair.get(1).copied().unwrap_or(0x0F) << 4 | pair[0]);
+    }
+
+    output
+}
+
+// Decode the IMEI or IMEISV digits. See `encode_identity_digits` for the format.
+fn decode_identity_digits(value: &[u8], ie: &'static str) -> Result<String, NasError> {
+    let mut digits = vec![value[0] >> 4];
+    for octet in &value[1..] {
+        digits.push(octet & 0x0F);
+        digits.push(octet >> 4);
+    }
+
+    // Even number of digits, the last one is the filler.
+    if value[0] & 0x08 == 0x00 && digits.pop() != Some(0x0F) {
+        return Err(NasError::semantically_incorrect(format!(
+            "Missing filler digit in {}",
+            ie
+        )));
+    }
+
+    digits
+        .into_iter()
+        .map(|digit| match digit {
+            0..=9 => Ok((b'0' + digit) as char),
+            _ => Err(NasError::semantically_incorrect(format!(
+                "Invalid digit in {}: {:#x}",
+                ie, digit
+            ))),
+        })
+        .collect()
+}
+
 impl Suci {
+    /// Get the SUPI (`imsi-<IMSI>`) when the Null Scheme is used, for which the Scheme Output is
+    /// the MSIN. Returns `None` for the other Protection Schemes, see [`Suci::deconceal`] for
+    /// those.
+    pub fn to_supi_imsi(&self) -> Option<String> {
+        if self.protection_scheme != ProtectionScheme::Null {
+            return None;
+        }
+
+        self.supi_imsi(&self.scheme_output)
+    }
+
+    /// Get the SUPI (`imsi-<IMSI>`) by de-concealing the Scheme Output using the Home Network
+    /// Private Keys in the `key_store`.
+    pub fn deconceal(&self, key_store: &HomeNetworkKeyStore) -> Result<String, SidfError> {
+        let msin = key_store.deconceal(
+            self.protection_scheme.into(),
+            self.home_network_pki,
+            &self.scheme_output,
+        )?;
+
+        self.supi_imsi(&msin).ok_or(SidfError::InvalidSchemeOutput)
+    }
+
+    // Get the SUPI from the BCD coded MSIN.
+    fn supi_imsi(&self, msin: &[u8]) -> Option<String> {
+        let msin = decode_bcd_digits(msin).filter(|msin| !msin.is_empty())?;
+        let (mcc, mnc) = self.plmn.to_mcc_mnc();
+
+        Some(format!(
+            "imsi-{:03}{:0width$}{}",
+            mcc,
+            mnc,
+            msin,
+            width = self.plmn.mnc_digits() as usize
+        ))
+    }
+
     pub(crate) fn encode(&self) -> Vec<u8> {
-        todo!();
+        let mut output = vec![(self.supi_format as u8) << 4 | MobileIdentity::SUCI_IDENTITY_TYPE];
+        output.extend(self.plmn.encode());
+
+        let mut routing_indicator = encode_bcd_digits(&self.routing_indicator);
+        routing_indicator.resize(2, 0xFF);
+        output.extend(&routing_indicator[..2]);
+
+        output.push(u8::from(self.protection_scheme));
+        output.push(self.home_network_pki);
+        output.extend(&self.scheme_output);
+
+        output
     }
 
-    pub(crate) fn decode(data: &[u8], length: u16) -> std::io::Result<(Self, usize)> {
+    pub(crate) fn decode(data: &[u8]) -> Result<Self, NasError> {
         log::trace!("Suci decode");
 
+        // Identity Type, PLMN, Routing Indicator, Protection Scheme and Home Network PKI.
+        if data.len() < 8 {
+            return Err(invalid_length("SUCI", data.len()));
+        }
+
         let mut decoded = 0;
 
-        let supi_format = (data[decoded] & 0xF0) >> 4;
+        let supi_format = SupiFormat::try_from((data[decoded] & 0x70) >> 4)?;
         decoded += 1;
 
-        let (mcc, mnc, mcc_mnc_decoded) = decode_mcc_mnc(&data[decoded..])?;
-        decoded += mcc_mnc_decoded;
+        let plmn = Plmn::decode(&data[decoded..])?;
+        decoded += 3;
 
-        let (ri2, ri1) = (((data[decoded] & 0xF0) >> 4), data[decoded] & 0x0F);
+        let routing_indicator = decode_bcd_digits(&data[decoded..decoded + 2])
+            .filter(|digits| !digits.is_empty())
+            .ok_or_else(|| NasError::semantically_incorrect("Invalid Routing Indicator"))?;
+        decoded += 2;
+
+        let protection_scheme = ProtectionScheme::try_from(data[decoded] & 0x0F)?;
         decoded += 1;
-        let (ri4, ri3) = (((data[decoded] & 0xF0) >> 4), data[decoded] & 0x0F);
+
+        let home_network_pki = data[decoded];
         decoded += 1;
 
-        let mut routing_indicator: u16 = ri1 as u16;
-        if ri2 != 0x0F {
-            routing_indicator = ri2 as u16 * 10
-        };
-        if ri3 != 0x0F {
-            routing_indicator = ri3 as u16 * 100
-        };
-        if ri4 != 0x0F {
-            routing_indicator = ri4 as u16 * 1000
+        let scheme_output = data[decoded..].to_vec();
+
+        Ok(Self {
+            supi_format,
+            plmn,
+            routing_indicator,
+            protection_scheme,
+            home_network_pki,
+            scheme_output,
+        })
+    }
+}
+
+impl std::fmt::Display for Suci {
+    // The SUCI as used on the Service Based Interfaces (`suci-0-<MCC>-<MNC>-<Routing
+    // Indicator>-<Protection Scheme>-<Home Network PKI>-<Scheme Output>` See 29.503 Section
+    // 6.1.6.2.2). For the Null Scheme the Scheme Output is the MSIN, else it is hex encoded.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let scheme_output = match self.protection_scheme {
+            ProtectionScheme::Null => decode_bcd_digits(&self.scheme_output).unwrap_or_default(),
+            _ => hex::encode(&self.scheme_output),
         };
 
-        let protection_scheme = data[decoded] & 0x0F;
-        decoded += 1;
+        write!(
+            f,
+            "suci-{}-{}-{}-{:x}-{}-{}",
+            self.supi_format as u8,
+            self.plmn,
+            self.routing_indicator,
+            u8::from(self.protection_scheme),
+            self.home_network_pki,
+            scheme_output
+        )
+    }
+}
 
-        let home_network_pki = data[decoded];
-        decoded += 1;
+impl UeSecurityCapability {
+    /// Whether the UE supports the given 5G NAS encryption algorithm.
+    pub fn supports_fiveg_ea(&self, algo: NasEncryptionAlgoIdentity) -> bool {
+        algorithm_supported(self.fiveg_ea, algo as u8)
+    }
+
+    /// Whether the UE supports the given 5G NAS integrity algorithm.
+    pub fn supports_fiveg_ia(&self, algo: NasIntegrityAlgoIdentity) -> bool {
+        algorithm_supported(self.fiveg_ia, algo as u8)
+    }
+
+    /// Whether the UE supports EPS encryption algorithm EEA`index` (0 - 7).
+    pub fn supports_eea(&self, index: u8) -> bool {
+        self.eea.is_some_and(|eea| algorithm_supported(eea, index))
+    }
+
+    /// Whether the UE supports EPS integrity algorithm EIA`index` (0 - 7).
+    pub fn supports_eia(&self, index: u8) -> bool {
+        self.eia.is_some_and(|eia| algorithm_supported(eia, index))
+    }
+
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        let mut value = vec![self.fiveg_ea, self.fiveg_ia];
+        if let Some(eea) = self.eea {
+            value.push(eea);
+            if let Some(eia) = self.eia {
+                value.push(eia);
+                value.extend(&self.spare);
+            }
+        }
+        encode_tlv(self.iei, encode_iei, &value)
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("UeSecurityCapability decode");
+
+        let (iei, value, decoded) = decode_tlv(data, decode_iei)?;
+        if !(2..=8).contains(&value.len()) {
+            return Err(invalid_length("UeSecurityCapability", value.len()));
+        }
 
-        let scheme_output = data[decoded..length as usize].try_into().unwrap();
-        decoded += length as usize - decoded;
         Ok((
             Self {
-                supi_format,
-                mcc,
-                mnc,
-                routing_indicator,
-                protection_scheme,
-                home_network_pki,
-                scheme_output,
+                iei,
+                fiveg_ea: value[0],
+                fiveg_ia: value[1],
+                eea: value.get(2).copied(),
+                eia: value.get(3).copied(),
+                spare: value.get(4..).unwrap_or_default().to_vec(),
             },
             decoded,
         ))
     }
 }
 
-impl UeSecurityCapability {
-    pub(crate) fn encode(&self) -> Vec<u8> {
-        todo!();
+// Algorithm `index` is supported if its bit is set, algorithm 0 being the most significant bit.
+fn algorithm_supported(bitmap: u8, index: u8) -> bool {
+    index < 8 && bitmap & (0x80 >> index) != 0
+}
+
+// Encode a value of a Type 1 IE (half octet). If the IEI is encoded, the value is always in the
+// lower half, else the value is in the half given by `upper`, the other half being zero.
+fn encode_half_octet(iei: Option<u8>, value: u8, encode_iei: bool, upper: bool) -> u8 {
+    let value = value & 0x0F;
+    if encode_iei {
+        iei.unwrap_or_default() << 4 | value
+    } else if upper {
+        value << 4
+    } else {
+        value
     }
+}
 
-    pub(crate) fn decode(data: &[u8]) -> std::io::Result<(Self, usize)> {
-        log::trace!("UeSecurityCapability decode");
+// Decode a Type 1 IE (half octet). If the IEI is decoded, the value is always in the lower half,
+// else the value is in the half given by `upper`.
+fn decode_half_octet(
+    data: &[u8],
+    decode_iei: bool,
+    upper: bool,
+) -> Result<(Option<u8>, u8), NasError> {
+    let value = *data.first().ok_or_else(|| ie_too_short(1, 0))?;
+
+    if decode_iei {
+        Ok((Some(value >> 4), value & 0x0F))
+    } else if upper {
+        Ok((None, value >> 4))
+    } else {
+        Ok((None, value & 0x0F))
+    }
+}
 
-        let mut decoded = 0;
-        let iei = data[decoded];
-        decoded += 1;
+// Decode an IE with a fixed length value (V or TV format). Returns the IEI (if decoded), the
+// value and the octets decoded.
+fn decode_fixed(
+    data: &[u8],
+    decode_iei: bool,
+    length: usize,
+) -> Result<(Option<u8>, &[u8], usize), NasError> {
+    let start = usize::from(decode_iei);
+    if data.len() < start + length {
+        return Err(ie_too_short(start + length, data.len()));
+    }
 
-        let length = data[decoded];
-        decoded += 1;
+    let iei = if decode_iei { Some(data[0]) } else { None };
+
+    Ok((iei, &data[start..start + length], start + length))
+}
+
+// Encode an IE with a fixed length value (V or TV format).
+fn encode_fixed(iei: Option<u8>, encode_iei: bool, value: &[u8]) -> Vec<u8> {
+    let mut output = vec![];
+    if encode_iei {
+        output.push(iei.unwrap_or_default());
+    }
+    output.extend(value);
 
-        let capabilities = data[decoded..decoded + length as usize].try_into().unwrap();
-        decoded += length as usize;
+    output
+}
+
+// Decode an IE in LV or TLV format. Returns the IEI (if decoded), the value and the octets
+// decoded.
+fn decode_tlv(data: &[u8], decode_iei: bool) -> Result<(Option<u8>, &[u8], usize), NasError> {
+    let start = usize::from(decode_iei);
+    let length = *data
+        .get(start)
+        .ok_or_else(|| ie_too_short(start + 1, data.len()))? as usize;
+
+    let (iei, value, _) = decode_fixed(data, decode_iei, length + 1)?;
+
+    Ok((iei, &value[1..], start + 1 + length))
+}
 
-        Ok((Self { capabilities }, decoded))
+// Encode an IE in LV or TLV format.
+fn encode_tlv(iei: Option<u8>, encode_iei: bool, value: &[u8]) -> Vec<u8> {
+    let mut output = vec![];
+    if encode_iei {
+        output.push(iei.unwrap_or_default());
     }
+    output.push(value.len() as u8);
+    output.extend(value);
+
+    output
+}
+
+// Decode an IE in LV-E or TLV-E format. Returns the IEI (if decoded), the value and the octets
+// decoded.
+fn decode_tlve(data: &[u8], decode_iei: bool) -> Result<(Option<u8>, &[u8], usize), NasError> {
+    let start = usize::from(decode_iei);
+    if data.len() < start + 2 {
+        return Err(ie_too_short(start + 2, data.len()));
+    }
+    let length = u16::from_be_bytes([data[start], data[start + 1]]) as usize;
+
+    let (iei, value, _) = decode_fixed(data, decode_iei, length + 2)?;
+
+    Ok((iei, &value[2..], start + 2 + length))
 }
 
-fn decode_mcc_mnc(data: &[u8]) -> std::io::Result<(u16, u16, usize)> {
-    log::trace!("decode mcc-mnc");
+// Encode an IE in LV-E or TLV-E format.
+fn encode_tlve(iei: Option<u8>, encode_iei: bool, value: &[u8]) -> Vec<u8> {
+    let mut output = vec![];
+    if encode_iei {
+        output.push(iei.unwrap_or_default());
+    }
+    output.extend((value.len() as u16).to_be_bytes());
+    output.extend(value);
 
-    let mut decoded = 0;
+    output
+}
 
-    let mcc1 = data[decoded] & 0x0f;
-    let mcc2 = (data[decoded] & 0xf0) >> 4;
-    decoded += 1;
+/// Get the IEI of an optional IE. For the Type 1 IEs (IEI values 0x8-0xF in the upper half) the
+/// IEI is the upper half of the octet. (Section 11.2.4 24.007)
+///
+/// Octets with the upper half zero are not a valid IEI and are returned as `0`, so that they are
+/// not mistaken for a Type 1 IEI.
+pub(crate) fn optional_iei(value: u8) -> u8 {
+    if !(0x10..0x80).contains(&value) {
+        value >> 4
+    } else {
+        value
+    }
+}
 
-    let mcc3 = data[decoded] & 0x0f;
-    let mnc3 = (data[decoded] & 0xf0) >> 4;
-    decoded += 1;
+/// Skip an optional IE not known to the decoder and return the octets to be skipped. The length
+/// of the IE is derived from the IEI (Section 11.2.4 24.007): IEIs with the upper bit set are one
+/// octet IEs and IEIs 0x70 - 0x7F are TLV-E IEs, all other IEs are TLV IEs. Unknown IEs encoded
+/// as 'comprehension required' are an error.
+pub(crate) fn skip_unknown_ie(data: &[u8]) -> Result<usize, NasError> {
+    let iei = *data.first().ok_or_else(|| ie_too_short(1, 0))?;
 
-    let mnc1 = data[decoded] & 0x0f;
-    let mnc2 = (data[decoded] & 0xf0) >> 4;
+    // IEIs with the upper half zero are 'comprehension required'.
+    if iei & 0xF0 == 0x00 {
+        return Err(NasError::InvalidIei(iei));
+    }
+    log::warn!("Skipping unknown IE: {:#x}", iei);
 
-    let mcc = mcc3 as u16 + 10 * mcc2 as u16 + 100 * mcc1 as u16;
-    let mnc = if mnc3 == 0x0f {
-        mnc2 as u16 + mnc1 as u16 * 10
+    if iei >= 0x80 {
+        Ok(1)
+    } else if iei & 0xF0 == 0x70 {
+        let (_, _, decoded) = decode_tlve(data, true)?;
+        Ok(decoded)
     } else {
-        mnc2 as u16 + mnc1 as u16 * 10 + mnc3 as u16 * 100
-    };
-    decoded += 1;
+        let (_, _, decoded) = decode_tlv(data, true)?;
+        Ok(decoded)
+    }
+}
 
-    Ok((mcc, mnc, decoded))
+fn ie_too_short(expected: usize, available: usize) -> NasError {
+    NasError::Truncated {
+        expected,
+        available,
+    }
+}
+
+// Error for a value of an IE of unexpected length.
+fn invalid_length(ie: &'static str, length: usize) -> NasError {
+    NasError::InvalidLength { ie, length }
+}
+
+// Serialize the octets of a value that is not decoded further as a hex string.
+#[cfg(feature = "serde")]
+fn serialize_hex<S: serde::Serializer>(value: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&hex::encode(value))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    pub(crate) fn registration_type() -> impl Strategy<Value = RegistrationType> {
+        prop_oneof![
+            Just(RegistrationType::Initial),
+            Just(RegistrationType::MobilityUpdating),
+            Just(RegistrationType::PeriodicUpdating),
+            Just(RegistrationType::Emergency),
+            Just(RegistrationType::SnpnOnboarding),
+            Just(RegistrationType::DisasterRoamingUpdating),
+            Just(RegistrationType::DisasterRoamingInitial),
+        ]
+    }
+
+    prop_compose! {
+        pub(crate) fn fiveg_registration_type(iei: Option<u8>)
+            (follow_on_req_pending in any::<bool>(), reg_type in registration_type())
+            -> FivegRegistrationType {
+            FivegRegistrationType { iei, follow_on_req_pending, reg_type }
+        }
+    }
+
+    prop_compose! {
+        pub(crate) fn nas_key_set_id(iei: Option<u8>)
+            (mapped in any::<bool>(), identifier in 0..8_u8) -> NasKeySetId {
+            let sec_context = if mapped {
+                SecurityContextType::Mapped
+            } else {
+                SecurityContextType::Native
+            };
+            NasKeySetId { iei, sec_context, identifier }
+        }
+    }
+
+    prop_compose! {
+        fn plmn()(mcc in 0..1000_u16, mnc in 0..1000_u16, three_digits in any::<bool>()) -> Plmn {
+            if three_digits {
+                Plmn::new(mcc, mnc, 3).unwrap()
+            } else {
+                Plmn::new(mcc, mnc % 100, 2).unwrap()
+            }
+        }
+    }
+
+    prop_compose! {
+        fn fiveg_guti()
+            (plmn in plmn(),
+             amf_region_id in any::<u8>(),
+             amf_set_id in 0..1024_u16,
+             amf_pointer in 0..64_u8,
+             tmsi in any::<u32>()) -> FivegGuti {
+            FivegGuti { plmn, amf_region_id, amf_set_id, amf_pointer, tmsi }
+        }
+    }
+
+    prop_compose! {
+        fn suci()
+            (plmn in plmn(),
+             routing_indicator in "[0-9]{1,4}",
+             protection_scheme in protection_scheme(),
+             home_network_pki in any::<u8>(),
+             scheme_output in proptest::collection::vec(any::<u8>(), 0..64)) -> Suci {
+            Suci {
+                supi_format: SupiFormat::Imsi,
+                plmn,
+                routing_indicator,
+                protection_scheme,
+                home_network_pki,
+                scheme_output,
+            }
+        }
+    }
+
+    fn protection_scheme() -> impl Strategy<Value = ProtectionScheme> {
+        prop_oneof![
+            Just(ProtectionScheme::Null),
+            Just(ProtectionScheme::EciesProfileA),
+            Just(ProtectionScheme::EciesProfileB),
+            (0x0C..=0x0F_u8).prop_map(ProtectionScheme::OperatorSpecific),
+        ]
+    }
+
+    prop_compose! {
+        fn suci_nai()
+            (supi_format in prop_oneof![
+                Just(SupiFormat::Nai),
+                Just(SupiFormat::Gci),
+                Just(SupiFormat::Gli)
+            ],
+             nai in any::<String>()) -> SuciNai {
+            SuciNai { supi_format, nai }
+        }
+    }
+
+    prop_compose! {
+        fn fiveg_s_tmsi()
+            (amf_set_id in 0..1024_u16, amf_pointer in 0..64_u8, tmsi in any::<u32>())
+            -> FivegSTmsi {
+            FivegSTmsi { amf_set_id, amf_pointer, tmsi }
+        }
+    }
+
+    prop_compose! {
+        fn mac_address()(usage_restriction in any::<bool>(), address in any::<[u8; 6]>())
+            -> MacAddress {
+            MacAddress { usage_restriction, address }
+        }
+    }
+
+    pub(crate) fn mobile_identity() -> impl Strategy<Value = MobileIdentity> {
+        prop_oneof![
+            Just(MobileIdentity::NoIdentity),
+            suci().prop_map(MobileIdentity::Suci),
+            suci_nai().prop_map(MobileIdentity::SuciNai),
+            fiveg_guti().prop_map(MobileIdentity::FivegGuti),
+            "[0-9]{15}".prop_map(|imei| MobileIdentity::Imei(Imei::new(&imei).unwrap())),
+            fiveg_s_tmsi().prop_map(MobileIdentity::FivegSTmsi),
+            "[0-9]{16}".prop_map(|imeisv| MobileIdentity::Imeisv(Imeisv::new(&imeisv).unwrap())),
+            mac_address().prop_map(MobileIdentity::MacAddress),
+            any::<[u8; 8]>().prop_map(MobileIdentity::Eui64),
+        ]
+    }
+
+    prop_compose! {
+        pub(crate) fn fivegs_mobile_identity(iei: Option<u8>)
+            (identity in mobile_identity()) -> FivegsMobileIdentity {
+            let length = identity.encode().len() as u16;
+            FivegsMobileIdentity { iei, length, identity }
+        }
+    }
+
+    prop_compose! {
+        pub(crate) fn ue_security_capability(iei: Option<u8>)
+            (value in proptest::collection::vec(any::<u8>(), 2..=8))
+            -> UeSecurityCapability {
+            UeSecurityCapability {
+                iei,
+                fiveg_ea: value[0],
+                fiveg_ia: value[1],
+                eea: value.get(2).copied(),
+                eia: value.get(3).copied(),
+                spare: value.get(4..).unwrap_or_default().to_vec(),
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn roundtrip_fiveg_registration_type(
+            value in fiveg_registration_type(None),
+            value_iei in fiveg_registration_type(Some(0x0B)),
+            upper in any::<bool>()
+        ) {
+            let encoded = value.encode(false, upper);
+            let (decoded, _) = FivegRegistrationType::decode(&encoded, false, upper).unwrap();
+            prop_assert_eq!(decoded, value);
+
+            let encoded = value_iei.encode(true, false);
+            let (decoded, _) = FivegRegistrationType::decode(&encoded, true, false).unwrap();
+            prop_assert_eq!(decoded, value_iei);
+        }
+
+        #[test]
+        fn roundtrip_nas_key_set_id(
+            value in nas_key_set_id(None),
+            value_iei in nas_key_set_id(Some(0x0C)),
+            upper in any::<bool>()
+        ) {
+            let encoded = value.encode(false, upper);
+            let (decoded, _) = NasKeySetId::decode(&encoded, false, upper).unwrap();
+            prop_assert_eq!(decoded, value);
+
+            let encoded = value_iei.encode(true, false);
+            let (decoded, _) = NasKeySetId::decode(&encoded, true, false).unwrap();
+            prop_assert_eq!(decoded, value_iei);
+        }
+
+        #[test]
+        fn roundtrip_fivegs_mobile_identity(
+            value in fivegs_mobile_identity(None),
+            value_iei in fivegs_mobile_identity(Some(0x77)),
+        ) {
+            let encoded = value.encode(false);
+            let (decoded, length) = FivegsMobileIdentity::decode(&encoded, false).unwrap();
+            prop_assert_eq!(length, encoded.len());
+            prop_assert_eq!(decoded, value);
+
+            let encoded = value_iei.encode(true);
+            let (decoded, length) = FivegsMobileIdentity::decode(&encoded, true).unwrap();
+            prop_assert_eq!(length, encoded.len());
+            prop_assert_eq!(decoded, value_iei);
+        }
+
+        #[test]
+        fn roundtrip_ue_security_capability(
+            value in ue_security_capability(None),
+            value_iei in ue_security_capability(Some(0x2E)),
+        ) {
+            let encoded = value.encode(false);
+            let (decoded, length) = UeSecurityCapability::decode(&encoded, false).unwrap();
+            prop_assert_eq!(length, encoded.len());
+            prop_assert_eq!(decoded, value);
+
+            let encoded = value_iei.encode(true);
+            let (decoded, length) = UeSecurityCapability::decode(&encoded, true).unwrap();
+            prop_assert_eq!(length, encoded.len());
+            prop_assert_eq!(decoded, value_iei);
+        }
+    }
+
+    #[test]
+    fn test_identity_digits() {
+        // IMEI with 15 digits and IMEISV with 16 digits.
+        let imei = hex::decode("3b35450011020946").unwrap();
+        assert_eq!(
+            MobileIdentity::decode(&imei),
+            Ok(MobileIdentity::Imei(Imei::new("353540011209064").unwrap()))
+        );
+        let imeisv = hex::decode("4573806121856151f1").unwrap();
+        assert_eq!(
+            MobileIdentity::decode(&imeisv),
+            Ok(MobileIdentity::Imeisv(
+                Imeisv::new("4370816125816151").unwrap()
+            ))
+        );
+        assert_eq!(
+            MobileIdentity::Imeisv(Imeisv::new("4370816125816151").unwrap()).encode(),
+            imeisv
+        );
+
+        // Digits are BCD coded.
+        assert!(MobileIdentity::decode(&[0x3b, 0x5a]).is_err());
+
+        // IMEI is 15 digits and IMEISV is 16 digits.
+        assert!(MobileIdentity::decode(&[0x3b, 0x35]).is_err());
+        assert!(MobileIdentity::decode(&imei[..7]).is_err());
+        let mut imei_as_imeisv = imei.clone();
+        imei_as_imeisv[0] = imei_as_imeisv[0] & 0xF8 | 0x05;
+        assert!(MobileIdentity::decode(&imei_as_imeisv).is_err());
+
+        for invalid in [
+            "35354001120906",
+            "3535400112090641",
+            "35354001120906a",
+            "35354001120906 ",
+        ] {
+            assert!(Imei::new(invalid).is_err(), "{}", invalid);
+        }
+        for invalid in ["437081612581615", "43708161258161510", "437081612581615/"] {
+            assert!(Imeisv::new(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_ue_security_capability() {
+        // 5G-EA0, 5G-EA2, 5G-IA2, EEA0, EEA2 and EIA2
+        let data = hex::decode("04a020a020").unwrap();
+        let (capability, decoded) = UeSecurityCapability::decode(&data, false).unwrap();
+        assert_eq!(decoded, data.len());
+        assert!(capability.supports_fiveg_ea(NasEncryptionAlgoIdentity::Nea0));
+        assert!(!capability.supports_fiveg_ea(NasEncryptionAlgoIdentity::Nea1));
+        assert!(capability.supports_fiveg_ea(NasEncryptionAlgoIdentity::Nea2));
+        assert!(!capability.supports_fiveg_ia(NasIntegrityAlgoIdentity::Nia0));
+        assert!(capability.supports_fiveg_ia(NasIntegrityAlgoIdentity::Nia2));
+        assert!(capability.supports_eea(2));
+        assert!(!capability.supports_eia(0));
+        assert!(capability.supports_eia(2));
+        assert!(!capability.supports_eia(8));
+
+        // Without the EPS algorithms
+        let (capability, _) = UeSecurityCapability::decode(&[0x02, 0x20, 0x20], false).unwrap();
+        assert_eq!(capability.eea, None);
+        assert!(!capability.supports_eea(2));
+
+        assert_eq!(
+            UeSecurityCapability::decode(&[0x01, 0x20], false),
+            Err(NasError::InvalidLength {
+                ie: "UeSecurityCapability",
+                length: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_nssai() {
+        // SST 1, SST 1 with SD 0x010203 and SST 2 with mapped HPLMN SST 3 and SD 0x0a0b0c
+        let data = hex::decode("1510010104010102030802aabbcc030a0b0c").unwrap();
+        let (nssai, decoded) = Nssai::decode(&data, true).unwrap();
+        assert_eq!(decoded, data.len());
+        assert_eq!(
+            nssai.s_nssais,
+            vec![
+                SNssai::new(1, None),
+                SNssai::new(1, Some([1, 2, 3])),
+                SNssai {
+                    iei: None,
+                    sst: 2,
+                    sd: Some([0xaa, 0xbb, 0xcc]),
+                    mapped_hplmn_sst: Some(3),
+                    mapped_hplmn_sd: Some([0x0a, 0x0b, 0x0c]),
+                },
+            ]
+        );
+        assert_eq!(nssai.encode(true), data);
+
+        // An S-NSSAI of length 3 is not valid.
+        assert_eq!(
+            Nssai::decode(&[0x04, 0x03, 0x01, 0x02, 0x03], false),
+            Err(NasError::InvalidLength {
+                ie: "S-NSSAI",
+                length: 3
+            })
+        );
+
+        // SST 1 not available in the PLMN, SST 2 with SD 0x000001 not available in the RA
+        let data = hex::decode("110710014102000001").unwrap();
+        let (rejected_nssai, decoded) = RejectedNssai::decode(&data, true).unwrap();
+        assert_eq!(decoded, data.len());
+        assert_eq!(
+            rejected_nssai.rejected_s_nssais,
+            vec![
+                RejectedSNssai {
+                    cause: RejectedSNssaiCause::NotAvailableInCurrentPlmn,
+                    sst: 1,
+                    sd: None,
+                },
+                RejectedSNssai {
+                    cause: RejectedSNssaiCause::NotAvailableInCurrentRegistrationArea,
+                    sst: 2,
+                    sd: Some([0, 0, 1]),
+                },
+            ]
+        );
+        assert_eq!(rejected_nssai.encode(true), data);
+
+        assert!(RejectedNssai::decode(&[0x02, 0x14, 0x01], false).is_err());
+    }
+
+    #[test]
+    fn test_tai_list() {
+        // Non-consecutive TACs 1 and 2 of 901-70, TACs 5 to 7 of 001-01 and TAI 286-101-3
+        let data = hex::decode("54180109f1070000010000022200f11000000540821601000003").unwrap();
+        let (tai_list, decoded) = TaiList::decode(&data, true).unwrap();
+        assert_eq!(decoded, data.len());
+        assert_eq!(
+            tai_list.partial_lists,
+            vec![
+                PartialTaiList::NonConsecutiveTacs {
+                    plmn: "901-70".parse().unwrap(),
+                    tacs: vec![1, 2]
+                },
+                PartialTaiList::ConsecutiveTacs {
+                    plmn: "001-01".parse().unwrap(),
+                    tac: 5,
+                    count: 3
+                },
+                PartialTaiList::Tais(vec![Tai {
+                    iei: None,
+                    plmn: "286-101".parse().unwrap(),
+                    tac: 3
+                }]),
+            ]
+        );
+        assert_eq!(tai_list.encode(true), data);
+
+        // Type of list '11' is reserved.
+        assert!(TaiList::decode(&hex::decode("076009f107000001").unwrap(), false).is_err());
+        // Partial TAI List shorter than the number of elements.
+        assert!(TaiList::decode(&hex::decode("070109f107000001").unwrap(), false).is_err());
+    }
+
+    #[cfg(feature = "ngap")]
+    #[test]
+    fn test_s_nssai_ngap() {
+        use ngap::messages::r17::{SD, SST, S_NSSAI};
+
+        let s_nssai = SNssai::new(1, Some([1, 2, 3]));
+        let ngap_s_nssai = S_NSSAI::from(&s_nssai);
+        assert_eq!(ngap_s_nssai.sst, SST(vec![1]));
+        assert_eq!(ngap_s_nssai.sd, Some(SD(vec![1, 2, 3])));
+        assert_eq!(SNssai::try_from(&ngap_s_nssai), Ok(s_nssai));
+    }
+
+    #[cfg(feature = "sbi")]
+    #[test]
+    fn test_s_nssai_sbi() {
+        use sbi5g::models::Snssai;
+
+        let s_nssai = SNssai::new(1, Some([0xab, 0x02, 0x03]));
+        let sbi_s_nssai = Snssai::from(&s_nssai);
+        assert_eq!(sbi_s_nssai.sst, 1);
+        assert_eq!(sbi_s_nssai.sd.as_deref(), Some("ab0203"));
+        assert_eq!(SNssai::try_from(&sbi_s_nssai), Ok(s_nssai));
+
+        let invalid = Snssai { sst: 256, sd: None };
+        assert!(SNssai::try_from(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_suci() {
+        // Routing Indicator "0000" and "0".
+        let value = hex::decode("0109f107000000000000000010").unwrap();
+        let Ok(MobileIdentity::Suci(suci)) = MobileIdentity::decode(&value) else {
+            panic!("Not a SUCI");
+        };
+        assert_eq!(suci.routing_indicator, "0000");
+        assert_eq!(suci.protection_scheme, ProtectionScheme::Null);
+        assert_eq!(suci.to_supi_imsi().as_deref(), Some("imsi-901700000000001"));
+        assert_eq!(suci.to_string(), "suci-0-901-70-0000-0-0-0000000001");
+        assert_eq!(
+            suci.deconceal(&HomeNetworkKeyStore::new()).as_deref(),
+            Ok("imsi-901700000000001")
+        );
+        assert_eq!(suci.encode(), value);
+
+        let value = hex::decode("0109f107f0ff01050000000010").unwrap();
+        let Ok(MobileIdentity::Suci(suci)) = MobileIdentity::decode(&value) else {
+            panic!("Not a SUCI");
+        };
+        assert_eq!(suci.routing_indicator, "0");
+        assert_eq!(suci.protection_scheme, ProtectionScheme::EciesProfileA);
+        assert_eq!(suci.to_supi_imsi(), None);
+        assert_eq!(suci.to_string(), "suci-0-901-70-0-1-5-0000000010");
+        assert_eq!(
+            suci.deconceal(&HomeNetworkKeyStore::new()),
+            Err(SidfError::UnknownHomeNetworkKey(5))
+        );
+        assert_eq!(suci.encode(), value);
+
+        // Digits after the filler in the Routing Indicator and a Reserved Protection Scheme.
+        assert!(MobileIdentity::decode(&hex::decode("0109f107f0f100000010").unwrap()).is_err());
+        assert!(MobileIdentity::decode(&hex::decode("0109f107000003000010").unwrap()).is_err());
+    }
 }
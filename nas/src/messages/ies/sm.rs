@@ -0,0 +1,783 @@
+//! Information Elements for 5GS Session Management. 24.501 (Release 17) Section 9.11.4
+
+use super::{
+    decode_fixed, decode_half_octet, decode_tlv, decode_tlve, encode_fixed, encode_half_octet,
+    encode_tlv, encode_tlve, ie_too_short, invalid_length,
+};
+use crate::NasError;
+
+// Semantic error in the QoS operation. 24.501 (Release 17) Section 9.11.4.2
+const CAUSE_SEMANTIC_ERROR_IN_QOS_OPERATION: u8 = 83;
+
+opaque_ie!(
+    /// 5GSM Capability: 24.501 (Release 17) Section 9.11.4.1
+    FivegsmCapability,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// 5GSM Congestion Re-attempt Indicator: 24.501 (Release 17) Section 9.11.4.21
+    FivegsmCongestionReAttemptIndicator,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// 5GSM Network Feature Support: 24.501 (Release 17) Section 9.11.4.18
+    FivegsmNetworkFeatureSupport,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// ATSSS Container: 24.501 (Release 17) Section 9.11.4.22
+    AtsssContainer,
+    encode_tlve,
+    decode_tlve
+);
+
+opaque_ie!(
+    /// DS-TT Ethernet Port MAC Address: 24.501 (Release 17) Section 9.11.4.25
+    DsTtEthernetPortMacAddress,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// Ethernet Header Compression Configuration: 24.501 (Release 17) Section 9.11.4.28
+    EthernetHeaderCompressionConfiguration,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// IP Header Compression Configuration: 24.501 (Release 17) Section 9.11.4.24
+    IpHeaderCompressionConfiguration,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// Mapped EPS Bearer Contexts: 24.501 (Release 17) Section 9.11.4.8
+    MappedEpsBearerContexts,
+    encode_tlve,
+    decode_tlve
+);
+
+opaque_ie!(
+    /// Port Management Information Container: 24.501 (Release 17) Section 9.11.4.27
+    PortManagementInformationContainer,
+    encode_tlve,
+    decode_tlve
+);
+
+opaque_ie!(
+    /// Re-attempt Indicator: 24.501 (Release 17) Section 9.11.4.17
+    ReAttemptIndicator,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// Serving PLMN Rate Control: 24.501 (Release 17) Section 9.11.4.20
+    ServingPlmnRateControl,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// SM PDU DN Request Container: 24.501 (Release 17) Section 9.11.4.15
+    SmPduDnRequestContainer,
+    encode_tlv,
+    decode_tlv
+);
+
+opaque_ie!(
+    /// UE-DS-TT Residence Time: 24.501 (Release 17) Section 9.11.4.26
+    UeDsTtResidenceTime,
+    encode_tlv,
+    decode_tlv
+);
+
+half_octet_ie!(
+    /// Always-on PDU Session Indication: 24.501 (Release 17) Section 9.11.4.3
+    AlwaysOnPduSessionIndication
+);
+
+half_octet_ie!(
+    /// Always-on PDU Session Requested: 24.501 (Release 17) Section 9.11.4.4
+    AlwaysOnPduSessionRequested
+);
+
+half_octet_ie!(
+    /// Control Plane Only Indication: 24.501 (Release 17) Section 9.11.4.23
+    ControlPlaneOnlyIndication
+);
+
+/// 5GSM Cause: 24.501 (Release 17) Section 9.11.4.2
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FivegsmCause {
+    pub iei: Option<u8>,
+    pub cause: u8,
+}
+
+/// Allowed SSC Mode: 24.501 (Release 17) Section 9.11.4.5
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AllowedSscMode {
+    pub iei: Option<u8>,
+    pub ssc1: bool,
+    pub ssc2: bool,
+    pub ssc3: bool,
+}
+
+/// Extended Protocol Configuration Options: 24.501 (Release 17) Section 9.11.4.6 (See 24.008
+/// Section 10.5.6.3)
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ExtendedProtocolConfigurationOptions {
+    pub iei: Option<u8>,
+    pub configuration_protocol: u8,
+    pub options: Vec<ProtocolConfigurationOption>,
+}
+
+/// A Protocol or a Container of [`ExtendedProtocolConfigurationOptions`]
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ProtocolConfigurationOption {
+    pub identifier: u16,
+    pub contents: Vec<u8>,
+}
+
+/// Integrity Protection Maximum Data Rate: 24.501 (Release 17) Section 9.11.4.7
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct IntegrityProtectionMaximumDataRate {
+    pub iei: Option<u8>,
+    pub uplink: u8,
+    pub downlink: u8,
+}
+
+/// Maximum Number of Supported Packet Filters: 24.501 (Release 17) Section 9.11.4.9
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MaximumNumberOfSupportedPacketFilters {
+    pub iei: Option<u8>,
+    pub value: u16,
+}
+
+/// PDU Address: 24.501 (Release 17) Section 9.11.4.10
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PduAddress {
+    pub iei: Option<u8>,
+    /// SMF's IPv6 Link Local Address present in the `address`.
+    pub si6lla: bool,
+    pub pdu_session_type: PduSessionTypeValue,
+    pub address: Vec<u8>,
+}
+
+/// PDU Session Type: 24.501 (Release 17) Section 9.11.4.11
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PduSessionType {
+    pub iei: Option<u8>,
+    pub value: PduSessionTypeValue,
+}
+
+/// Value of the [`PduSessionType`] (Also used by the [`PduAddress`])
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum PduSessionTypeValue {
+    Ipv4 = 0x01,
+    Ipv6 = 0x02,
+    Ipv4v6 = 0x03,
+    Unstructured = 0x04,
+    Ethernet = 0x05,
+}
+
+/// QoS Flow Descriptions: 24.501 (Release 17) Section 9.11.4.12
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct QosFlowDescriptions {
+    pub iei: Option<u8>,
+    pub descriptions: Vec<QosFlowDescription>,
+}
+
+/// A QoS Flow Description of [`QosFlowDescriptions`]
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct QosFlowDescription {
+    pub qfi: u8,
+    pub operation_code: QosFlowOperationCode,
+    /// For a modified QoS Flow, whether the parameters list replaces all the parameters.
+    pub e_bit: bool,
+    pub parameters: Vec<QosFlowParameter>,
+}
+
+/// Operation Code of a [`QosFlowDescription`]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum QosFlowOperationCode {
+    Create = 0x01,
+    Delete = 0x02,
+    Modify = 0x03,
+}
+
+/// A Parameter of a [`QosFlowDescription`]. (Eg. 5QI, GFBR, MFBR etc.)
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct QosFlowParameter {
+    pub identifier: u8,
+    pub contents: Vec<u8>,
+}
+
+/// QoS Rules: 24.501 (Release 17) Section 9.11.4.13
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct QosRules {
+    pub iei: Option<u8>,
+    pub rules: Vec<QosRule>,
+}
+
+/// A QoS Rule of [`QosRules`]
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct QosRule {
+    pub identifier: u8,
+    pub operation_code: QosRuleOperationCode,
+    /// Default QoS Rule
+    pub dqr: bool,
+    pub packet_filters: Vec<PacketFilter>,
+    /// Not present for the QoS Rules being deleted.
+    pub precedence: Option<u8>,
+    pub segregation: bool,
+    /// Not present for the QoS Rules being deleted.
+    pub qfi: Option<u8>,
+}
+
+/// Operation Code of a [`QosRule`]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum QosRuleOperationCode {
+    Create = 0x01,
+    Delete = 0x02,
+    ModifyAndAddPacketFilters = 0x03,
+    ModifyAndReplacePacketFilters = 0x04,
+    ModifyAndDeletePacketFilters = 0x05,
+    ModifyWithoutModifyingPacketFilters = 0x06,
+}
+
+/// A Packet Filter of a [`QosRule`]. For the `ModifyAndDeletePacketFilters` operation only the
+/// `identifier` is encoded.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PacketFilter {
+    pub direction: u8,
+    pub identifier: u8,
+    pub components: Vec<u8>,
+}
+
+/// Session-AMBR: 24.501 (Release 17) Section 9.11.4.14
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SessionAmbr {
+    pub iei: Option<u8>,
+    pub downlink_unit: u8,
+    pub downlink: u16,
+    pub uplink_unit: u8,
+    pub uplink: u16,
+}
+
+/// SSC Mode: 24.501 (Release 17) Section 9.11.4.16
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SscMode {
+    pub iei: Option<u8>,
+    pub value: SscModeValue,
+}
+
+/// Value of the [`SscMode`]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SscModeValue {
+    SscMode1 = 0x01,
+    SscMode2 = 0x02,
+    SscMode3 = 0x03,
+}
+
+impl FivegsmCause {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        encode_fixed(self.iei, encode_iei, &[self.cause])
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("FivegsmCause decode");
+
+        let (iei, value, decoded) = decode_fixed(data, decode_iei, 1)?;
+
+        Ok((
+            Self {
+                iei,
+                cause: value[0],
+            },
+            decoded,
+        ))
+    }
+}
+
+impl AllowedSscMode {
+    pub(crate) fn encode(&self, encode_iei: bool, upper: bool) -> Vec<u8> {
+        let mut value = 0;
+        if self.ssc1 {
+            value |= 0x01;
+        }
+        if self.ssc2 {
+            value |= 0x02;
+        }
+        if self.ssc3 {
+            value |= 0x04;
+        }
+
+        vec![encode_half_octet(self.iei, value, encode_iei, upper)]
+    }
+
+    pub(crate) fn decode(
+        data: &[u8],
+        decode_iei: bool,
+        upper: bool,
+    ) -> Result<(Self, usize), NasError> {
+        let (iei, value) = decode_half_octet(data, decode_iei, upper)?;
+
+        Ok((
+            Self {
+                iei,
+                ssc1: value & 0x01 == 0x01,
+                ssc2: value & 0x02 == 0x02,
+                ssc3: value & 0x04 == 0x04,
+            },
+            0,
+        ))
+    }
+}
+
+impl ExtendedProtocolConfigurationOptions {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        // The extension bit is always set.
+        let mut value = vec![0x80 | (self.configuration_protocol & 0x07)];
+        for option in &self.options {
+            value.extend(option.identifier.to_be_bytes());
+            value.push(option.contents.len() as u8);
+            value.extend(&option.contents);
+        }
+
+        encode_tlve(self.iei, encode_iei, &value)
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("ExtendedProtocolConfigurationOptions decode");
+
+        let (iei, value, decoded) = decode_tlve(data, decode_iei)?;
+        let configuration_protocol = value
+            .first()
+            .ok_or_else(|| invalid_length("Extended Protocol Configuration Options", 0))?
+            & 0x07;
+
+        let mut options = vec![];
+        let mut offset = 1;
+        while offset < value.len() {
+            let (_, header, _) = decode_fixed(&value[offset..], false, 2)?;
+            let identifier = u16::from_be_bytes([header[0], header[1]]);
+            let (_, contents, option_decoded) = decode_tlv(&value[offset + 2..], false)?;
+            offset += 2 + option_decoded;
+
+            options.push(ProtocolConfigurationOption {
+                identifier,
+                contents: contents.to_vec(),
+            });
+        }
+
+        Ok((
+            Self {
+                iei,
+                configuration_protocol,
+                options,
+            },
+            decoded,
+        ))
+    }
+}
+
+impl IntegrityProtectionMaximumDataRate {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        encode_fixed(self.iei, encode_iei, &[self.uplink, self.downlink])
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("IntegrityProtectionMaximumDataRate decode");
+
+        let (iei, value, decoded) = decode_fixed(data, decode_iei, 2)?;
+
+        Ok((
+            Self {
+                iei,
+                uplink: value[0],
+                downlink: value[1],
+            },
+            decoded,
+        ))
+    }
+}
+
+impl MaximumNumberOfSupportedPacketFilters {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        // The value is 11 bits, the lower 5 bits of the second octet are spare.
+        let value = (self.value & 0x07FF) << 5;
+
+        encode_fixed(self.iei, encode_iei, &value.to_be_bytes())
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("MaximumNumberOfSupportedPacketFilters decode");
+
+        let (iei, value, decoded) = decode_fixed(data, decode_iei, 2)?;
+
+        Ok((
+            Self {
+                iei,
+                value: u16::from_be_bytes([value[0], value[1]]) >> 5,
+            },
+            decoded,
+        ))
+    }
+}
+
+impl PduAddress {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        let mut value = vec![self.pdu_session_type as u8];
+        if self.si6lla {
+            value[0] |= 0x08;
+        }
+        value.extend(&self.address);
+
+        encode_tlv(self.iei, encode_iei, &value)
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("PduAddress decode");
+
+        let (iei, value, decoded) = decode_tlv(data, decode_iei)?;
+        let first = *value
+            .first()
+            .ok_or_else(|| invalid_length("PDU Address", 0))?;
+
+        Ok((
+            Self {
+                iei,
+                si6lla: first & 0x08 == 0x08,
+                pdu_session_type: PduSessionTypeValue::from_u8(first & 0x07),
+                address: value[1..].to_vec(),
+            },
+            decoded,
+        ))
+    }
+}
+
+impl PduSessionType {
+    pub(crate) fn encode(&self, encode_iei: bool, upper: bool) -> Vec<u8> {
+        vec![encode_half_octet(
+            self.iei,
+            self.value as u8,
+            encode_iei,
+            upper,
+        )]
+    }
+
+    pub(crate) fn decode(
+        data: &[u8],
+        decode_iei: bool,
+        upper: bool,
+    ) -> Result<(Self, usize), NasError> {
+        let (iei, value) = decode_half_octet(data, decode_iei, upper)?;
+
+        Ok((
+            Self {
+                iei,
+                value: PduSessionTypeValue::from_u8(value & 0x07),
+            },
+            0,
+        ))
+    }
+}
+
+impl PduSessionTypeValue {
+    // All other values are interpreted as IPv4v6. (24.501 Section 9.11.4.11)
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Ipv4,
+            2 => Self::Ipv6,
+            4 => Self::Unstructured,
+            5 => Self::Ethernet,
+            _ => Self::Ipv4v6,
+        }
+    }
+}
+
+impl QosFlowDescriptions {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        let mut value = vec![];
+        for description in &self.descriptions {
+            value.push(description.qfi & 0x3F);
+            value.push((description.operation_code as u8) << 5);
+
+            let mut parameters = description.parameters.len() as u8 & 0x3F;
+            if description.e_bit {
+                parameters |= 0x40;
+            }
+            value.push(parameters);
+
+            for parameter in &description.parameters {
+                value.push(parameter.identifier);
+                value.extend(encode_tlv(None, false, &parameter.contents));
+            }
+        }
+
+        encode_tlve(self.iei, encode_iei, &value)
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("QosFlowDescriptions decode");
+
+        let (iei, value, decoded) = decode_tlve(data, decode_iei)?;
+
+        let mut descriptions = vec![];
+        let mut offset = 0;
+        while offset < value.len() {
+            let (_, header, _) = decode_fixed(&value[offset..], false, 3)?;
+            offset += 3;
+
+            let operation_code = match header[1] >> 5 {
+                1 => QosFlowOperationCode::Create,
+                2 => QosFlowOperationCode::Delete,
+                3 => QosFlowOperationCode::Modify,
+                operation_code => {
+                    return Err(NasError::SemanticallyIncorrect {
+                        cause: CAUSE_SEMANTIC_ERROR_IN_QOS_OPERATION,
+                        reason: format!("Invalid QoS Flow Operation Code: {}", operation_code),
+                    })
+                }
+            };
+
+            let mut parameters = vec![];
+            for _ in 0..(header[2] & 0x3F) {
+                let identifier = *value.get(offset).ok_or_else(|| ie_too_short(1, 0))?;
+                let (_, contents, parameter_decoded) = decode_tlv(&value[offset + 1..], false)?;
+                offset += 1 + parameter_decoded;
+
+                parameters.push(QosFlowParameter {
+                    identifier,
+                    contents: contents.to_vec(),
+                });
+            }
+
+            descriptions.push(QosFlowDescription {
+                qfi: header[0] & 0x3F,
+                operation_code,
+                e_bit: header[2] & 0x40 == 0x40,
+                parameters,
+            });
+        }
+
+        Ok((Self { iei, descriptions }, decoded))
+    }
+}
+
+impl QosRules {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        let mut value = vec![];
+        for rule in &self.rules {
+            let mut contents = vec![];
+
+            let mut first =
+                (rule.operation_code as u8) << 5 | rule.packet_filters.len() as u8 & 0x0F;
+            if rule.dqr {
+                first |= 0x10;
+            }
+            contents.push(first);
+
+            for filter in &rule.packet_filters {
+                if rule.operation_code == QosRuleOperationCode::ModifyAndDeletePacketFilters {
+                    contents.push(filter.identifier & 0x0F);
+                } else {
+                    contents.push((filter.direction & 0x03) << 4 | filter.identifier & 0x0F);
+                    contents.extend(encode_tlv(None, false, &filter.components));
+                }
+            }
+
+            if let Some(precedence) = rule.precedence {
+                contents.push(precedence);
+            }
+            if let Some(qfi) = rule.qfi {
+                let mut last = qfi & 0x3F;
+                if rule.segregation {
+                    last |= 0x40;
+                }
+                contents.push(last);
+            }
+
+            value.push(rule.identifier);
+            value.extend(encode_tlve(None, false, &contents));
+        }
+
+        encode_tlve(self.iei, encode_iei, &value)
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("QosRules decode");
+
+        let (iei, value, decoded) = decode_tlve(data, decode_iei)?;
+
+        let mut rules = vec![];
+        let mut offset = 0;
+        while offset < value.len() {
+            let identifier = value[offset];
+            let (_, contents, rule_decoded) = decode_tlve(&value[offset + 1..], false)?;
+            offset += 1 + rule_decoded;
+
+            rules.push(QosRule::decode(identifier, contents)?);
+        }
+
+        Ok((Self { iei, rules }, decoded))
+    }
+}
+
+impl QosRule {
+    fn decode(identifier: u8, contents: &[u8]) -> Result<Self, NasError> {
+        let first = *contents
+            .first()
+            .ok_or_else(|| invalid_length("QoS Rule", 0))?;
+
+        let operation_code = match first >> 5 {
+            1 => QosRuleOperationCode::Create,
+            2 => QosRuleOperationCode::Delete,
+            3 => QosRuleOperationCode::ModifyAndAddPacketFilters,
+            4 => QosRuleOperationCode::ModifyAndReplacePacketFilters,
+            5 => QosRuleOperationCode::ModifyAndDeletePacketFilters,
+            6 => QosRuleOperationCode::ModifyWithoutModifyingPacketFilters,
+            operation_code => {
+                return Err(NasError::SemanticallyIncorrect {
+                    cause: CAUSE_SEMANTIC_ERROR_IN_QOS_OPERATION,
+                    reason: format!("Invalid QoS Rule Operation Code: {}", operation_code),
+                })
+            }
+        };
+
+        let mut packet_filters = vec![];
+        let mut offset = 1;
+        for _ in 0..(first & 0x0F) {
+            let filter = *contents.get(offset).ok_or_else(|| ie_too_short(1, 0))?;
+            offset += 1;
+
+            // The Packet Filter Direction bits are spare in the list of packet filters to delete.
+            let (direction, components) =
+                if operation_code == QosRuleOperationCode::ModifyAndDeletePacketFilters {
+                    (0, vec![])
+                } else {
+                    let (_, components, filter_decoded) = decode_tlv(&contents[offset..], false)?;
+                    offset += filter_decoded;
+                    ((filter >> 4) & 0x03, components.to_vec())
+                };
+
+            packet_filters.push(PacketFilter {
+                direction,
+                identifier: filter & 0x0F,
+                components,
+            });
+        }
+
+        // QoS Rule Precedence and QFI are not included for some of the operations.
+        let precedence = contents.get(offset).copied();
+        let last = contents.get(offset + 1).copied();
+
+        Ok(Self {
+            identifier,
+            operation_code,
+            dqr: first & 0x10 == 0x10,
+            packet_filters,
+            precedence,
+            segregation: last.map(|last| last & 0x40 == 0x40).unwrap_or_default(),
+            qfi: last.map(|last| last & 0x3F),
+        })
+    }
+}
+
+impl SessionAmbr {
+    pub(crate) fn encode(&self, encode_iei: bool) -> Vec<u8> {
+        let mut value = vec![self.downlink_unit];
+        value.extend(self.downlink.to_be_bytes());
+        value.push(self.uplink_unit);
+        value.extend(self.uplink.to_be_bytes());
+
+        encode_tlv(self.iei, encode_iei, &value)
+    }
+
+    pub(crate) fn decode(data: &[u8], decode_iei: bool) -> Result<(Self, usize), NasError> {
+        log::trace!("SessionAmbr decode");
+
+        let (iei, value, decoded) = decode_tlv(data, decode_iei)?;
+        if value.len() != 6 {
+            return Err(invalid_length("Session-AMBR", value.len()));
+        }
+
+        Ok((
+            Self {
+                iei,
+                downlink_unit: value[0],
+                downlink: u16::from_be_bytes([value[1], value[2]]),
+                uplink_unit: value[3],
+                uplink: u16::from_be_bytes([value[4], value[5]]),
+            },
+            decoded,
+        ))
+    }
+}
+
+impl SscMode {
+    pub(crate) fn encode(&self, encode_iei: bool, upper: bool) -> Vec<u8> {
+        vec![encode_half_octet(
+            self.iei,
+            self.value as u8,
+            encode_iei,
+            upper,
+        )]
+    }
+
+    pub(crate) fn decode(
+        data: &[u8],
+        decode_iei: bool,
+        upper: bool,
+    ) -> Result<(Self, usize), NasError> {
+        let (iei, value) = decode_half_octet(data, decode_iei, upper)?;
+
+        // The unused values 4 to 6 are interpreted as SSC Mode 1 to 3. (24.501 Section 9.11.4.16)
+        let value = match value & 0x07 {
+            1 | 4 => SscModeValue::SscMode1,
+            2 | 5 => SscModeValue::SscMode2,
+            3 | 6 => SscModeValue::SscMode3,
+            value => {
+                return Err(NasError::semantically_incorrect(format!(
+                    "Invalid SSC Mode: {}",
+                    value
+                )))
+            }
+        };
+
+        Ok((Self { iei, value }, 0))
+    }
+}
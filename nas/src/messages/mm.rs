@@ -1,20 +1,190 @@
 //! Structures, Enumerations and constants for NAS 5G MM Messages.
 
 use crate::messages::headers::Nas5gMmMessageHeader;
+use crate::NasError;
 
 mod registration_request;
 pub use registration_request::RegistrationRequest;
 
+mod registration_accept;
+pub use registration_accept::RegistrationAccept;
+
+mod registration_complete;
+pub use registration_complete::RegistrationComplete;
+
+mod registration_reject;
+pub use registration_reject::RegistrationReject;
+
+mod deregistration_request_from_ue;
+pub use deregistration_request_from_ue::DeregistrationRequestFromUe;
+
+mod deregistration_accept_from_ue;
+pub use deregistration_accept_from_ue::DeregistrationAcceptFromUe;
+
+mod deregistration_request_to_ue;
+pub use deregistration_request_to_ue::DeregistrationRequestToUe;
+
+mod deregistration_accept_to_ue;
+pub use deregistration_accept_to_ue::DeregistrationAcceptToUe;
+
+mod service_request;
+pub use service_request::ServiceRequest;
+
+mod service_reject;
+pub use service_reject::ServiceReject;
+
+mod service_accept;
+pub use service_accept::ServiceAccept;
+
+mod configuration_update_command;
+pub use configuration_update_command::ConfigurationUpdateCommand;
+
+mod configuration_update_complete;
+pub use configuration_update_complete::ConfigurationUpdateComplete;
+
+mod authentication_request;
+pub use authentication_request::AuthenticationRequest;
+
+mod authentication_response;
+pub use authentication_response::AuthenticationResponse;
+
+mod authentication_reject;
+pub use authentication_reject::AuthenticationReject;
+
+mod authentication_failure;
+pub use authentication_failure::AuthenticationFailure;
+
+mod authentication_result;
+pub use authentication_result::AuthenticationResult;
+
+mod identity_request;
+pub use identity_request::IdentityRequest;
+
+mod identity_response;
+pub use identity_response::IdentityResponse;
+
+mod security_mode_command;
+pub use security_mode_command::SecurityModeCommand;
+
+mod security_mode_complete;
+pub use security_mode_complete::SecurityModeComplete;
+
+mod security_mode_reject;
+pub use security_mode_reject::SecurityModeReject;
+
+mod fivegmm_status;
+pub use fivegmm_status::FivegmmStatus;
+
+mod notification;
+pub use notification::Notification;
+
+mod notification_response;
+pub use notification_response::NotificationResponse;
+
+mod ul_nas_transport;
+pub use ul_nas_transport::UlNasTransport;
+
+mod dl_nas_transport;
+pub use dl_nas_transport::DlNasTransport;
+
 pub const MM_MSG_TYPE_REGISTRATION_REQUEST: u8 = 0x41;
+pub const MM_MSG_TYPE_REGISTRATION_ACCEPT: u8 = 0x42;
+pub const MM_MSG_TYPE_REGISTRATION_COMPLETE: u8 = 0x43;
+pub const MM_MSG_TYPE_REGISTRATION_REJECT: u8 = 0x44;
+pub const MM_MSG_TYPE_DEREGISTRATION_REQUEST_FROM_UE: u8 = 0x45;
+pub const MM_MSG_TYPE_DEREGISTRATION_ACCEPT_FROM_UE: u8 = 0x46;
+pub const MM_MSG_TYPE_DEREGISTRATION_REQUEST_TO_UE: u8 = 0x47;
+pub const MM_MSG_TYPE_DEREGISTRATION_ACCEPT_TO_UE: u8 = 0x48;
+pub const MM_MSG_TYPE_SERVICE_REQUEST: u8 = 0x4C;
+pub const MM_MSG_TYPE_SERVICE_REJECT: u8 = 0x4D;
+pub const MM_MSG_TYPE_SERVICE_ACCEPT: u8 = 0x4E;
+pub const MM_MSG_TYPE_CONFIGURATION_UPDATE_COMMAND: u8 = 0x54;
+pub const MM_MSG_TYPE_CONFIGURATION_UPDATE_COMPLETE: u8 = 0x55;
+pub const MM_MSG_TYPE_AUTHENTICATION_REQUEST: u8 = 0x56;
+pub const MM_MSG_TYPE_AUTHENTICATION_RESPONSE: u8 = 0x57;
+pub const MM_MSG_TYPE_AUTHENTICATION_REJECT: u8 = 0x58;
+pub const MM_MSG_TYPE_AUTHENTICATION_FAILURE: u8 = 0x59;
+pub const MM_MSG_TYPE_AUTHENTICATION_RESULT: u8 = 0x5A;
+pub const MM_MSG_TYPE_IDENTITY_REQUEST: u8 = 0x5B;
+pub const MM_MSG_TYPE_IDENTITY_RESPONSE: u8 = 0x5C;
+pub const MM_MSG_TYPE_SECURITY_MODE_COMMAND: u8 = 0x5D;
+pub const MM_MSG_TYPE_SECURITY_MODE_COMPLETE: u8 = 0x5E;
+pub const MM_MSG_TYPE_SECURITY_MODE_REJECT: u8 = 0x5F;
+pub const MM_MSG_TYPE_FIVEGMM_STATUS: u8 = 0x64;
+pub const MM_MSG_TYPE_NOTIFICATION: u8 = 0x65;
+pub const MM_MSG_TYPE_NOTIFICATION_RESPONSE: u8 = 0x66;
+pub const MM_MSG_TYPE_UL_NAS_TRANSPORT: u8 = 0x67;
+pub const MM_MSG_TYPE_DL_NAS_TRANSPORT: u8 = 0x68;
 
 /// NAS 5G MM Message. The Message will be one of the 5G MM Messages.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Nas5gMmMessage {
-    RegistrationRequest(RegistrationRequest),
+    RegistrationRequest(Box<RegistrationRequest>),
+    RegistrationAccept(RegistrationAccept),
+    RegistrationComplete(RegistrationComplete),
+    RegistrationReject(RegistrationReject),
+    DeregistrationRequestFromUe(DeregistrationRequestFromUe),
+    DeregistrationAcceptFromUe(DeregistrationAcceptFromUe),
+    DeregistrationRequestToUe(DeregistrationRequestToUe),
+    DeregistrationAcceptToUe(DeregistrationAcceptToUe),
+    ServiceRequest(ServiceRequest),
+    ServiceReject(ServiceReject),
+    ServiceAccept(ServiceAccept),
+    ConfigurationUpdateCommand(ConfigurationUpdateCommand),
+    ConfigurationUpdateComplete(ConfigurationUpdateComplete),
+    AuthenticationRequest(AuthenticationRequest),
+    AuthenticationResponse(AuthenticationResponse),
+    AuthenticationReject(AuthenticationReject),
+    AuthenticationFailure(AuthenticationFailure),
+    AuthenticationResult(AuthenticationResult),
+    IdentityRequest(IdentityRequest),
+    IdentityResponse(IdentityResponse),
+    SecurityModeCommand(SecurityModeCommand),
+    SecurityModeComplete(SecurityModeComplete),
+    SecurityModeReject(SecurityModeReject),
+    FivegmmStatus(FivegmmStatus),
+    Notification(Notification),
+    NotificationResponse(NotificationResponse),
+    UlNasTransport(UlNasTransport),
+    DlNasTransport(DlNasTransport),
 }
 
 impl Nas5gMmMessage {
-    pub fn decode(data: &[u8]) -> std::io::Result<Self> {
-        let mut decoded = 0;
+    pub fn encode(&self) -> Vec<u8> {
+        match self {
+            Self::RegistrationRequest(message) => message.encode(),
+            Self::RegistrationAccept(message) => message.encode(),
+            Self::RegistrationComplete(message) => message.encode(),
+            Self::RegistrationReject(message) => message.encode(),
+            Self::DeregistrationRequestFromUe(message) => message.encode(),
+            Self::DeregistrationAcceptFromUe(message) => message.encode(),
+            Self::DeregistrationRequestToUe(message) => message.encode(),
+            Self::DeregistrationAcceptToUe(message) => message.encode(),
+            Self::ServiceRequest(message) => message.encode(),
+            Self::ServiceReject(message) => message.encode(),
+            Self::ServiceAccept(message) => message.encode(),
+            Self::ConfigurationUpdateCommand(message) => message.encode(),
+            Self::ConfigurationUpdateComplete(message) => message.encode(),
+            Self::AuthenticationRequest(message) => message.encode(),
+            Self::AuthenticationResponse(message) => message.encode(),
+            Self::AuthenticationReject(message) => message.encode(),
+            Self::AuthenticationFailure(message) => message.encode(),
+            Self::AuthenticationResult(message) => message.encode(),
+            Self::IdentityRequest(message) => message.encode(),
+            Self::IdentityResponse(message) => message.encode(),
+            Self::SecurityModeCommand(message) => message.encode(),
+            Self::SecurityModeComplete(message) => message.encode(),
+            Self::SecurityModeReject(message) => message.encode(),
+            Self::FivegmmStatus(message) => message.encode(),
+            Self::Notification(message) => message.encode(),
+            Self::NotificationResponse(message) => message.encode(),
+            Self::UlNasTransport(message) => message.encode(),
+            Self::DlNasTransport(message) => message.encode(),
+        }
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self, NasError> {
         // TODO: Not sure yet whether we need to 'keep' the header part in the underlying message
         // structure. For now keeping it, but may be we will have to revisit that part.
         //
@@ -25,23 +195,316 @@ impl Nas5gMmMessage {
         // TODO: Do we error if this is not plain text?
         let (header, _) = Nas5gMmMessageHeader::decode(data)?;
 
-        match header.message_type {
+        let (message, decoded) = match header.message_type {
             MM_MSG_TYPE_REGISTRATION_REQUEST => {
-                let (reg_request, decoded) = RegistrationRequest::decode(data)?;
-                if decoded != data.len() {
-                    Err(std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        format!(
-                            "Undecoded octets. Decoded: {}, length: {}",
-                            decoded,
-                            data.len()
-                        ),
-                    ))
-                } else {
-                    Ok(Self::RegistrationRequest(reg_request))
-                }
-            }
-            _ => todo!(),
+                let (message, decoded) = RegistrationRequest::decode(data)?;
+                (Self::RegistrationRequest(Box::new(message)), decoded)
+            }
+            MM_MSG_TYPE_REGISTRATION_ACCEPT => {
+                let (message, decoded) = RegistrationAccept::decode(data)?;
+                (Self::RegistrationAccept(message), decoded)
+            }
+            MM_MSG_TYPE_REGISTRATION_COMPLETE => {
+                let (message, decoded) = RegistrationComplete::decode(data)?;
+                (Self::RegistrationComplete(message), decoded)
+            }
+            MM_MSG_TYPE_REGISTRATION_REJECT => {
+                let (message, decoded) = RegistrationReject::decode(data)?;
+                (Self::RegistrationReject(message), decoded)
+            }
+            MM_MSG_TYPE_DEREGISTRATION_REQUEST_FROM_UE => {
+                let (message, decoded) = DeregistrationRequestFromUe::decode(data)?;
+                (Self::DeregistrationRequestFromUe(message), decoded)
+            }
+            MM_MSG_TYPE_DEREGISTRATION_ACCEPT_FROM_UE => {
+                let (message, decoded) = DeregistrationAcceptFromUe::decode(data)?;
+                (Self::DeregistrationAcceptFromUe(message), decoded)
+            }
+            MM_MSG_TYPE_DEREGISTRATION_REQUEST_TO_UE => {
+                let (message, decoded) = DeregistrationRequestToUe::decode(data)?;
+                (Self::DeregistrationRequestToUe(message), decoded)
+            }
+            MM_MSG_TYPE_DEREGISTRATION_ACCEPT_TO_UE => {
+                let (message, decoded) = DeregistrationAcceptToUe::decode(data)?;
+                (Self::DeregistrationAcceptToUe(message), decoded)
+            }
+            MM_MSG_TYPE_SERVICE_REQUEST => {
+                let (message, decoded) = ServiceRequest::decode(data)?;
+                (Self::ServiceRequest(message), decoded)
+            }
+            MM_MSG_TYPE_SERVICE_REJECT => {
+                let (message, decoded) = ServiceReject::decode(data)?;
+                (Self::ServiceReject(message), decoded)
+            }
+            MM_MSG_TYPE_SERVICE_ACCEPT => {
+                let (message, decoded) = ServiceAccept::decode(data)?;
+                (Self::ServiceAccept(message), decoded)
+            }
+            MM_MSG_TYPE_CONFIGURATION_UPDATE_COMMAND => {
+                let (message, decoded) = ConfigurationUpdateCommand::decode(data)?;
+                (Self::ConfigurationUpdateCommand(message), decoded)
+            }
+            MM_MSG_TYPE_CONFIGURATION_UPDATE_COMPLETE => {
+                let (message, decoded) = ConfigurationUpdateComplete::decode(data)?;
+                (Self::ConfigurationUpdateComplete(message), decoded)
+            }
+            MM_MSG_TYPE_AUTHENTICATION_REQUEST => {
+                let (message, decoded) = AuthenticationRequest::decode(data)?;
+                (Self::AuthenticationRequest(message), decoded)
+            }
+            MM_MSG_TYPE_AUTHENTICATION_RESPONSE => {
+                let (message, decoded) = AuthenticationResponse::decode(data)?;
+                (Self::AuthenticationResponse(message), decoded)
+            }
+            MM_MSG_TYPE_AUTHENTICATION_REJECT => {
+                let (message, decoded) = AuthenticationReject::decode(data)?;
+                (Self::AuthenticationReject(message), decoded)
+            }
+            MM_MSG_TYPE_AUTHENTICATION_FAILURE => {
+                let (message, decoded) = AuthenticationFailure::decode(data)?;
+                (Self::AuthenticationFailure(message), decoded)
+            }
+            MM_MSG_TYPE_AUTHENTICATION_RESULT => {
+                let (message, decoded) = AuthenticationResult::decode(data)?;
+                (Self::AuthenticationResult(message), decoded)
+            }
+            MM_MSG_TYPE_IDENTITY_REQUEST => {
+                let (message, decoded) = IdentityRequest::decode(data)?;
+                (Self::IdentityRequest(message), decoded)
+            }
+            MM_MSG_TYPE_IDENTITY_RESPONSE => {
+                let (message, decoded) = IdentityResponse::decode(data)?;
+                (Self::IdentityResponse(message), decoded)
+            }
+            MM_MSG_TYPE_SECURITY_MODE_COMMAND => {
+                let (message, decoded) = SecurityModeCommand::decode(data)?;
+                (Self::SecurityModeCommand(message), decoded)
+            }
+            MM_MSG_TYPE_SECURITY_MODE_COMPLETE => {
+                let (message, decoded) = SecurityModeComplete::decode(data)?;
+                (Self::SecurityModeComplete(message), decoded)
+            }
+            MM_MSG_TYPE_SECURITY_MODE_REJECT => {
+                let (message, decoded) = SecurityModeReject::decode(data)?;
+                (Self::SecurityModeReject(message), decoded)
+            }
+            MM_MSG_TYPE_FIVEGMM_STATUS => {
+                let (message, decoded) = FivegmmStatus::decode(data)?;
+                (Self::FivegmmStatus(message), decoded)
+            }
+            MM_MSG_TYPE_NOTIFICATION => {
+                let (message, decoded) = Notification::decode(data)?;
+                (Self::Notification(message), decoded)
+            }
+            MM_MSG_TYPE_NOTIFICATION_RESPONSE => {
+                let (message, decoded) = NotificationResponse::decode(data)?;
+                (Self::NotificationResponse(message), decoded)
+            }
+            MM_MSG_TYPE_UL_NAS_TRANSPORT => {
+                let (message, decoded) = UlNasTransport::decode(data)?;
+                (Self::UlNasTransport(message), decoded)
+            }
+            MM_MSG_TYPE_DL_NAS_TRANSPORT => {
+                let (message, decoded) = DlNasTransport::decode(data)?;
+                (Self::DlNasTransport(message), decoded)
+            }
+            message_type => return Err(NasError::UnknownMessageType(message_type)),
+        };
+
+        if decoded != data.len() {
+            Err(NasError::UndecodedOctets {
+                decoded,
+                length: data.len(),
+            })
+        } else {
+            Ok(message)
         }
     }
 }
+
+// Decode the 5GMM Message Header and verify that it is for the expected message type.
+fn decode_header(data: &[u8], message_type: u8) -> Result<(Nas5gMmMessageHeader, usize), NasError> {
+    let (header, decoded) = Nas5gMmMessageHeader::decode(data)?;
+    if header.message_type != message_type {
+        return Err(NasError::UnexpectedMessageType {
+            expected: message_type,
+            received: header.message_type,
+        });
+    }
+
+    Ok((header, decoded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    const MESSAGES: [(&str, &str); 29] = [
+        (
+            "RegistrationRequest",
+            "7e004179000d0109f107000000000000000010c11001032e04f0f0f0f02f0201015209f1070000011707f0f0c0c00010204002200050022000b12b01011801015101007100037e00436a01213b0121",
+        ),
+        (
+            "RegistrationAccept",
+            "7e0042010977000bf209f1070200410000000154070009f10700000115020101210200005e010616012c",
+        ),
+        ("RegistrationComplete", "7e0043"),
+        ("RegistrationReject", "7e0044035f010a"),
+        (
+            "DeregistrationRequestFromUe",
+            "7e004529000bf209f10702004100000001",
+        ),
+        ("DeregistrationAcceptFromUe", "7e0046"),
+        ("DeregistrationRequestToUe", "7e0047055816"),
+        ("DeregistrationAcceptToUe", "7e0048"),
+        (
+            "ServiceRequest",
+            "7e004c100007f40041000000014002200050022000",
+        ),
+        ("ServiceReject", "7e004d0950020000"),
+        ("ServiceAccept", "7e004e5002200026020000"),
+        (
+            "ConfigurationUpdateCommand",
+            "7e0054d1430580c1f41a0146004722103151520400490100",
+        ),
+        ("ConfigurationUpdateComplete", "7e0055"),
+        (
+            "AuthenticationRequest",
+            "7e0056000200002123553cbe9637a89d218ae64dae47bf35201055f328b43577b9b94a9ffac354dfafb3",
+        ),
+        (
+            "AuthenticationResponse",
+            "7e00572d10a54211d5e3ba50bfa54211d5e3ba50bf",
+        ),
+        ("AuthenticationReject", "7e0058"),
+        (
+            "AuthenticationFailure",
+            "7e005915300e0102030405060708090a0b0c0d0e",
+        ),
+        ("AuthenticationResult", "7e005a0000040301000438020000"),
+        ("IdentityRequest", "7e005b01"),
+        ("IdentityResponse", "7e005c000d0109f107f0ff00000000000010"),
+        ("IdentityResponse", "7e005c00094573806121856151f1"),
+        (
+            "SecurityModeCommand",
+            "7e005d020104f0f0f0f0e136010138020000",
+        ),
+        ("SecurityModeComplete", "7e005e7100037e0043"),
+        ("SecurityModeReject", "7e005f18"),
+        ("FivegmmStatus", "7e006462"),
+        ("Notification", "7e006501"),
+        ("NotificationResponse", "7e006650020000"),
+        (
+            "UlNasTransport",
+            "7e00670100042e0101c1120181220101250908696e7465726e6574",
+        ),
+        ("DlNasTransport", "7e00680100032e01021201581b370121"),
+    ];
+
+    #[test]
+    fn roundtrip_5gmm_messages() {
+        for (name, message) in MESSAGES {
+            let data = hex::decode(message).unwrap();
+
+            let result = Nas5gMmMessage::decode(&data);
+            assert!(result.is_ok(), "{}: {:?}", name, result.err());
+
+            let decoded = result.unwrap();
+            assert!(format!("{:?}", decoded).starts_with(name), "{}", name);
+            assert_eq!(decoded.encode(), data, "{}", name);
+        }
+    }
+
+    #[test]
+    fn decode_skips_unknown_ies() {
+        // Authentication Request with an unknown TLV IE, an unknown TLV-E IE and an unknown Type 1
+        // IE before the RAND.
+        let data =
+            hex::decode("7e00560002000066020102700003010203c02123553cbe9637a89d218ae64dae47bf35")
+                .unwrap();
+
+        let message = Nas5gMmMessage::decode(&data);
+        assert!(message.is_ok(), "{:?}", message.err());
+
+        let Nas5gMmMessage::AuthenticationRequest(request) = message.unwrap() else {
+            panic!("Not an Authentication Request");
+        };
+        assert!(request.rand.is_some());
+        assert!(request.autn.is_none());
+    }
+
+    #[test]
+    fn decode_truncated_messages() {
+        // Decoding any prefix of a message must not panic.
+        for (_, message) in MESSAGES {
+            let data = hex::decode(message).unwrap();
+            for length in 0..data.len() {
+                let _ = Nas5gMmMessage::decode(&data[..length]);
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn decode_arbitrary_messages(
+            message_type in MM_MSG_TYPE_REGISTRATION_REQUEST..=MM_MSG_TYPE_DL_NAS_TRANSPORT,
+            body in proptest::collection::vec(any::<u8>(), 0..64)
+        ) {
+            let mut data = vec![0x7e, 0x00, message_type];
+            data.extend(body);
+
+            let _ = Nas5gMmMessage::decode(&data);
+        }
+    }
+
+    #[test]
+    fn decode_errors() {
+        // Unknown message type
+        assert_eq!(
+            Nas5gMmMessage::decode(&[0x7e, 0x00, 0x40]),
+            Err(NasError::UnknownMessageType(0x40))
+        );
+
+        // Unknown Extended Protocol Discriminator
+        assert_eq!(
+            Nas5gMmMessage::decode(&[0x7f, 0x00, 0x41]),
+            Err(NasError::UnknownProtocolDiscriminator(0x7f))
+        );
+
+        // Unknown Security Header Type
+        assert_eq!(
+            Nas5gMmMessage::decode(&[0x7e, 0x05, 0x41]),
+            Err(NasError::UnknownSecurityHeaderType(5))
+        );
+
+        // Unknown 'comprehension required' IE
+        assert_eq!(
+            Nas5gMmMessage::decode(&[0x7e, 0x00, 0x57, 0x0f, 0x00]),
+            Err(NasError::InvalidIei(0x0f))
+        );
+
+        // Unexpected message type
+        assert_eq!(
+            AuthenticationResponse::decode(&[0x7e, 0x00, 0x58]).map(|_| ()),
+            Err(NasError::UnexpectedMessageType {
+                expected: 0x57,
+                received: 0x58
+            })
+        );
+
+        // Truncated message and IE
+        assert_eq!(
+            Nas5gMmMessage::decode(&[0x7e, 0x00]),
+            Err(NasError::Truncated {
+                expected: 3,
+                available: 2
+            })
+        );
+        assert_eq!(
+            Nas5gMmMessage::decode(&[0x7e, 0x00, 0x57, 0x2d, 0x10, 0x00]).map_err(|e| e.cause()),
+            Err(96)
+        );
+    }
+}
@@ -0,0 +1,64 @@
+//! 5G MM Authentication Failure
+
+use crate::messages::{
+    headers::Nas5gMmMessageHeader,
+    ies::{optional_iei, skip_unknown_ie, AuthenticationFailureParameter, FivegmmCause},
+};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_AUTHENTICATION_FAILURE};
+
+/// Authentication Failure: 24.501 (Release 17) Section 8.2.4
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AuthenticationFailure {
+    pub header: Nas5gMmMessageHeader,
+    pub cause: FivegmmCause,
+    pub failure_parameter: Option<AuthenticationFailureParameter>,
+}
+
+impl AuthenticationFailure {
+    pub const FAILURE_PARAMETER_IEI: u8 = 0x30;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+        output.extend(self.cause.encode(false));
+
+        if let Some(failure_parameter) = &self.failure_parameter {
+            output.extend(failure_parameter.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("AuthenticationFailure decode.");
+        let (header, mut decoded) = decode_header(data, MM_MSG_TYPE_AUTHENTICATION_FAILURE)?;
+
+        let (cause, cause_decoded) = FivegmmCause::decode(&data[decoded..], false)?;
+        decoded += cause_decoded;
+
+        let mut failure_parameter = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::FAILURE_PARAMETER_IEI => {
+                    let (ie, ie_decoded) =
+                        AuthenticationFailureParameter::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    failure_parameter = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                cause,
+                failure_parameter,
+            },
+            decoded,
+        ))
+    }
+}
@@ -0,0 +1,57 @@
+//! 5G MM Authentication Reject
+
+use crate::messages::{
+    headers::Nas5gMmMessageHeader,
+    ies::{optional_iei, skip_unknown_ie, EapMessage},
+};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_AUTHENTICATION_REJECT};
+
+/// Authentication Reject: 24.501 (Release 17) Section 8.2.5
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AuthenticationReject {
+    pub header: Nas5gMmMessageHeader,
+    pub eap_message: Option<EapMessage>,
+}
+
+impl AuthenticationReject {
+    pub const EAP_MESSAGE_IEI: u8 = 0x78;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+
+        if let Some(eap_message) = &self.eap_message {
+            output.extend(eap_message.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("AuthenticationReject decode.");
+        let (header, mut decoded) = decode_header(data, MM_MSG_TYPE_AUTHENTICATION_REJECT)?;
+
+        let mut eap_message = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::EAP_MESSAGE_IEI => {
+                    let (ie, ie_decoded) = EapMessage::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    eap_message = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                eap_message,
+            },
+            decoded,
+        ))
+    }
+}
@@ -0,0 +1,100 @@
+//! 5G MM Authentication Request
+
+use crate::messages::{
+    headers::Nas5gMmMessageHeader,
+    ies::{
+        optional_iei, skip_unknown_ie, Abba, AuthenticationParameterAutn,
+        AuthenticationParameterRand, EapMessage, NasKeySetId,
+    },
+};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_AUTHENTICATION_REQUEST};
+
+/// Authentication Request: 24.501 (Release 17) Section 8.2.1
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AuthenticationRequest {
+    pub header: Nas5gMmMessageHeader,
+    pub ngksi: NasKeySetId,
+    pub abba: Abba,
+    pub rand: Option<AuthenticationParameterRand>,
+    pub autn: Option<AuthenticationParameterAutn>,
+    pub eap_message: Option<EapMessage>,
+}
+
+impl AuthenticationRequest {
+    pub const RAND_IEI: u8 = 0x21;
+    pub const AUTN_IEI: u8 = 0x20;
+    pub const EAP_MESSAGE_IEI: u8 = 0x78;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+        // Upper half octet is spare.
+        output.extend(self.ngksi.encode(false, false));
+        output.extend(self.abba.encode(false));
+
+        if let Some(rand) = &self.rand {
+            output.extend(rand.encode(true));
+        }
+        if let Some(autn) = &self.autn {
+            output.extend(autn.encode(true));
+        }
+        if let Some(eap_message) = &self.eap_message {
+            output.extend(eap_message.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("AuthenticationRequest decode.");
+        let (header, mut decoded) = decode_header(data, MM_MSG_TYPE_AUTHENTICATION_REQUEST)?;
+
+        let (ngksi, _) = NasKeySetId::decode(&data[decoded..], false, false)?;
+        // Upper half octet is spare.
+        decoded += 1;
+
+        let (abba, abba_decoded) = Abba::decode(&data[decoded..], false)?;
+        decoded += abba_decoded;
+
+        let mut rand = None;
+        let mut autn = None;
+        let mut eap_message = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::RAND_IEI => {
+                    let (ie, ie_decoded) =
+                        AuthenticationParameterRand::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    rand = Some(ie);
+                }
+                Self::AUTN_IEI => {
+                    let (ie, ie_decoded) =
+                        AuthenticationParameterAutn::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    autn = Some(ie);
+                }
+                Self::EAP_MESSAGE_IEI => {
+                    let (ie, ie_decoded) = EapMessage::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    eap_message = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                ngksi,
+                abba,
+                rand,
+                autn,
+                eap_message,
+            },
+            decoded,
+        ))
+    }
+}
@@ -0,0 +1,70 @@
+//! 5G MM Authentication Response
+
+use crate::messages::{
+    headers::Nas5gMmMessageHeader,
+    ies::{optional_iei, skip_unknown_ie, AuthenticationResponseParameter, EapMessage},
+};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_AUTHENTICATION_RESPONSE};
+
+/// Authentication Response: 24.501 (Release 17) Section 8.2.2
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AuthenticationResponse {
+    pub header: Nas5gMmMessageHeader,
+    pub response_parameter: Option<AuthenticationResponseParameter>,
+    pub eap_message: Option<EapMessage>,
+}
+
+impl AuthenticationResponse {
+    pub const RESPONSE_PARAMETER_IEI: u8 = 0x2D;
+    pub const EAP_MESSAGE_IEI: u8 = 0x78;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+
+        if let Some(response_parameter) = &self.response_parameter {
+            output.extend(response_parameter.encode(true));
+        }
+        if let Some(eap_message) = &self.eap_message {
+            output.extend(eap_message.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("AuthenticationResponse decode.");
+        let (header, mut decoded) = decode_header(data, MM_MSG_TYPE_AUTHENTICATION_RESPONSE)?;
+
+        let mut response_parameter = None;
+        let mut eap_message = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::RESPONSE_PARAMETER_IEI => {
+                    let (ie, ie_decoded) =
+                        AuthenticationResponseParameter::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    response_parameter = Some(ie);
+                }
+                Self::EAP_MESSAGE_IEI => {
+                    let (ie, ie_decoded) = EapMessage::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    eap_message = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                response_parameter,
+                eap_message,
+            },
+            decoded,
+        ))
+    }
+}
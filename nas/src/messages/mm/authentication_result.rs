@@ -0,0 +1,71 @@
+//! 5G MM Authentication Result
+
+use crate::messages::{
+    headers::Nas5gMmMessageHeader,
+    ies::{optional_iei, skip_unknown_ie, Abba, EapMessage, NasKeySetId},
+};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_AUTHENTICATION_RESULT};
+
+/// Authentication Result: 24.501 (Release 17) Section 8.2.3
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AuthenticationResult {
+    pub header: Nas5gMmMessageHeader,
+    pub ngksi: NasKeySetId,
+    pub eap_message: EapMessage,
+    pub abba: Option<Abba>,
+}
+
+impl AuthenticationResult {
+    pub const ABBA_IEI: u8 = 0x38;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+        // Upper half octet is spare.
+        output.extend(self.ngksi.encode(false, false));
+        output.extend(self.eap_message.encode(false));
+
+        if let Some(abba) = &self.abba {
+            output.extend(abba.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("AuthenticationResult decode.");
+        let (header, mut decoded) = decode_header(data, MM_MSG_TYPE_AUTHENTICATION_RESULT)?;
+
+        let (ngksi, _) = NasKeySetId::decode(&data[decoded..], false, false)?;
+        // Upper half octet is spare.
+        decoded += 1;
+
+        let (eap_message, eap_message_decoded) = EapMessage::decode(&data[decoded..], false)?;
+        decoded += eap_message_decoded;
+
+        let mut abba = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::ABBA_IEI => {
+                    let (ie, ie_decoded) = Abba::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    abba = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                ngksi,
+                eap_message,
+                abba,
+            },
+            decoded,
+        ))
+    }
+}
@@ -0,0 +1,247 @@
+//! 5G MM Configuration Update Command
+
+use crate::messages::{
+    headers::Nas5gMmMessageHeader,
+    ies::{
+        optional_iei, skip_unknown_ie, ConfigurationUpdateIndication, DaylightSavingTime,
+        FivegsMobileIdentity, LadnInformation, MicoIndication, NetworkName,
+        NetworkSlicingIndication, Nssai, OperatorDefinedAccessCategoryDefinitions, RejectedNssai,
+        ServiceAreaList, TaiList, TimeZone, TimeZoneAndTime,
+    },
+};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_CONFIGURATION_UPDATE_COMMAND};
+
+/// Configuration Update Command: 24.501 (Release 17) Section 8.2.19
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ConfigurationUpdateCommand {
+    pub header: Nas5gMmMessageHeader,
+    pub configuration_update_indication: Option<ConfigurationUpdateIndication>,
+    pub guti: Option<FivegsMobileIdentity>,
+    pub tai_list: Option<TaiList>,
+    pub allowed_nssai: Option<Nssai>,
+    pub service_area_list: Option<ServiceAreaList>,
+    pub full_name_for_network: Option<NetworkName>,
+    pub short_name_for_network: Option<NetworkName>,
+    pub local_time_zone: Option<TimeZone>,
+    pub universal_time_and_local_time_zone: Option<TimeZoneAndTime>,
+    pub network_daylight_saving_time: Option<DaylightSavingTime>,
+    pub ladn_information: Option<LadnInformation>,
+    pub mico_indication: Option<MicoIndication>,
+    pub network_slicing_indication: Option<NetworkSlicingIndication>,
+    pub configured_nssai: Option<Nssai>,
+    pub rejected_nssai: Option<RejectedNssai>,
+    pub operator_defined_access_category_definitions:
+        Option<OperatorDefinedAccessCategoryDefinitions>,
+}
+
+impl ConfigurationUpdateCommand {
+    pub const CONFIGURATION_UPDATE_INDICATION_IEI: u8 = 0x0D;
+    pub const GUTI_IEI: u8 = 0x77;
+    pub const TAI_LIST_IEI: u8 = 0x54;
+    pub const ALLOWED_NSSAI_IEI: u8 = 0x15;
+    pub const SERVICE_AREA_LIST_IEI: u8 = 0x27;
+    pub const FULL_NAME_FOR_NETWORK_IEI: u8 = 0x43;
+    pub const SHORT_NAME_FOR_NETWORK_IEI: u8 = 0x45;
+    pub const LOCAL_TIME_ZONE_IEI: u8 = 0x46;
+    pub const UNIVERSAL_TIME_AND_LOCAL_TIME_ZONE_IEI: u8 = 0x47;
+    pub const NETWORK_DAYLIGHT_SAVING_TIME_IEI: u8 = 0x49;
+    pub const LADN_INFORMATION_IEI: u8 = 0x79;
+    pub const MICO_INDICATION_IEI: u8 = 0x0B;
+    pub const NETWORK_SLICING_INDICATION_IEI: u8 = 0x09;
+    pub const CONFIGURED_NSSAI_IEI: u8 = 0x31;
+    pub const REJECTED_NSSAI_IEI: u8 = 0x11;
+    pub const OPERATOR_DEFINED_ACCESS_CATEGORY_DEFINITIONS_IEI: u8 = 0x76;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+
+        if let Some(configuration_update_indication) = &self.configuration_update_indication {
+            output.extend(configuration_update_indication.encode(true, false));
+        }
+        if let Some(guti) = &self.guti {
+            output.extend(guti.encode(true));
+        }
+        if let Some(tai_list) = &self.tai_list {
+            output.extend(tai_list.encode(true));
+        }
+        if let Some(allowed_nssai) = &self.allowed_nssai {
+            output.extend(allowed_nssai.encode(true));
+        }
+        if let Some(service_area_list) = &self.service_area_list {
+            output.extend(service_area_list.encode(true));
+        }
+        if let Some(full_name_for_network) = &self.full_name_for_network {
+            output.extend(full_name_for_network.encode(true));
+        }
+        if let Some(short_name_for_network) = &self.short_name_for_network {
+            output.extend(short_name_for_network.encode(true));
+        }
+        if let Some(local_time_zone) = &self.local_time_zone {
+            output.extend(local_time_zone.encode(true));
+        }
+        if let Some(universal_time_and_local_time_zone) = &self.universal_time_and_local_time_zone {
+            output.extend(universal_time_and_local_time_zone.encode(true));
+        }
+        if let Some(network_daylight_saving_time) = &self.network_daylight_saving_time {
+            output.extend(network_daylight_saving_time.encode(true));
+        }
+        if let Some(ladn_information) = &self.ladn_information {
+            output.extend(ladn_information.encode(true));
+        }
+        if let Some(mico_indication) = &self.mico_indication {
+            output.extend(mico_indication.encode(true, false));
+        }
+        if let Some(network_slicing_indication) = &self.network_slicing_indication {
+            output.extend(network_slicing_indication.encode(true, false));
+        }
+        if let Some(configured_nssai) = &self.configured_nssai {
+            output.extend(configured_nssai.encode(true));
+        }
+        if let Some(rejected_nssai) = &self.rejected_nssai {
+            output.extend(rejected_nssai.encode(true));
+        }
+        if let Some(operator_defined_access_category_definitions) =
+            &self.operator_defined_access_category_definitions
+        {
+            output.extend(operator_defined_access_category_definitions.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("ConfigurationUpdateCommand decode.");
+        let (header, mut decoded) = decode_header(data, MM_MSG_TYPE_CONFIGURATION_UPDATE_COMMAND)?;
+
+        let mut configuration_update_indication = None;
+        let mut guti = None;
+        let mut tai_list = None;
+        let mut allowed_nssai = None;
+        let mut service_area_list = None;
+        let mut full_name_for_network = None;
+        let mut short_name_for_network = None;
+        let mut local_time_zone = None;
+        let mut universal_time_and_local_time_zone = None;
+        let mut network_daylight_saving_time = None;
+        let mut ladn_information = None;
+        let mut mico_indication = None;
+        let mut network_slicing_indication = None;
+        let mut configured_nssai = None;
+        let mut rejected_nssai = None;
+        let mut operator_defined_access_category_definitions = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::CONFIGURATION_UPDATE_INDICATION_IEI => {
+                    let (ie, _) =
+                        ConfigurationUpdateIndication::decode(&data[decoded..], true, false)?;
+                    decoded += 1;
+                    configuration_update_indication = Some(ie);
+                }
+                Self::GUTI_IEI => {
+                    let (ie, ie_decoded) = FivegsMobileIdentity::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    guti = Some(ie);
+                }
+                Self::TAI_LIST_IEI => {
+                    let (ie, ie_decoded) = TaiList::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    tai_list = Some(ie);
+                }
+                Self::ALLOWED_NSSAI_IEI => {
+                    let (ie, ie_decoded) = Nssai::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    allowed_nssai = Some(ie);
+                }
+                Self::SERVICE_AREA_LIST_IEI => {
+                    let (ie, ie_decoded) = ServiceAreaList::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    service_area_list = Some(ie);
+                }
+                Self::FULL_NAME_FOR_NETWORK_IEI => {
+                    let (ie, ie_decoded) = NetworkName::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    full_name_for_network = Some(ie);
+                }
+                Self::SHORT_NAME_FOR_NETWORK_IEI => {
+                    let (ie, ie_decoded) = NetworkName::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    short_name_for_network = Some(ie);
+                }
+                Self::LOCAL_TIME_ZONE_IEI => {
+                    let (ie, ie_decoded) = TimeZone::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    local_time_zone = Some(ie);
+                }
+                Self::UNIVERSAL_TIME_AND_LOCAL_TIME_ZONE_IEI => {
+                    let (ie, ie_decoded) = TimeZoneAndTime::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    universal_time_and_local_time_zone = Some(ie);
+                }
+                Self::NETWORK_DAYLIGHT_SAVING_TIME_IEI => {
+                    let (ie, ie_decoded) = DaylightSavingTime::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    network_daylight_saving_time = Some(ie);
+                }
+                Self::LADN_INFORMATION_IEI => {
+                    let (ie, ie_decoded) = LadnInformation::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    ladn_information = Some(ie);
+                }
+                Self::MICO_INDICATION_IEI => {
+                    let (ie, _) = MicoIndication::decode(&data[decoded..], true, false)?;
+                    decoded += 1;
+                    mico_indication = Some(ie);
+                }
+                Self::NETWORK_SLICING_INDICATION_IEI => {
+                    let (ie, _) = NetworkSlicingIndication::decode(&data[decoded..], true, false)?;
+                    decoded += 1;
+                    network_slicing_indication = Some(ie);
+                }
+                Self::CONFIGURED_NSSAI_IEI => {
+                    let (ie, ie_decoded) = Nssai::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    configured_nssai = Some(ie);
+                }
+                Self::REJECTED_NSSAI_IEI => {
+                    let (ie, ie_decoded) = RejectedNssai::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    rejected_nssai = Some(ie);
+                }
+                Self::OPERATOR_DEFINED_ACCESS_CATEGORY_DEFINITIONS_IEI => {
+                    let (ie, ie_decoded) =
+                        OperatorDefinedAccessCategoryDefinitions::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    operator_defined_access_category_definitions = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                configuration_update_indication,
+                guti,
+                tai_list,
+                allowed_nssai,
+                service_area_list,
+                full_name_for_network,
+                short_name_for_network,
+                local_time_zone,
+                universal_time_and_local_time_zone,
+                network_daylight_saving_time,
+                ladn_information,
+                mico_indication,
+                network_slicing_indication,
+                configured_nssai,
+                rejected_nssai,
+                operator_defined_access_category_definitions,
+            },
+            decoded,
+        ))
+    }
+}
@@ -0,0 +1,29 @@
+//! 5G MM Configuration Update Complete
+
+use crate::messages::headers::Nas5gMmMessageHeader;
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_CONFIGURATION_UPDATE_COMPLETE};
+
+/// Configuration Update Complete: 24.501 (Release 17) Section 8.2.20
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ConfigurationUpdateComplete {
+    pub header: Nas5gMmMessageHeader,
+}
+
+impl ConfigurationUpdateComplete {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("ConfigurationUpdateComplete decode.");
+        let (header, decoded) = decode_header(data, MM_MSG_TYPE_CONFIGURATION_UPDATE_COMPLETE)?;
+
+        Ok((Self { header }, decoded))
+    }
+}
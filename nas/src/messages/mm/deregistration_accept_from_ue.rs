@@ -0,0 +1,29 @@
+//! 5G MM De-registration Accept (UE originating de-registration)
+
+use crate::messages::headers::Nas5gMmMessageHeader;
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_DEREGISTRATION_ACCEPT_FROM_UE};
+
+/// De-registration Accept (UE originating de-registration): 24.501 (Release 17) Section 8.2.13
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DeregistrationAcceptFromUe {
+    pub header: Nas5gMmMessageHeader,
+}
+
+impl DeregistrationAcceptFromUe {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("DeregistrationAcceptFromUe decode.");
+        let (header, decoded) = decode_header(data, MM_MSG_TYPE_DEREGISTRATION_ACCEPT_FROM_UE)?;
+
+        Ok((Self { header }, decoded))
+    }
+}
@@ -0,0 +1,56 @@
+//! 5G MM De-registration Request (UE originating de-registration)
+
+use crate::messages::{
+    headers::Nas5gMmMessageHeader,
+    ies::{DeregistrationType, FivegsMobileIdentity, NasKeySetId},
+};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_DEREGISTRATION_REQUEST_FROM_UE};
+
+/// De-registration Request (UE originating de-registration): 24.501 (Release 17) Section 8.2.12
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DeregistrationRequestFromUe {
+    pub header: Nas5gMmMessageHeader,
+    pub deregistration_type: DeregistrationType,
+    pub ngksi: NasKeySetId,
+    pub identity: FivegsMobileIdentity,
+}
+
+impl DeregistrationRequestFromUe {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+        output.push(
+            self.deregistration_type.encode(false, false)[0] | self.ngksi.encode(false, true)[0],
+        );
+        output.extend(self.identity.encode(false));
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("DeregistrationRequestFromUe decode.");
+        let (header, mut decoded) =
+            decode_header(data, MM_MSG_TYPE_DEREGISTRATION_REQUEST_FROM_UE)?;
+
+        let (deregistration_type, _) = DeregistrationType::decode(&data[decoded..], false, false)?;
+        let (ngksi, _) = NasKeySetId::decode(&data[decoded..], false, true)?;
+        // Both the above share an octet.
+        decoded += 1;
+
+        let (identity, identity_decoded) = FivegsMobileIdentity::decode(&data[decoded..], false)?;
+        decoded += identity_decoded;
+
+        Ok((
+            Self {
+                header,
+                deregistration_type,
+                ngksi,
+                identity,
+            },
+            decoded,
+        ))
+    }
+}
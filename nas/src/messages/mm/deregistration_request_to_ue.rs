@@ -0,0 +1,91 @@
+//! 5G MM De-registration Request (UE terminated de-registration)
+
+use crate::messages::{
+    headers::Nas5gMmMessageHeader,
+    ies::{
+        optional_iei, skip_unknown_ie, DeregistrationType, FivegmmCause, GprsTimer2, RejectedNssai,
+    },
+};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_DEREGISTRATION_REQUEST_TO_UE};
+
+/// De-registration Request (UE terminated de-registration): 24.501 (Release 17) Section 8.2.14
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DeregistrationRequestToUe {
+    pub header: Nas5gMmMessageHeader,
+    pub deregistration_type: DeregistrationType,
+    pub cause: Option<FivegmmCause>,
+    pub t3346: Option<GprsTimer2>,
+    pub rejected_nssai: Option<RejectedNssai>,
+}
+
+impl DeregistrationRequestToUe {
+    pub const CAUSE_IEI: u8 = 0x58;
+    pub const T3346_IEI: u8 = 0x5F;
+    pub const REJECTED_NSSAI_IEI: u8 = 0x6D;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+        // Upper half octet is spare.
+        output.extend(self.deregistration_type.encode(false, false));
+
+        if let Some(cause) = &self.cause {
+            output.extend(cause.encode(true));
+        }
+        if let Some(t3346) = &self.t3346 {
+            output.extend(t3346.encode(true));
+        }
+        if let Some(rejected_nssai) = &self.rejected_nssai {
+            output.extend(rejected_nssai.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("DeregistrationRequestToUe decode.");
+        let (header, mut decoded) = decode_header(data, MM_MSG_TYPE_DEREGISTRATION_REQUEST_TO_UE)?;
+
+        let (deregistration_type, _) = DeregistrationType::decode(&data[decoded..], false, false)?;
+        // Upper half octet is spare.
+        decoded += 1;
+
+        let mut cause = None;
+        let mut t3346 = None;
+        let mut rejected_nssai = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::CAUSE_IEI => {
+                    let (ie, ie_decoded) = FivegmmCause::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    cause = Some(ie);
+                }
+                Self::T3346_IEI => {
+                    let (ie, ie_decoded) = GprsTimer2::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    t3346 = Some(ie);
+                }
+                Self::REJECTED_NSSAI_IEI => {
+                    let (ie, ie_decoded) = RejectedNssai::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    rejected_nssai = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                deregistration_type,
+                cause,
+                t3346,
+                rejected_nssai,
+            },
+            decoded,
+        ))
+    }
+}
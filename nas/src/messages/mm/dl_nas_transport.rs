@@ -0,0 +1,112 @@
+//! 5G MM DL NAS Transport
+
+use crate::messages::{
+    headers::Nas5gMmMessageHeader,
+    ies::{
+        optional_iei, skip_unknown_ie, AdditionalInformation, FivegmmCause, GprsTimer3,
+        PayloadContainer, PayloadContainerType, PduSessionIdentity2,
+    },
+};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_DL_NAS_TRANSPORT};
+
+/// DL NAS Transport: 24.501 (Release 17) Section 8.2.11
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DlNasTransport {
+    pub header: Nas5gMmMessageHeader,
+    pub payload_container_type: PayloadContainerType,
+    pub payload_container: PayloadContainer,
+    pub pdu_session_id: Option<PduSessionIdentity2>,
+    pub additional_information: Option<AdditionalInformation>,
+    pub cause: Option<FivegmmCause>,
+    pub back_off_timer: Option<GprsTimer3>,
+}
+
+impl DlNasTransport {
+    pub const PDU_SESSION_ID_IEI: u8 = 0x12;
+    pub const ADDITIONAL_INFORMATION_IEI: u8 = 0x24;
+    pub const CAUSE_IEI: u8 = 0x58;
+    pub const BACK_OFF_TIMER_IEI: u8 = 0x37;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+        // Upper half octet is spare.
+        output.extend(self.payload_container_type.encode(false, false));
+        output.extend(self.payload_container.encode(false));
+
+        if let Some(pdu_session_id) = &self.pdu_session_id {
+            output.extend(pdu_session_id.encode(true));
+        }
+        if let Some(additional_information) = &self.additional_information {
+            output.extend(additional_information.encode(true));
+        }
+        if let Some(cause) = &self.cause {
+            output.extend(cause.encode(true));
+        }
+        if let Some(back_off_timer) = &self.back_off_timer {
+            output.extend(back_off_timer.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("DlNasTransport decode.");
+        let (header, mut decoded) = decode_header(data, MM_MSG_TYPE_DL_NAS_TRANSPORT)?;
+
+        let (payload_container_type, _) =
+            PayloadContainerType::decode(&data[decoded..], false, false)?;
+        // Upper half octet is spare.
+        decoded += 1;
+
+        let (payload_container, payload_container_decoded) =
+            PayloadContainer::decode(&data[decoded..], false)?;
+        decoded += payload_container_decoded;
+
+        let mut pdu_session_id = None;
+        let mut additional_information = None;
+        let mut cause = None;
+        let mut back_off_timer = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::PDU_SESSION_ID_IEI => {
+                    let (ie, ie_decoded) = PduSessionIdentity2::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    pdu_session_id = Some(ie);
+                }
+                Self::ADDITIONAL_INFORMATION_IEI => {
+                    let (ie, ie_decoded) = AdditionalInformation::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    additional_information = Some(ie);
+                }
+                Self::CAUSE_IEI => {
+                    let (ie, ie_decoded) = FivegmmCause::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    cause = Some(ie);
+                }
+                Self::BACK_OFF_TIMER_IEI => {
+                    let (ie, ie_decoded) = GprsTimer3::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    back_off_timer = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                payload_container_type,
+                payload_container,
+                pdu_session_id,
+                additional_information,
+                cause,
+                back_off_timer,
+            },
+            decoded,
+        ))
+    }
+}
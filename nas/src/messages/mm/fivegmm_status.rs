@@ -0,0 +1,34 @@
+//! 5G MM 5GMM Status
+
+use crate::messages::{headers::Nas5gMmMessageHeader, ies::FivegmmCause};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_FIVEGMM_STATUS};
+
+/// 5GMM Status: 24.501 (Release 17) Section 8.2.29
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FivegmmStatus {
+    pub header: Nas5gMmMessageHeader,
+    pub cause: FivegmmCause,
+}
+
+impl FivegmmStatus {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+        output.extend(self.cause.encode(false));
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("FivegmmStatus decode.");
+        let (header, mut decoded) = decode_header(data, MM_MSG_TYPE_FIVEGMM_STATUS)?;
+
+        let (cause, cause_decoded) = FivegmmCause::decode(&data[decoded..], false)?;
+        decoded += cause_decoded;
+
+        Ok((Self { header, cause }, decoded))
+    }
+}
@@ -0,0 +1,42 @@
+//! 5G MM Identity Request
+
+use crate::messages::{headers::Nas5gMmMessageHeader, ies::IdentityType};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_IDENTITY_REQUEST};
+
+/// Identity Request: 24.501 (Release 17) Section 8.2.21
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct IdentityRequest {
+    pub header: Nas5gMmMessageHeader,
+    pub identity_type: IdentityType,
+}
+
+impl IdentityRequest {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+        // Upper half octet is spare.
+        output.extend(self.identity_type.encode(false, false));
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("IdentityRequest decode.");
+        let (header, mut decoded) = decode_header(data, MM_MSG_TYPE_IDENTITY_REQUEST)?;
+
+        let (identity_type, _) = IdentityType::decode(&data[decoded..], false, false)?;
+        // Upper half octet is spare.
+        decoded += 1;
+
+        Ok((
+            Self {
+                header,
+                identity_type,
+            },
+            decoded,
+        ))
+    }
+}
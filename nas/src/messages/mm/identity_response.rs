@@ -0,0 +1,34 @@
+//! 5G MM Identity Response
+
+use crate::messages::{headers::Nas5gMmMessageHeader, ies::FivegsMobileIdentity};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_IDENTITY_RESPONSE};
+
+/// Identity Response: 24.501 (Release 17) Section 8.2.22
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct IdentityResponse {
+    pub header: Nas5gMmMessageHeader,
+    pub identity: FivegsMobileIdentity,
+}
+
+impl IdentityResponse {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+        output.extend(self.identity.encode(false));
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("IdentityResponse decode.");
+        let (header, mut decoded) = decode_header(data, MM_MSG_TYPE_IDENTITY_RESPONSE)?;
+
+        let (identity, identity_decoded) = FivegsMobileIdentity::decode(&data[decoded..], false)?;
+        decoded += identity_decoded;
+
+        Ok((Self { header, identity }, decoded))
+    }
+}
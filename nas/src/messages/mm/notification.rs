@@ -0,0 +1,42 @@
+//! 5G MM Notification
+
+use crate::messages::{headers::Nas5gMmMessageHeader, ies::AccessType};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_NOTIFICATION};
+
+/// Notification: 24.501 (Release 17) Section 8.2.23
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Notification {
+    pub header: Nas5gMmMessageHeader,
+    pub access_type: AccessType,
+}
+
+impl Notification {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+        // Upper half octet is spare.
+        output.extend(self.access_type.encode(false, false));
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("Notification decode.");
+        let (header, mut decoded) = decode_header(data, MM_MSG_TYPE_NOTIFICATION)?;
+
+        let (access_type, _) = AccessType::decode(&data[decoded..], false, false)?;
+        // Upper half octet is spare.
+        decoded += 1;
+
+        Ok((
+            Self {
+                header,
+                access_type,
+            },
+            decoded,
+        ))
+    }
+}
@@ -0,0 +1,57 @@
+//! 5G MM Notification Response
+
+use crate::messages::{
+    headers::Nas5gMmMessageHeader,
+    ies::{optional_iei, skip_unknown_ie, PduSessionStatus},
+};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_NOTIFICATION_RESPONSE};
+
+/// Notification Response: 24.501 (Release 17) Section 8.2.24
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct NotificationResponse {
+    pub header: Nas5gMmMessageHeader,
+    pub pdu_session_status: Option<PduSessionStatus>,
+}
+
+impl NotificationResponse {
+    pub const PDU_SESSION_STATUS_IEI: u8 = 0x50;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+
+        if let Some(pdu_session_status) = &self.pdu_session_status {
+            output.extend(pdu_session_status.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("NotificationResponse decode.");
+        let (header, mut decoded) = decode_header(data, MM_MSG_TYPE_NOTIFICATION_RESPONSE)?;
+
+        let mut pdu_session_status = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::PDU_SESSION_STATUS_IEI => {
+                    let (ie, ie_decoded) = PduSessionStatus::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    pdu_session_status = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                pdu_session_status,
+            },
+            decoded,
+        ))
+    }
+}
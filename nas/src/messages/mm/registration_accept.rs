@@ -0,0 +1,346 @@
+//! 5G MM Registration Accept
+
+use crate::messages::{
+    headers::Nas5gMmMessageHeader,
+    ies::{
+        optional_iei, skip_unknown_ie, EapMessage, EmergencyNumberList, EquivalentPlmns,
+        ExtendedEmergencyNumberList, FivegsMobileIdentity, FivegsNetworkFeatureSupport,
+        FivegsRegistrationResult, GprsTimer2, GprsTimer3, LadnInformation, MicoIndication,
+        NetworkSlicingIndication, Nssai, NssaiInclusionMode,
+        OperatorDefinedAccessCategoryDefinitions, PduSessionReactivationResult,
+        PduSessionReactivationResultErrorCause, PduSessionStatus, RejectedNssai, ServiceAreaList,
+        SorTransparentContainer, TaiList,
+    },
+};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_REGISTRATION_ACCEPT};
+
+/// Registration Accept: 24.501 (Release 17) Section 8.2.7
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RegistrationAccept {
+    pub header: Nas5gMmMessageHeader,
+    pub registration_result: FivegsRegistrationResult,
+    pub guti: Option<FivegsMobileIdentity>,
+    pub equivalent_plmns: Option<EquivalentPlmns>,
+    pub tai_list: Option<TaiList>,
+    pub allowed_nssai: Option<Nssai>,
+    pub rejected_nssai: Option<RejectedNssai>,
+    pub configured_nssai: Option<Nssai>,
+    pub network_feature_support: Option<FivegsNetworkFeatureSupport>,
+    pub pdu_session_status: Option<PduSessionStatus>,
+    pub pdu_session_reactivation_result: Option<PduSessionReactivationResult>,
+    pub pdu_session_reactivation_result_error_cause: Option<PduSessionReactivationResultErrorCause>,
+    pub ladn_information: Option<LadnInformation>,
+    pub mico_indication: Option<MicoIndication>,
+    pub network_slicing_indication: Option<NetworkSlicingIndication>,
+    pub service_area_list: Option<ServiceAreaList>,
+    pub t3512: Option<GprsTimer3>,
+    pub non_3gpp_deregistration_timer: Option<GprsTimer2>,
+    pub t3502: Option<GprsTimer2>,
+    pub emergency_number_list: Option<EmergencyNumberList>,
+    pub extended_emergency_number_list: Option<ExtendedEmergencyNumberList>,
+    pub sor_transparent_container: Option<SorTransparentContainer>,
+    pub eap_message: Option<EapMessage>,
+    pub nssai_inclusion_mode: Option<NssaiInclusionMode>,
+    pub operator_defined_access_category_definitions:
+        Option<OperatorDefinedAccessCategoryDefinitions>,
+}
+
+impl RegistrationAccept {
+    pub const GUTI_IEI: u8 = 0x77;
+    pub const EQUIVALENT_PLMNS_IEI: u8 = 0x4A;
+    pub const TAI_LIST_IEI: u8 = 0x54;
+    pub const ALLOWED_NSSAI_IEI: u8 = 0x15;
+    pub const REJECTED_NSSAI_IEI: u8 = 0x11;
+    pub const CONFIGURED_NSSAI_IEI: u8 = 0x31;
+    pub const NETWORK_FEATURE_SUPPORT_IEI: u8 = 0x21;
+    pub const PDU_SESSION_STATUS_IEI: u8 = 0x50;
+    pub const PDU_SESSION_REACTIVATION_RESULT_IEI: u8 = 0x26;
+    pub const PDU_SESSION_REACTIVATION_RESULT_ERROR_CAUSE_IEI: u8 = 0x72;
+    pub const LADN_INFORMATION_IEI: u8 = 0x79;
+    pub const MICO_INDICATION_IEI: u8 = 0x0B;
+    pub const NETWORK_SLICING_INDICATION_IEI: u8 = 0x09;
+    pub const SERVICE_AREA_LIST_IEI: u8 = 0x27;
+    pub const T3512_IEI: u8 = 0x5E;
+    pub const NON_3GPP_DEREGISTRATION_TIMER_IEI: u8 = 0x5D;
+    pub const T3502_IEI: u8 = 0x16;
+    pub const EMERGENCY_NUMBER_LIST_IEI: u8 = 0x34;
+    pub const EXTENDED_EMERGENCY_NUMBER_LIST_IEI: u8 = 0x7A;
+    pub const SOR_TRANSPARENT_CONTAINER_IEI: u8 = 0x73;
+    pub const EAP_MESSAGE_IEI: u8 = 0x78;
+    pub const NSSAI_INCLUSION_MODE_IEI: u8 = 0x0A;
+    pub const OPERATOR_DEFINED_ACCESS_CATEGORY_DEFINITIONS_IEI: u8 = 0x76;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+        output.extend(self.registration_result.encode(false));
+
+        if let Some(guti) = &self.guti {
+            output.extend(guti.encode(true));
+        }
+        if let Some(equivalent_plmns) = &self.equivalent_plmns {
+            output.extend(equivalent_plmns.encode(true));
+        }
+        if let Some(tai_list) = &self.tai_list {
+            output.extend(tai_list.encode(true));
+        }
+        if let Some(allowed_nssai) = &self.allowed_nssai {
+            output.extend(allowed_nssai.encode(true));
+        }
+        if let Some(rejected_nssai) = &self.rejected_nssai {
+            output.extend(rejected_nssai.encode(true));
+        }
+        if let Some(configured_nssai) = &self.configured_nssai {
+            output.extend(configured_nssai.encode(true));
+        }
+        if let Some(network_feature_support) = &self.network_feature_support {
+            output.extend(network_feature_support.encode(true));
+        }
+        if let Some(pdu_session_status) = &self.pdu_session_status {
+            output.extend(pdu_session_status.encode(true));
+        }
+        if let Some(pdu_session_reactivation_result) = &self.pdu_session_reactivation_result {
+            output.extend(pdu_session_reactivation_result.encode(true));
+        }
+        if let Some(pdu_session_reactivation_result_error_cause) =
+            &self.pdu_session_reactivation_result_error_cause
+        {
+            output.extend(pdu_session_reactivation_result_error_cause.encode(true));
+        }
+        if let Some(ladn_information) = &self.ladn_information {
+            output.extend(ladn_information.encode(true));
+        }
+        if let Some(mico_indication) = &self.mico_indication {
+            output.extend(mico_indication.encode(true, false));
+        }
+        if let Some(network_slicing_indication) = &self.network_slicing_indication {
+            output.extend(network_slicing_indication.encode(true, false));
+        }
+        if let Some(service_area_list) = &self.service_area_list {
+            output.extend(service_area_list.encode(true));
+        }
+        if let Some(t3512) = &self.t3512 {
+            output.extend(t3512.encode(true));
+        }
+        if let Some(non_3gpp_deregistration_timer) = &self.non_3gpp_deregistration_timer {
+            output.extend(non_3gpp_deregistration_timer.encode(true));
+        }
+        if let Some(t3502) = &self.t3502 {
+            output.extend(t3502.encode(true));
+        }
+        if let Some(emergency_number_list) = &self.emergency_number_list {
+            output.extend(emergency_number_list.encode(true));
+        }
+        if let Some(extended_emergency_number_list) = &self.extended_emergency_number_list {
+            output.extend(extended_emergency_number_list.encode(true));
+        }
+        if let Some(sor_transparent_container) = &self.sor_transparent_container {
+            output.extend(sor_transparent_container.encode(true));
+        }
+        if let Some(eap_message) = &self.eap_message {
+            output.extend(eap_message.encode(true));
+        }
+        if let Some(nssai_inclusion_mode) = &self.nssai_inclusion_mode {
+            output.extend(nssai_inclusion_mode.encode(true, false));
+        }
+        if let Some(operator_defined_access_category_definitions) =
+            &self.operator_defined_access_category_definitions
+        {
+            output.extend(operator_defined_access_category_definitions.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("RegistrationAccept decode.");
+        let (header, mut decoded) = decode_header(data, MM_MSG_TYPE_REGISTRATION_ACCEPT)?;
+
+        let (registration_result, registration_result_decoded) =
+            FivegsRegistrationResult::decode(&data[decoded..], false)?;
+        decoded += registration_result_decoded;
+
+        let mut guti = None;
+        let mut equivalent_plmns = None;
+        let mut tai_list = None;
+        let mut allowed_nssai = None;
+        let mut rejected_nssai = None;
+        let mut configured_nssai = None;
+        let mut network_feature_support = None;
+        let mut pdu_session_status = None;
+        let mut pdu_session_reactivation_result = None;
+        let mut pdu_session_reactivation_result_error_cause = None;
+        let mut ladn_information = None;
+        let mut mico_indication = None;
+        let mut network_slicing_indication = None;
+        let mut service_area_list = None;
+        let mut t3512 = None;
+        let mut non_3gpp_deregistration_timer = None;
+        let mut t3502 = None;
+        let mut emergency_number_list = None;
+        let mut extended_emergency_number_list = None;
+        let mut sor_transparent_container = None;
+        let mut eap_message = None;
+        let mut nssai_inclusion_mode = None;
+        let mut operator_defined_access_category_definitions = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::GUTI_IEI => {
+                    let (ie, ie_decoded) = FivegsMobileIdentity::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    guti = Some(ie);
+                }
+                Self::EQUIVALENT_PLMNS_IEI => {
+                    let (ie, ie_decoded) = EquivalentPlmns::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    equivalent_plmns = Some(ie);
+                }
+                Self::TAI_LIST_IEI => {
+                    let (ie, ie_decoded) = TaiList::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    tai_list = Some(ie);
+                }
+                Self::ALLOWED_NSSAI_IEI => {
+                    let (ie, ie_decoded) = Nssai::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    allowed_nssai = Some(ie);
+                }
+                Self::REJECTED_NSSAI_IEI => {
+                    let (ie, ie_decoded) = RejectedNssai::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    rejected_nssai = Some(ie);
+                }
+                Self::CONFIGURED_NSSAI_IEI => {
+                    let (ie, ie_decoded) = Nssai::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    configured_nssai = Some(ie);
+                }
+                Self::NETWORK_FEATURE_SUPPORT_IEI => {
+                    let (ie, ie_decoded) =
+                        FivegsNetworkFeatureSupport::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    network_feature_support = Some(ie);
+                }
+                Self::PDU_SESSION_STATUS_IEI => {
+                    let (ie, ie_decoded) = PduSessionStatus::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    pdu_session_status = Some(ie);
+                }
+                Self::PDU_SESSION_REACTIVATION_RESULT_IEI => {
+                    let (ie, ie_decoded) =
+                        PduSessionReactivationResult::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    pdu_session_reactivation_result = Some(ie);
+                }
+                Self::PDU_SESSION_REACTIVATION_RESULT_ERROR_CAUSE_IEI => {
+                    let (ie, ie_decoded) =
+                        PduSessionReactivationResultErrorCause::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    pdu_session_reactivation_result_error_cause = Some(ie);
+                }
+                Self::LADN_INFORMATION_IEI => {
+                    let (ie, ie_decoded) = LadnInformation::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    ladn_information = Some(ie);
+                }
+                Self::MICO_INDICATION_IEI => {
+                    let (ie, _) = MicoIndication::decode(&data[decoded..], true, false)?;
+                    decoded += 1;
+                    mico_indication = Some(ie);
+                }
+                Self::NETWORK_SLICING_INDICATION_IEI => {
+                    let (ie, _) = NetworkSlicingIndication::decode(&data[decoded..], true, false)?;
+                    decoded += 1;
+                    network_slicing_indication = Some(ie);
+                }
+                Self::SERVICE_AREA_LIST_IEI => {
+                    let (ie, ie_decoded) = ServiceAreaList::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    service_area_list = Some(ie);
+                }
+                Self::T3512_IEI => {
+                    let (ie, ie_decoded) = GprsTimer3::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    t3512 = Some(ie);
+                }
+                Self::NON_3GPP_DEREGISTRATION_TIMER_IEI => {
+                    let (ie, ie_decoded) = GprsTimer2::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    non_3gpp_deregistration_timer = Some(ie);
+                }
+                Self::T3502_IEI => {
+                    let (ie, ie_decoded) = GprsTimer2::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    t3502 = Some(ie);
+                }
+                Self::EMERGENCY_NUMBER_LIST_IEI => {
+                    let (ie, ie_decoded) = EmergencyNumberList::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    emergency_number_list = Some(ie);
+                }
+                Self::EXTENDED_EMERGENCY_NUMBER_LIST_IEI => {
+                    let (ie, ie_decoded) =
+                        ExtendedEmergencyNumberList::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    extended_emergency_number_list = Some(ie);
+                }
+                Self::SOR_TRANSPARENT_CONTAINER_IEI => {
+                    let (ie, ie_decoded) = SorTransparentContainer::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    sor_transparent_container = Some(ie);
+                }
+                Self::EAP_MESSAGE_IEI => {
+                    let (ie, ie_decoded) = EapMessage::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    eap_message = Some(ie);
+                }
+                Self::NSSAI_INCLUSION_MODE_IEI => {
+                    let (ie, _) = NssaiInclusionMode::decode(&data[decoded..], true, false)?;
+                    decoded += 1;
+                    nssai_inclusion_mode = Some(ie);
+                }
+                Self::OPERATOR_DEFINED_ACCESS_CATEGORY_DEFINITIONS_IEI => {
+                    let (ie, ie_decoded) =
+                        OperatorDefinedAccessCategoryDefinitions::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    operator_defined_access_category_definitions = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                registration_result,
+                guti,
+                equivalent_plmns,
+                tai_list,
+                allowed_nssai,
+                rejected_nssai,
+                configured_nssai,
+                network_feature_support,
+                pdu_session_status,
+                pdu_session_reactivation_result,
+                pdu_session_reactivation_result_error_cause,
+                ladn_information,
+                mico_indication,
+                network_slicing_indication,
+                service_area_list,
+                t3512,
+                non_3gpp_deregistration_timer,
+                t3502,
+                emergency_number_list,
+                extended_emergency_number_list,
+                sor_transparent_container,
+                eap_message,
+                nssai_inclusion_mode,
+                operator_defined_access_category_definitions,
+            },
+            decoded,
+        ))
+    }
+}
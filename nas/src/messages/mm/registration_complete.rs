@@ -0,0 +1,57 @@
+//! 5G MM Registration Complete
+
+use crate::messages::{
+    headers::Nas5gMmMessageHeader,
+    ies::{optional_iei, skip_unknown_ie, SorTransparentContainer},
+};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_REGISTRATION_COMPLETE};
+
+/// Registration Complete: 24.501 (Release 17) Section 8.2.8
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RegistrationComplete {
+    pub header: Nas5gMmMessageHeader,
+    pub sor_transparent_container: Option<SorTransparentContainer>,
+}
+
+impl RegistrationComplete {
+    pub const SOR_TRANSPARENT_CONTAINER_IEI: u8 = 0x73;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+
+        if let Some(sor_transparent_container) = &self.sor_transparent_container {
+            output.extend(sor_transparent_container.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("RegistrationComplete decode.");
+        let (header, mut decoded) = decode_header(data, MM_MSG_TYPE_REGISTRATION_COMPLETE)?;
+
+        let mut sor_transparent_container = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::SOR_TRANSPARENT_CONTAINER_IEI => {
+                    let (ie, ie_decoded) = SorTransparentContainer::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    sor_transparent_container = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                sor_transparent_container,
+            },
+            decoded,
+        ))
+    }
+}
@@ -0,0 +1,99 @@
+//! 5G MM Registration Reject
+
+use crate::messages::{
+    headers::Nas5gMmMessageHeader,
+    ies::{optional_iei, skip_unknown_ie, EapMessage, FivegmmCause, GprsTimer2, RejectedNssai},
+};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_REGISTRATION_REJECT};
+
+/// Registration Reject: 24.501 (Release 17) Section 8.2.9
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RegistrationReject {
+    pub header: Nas5gMmMessageHeader,
+    pub cause: FivegmmCause,
+    pub t3346: Option<GprsTimer2>,
+    pub t3502: Option<GprsTimer2>,
+    pub eap_message: Option<EapMessage>,
+    pub rejected_nssai: Option<RejectedNssai>,
+}
+
+impl RegistrationReject {
+    pub const T3346_IEI: u8 = 0x5F;
+    pub const T3502_IEI: u8 = 0x16;
+    pub const EAP_MESSAGE_IEI: u8 = 0x78;
+    pub const REJECTED_NSSAI_IEI: u8 = 0x69;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+        output.extend(self.cause.encode(false));
+
+        if let Some(t3346) = &self.t3346 {
+            output.extend(t3346.encode(true));
+        }
+        if let Some(t3502) = &self.t3502 {
+            output.extend(t3502.encode(true));
+        }
+        if let Some(eap_message) = &self.eap_message {
+            output.extend(eap_message.encode(true));
+        }
+        if let Some(rejected_nssai) = &self.rejected_nssai {
+            output.extend(rejected_nssai.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("RegistrationReject decode.");
+        let (header, mut decoded) = decode_header(data, MM_MSG_TYPE_REGISTRATION_REJECT)?;
+
+        let (cause, cause_decoded) = FivegmmCause::decode(&data[decoded..], false)?;
+        decoded += cause_decoded;
+
+        let mut t3346 = None;
+        let mut t3502 = None;
+        let mut eap_message = None;
+        let mut rejected_nssai = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::T3346_IEI => {
+                    let (ie, ie_decoded) = GprsTimer2::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    t3346 = Some(ie);
+                }
+                Self::T3502_IEI => {
+                    let (ie, ie_decoded) = GprsTimer2::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    t3502 = Some(ie);
+                }
+                Self::EAP_MESSAGE_IEI => {
+                    let (ie, ie_decoded) = EapMessage::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    eap_message = Some(ie);
+                }
+                Self::REJECTED_NSSAI_IEI => {
+                    let (ie, ie_decoded) = RejectedNssai::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    rejected_nssai = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                cause,
+                t3346,
+                t3502,
+                eap_message,
+                rejected_nssai,
+            },
+            decoded,
+        ))
+    }
+}
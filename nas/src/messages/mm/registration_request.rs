@@ -2,40 +2,254 @@
 
 use crate::messages::{
     headers::Nas5gMmMessageHeader,
-    ies::{FivegRegistrationType, FivegsMobileIdentity, NasKeySetId, UeSecurityCapability},
+    ies::{
+        optional_iei, skip_unknown_ie, AdditionalInformationRequested, AllowedPduSessionStatus,
+        EpsBearerContextStatus, EpsNasMessageContainer, ExtendedDrxParameters,
+        FivegRegistrationType, FivegmmCapability, FivegsDrxParameters, FivegsMobileIdentity,
+        FivegsUpdateType, GprsTimer3, LadnIndication, MappedNssai, MicoIndication,
+        MobileStationClassmark2, N5gcIndication, NasKeySetId, NasMessageContainer,
+        NbN1ModeDrxParameters, NetworkSlicingIndication, Nid, Nssai, PagingRestriction,
+        PayloadContainer, PayloadContainerType, PduSessionStatus, PeipsAssistanceInformation,
+        PlmnIdentity, S1UeNetworkCapability, ServiceLevelAaContainer, SupportedCodecList, Tai,
+        UeRadioCapabilityId, UeRequestType, UeSecurityCapability, UeStatus, UesUsageSetting,
+        UplinkDataStatus, WusAssistanceInformation,
+    },
 };
+use crate::NasError;
 
-#[derive(Debug, Eq, PartialEq)]
+/// Registration Request: 24.501 (Release 17) Section 8.2.6
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct RegistrationRequest {
-    header: Nas5gMmMessageHeader,
-    req_type: FivegRegistrationType,
-    ngksi: NasKeySetId,
-    identity: FivegsMobileIdentity,
-    ue_sec_capability: Option<UeSecurityCapability>,
+    pub header: Nas5gMmMessageHeader,
+    pub req_type: FivegRegistrationType,
+    pub ngksi: NasKeySetId,
+    pub identity: FivegsMobileIdentity,
+    pub non_current_native_ngksi: Option<NasKeySetId>,
+    pub fivegmm_capability: Option<FivegmmCapability>,
+    pub ue_sec_capability: Option<UeSecurityCapability>,
+    pub requested_nssai: Option<Nssai>,
+    pub last_visited_registered_tai: Option<Tai>,
+    pub s1_ue_network_capability: Option<S1UeNetworkCapability>,
+    pub uplink_data_status: Option<UplinkDataStatus>,
+    pub pdu_session_status: Option<PduSessionStatus>,
+    pub mico_indication: Option<MicoIndication>,
+    pub ue_status: Option<UeStatus>,
+    pub additional_guti: Option<FivegsMobileIdentity>,
+    pub allowed_pdu_session_status: Option<AllowedPduSessionStatus>,
+    pub ues_usage_setting: Option<UesUsageSetting>,
+    pub requested_drx_parameters: Option<FivegsDrxParameters>,
+    pub eps_nas_message_container: Option<EpsNasMessageContainer>,
+    pub ladn_indication: Option<LadnIndication>,
+    pub payload_container_type: Option<PayloadContainerType>,
+    pub payload_container: Option<PayloadContainer>,
+    pub network_slicing_indication: Option<NetworkSlicingIndication>,
+    pub update_type: Option<FivegsUpdateType>,
+    pub mobile_station_classmark_2: Option<MobileStationClassmark2>,
+    pub supported_codecs: Option<SupportedCodecList>,
+    pub nas_message_container: Option<NasMessageContainer>,
+    pub eps_bearer_context_status: Option<EpsBearerContextStatus>,
+    pub requested_extended_drx_parameters: Option<ExtendedDrxParameters>,
+    pub t3324: Option<GprsTimer3>,
+    pub ue_radio_capability_id: Option<UeRadioCapabilityId>,
+    pub requested_mapped_nssai: Option<MappedNssai>,
+    pub additional_information_requested: Option<AdditionalInformationRequested>,
+    pub requested_wus_assistance_information: Option<WusAssistanceInformation>,
+    pub n5gc_indication: Option<N5gcIndication>,
+    pub requested_nb_n1_mode_drx_parameters: Option<NbN1ModeDrxParameters>,
+    pub ue_request_type: Option<UeRequestType>,
+    pub paging_restriction: Option<PagingRestriction>,
+    pub service_level_aa_container: Option<ServiceLevelAaContainer>,
+    pub nid: Option<Nid>,
+    pub ms_determined_plmn_with_disaster_condition: Option<PlmnIdentity>,
+    pub requested_peips_assistance_information: Option<PeipsAssistanceInformation>,
+    pub requested_t3512: Option<GprsTimer3>,
 }
 
 impl RegistrationRequest {
+    pub const NON_CURRENT_NATIVE_NGKSI_IEI: u8 = 0x0C;
+    pub const FIVEGMM_CAPABILITY_IEI: u8 = 0x10;
     pub const UE_SEC_CAPABILITY_IEI: u8 = 0x2E;
+    pub const REQUESTED_NSSAI_IEI: u8 = 0x2F;
+    pub const LAST_VISITED_REGISTERED_TAI_IEI: u8 = 0x52;
+    pub const S1_UE_NETWORK_CAPABILITY_IEI: u8 = 0x17;
+    pub const UPLINK_DATA_STATUS_IEI: u8 = 0x40;
+    pub const PDU_SESSION_STATUS_IEI: u8 = 0x50;
+    pub const MICO_INDICATION_IEI: u8 = 0x0B;
+    pub const UE_STATUS_IEI: u8 = 0x2B;
+    pub const ADDITIONAL_GUTI_IEI: u8 = 0x77;
+    pub const ALLOWED_PDU_SESSION_STATUS_IEI: u8 = 0x25;
+    pub const UES_USAGE_SETTING_IEI: u8 = 0x18;
+    pub const REQUESTED_DRX_PARAMETERS_IEI: u8 = 0x51;
+    pub const EPS_NAS_MESSAGE_CONTAINER_IEI: u8 = 0x70;
+    pub const LADN_INDICATION_IEI: u8 = 0x74;
+    pub const PAYLOAD_CONTAINER_TYPE_IEI: u8 = 0x08;
+    pub const PAYLOAD_CONTAINER_IEI: u8 = 0x7B;
+    pub const NETWORK_SLICING_INDICATION_IEI: u8 = 0x09;
+    pub const UPDATE_TYPE_IEI: u8 = 0x53;
+    pub const MOBILE_STATION_CLASSMARK_2_IEI: u8 = 0x41;
+    pub const SUPPORTED_CODECS_IEI: u8 = 0x42;
+    pub const NAS_MESSAGE_CONTAINER_IEI: u8 = 0x71;
+    pub const EPS_BEARER_CONTEXT_STATUS_IEI: u8 = 0x60;
+    pub const REQUESTED_EXTENDED_DRX_PARAMETERS_IEI: u8 = 0x6E;
+    pub const T3324_IEI: u8 = 0x6A;
+    pub const UE_RADIO_CAPABILITY_ID_IEI: u8 = 0x67;
+    pub const REQUESTED_MAPPED_NSSAI_IEI: u8 = 0x35;
+    pub const ADDITIONAL_INFORMATION_REQUESTED_IEI: u8 = 0x48;
+    pub const REQUESTED_WUS_ASSISTANCE_INFORMATION_IEI: u8 = 0x1A;
+    pub const N5GC_INDICATION_IEI: u8 = 0x0A;
+    pub const REQUESTED_NB_N1_MODE_DRX_PARAMETERS_IEI: u8 = 0x30;
+    pub const UE_REQUEST_TYPE_IEI: u8 = 0x29;
+    pub const PAGING_RESTRICTION_IEI: u8 = 0x28;
+    pub const SERVICE_LEVEL_AA_CONTAINER_IEI: u8 = 0x72;
+    pub const NID_IEI: u8 = 0x32;
+    pub const MS_DETERMINED_PLMN_WITH_DISASTER_CONDITION_IEI: u8 = 0x16;
+    pub const REQUESTED_PEIPS_ASSISTANCE_INFORMATION_IEI: u8 = 0x2A;
+    pub const REQUESTED_T3512_IEI: u8 = 0x3B;
 
     pub fn encode(&self) -> Vec<u8> {
         let mut output: Vec<u8> = vec![];
         output.extend(self.header.encode());
-        output.extend(self.req_type.encode(false));
-        output.extend(self.ngksi.encode(false));
+        // 5GS Registration Type and ngKSI share an octet.
+        output.push(self.req_type.encode(false, false)[0] | self.ngksi.encode(false, true)[0]);
         output.extend(self.identity.encode(false));
 
+        if let Some(non_current_native_ngksi) = &self.non_current_native_ngksi {
+            output.extend(non_current_native_ngksi.encode(true, false));
+        }
+        if let Some(fivegmm_capability) = &self.fivegmm_capability {
+            output.extend(fivegmm_capability.encode(true));
+        }
+        if let Some(ue_sec_capability) = &self.ue_sec_capability {
+            output.extend(ue_sec_capability.encode(true));
+        }
+        if let Some(requested_nssai) = &self.requested_nssai {
+            output.extend(requested_nssai.encode(true));
+        }
+        if let Some(last_visited_registered_tai) = &self.last_visited_registered_tai {
+            output.extend(last_visited_registered_tai.encode(true));
+        }
+        if let Some(s1_ue_network_capability) = &self.s1_ue_network_capability {
+            output.extend(s1_ue_network_capability.encode(true));
+        }
+        if let Some(uplink_data_status) = &self.uplink_data_status {
+            output.extend(uplink_data_status.encode(true));
+        }
+        if let Some(pdu_session_status) = &self.pdu_session_status {
+            output.extend(pdu_session_status.encode(true));
+        }
+        if let Some(mico_indication) = &self.mico_indication {
+            output.extend(mico_indication.encode(true, false));
+        }
+        if let Some(ue_status) = &self.ue_status {
+            output.extend(ue_status.encode(true));
+        }
+        if let Some(additional_guti) = &self.additional_guti {
+            output.extend(additional_guti.encode(true));
+        }
+        if let Some(allowed_pdu_session_status) = &self.allowed_pdu_session_status {
+            output.extend(allowed_pdu_session_status.encode(true));
+        }
+        if let Some(ues_usage_setting) = &self.ues_usage_setting {
+            output.extend(ues_usage_setting.encode(true));
+        }
+        if let Some(requested_drx_parameters) = &self.requested_drx_parameters {
+            output.extend(requested_drx_parameters.encode(true));
+        }
+        if let Some(eps_nas_message_container) = &self.eps_nas_message_container {
+            output.extend(eps_nas_message_container.encode(true));
+        }
+        if let Some(ladn_indication) = &self.ladn_indication {
+            output.extend(ladn_indication.encode(true));
+        }
+        if let Some(payload_container_type) = &self.payload_container_type {
+            output.extend(payload_container_type.encode(true, false));
+        }
+        if let Some(payload_container) = &self.payload_container {
+            output.extend(payload_container.encode(true));
+        }
+        if let Some(network_slicing_indication) = &self.network_slicing_indication {
+            output.extend(network_slicing_indication.encode(true, false));
+        }
+        if let Some(update_type) = &self.update_type {
+            output.extend(update_type.encode(true));
+        }
+        if let Some(mobile_station_classmark_2) = &self.mobile_station_classmark_2 {
+            output.extend(mobile_station_classmark_2.encode(true));
+        }
+        if let Some(supported_codecs) = &self.supported_codecs {
+            output.extend(supported_codecs.encode(true));
+        }
+        if let Some(nas_message_container) = &self.nas_message_container {
+            output.extend(nas_message_container.encode(true));
+        }
+        if let Some(eps_bearer_context_status) = &self.eps_bearer_context_status {
+            output.extend(eps_bearer_context_status.encode(true));
+        }
+        if let Some(requested_extended_drx_parameters) = &self.requested_extended_drx_parameters {
+            output.extend(requested_extended_drx_parameters.encode(true));
+        }
+        if let Some(t3324) = &self.t3324 {
+            output.extend(t3324.encode(true));
+        }
+        if let Some(ue_radio_capability_id) = &self.ue_radio_capability_id {
+            output.extend(ue_radio_capability_id.encode(true));
+        }
+        if let Some(requested_mapped_nssai) = &self.requested_mapped_nssai {
+            output.extend(requested_mapped_nssai.encode(true));
+        }
+        if let Some(additional_information_requested) = &self.additional_information_requested {
+            output.extend(additional_information_requested.encode(true));
+        }
+        if let Some(requested_wus_assistance_information) =
+            &self.requested_wus_assistance_information
+        {
+            output.extend(requested_wus_assistance_information.encode(true));
+        }
+        if let Some(n5gc_indication) = &self.n5gc_indication {
+            output.extend(n5gc_indication.encode(true, false));
+        }
+        if let Some(requested_nb_n1_mode_drx_parameters) = &self.requested_nb_n1_mode_drx_parameters
+        {
+            output.extend(requested_nb_n1_mode_drx_parameters.encode(true));
+        }
+        if let Some(ue_request_type) = &self.ue_request_type {
+            output.extend(ue_request_type.encode(true));
+        }
+        if let Some(paging_restriction) = &self.paging_restriction {
+            output.extend(paging_restriction.encode(true));
+        }
+        if let Some(service_level_aa_container) = &self.service_level_aa_container {
+            output.extend(service_level_aa_container.encode(true));
+        }
+        if let Some(nid) = &self.nid {
+            output.extend(nid.encode(true));
+        }
+        if let Some(ms_determined_plmn_with_disaster_condition) =
+            &self.ms_determined_plmn_with_disaster_condition
+        {
+            output.extend(ms_determined_plmn_with_disaster_condition.encode(true));
+        }
+        if let Some(requested_peips_assistance_information) =
+            &self.requested_peips_assistance_information
+        {
+            output.extend(requested_peips_assistance_information.encode(true));
+        }
+        if let Some(requested_t3512) = &self.requested_t3512 {
+            output.extend(requested_t3512.encode(true));
+        }
+
         output
     }
 
-    pub fn decode(data: &[u8]) -> std::io::Result<(Self, usize)> {
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
         log::trace!("RegistrationRequest decode.");
         let mut decoded = 0;
 
-        let (header, header_decoded) = Nas5gMmMessageHeader::decode(&data[decoded..])?;
+        let (header, header_decoded) =
+            super::decode_header(&data[decoded..], super::MM_MSG_TYPE_REGISTRATION_REQUEST)?;
         decoded += header_decoded;
 
-        assert_eq!(header.message_type, super::MM_MSG_TYPE_REGISTRATION_REQUEST);
-
         let (req_type, req_type_decoded) =
             FivegRegistrationType::decode(&data[decoded..], false, false)?;
         decoded += req_type_decoded;
@@ -49,32 +263,246 @@ impl RegistrationRequest {
         let (identity, identity_decoded) = FivegsMobileIdentity::decode(&data[decoded..], false)?;
         decoded += identity_decoded;
 
+        let mut non_current_native_ngksi = None;
+        let mut fivegmm_capability = None;
         let mut ue_sec_capability = None;
+        let mut requested_nssai = None;
+        let mut last_visited_registered_tai = None;
+        let mut s1_ue_network_capability = None;
+        let mut uplink_data_status = None;
+        let mut pdu_session_status = None;
+        let mut mico_indication = None;
+        let mut ue_status = None;
+        let mut additional_guti = None;
+        let mut allowed_pdu_session_status = None;
+        let mut ues_usage_setting = None;
+        let mut requested_drx_parameters = None;
+        let mut eps_nas_message_container = None;
+        let mut ladn_indication = None;
+        let mut payload_container_type = None;
+        let mut payload_container = None;
+        let mut network_slicing_indication = None;
+        let mut update_type = None;
+        let mut mobile_station_classmark_2 = None;
+        let mut supported_codecs = None;
+        let mut nas_message_container = None;
+        let mut eps_bearer_context_status = None;
+        let mut requested_extended_drx_parameters = None;
+        let mut t3324 = None;
+        let mut ue_radio_capability_id = None;
+        let mut requested_mapped_nssai = None;
+        let mut additional_information_requested = None;
+        let mut requested_wus_assistance_information = None;
+        let mut n5gc_indication = None;
+        let mut requested_nb_n1_mode_drx_parameters = None;
+        let mut ue_request_type = None;
+        let mut paging_restriction = None;
+        let mut service_level_aa_container = None;
+        let mut nid = None;
+        let mut ms_determined_plmn_with_disaster_condition = None;
+        let mut requested_peips_assistance_information = None;
+        let mut requested_t3512 = None;
         while decoded < data.len() {
-            let value = data[decoded];
-            let iei = if value > 0x80 {
-                (value & 0xF0) >> 4
-            } else {
-                value
-            };
-
-            match iei {
+            match optional_iei(data[decoded]) {
+                Self::NON_CURRENT_NATIVE_NGKSI_IEI => {
+                    let (ie, _) = NasKeySetId::decode(&data[decoded..], true, false)?;
+                    decoded += 1;
+                    non_current_native_ngksi = Some(ie);
+                }
+                Self::FIVEGMM_CAPABILITY_IEI => {
+                    let (ie, ie_decoded) = FivegmmCapability::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    fivegmm_capability = Some(ie);
+                }
                 Self::UE_SEC_CAPABILITY_IEI => {
-                    let (ue_sec_cap, ue_sec_cap_decoded) =
-                        UeSecurityCapability::decode(&data[decoded..])?;
-                    decoded += ue_sec_cap_decoded;
-                    ue_sec_capability = Some(ue_sec_cap);
-                }
-                _ => {
-                    log::error!("Unsupported IEI Type: {:x}", value);
-                    return Err(std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        format!(
-                            "Unsupported IEI :{:x}, {:?}, decoded: {}",
-                            value, identity, decoded
-                        ),
-                    ));
+                    let (ie, ie_decoded) = UeSecurityCapability::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    ue_sec_capability = Some(ie);
+                }
+                Self::REQUESTED_NSSAI_IEI => {
+                    let (ie, ie_decoded) = Nssai::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    requested_nssai = Some(ie);
+                }
+                Self::LAST_VISITED_REGISTERED_TAI_IEI => {
+                    let (ie, ie_decoded) = Tai::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    last_visited_registered_tai = Some(ie);
+                }
+                Self::S1_UE_NETWORK_CAPABILITY_IEI => {
+                    let (ie, ie_decoded) = S1UeNetworkCapability::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    s1_ue_network_capability = Some(ie);
+                }
+                Self::UPLINK_DATA_STATUS_IEI => {
+                    let (ie, ie_decoded) = UplinkDataStatus::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    uplink_data_status = Some(ie);
+                }
+                Self::PDU_SESSION_STATUS_IEI => {
+                    let (ie, ie_decoded) = PduSessionStatus::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    pdu_session_status = Some(ie);
+                }
+                Self::MICO_INDICATION_IEI => {
+                    let (ie, _) = MicoIndication::decode(&data[decoded..], true, false)?;
+                    decoded += 1;
+                    mico_indication = Some(ie);
+                }
+                Self::UE_STATUS_IEI => {
+                    let (ie, ie_decoded) = UeStatus::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    ue_status = Some(ie);
                 }
+                Self::ADDITIONAL_GUTI_IEI => {
+                    let (ie, ie_decoded) = FivegsMobileIdentity::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    additional_guti = Some(ie);
+                }
+                Self::ALLOWED_PDU_SESSION_STATUS_IEI => {
+                    let (ie, ie_decoded) = AllowedPduSessionStatus::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    allowed_pdu_session_status = Some(ie);
+                }
+                Self::UES_USAGE_SETTING_IEI => {
+                    let (ie, ie_decoded) = UesUsageSetting::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    ues_usage_setting = Some(ie);
+                }
+                Self::REQUESTED_DRX_PARAMETERS_IEI => {
+                    let (ie, ie_decoded) = FivegsDrxParameters::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    requested_drx_parameters = Some(ie);
+                }
+                Self::EPS_NAS_MESSAGE_CONTAINER_IEI => {
+                    let (ie, ie_decoded) = EpsNasMessageContainer::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    eps_nas_message_container = Some(ie);
+                }
+                Self::LADN_INDICATION_IEI => {
+                    let (ie, ie_decoded) = LadnIndication::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    ladn_indication = Some(ie);
+                }
+                Self::PAYLOAD_CONTAINER_TYPE_IEI => {
+                    let (ie, _) = PayloadContainerType::decode(&data[decoded..], true, false)?;
+                    decoded += 1;
+                    payload_container_type = Some(ie);
+                }
+                Self::PAYLOAD_CONTAINER_IEI => {
+                    let (ie, ie_decoded) = PayloadContainer::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    payload_container = Some(ie);
+                }
+                Self::NETWORK_SLICING_INDICATION_IEI => {
+                    let (ie, _) = NetworkSlicingIndication::decode(&data[decoded..], true, false)?;
+                    decoded += 1;
+                    network_slicing_indication = Some(ie);
+                }
+                Self::UPDATE_TYPE_IEI => {
+                    let (ie, ie_decoded) = FivegsUpdateType::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    update_type = Some(ie);
+                }
+                Self::MOBILE_STATION_CLASSMARK_2_IEI => {
+                    let (ie, ie_decoded) = MobileStationClassmark2::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    mobile_station_classmark_2 = Some(ie);
+                }
+                Self::SUPPORTED_CODECS_IEI => {
+                    let (ie, ie_decoded) = SupportedCodecList::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    supported_codecs = Some(ie);
+                }
+                Self::NAS_MESSAGE_CONTAINER_IEI => {
+                    let (ie, ie_decoded) = NasMessageContainer::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    nas_message_container = Some(ie);
+                }
+                Self::EPS_BEARER_CONTEXT_STATUS_IEI => {
+                    let (ie, ie_decoded) = EpsBearerContextStatus::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    eps_bearer_context_status = Some(ie);
+                }
+                Self::REQUESTED_EXTENDED_DRX_PARAMETERS_IEI => {
+                    let (ie, ie_decoded) = ExtendedDrxParameters::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    requested_extended_drx_parameters = Some(ie);
+                }
+                Self::T3324_IEI => {
+                    let (ie, ie_decoded) = GprsTimer3::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    t3324 = Some(ie);
+                }
+                Self::UE_RADIO_CAPABILITY_ID_IEI => {
+                    let (ie, ie_decoded) = UeRadioCapabilityId::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    ue_radio_capability_id = Some(ie);
+                }
+                Self::REQUESTED_MAPPED_NSSAI_IEI => {
+                    let (ie, ie_decoded) = MappedNssai::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    requested_mapped_nssai = Some(ie);
+                }
+                Self::ADDITIONAL_INFORMATION_REQUESTED_IEI => {
+                    let (ie, ie_decoded) =
+                        AdditionalInformationRequested::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    additional_information_requested = Some(ie);
+                }
+                Self::REQUESTED_WUS_ASSISTANCE_INFORMATION_IEI => {
+                    let (ie, ie_decoded) =
+                        WusAssistanceInformation::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    requested_wus_assistance_information = Some(ie);
+                }
+                Self::N5GC_INDICATION_IEI => {
+                    let (ie, _) = N5gcIndication::decode(&data[decoded..], true, false)?;
+                    decoded += 1;
+                    n5gc_indication = Some(ie);
+                }
+                Self::REQUESTED_NB_N1_MODE_DRX_PARAMETERS_IEI => {
+                    let (ie, ie_decoded) = NbN1ModeDrxParameters::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    requested_nb_n1_mode_drx_parameters = Some(ie);
+                }
+                Self::UE_REQUEST_TYPE_IEI => {
+                    let (ie, ie_decoded) = UeRequestType::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    ue_request_type = Some(ie);
+                }
+                Self::PAGING_RESTRICTION_IEI => {
+                    let (ie, ie_decoded) = PagingRestriction::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    paging_restriction = Some(ie);
+                }
+                Self::SERVICE_LEVEL_AA_CONTAINER_IEI => {
+                    let (ie, ie_decoded) = ServiceLevelAaContainer::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    service_level_aa_container = Some(ie);
+                }
+                Self::NID_IEI => {
+                    let (ie, ie_decoded) = Nid::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    nid = Some(ie);
+                }
+                Self::MS_DETERMINED_PLMN_WITH_DISASTER_CONDITION_IEI => {
+                    let (ie, ie_decoded) = PlmnIdentity::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    ms_determined_plmn_with_disaster_condition = Some(ie);
+                }
+                Self::REQUESTED_PEIPS_ASSISTANCE_INFORMATION_IEI => {
+                    let (ie, ie_decoded) =
+                        PeipsAssistanceInformation::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    requested_peips_assistance_information = Some(ie);
+                }
+                Self::REQUESTED_T3512_IEI => {
+                    let (ie, ie_decoded) = GprsTimer3::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    requested_t3512 = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
             }
         }
 
@@ -84,7 +512,45 @@ impl RegistrationRequest {
                 req_type,
                 ngksi,
                 identity,
+                non_current_native_ngksi,
+                fivegmm_capability,
                 ue_sec_capability,
+                requested_nssai,
+                last_visited_registered_tai,
+                s1_ue_network_capability,
+                uplink_data_status,
+                pdu_session_status,
+                mico_indication,
+                ue_status,
+                additional_guti,
+                allowed_pdu_session_status,
+                ues_usage_setting,
+                requested_drx_parameters,
+                eps_nas_message_container,
+                ladn_indication,
+                payload_container_type,
+                payload_container,
+                network_slicing_indication,
+                update_type,
+                mobile_station_classmark_2,
+                supported_codecs,
+                nas_message_container,
+                eps_bearer_context_status,
+                requested_extended_drx_parameters,
+                t3324,
+                ue_radio_capability_id,
+                requested_mapped_nssai,
+                additional_information_requested,
+                requested_wus_assistance_information,
+                n5gc_indication,
+                requested_nb_n1_mode_drx_parameters,
+                ue_request_type,
+                paging_restriction,
+                service_level_aa_container,
+                nid,
+                ms_determined_plmn_with_disaster_condition,
+                requested_peips_assistance_information,
+                requested_t3512,
             },
             decoded,
         ))
@@ -96,6 +562,13 @@ mod tests {
 
     use super::*;
 
+    use proptest::prelude::*;
+
+    use crate::messages::headers::{ExtProtoDiscriminator, Nas5gSecurityHeader};
+    use crate::messages::ies::tests::{
+        fiveg_registration_type, fivegs_mobile_identity, nas_key_set_id, ue_security_capability,
+    };
+
     #[test]
     fn decode_registration_request() {
         let data = [
@@ -115,4 +588,99 @@ mod tests {
             }
         ));
     }
+
+    #[test]
+    fn encode_registration_request() {
+        // Routing Indicator is "0"
+        let data = [
+            126, 0, 65, 121, 0, 13, 1, 9, 241, 7, 240, 255, 0, 0, 0, 0, 0, 0, 16, 46, 4, 240, 240,
+            240, 240,
+        ];
+
+        let (reg_request, _decoded) = RegistrationRequest::decode(&data).unwrap();
+        assert_eq!(reg_request.encode(), data);
+    }
+
+    #[test]
+    fn decode_registration_request_unknown_ies() {
+        // Unknown TLV (0x6F), TLV-E (0x7F) and Type 1 (0xE-) IEs are skipped.
+        let data = hex::decode("7e004179000d0109f107000000000000000010e16f01007f00020000").unwrap();
+
+        let (reg_request, decoded) = RegistrationRequest::decode(&data).unwrap();
+        assert_eq!(decoded, data.len());
+        assert!(reg_request.ue_sec_capability.is_none());
+
+        // Unknown 'comprehension required' IEs are an error.
+        let data = hex::decode("7e004179000d0109f1070000000000000000100100").unwrap();
+        assert_eq!(
+            RegistrationRequest::decode(&data),
+            Err(NasError::InvalidIei(0x01))
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn roundtrip_registration_request(
+            message_type in Just(crate::messages::mm::MM_MSG_TYPE_REGISTRATION_REQUEST),
+            req_type in fiveg_registration_type(None),
+            ngksi in nas_key_set_id(None),
+            identity in fivegs_mobile_identity(None),
+            ue_sec_capability in proptest::option::of(ue_security_capability(Some(0x2E))),
+        ) {
+            let reg_request = RegistrationRequest {
+                header: Nas5gMmMessageHeader {
+                    extended_protocol_disc: ExtProtoDiscriminator::FivegNasMobilityManagementType,
+                    sec_header_type: Nas5gSecurityHeader::PlainText,
+                    message_type,
+                },
+                req_type,
+                ngksi,
+                identity,
+                non_current_native_ngksi: None,
+                fivegmm_capability: None,
+                ue_sec_capability,
+                requested_nssai: None,
+                last_visited_registered_tai: None,
+                s1_ue_network_capability: None,
+                uplink_data_status: None,
+                pdu_session_status: None,
+                mico_indication: None,
+                ue_status: None,
+                additional_guti: None,
+                allowed_pdu_session_status: None,
+                ues_usage_setting: None,
+                requested_drx_parameters: None,
+                eps_nas_message_container: None,
+                ladn_indication: None,
+                payload_container_type: None,
+                payload_container: None,
+                network_slicing_indication: None,
+                update_type: None,
+                mobile_station_classmark_2: None,
+                supported_codecs: None,
+                nas_message_container: None,
+                eps_bearer_context_status: None,
+                requested_extended_drx_parameters: None,
+                t3324: None,
+                ue_radio_capability_id: None,
+                requested_mapped_nssai: None,
+                additional_information_requested: None,
+                requested_wus_assistance_information: None,
+                n5gc_indication: None,
+                requested_nb_n1_mode_drx_parameters: None,
+                ue_request_type: None,
+                paging_restriction: None,
+                service_level_aa_container: None,
+                nid: None,
+                ms_determined_plmn_with_disaster_condition: None,
+                requested_peips_assistance_information: None,
+                requested_t3512: None,
+            };
+
+            let encoded = reg_request.encode();
+            let (decoded, length) = RegistrationRequest::decode(&encoded).unwrap();
+            prop_assert_eq!(length, encoded.len());
+            prop_assert_eq!(decoded, reg_request);
+        }
+    }
 }
@@ -0,0 +1,119 @@
+//! 5G MM Security Mode Command
+
+use crate::messages::{
+    headers::Nas5gMmMessageHeader,
+    ies::{
+        optional_iei, skip_unknown_ie, Abba, Additional5gSecurityInformation, EapMessage,
+        ImeisvRequest, NasKeySetId, NasSecurityAlgorithms, UeSecurityCapability,
+    },
+};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_SECURITY_MODE_COMMAND};
+
+/// Security Mode Command: 24.501 (Release 17) Section 8.2.25
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SecurityModeCommand {
+    pub header: Nas5gMmMessageHeader,
+    pub selected_nas_security_algorithms: NasSecurityAlgorithms,
+    pub ngksi: NasKeySetId,
+    pub replayed_ue_security_capability: UeSecurityCapability,
+    pub imeisv_request: Option<ImeisvRequest>,
+    pub additional_5g_security_information: Option<Additional5gSecurityInformation>,
+    pub eap_message: Option<EapMessage>,
+    pub abba: Option<Abba>,
+}
+
+impl SecurityModeCommand {
+    pub const IMEISV_REQUEST_IEI: u8 = 0x0E;
+    pub const ADDITIONAL_5G_SECURITY_INFORMATION_IEI: u8 = 0x36;
+    pub const EAP_MESSAGE_IEI: u8 = 0x78;
+    pub const ABBA_IEI: u8 = 0x38;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+        output.extend(self.selected_nas_security_algorithms.encode(false));
+        // Upper half octet is spare.
+        output.extend(self.ngksi.encode(false, false));
+        output.extend(self.replayed_ue_security_capability.encode(false));
+
+        if let Some(imeisv_request) = &self.imeisv_request {
+            output.extend(imeisv_request.encode(true, false));
+        }
+        if let Some(additional_5g_security_information) = &self.additional_5g_security_information {
+            output.extend(additional_5g_security_information.encode(true));
+        }
+        if let Some(eap_message) = &self.eap_message {
+            output.extend(eap_message.encode(true));
+        }
+        if let Some(abba) = &self.abba {
+            output.extend(abba.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("SecurityModeCommand decode.");
+        let (header, mut decoded) = decode_header(data, MM_MSG_TYPE_SECURITY_MODE_COMMAND)?;
+
+        let (selected_nas_security_algorithms, selected_nas_security_algorithms_decoded) =
+            NasSecurityAlgorithms::decode(&data[decoded..], false)?;
+        decoded += selected_nas_security_algorithms_decoded;
+
+        let (ngksi, _) = NasKeySetId::decode(&data[decoded..], false, false)?;
+        // Upper half octet is spare.
+        decoded += 1;
+
+        let (replayed_ue_security_capability, replayed_ue_security_capability_decoded) =
+            UeSecurityCapability::decode(&data[decoded..], false)?;
+        decoded += replayed_ue_security_capability_decoded;
+
+        let mut imeisv_request = None;
+        let mut additional_5g_security_information = None;
+        let mut eap_message = None;
+        let mut abba = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::IMEISV_REQUEST_IEI => {
+                    let (ie, _) = ImeisvRequest::decode(&data[decoded..], true, false)?;
+                    decoded += 1;
+                    imeisv_request = Some(ie);
+                }
+                Self::ADDITIONAL_5G_SECURITY_INFORMATION_IEI => {
+                    let (ie, ie_decoded) =
+                        Additional5gSecurityInformation::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    additional_5g_security_information = Some(ie);
+                }
+                Self::EAP_MESSAGE_IEI => {
+                    let (ie, ie_decoded) = EapMessage::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    eap_message = Some(ie);
+                }
+                Self::ABBA_IEI => {
+                    let (ie, ie_decoded) = Abba::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    abba = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                selected_nas_security_algorithms,
+                ngksi,
+                replayed_ue_security_capability,
+                imeisv_request,
+                additional_5g_security_information,
+                eap_message,
+                abba,
+            },
+            decoded,
+        ))
+    }
+}
@@ -0,0 +1,81 @@
+//! 5G MM Security Mode Complete
+
+use crate::messages::{
+    headers::Nas5gMmMessageHeader,
+    ies::{optional_iei, skip_unknown_ie, FivegsMobileIdentity, NasMessageContainer},
+};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_SECURITY_MODE_COMPLETE};
+
+/// Security Mode Complete: 24.501 (Release 17) Section 8.2.26
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SecurityModeComplete {
+    pub header: Nas5gMmMessageHeader,
+    pub imeisv: Option<FivegsMobileIdentity>,
+    pub nas_message_container: Option<NasMessageContainer>,
+    pub non_imeisv_pei: Option<FivegsMobileIdentity>,
+}
+
+impl SecurityModeComplete {
+    pub const IMEISV_IEI: u8 = 0x77;
+    pub const NAS_MESSAGE_CONTAINER_IEI: u8 = 0x71;
+    pub const NON_IMEISV_PEI_IEI: u8 = 0x78;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+
+        if let Some(imeisv) = &self.imeisv {
+            output.extend(imeisv.encode(true));
+        }
+        if let Some(nas_message_container) = &self.nas_message_container {
+            output.extend(nas_message_container.encode(true));
+        }
+        if let Some(non_imeisv_pei) = &self.non_imeisv_pei {
+            output.extend(non_imeisv_pei.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("SecurityModeComplete decode.");
+        let (header, mut decoded) = decode_header(data, MM_MSG_TYPE_SECURITY_MODE_COMPLETE)?;
+
+        let mut imeisv = None;
+        let mut nas_message_container = None;
+        let mut non_imeisv_pei = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::IMEISV_IEI => {
+                    let (ie, ie_decoded) = FivegsMobileIdentity::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    imeisv = Some(ie);
+                }
+                Self::NAS_MESSAGE_CONTAINER_IEI => {
+                    let (ie, ie_decoded) = NasMessageContainer::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    nas_message_container = Some(ie);
+                }
+                Self::NON_IMEISV_PEI_IEI => {
+                    let (ie, ie_decoded) = FivegsMobileIdentity::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    non_imeisv_pei = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                imeisv,
+                nas_message_container,
+                non_imeisv_pei,
+            },
+            decoded,
+        ))
+    }
+}
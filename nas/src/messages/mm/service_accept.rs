@@ -0,0 +1,112 @@
+//! 5G MM Service Accept
+
+use crate::messages::{
+    headers::Nas5gMmMessageHeader,
+    ies::{
+        optional_iei, skip_unknown_ie, EapMessage, GprsTimer3, PduSessionReactivationResult,
+        PduSessionReactivationResultErrorCause, PduSessionStatus,
+    },
+};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_SERVICE_ACCEPT};
+
+/// Service Accept: 24.501 (Release 17) Section 8.2.17
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ServiceAccept {
+    pub header: Nas5gMmMessageHeader,
+    pub pdu_session_status: Option<PduSessionStatus>,
+    pub pdu_session_reactivation_result: Option<PduSessionReactivationResult>,
+    pub pdu_session_reactivation_result_error_cause: Option<PduSessionReactivationResultErrorCause>,
+    pub eap_message: Option<EapMessage>,
+    pub t3448: Option<GprsTimer3>,
+}
+
+impl ServiceAccept {
+    pub const PDU_SESSION_STATUS_IEI: u8 = 0x50;
+    pub const PDU_SESSION_REACTIVATION_RESULT_IEI: u8 = 0x26;
+    pub const PDU_SESSION_REACTIVATION_RESULT_ERROR_CAUSE_IEI: u8 = 0x72;
+    pub const EAP_MESSAGE_IEI: u8 = 0x78;
+    pub const T3448_IEI: u8 = 0x6B;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+
+        if let Some(pdu_session_status) = &self.pdu_session_status {
+            output.extend(pdu_session_status.encode(true));
+        }
+        if let Some(pdu_session_reactivation_result) = &self.pdu_session_reactivation_result {
+            output.extend(pdu_session_reactivation_result.encode(true));
+        }
+        if let Some(pdu_session_reactivation_result_error_cause) =
+            &self.pdu_session_reactivation_result_error_cause
+        {
+            output.extend(pdu_session_reactivation_result_error_cause.encode(true));
+        }
+        if let Some(eap_message) = &self.eap_message {
+            output.extend(eap_message.encode(true));
+        }
+        if let Some(t3448) = &self.t3448 {
+            output.extend(t3448.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("ServiceAccept decode.");
+        let (header, mut decoded) = decode_header(data, MM_MSG_TYPE_SERVICE_ACCEPT)?;
+
+        let mut pdu_session_status = None;
+        let mut pdu_session_reactivation_result = None;
+        let mut pdu_session_reactivation_result_error_cause = None;
+        let mut eap_message = None;
+        let mut t3448 = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::PDU_SESSION_STATUS_IEI => {
+                    let (ie, ie_decoded) = PduSessionStatus::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    pdu_session_status = Some(ie);
+                }
+                Self::PDU_SESSION_REACTIVATION_RESULT_IEI => {
+                    let (ie, ie_decoded) =
+                        PduSessionReactivationResult::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    pdu_session_reactivation_result = Some(ie);
+                }
+                Self::PDU_SESSION_REACTIVATION_RESULT_ERROR_CAUSE_IEI => {
+                    let (ie, ie_decoded) =
+                        PduSessionReactivationResultErrorCause::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    pdu_session_reactivation_result_error_cause = Some(ie);
+                }
+                Self::EAP_MESSAGE_IEI => {
+                    let (ie, ie_decoded) = EapMessage::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    eap_message = Some(ie);
+                }
+                Self::T3448_IEI => {
+                    let (ie, ie_decoded) = GprsTimer3::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    t3448 = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                pdu_session_status,
+                pdu_session_reactivation_result,
+                pdu_session_reactivation_result_error_cause,
+                eap_message,
+                t3448,
+            },
+            decoded,
+        ))
+    }
+}
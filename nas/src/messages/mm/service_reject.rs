@@ -0,0 +1,102 @@
+//! 5G MM Service Reject
+
+use crate::messages::{
+    headers::Nas5gMmMessageHeader,
+    ies::{
+        optional_iei, skip_unknown_ie, EapMessage, FivegmmCause, GprsTimer2, GprsTimer3,
+        PduSessionStatus,
+    },
+};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_SERVICE_REJECT};
+
+/// Service Reject: 24.501 (Release 17) Section 8.2.18
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ServiceReject {
+    pub header: Nas5gMmMessageHeader,
+    pub cause: FivegmmCause,
+    pub pdu_session_status: Option<PduSessionStatus>,
+    pub t3346: Option<GprsTimer2>,
+    pub eap_message: Option<EapMessage>,
+    pub t3448: Option<GprsTimer3>,
+}
+
+impl ServiceReject {
+    pub const PDU_SESSION_STATUS_IEI: u8 = 0x50;
+    pub const T3346_IEI: u8 = 0x5F;
+    pub const EAP_MESSAGE_IEI: u8 = 0x78;
+    pub const T3448_IEI: u8 = 0x6B;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+        output.extend(self.cause.encode(false));
+
+        if let Some(pdu_session_status) = &self.pdu_session_status {
+            output.extend(pdu_session_status.encode(true));
+        }
+        if let Some(t3346) = &self.t3346 {
+            output.extend(t3346.encode(true));
+        }
+        if let Some(eap_message) = &self.eap_message {
+            output.extend(eap_message.encode(true));
+        }
+        if let Some(t3448) = &self.t3448 {
+            output.extend(t3448.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("ServiceReject decode.");
+        let (header, mut decoded) = decode_header(data, MM_MSG_TYPE_SERVICE_REJECT)?;
+
+        let (cause, cause_decoded) = FivegmmCause::decode(&data[decoded..], false)?;
+        decoded += cause_decoded;
+
+        let mut pdu_session_status = None;
+        let mut t3346 = None;
+        let mut eap_message = None;
+        let mut t3448 = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::PDU_SESSION_STATUS_IEI => {
+                    let (ie, ie_decoded) = PduSessionStatus::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    pdu_session_status = Some(ie);
+                }
+                Self::T3346_IEI => {
+                    let (ie, ie_decoded) = GprsTimer2::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    t3346 = Some(ie);
+                }
+                Self::EAP_MESSAGE_IEI => {
+                    let (ie, ie_decoded) = EapMessage::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    eap_message = Some(ie);
+                }
+                Self::T3448_IEI => {
+                    let (ie, ie_decoded) = GprsTimer3::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    t3448 = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                cause,
+                pdu_session_status,
+                t3346,
+                eap_message,
+                t3448,
+            },
+            decoded,
+        ))
+    }
+}
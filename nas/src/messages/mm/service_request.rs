@@ -0,0 +1,112 @@
+//! 5G MM Service Request
+
+use crate::messages::{
+    headers::Nas5gMmMessageHeader,
+    ies::{
+        optional_iei, skip_unknown_ie, AllowedPduSessionStatus, FivegsMobileIdentity, NasKeySetId,
+        NasMessageContainer, PduSessionStatus, ServiceType, UplinkDataStatus,
+    },
+};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_SERVICE_REQUEST};
+
+/// Service Request: 24.501 (Release 17) Section 8.2.16
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ServiceRequest {
+    pub header: Nas5gMmMessageHeader,
+    pub ngksi: NasKeySetId,
+    pub service_type: ServiceType,
+    pub s_tmsi: FivegsMobileIdentity,
+    pub uplink_data_status: Option<UplinkDataStatus>,
+    pub pdu_session_status: Option<PduSessionStatus>,
+    pub allowed_pdu_session_status: Option<AllowedPduSessionStatus>,
+    pub nas_message_container: Option<NasMessageContainer>,
+}
+
+impl ServiceRequest {
+    pub const UPLINK_DATA_STATUS_IEI: u8 = 0x40;
+    pub const PDU_SESSION_STATUS_IEI: u8 = 0x50;
+    pub const ALLOWED_PDU_SESSION_STATUS_IEI: u8 = 0x25;
+    pub const NAS_MESSAGE_CONTAINER_IEI: u8 = 0x71;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+        output.push(self.ngksi.encode(false, false)[0] | self.service_type.encode(false, true)[0]);
+        output.extend(self.s_tmsi.encode(false));
+
+        if let Some(uplink_data_status) = &self.uplink_data_status {
+            output.extend(uplink_data_status.encode(true));
+        }
+        if let Some(pdu_session_status) = &self.pdu_session_status {
+            output.extend(pdu_session_status.encode(true));
+        }
+        if let Some(allowed_pdu_session_status) = &self.allowed_pdu_session_status {
+            output.extend(allowed_pdu_session_status.encode(true));
+        }
+        if let Some(nas_message_container) = &self.nas_message_container {
+            output.extend(nas_message_container.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("ServiceRequest decode.");
+        let (header, mut decoded) = decode_header(data, MM_MSG_TYPE_SERVICE_REQUEST)?;
+
+        let (ngksi, _) = NasKeySetId::decode(&data[decoded..], false, false)?;
+        let (service_type, _) = ServiceType::decode(&data[decoded..], false, true)?;
+        // Both the above share an octet.
+        decoded += 1;
+
+        let (s_tmsi, s_tmsi_decoded) = FivegsMobileIdentity::decode(&data[decoded..], false)?;
+        decoded += s_tmsi_decoded;
+
+        let mut uplink_data_status = None;
+        let mut pdu_session_status = None;
+        let mut allowed_pdu_session_status = None;
+        let mut nas_message_container = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::UPLINK_DATA_STATUS_IEI => {
+                    let (ie, ie_decoded) = UplinkDataStatus::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    uplink_data_status = Some(ie);
+                }
+                Self::PDU_SESSION_STATUS_IEI => {
+                    let (ie, ie_decoded) = PduSessionStatus::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    pdu_session_status = Some(ie);
+                }
+                Self::ALLOWED_PDU_SESSION_STATUS_IEI => {
+                    let (ie, ie_decoded) = AllowedPduSessionStatus::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    allowed_pdu_session_status = Some(ie);
+                }
+                Self::NAS_MESSAGE_CONTAINER_IEI => {
+                    let (ie, ie_decoded) = NasMessageContainer::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    nas_message_container = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                ngksi,
+                service_type,
+                s_tmsi,
+                uplink_data_status,
+                pdu_session_status,
+                allowed_pdu_session_status,
+                nas_message_container,
+            },
+            decoded,
+        ))
+    }
+}
@@ -0,0 +1,136 @@
+//! 5G MM UL NAS Transport
+
+use crate::messages::{
+    headers::Nas5gMmMessageHeader,
+    ies::{
+        optional_iei, skip_unknown_ie, AdditionalInformation, Dnn, PayloadContainer,
+        PayloadContainerType, PduSessionIdentity2, RequestType, SNssai,
+    },
+};
+use crate::NasError;
+
+use super::{decode_header, MM_MSG_TYPE_UL_NAS_TRANSPORT};
+
+/// UL NAS Transport: 24.501 (Release 17) Section 8.2.10
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct UlNasTransport {
+    pub header: Nas5gMmMessageHeader,
+    pub payload_container_type: PayloadContainerType,
+    pub payload_container: PayloadContainer,
+    pub pdu_session_id: Option<PduSessionIdentity2>,
+    pub old_pdu_session_id: Option<PduSessionIdentity2>,
+    pub request_type: Option<RequestType>,
+    pub s_nssai: Option<SNssai>,
+    pub dnn: Option<Dnn>,
+    pub additional_information: Option<AdditionalInformation>,
+}
+
+impl UlNasTransport {
+    pub const PDU_SESSION_ID_IEI: u8 = 0x12;
+    pub const OLD_PDU_SESSION_ID_IEI: u8 = 0x59;
+    pub const REQUEST_TYPE_IEI: u8 = 0x08;
+    pub const S_NSSAI_IEI: u8 = 0x22;
+    pub const DNN_IEI: u8 = 0x25;
+    pub const ADDITIONAL_INFORMATION_IEI: u8 = 0x24;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+        // Upper half octet is spare.
+        output.extend(self.payload_container_type.encode(false, false));
+        output.extend(self.payload_container.encode(false));
+
+        if let Some(pdu_session_id) = &self.pdu_session_id {
+            output.extend(pdu_session_id.encode(true));
+        }
+        if let Some(old_pdu_session_id) = &self.old_pdu_session_id {
+            output.extend(old_pdu_session_id.encode(true));
+        }
+        if let Some(request_type) = &self.request_type {
+            output.extend(request_type.encode(true, false));
+        }
+        if let Some(s_nssai) = &self.s_nssai {
+            output.extend(s_nssai.encode(true));
+        }
+        if let Some(dnn) = &self.dnn {
+            output.extend(dnn.encode(true));
+        }
+        if let Some(additional_information) = &self.additional_information {
+            output.extend(additional_information.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("UlNasTransport decode.");
+        let (header, mut decoded) = decode_header(data, MM_MSG_TYPE_UL_NAS_TRANSPORT)?;
+
+        let (payload_container_type, _) =
+            PayloadContainerType::decode(&data[decoded..], false, false)?;
+        // Upper half octet is spare.
+        decoded += 1;
+
+        let (payload_container, payload_container_decoded) =
+            PayloadContainer::decode(&data[decoded..], false)?;
+        decoded += payload_container_decoded;
+
+        let mut pdu_session_id = None;
+        let mut old_pdu_session_id = None;
+        let mut request_type = None;
+        let mut s_nssai = None;
+        let mut dnn = None;
+        let mut additional_information = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::PDU_SESSION_ID_IEI => {
+                    let (ie, ie_decoded) = PduSessionIdentity2::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    pdu_session_id = Some(ie);
+                }
+                Self::OLD_PDU_SESSION_ID_IEI => {
+                    let (ie, ie_decoded) = PduSessionIdentity2::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    old_pdu_session_id = Some(ie);
+                }
+                Self::REQUEST_TYPE_IEI => {
+                    let (ie, _) = RequestType::decode(&data[decoded..], true, false)?;
+                    decoded += 1;
+                    request_type = Some(ie);
+                }
+                Self::S_NSSAI_IEI => {
+                    let (ie, ie_decoded) = SNssai::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    s_nssai = Some(ie);
+                }
+                Self::DNN_IEI => {
+                    let (ie, ie_decoded) = Dnn::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    dnn = Some(ie);
+                }
+                Self::ADDITIONAL_INFORMATION_IEI => {
+                    let (ie, ie_decoded) = AdditionalInformation::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    additional_information = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                payload_container_type,
+                payload_container,
+                pdu_session_id,
+                old_pdu_session_id,
+                request_type,
+                s_nssai,
+                dnn,
+                additional_information,
+            },
+            decoded,
+        ))
+    }
+}
@@ -1,6 +1,40 @@
-mod ies;
+pub mod ies;
 
 pub mod headers;
 
 mod mm;
-pub use mm::{Nas5gMmMessage, RegistrationRequest};
+pub use mm::{
+    AuthenticationFailure, AuthenticationReject, AuthenticationRequest, AuthenticationResponse,
+    AuthenticationResult, ConfigurationUpdateCommand, ConfigurationUpdateComplete,
+    DeregistrationAcceptFromUe, DeregistrationAcceptToUe, DeregistrationRequestFromUe,
+    DeregistrationRequestToUe, DlNasTransport, FivegmmStatus, IdentityRequest, IdentityResponse,
+    Nas5gMmMessage, Notification, NotificationResponse, RegistrationAccept, RegistrationComplete,
+    RegistrationReject, RegistrationRequest, SecurityModeCommand, SecurityModeComplete,
+    SecurityModeReject, ServiceAccept, ServiceReject, ServiceRequest, UlNasTransport,
+};
+pub use mm::{
+    MM_MSG_TYPE_AUTHENTICATION_FAILURE, MM_MSG_TYPE_AUTHENTICATION_REJECT,
+    MM_MSG_TYPE_AUTHENTICATION_REQUEST, MM_MSG_TYPE_AUTHENTICATION_RESPONSE,
+    MM_MSG_TYPE_AUTHENTICATION_RESULT, MM_MSG_TYPE_CONFIGURATION_UPDATE_COMMAND,
+    MM_MSG_TYPE_CONFIGURATION_UPDATE_COMPLETE, MM_MSG_TYPE_DEREGISTRATION_ACCEPT_FROM_UE,
+    MM_MSG_TYPE_DEREGISTRATION_ACCEPT_TO_UE, MM_MSG_TYPE_DEREGISTRATION_REQUEST_FROM_UE,
+    MM_MSG_TYPE_DEREGISTRATION_REQUEST_TO_UE, MM_MSG_TYPE_DL_NAS_TRANSPORT,
+    MM_MSG_TYPE_FIVEGMM_STATUS, MM_MSG_TYPE_IDENTITY_REQUEST, MM_MSG_TYPE_IDENTITY_RESPONSE,
+    MM_MSG_TYPE_NOTIFICATION, MM_MSG_TYPE_NOTIFICATION_RESPONSE, MM_MSG_TYPE_REGISTRATION_ACCEPT,
+    MM_MSG_TYPE_REGISTRATION_COMPLETE, MM_MSG_TYPE_REGISTRATION_REJECT,
+    MM_MSG_TYPE_REGISTRATION_REQUEST, MM_MSG_TYPE_SECURITY_MODE_COMMAND,
+    MM_MSG_TYPE_SECURITY_MODE_COMPLETE, MM_MSG_TYPE_SECURITY_MODE_REJECT,
+    MM_MSG_TYPE_SERVICE_ACCEPT, MM_MSG_TYPE_SERVICE_REJECT, MM_MSG_TYPE_SERVICE_REQUEST,
+    MM_MSG_TYPE_UL_NAS_TRANSPORT,
+};
+
+mod sm;
+pub use sm::{
+    FivegsmStatus, Nas5gSmMessage, PduSessionAuthenticationCommand,
+    PduSessionAuthenticationComplete, PduSessionAuthenticationResult,
+    PduSessionEstablishmentAccept, PduSessionEstablishmentReject, PduSessionEstablishmentRequest,
+    PduSessionModificationCommand, PduSessionModificationCommandReject,
+    PduSessionModificationComplete, PduSessionModificationReject, PduSessionModificationRequest,
+    PduSessionReleaseCommand, PduSessionReleaseComplete, PduSessionReleaseReject,
+    PduSessionReleaseRequest,
+};
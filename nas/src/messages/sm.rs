@@ -0,0 +1,364 @@
+//! Structures, Enumerations and constants for NAS 5G SM Messages.
+
+use crate::messages::headers::Nas5gSmMessageHeader;
+use crate::NasError;
+
+mod pdu_session_establishment_request;
+pub use pdu_session_establishment_request::PduSessionEstablishmentRequest;
+
+mod pdu_session_establishment_accept;
+pub use pdu_session_establishment_accept::PduSessionEstablishmentAccept;
+
+mod pdu_session_establishment_reject;
+pub use pdu_session_establishment_reject::PduSessionEstablishmentReject;
+
+mod pdu_session_authentication_command;
+pub use pdu_session_authentication_command::PduSessionAuthenticationCommand;
+
+mod pdu_session_authentication_complete;
+pub use pdu_session_authentication_complete::PduSessionAuthenticationComplete;
+
+mod pdu_session_authentication_result;
+pub use pdu_session_authentication_result::PduSessionAuthenticationResult;
+
+mod pdu_session_modification_request;
+pub use pdu_session_modification_request::PduSessionModificationRequest;
+
+mod pdu_session_modification_reject;
+pub use pdu_session_modification_reject::PduSessionModificationReject;
+
+mod pdu_session_modification_command;
+pub use pdu_session_modification_command::PduSessionModificationCommand;
+
+mod pdu_session_modification_complete;
+pub use pdu_session_modification_complete::PduSessionModificationComplete;
+
+mod pdu_session_modification_command_reject;
+pub use pdu_session_modification_command_reject::PduSessionModificationCommandReject;
+
+mod pdu_session_release_request;
+pub use pdu_session_release_request::PduSessionReleaseRequest;
+
+mod pdu_session_release_reject;
+pub use pdu_session_release_reject::PduSessionReleaseReject;
+
+mod pdu_session_release_command;
+pub use pdu_session_release_command::PduSessionReleaseCommand;
+
+mod pdu_session_release_complete;
+pub use pdu_session_release_complete::PduSessionReleaseComplete;
+
+mod fivegsm_status;
+pub use fivegsm_status::FivegsmStatus;
+
+pub const SM_MSG_TYPE_PDU_SESSION_ESTABLISHMENT_REQUEST: u8 = 0xC1;
+pub const SM_MSG_TYPE_PDU_SESSION_ESTABLISHMENT_ACCEPT: u8 = 0xC2;
+pub const SM_MSG_TYPE_PDU_SESSION_ESTABLISHMENT_REJECT: u8 = 0xC3;
+pub const SM_MSG_TYPE_PDU_SESSION_AUTHENTICATION_COMMAND: u8 = 0xC5;
+pub const SM_MSG_TYPE_PDU_SESSION_AUTHENTICATION_COMPLETE: u8 = 0xC6;
+pub const SM_MSG_TYPE_PDU_SESSION_AUTHENTICATION_RESULT: u8 = 0xC7;
+pub const SM_MSG_TYPE_PDU_SESSION_MODIFICATION_REQUEST: u8 = 0xC9;
+pub const SM_MSG_TYPE_PDU_SESSION_MODIFICATION_REJECT: u8 = 0xCA;
+pub const SM_MSG_TYPE_PDU_SESSION_MODIFICATION_COMMAND: u8 = 0xCB;
+pub const SM_MSG_TYPE_PDU_SESSION_MODIFICATION_COMPLETE: u8 = 0xCC;
+pub const SM_MSG_TYPE_PDU_SESSION_MODIFICATION_COMMAND_REJECT: u8 = 0xCD;
+pub const SM_MSG_TYPE_PDU_SESSION_RELEASE_REQUEST: u8 = 0xD1;
+pub const SM_MSG_TYPE_PDU_SESSION_RELEASE_REJECT: u8 = 0xD2;
+pub const SM_MSG_TYPE_PDU_SESSION_RELEASE_COMMAND: u8 = 0xD3;
+pub const SM_MSG_TYPE_PDU_SESSION_RELEASE_COMPLETE: u8 = 0xD4;
+pub const SM_MSG_TYPE_FIVEGSM_STATUS: u8 = 0xD6;
+
+/// NAS 5G SM Message. The Message will be one of the 5G SM Messages.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Nas5gSmMessage {
+    PduSessionEstablishmentRequest(PduSessionEstablishmentRequest),
+    PduSessionEstablishmentAccept(PduSessionEstablishmentAccept),
+    PduSessionEstablishmentReject(PduSessionEstablishmentReject),
+    PduSessionAuthenticationCommand(PduSessionAuthenticationCommand),
+    PduSessionAuthenticationComplete(PduSessionAuthenticationComplete),
+    PduSessionAuthenticationResult(PduSessionAuthenticationResult),
+    PduSessionModificationRequest(PduSessionModificationRequest),
+    PduSessionModificationReject(PduSessionModificationReject),
+    PduSessionModificationCommand(PduSessionModificationCommand),
+    PduSessionModificationComplete(PduSessionModificationComplete),
+    PduSessionModificationCommandReject(PduSessionModificationCommandReject),
+    PduSessionReleaseRequest(PduSessionReleaseRequest),
+    PduSessionReleaseReject(PduSessionReleaseReject),
+    PduSessionReleaseCommand(PduSessionReleaseCommand),
+    PduSessionReleaseComplete(PduSessionReleaseComplete),
+    FivegsmStatus(FivegsmStatus),
+}
+
+impl Nas5gSmMessage {
+    pub fn encode(&self) -> Vec<u8> {
+        match self {
+            Self::PduSessionEstablishmentRequest(message) => message.encode(),
+            Self::PduSessionEstablishmentAccept(message) => message.encode(),
+            Self::PduSessionEstablishmentReject(message) => message.encode(),
+            Self::PduSessionAuthenticationCommand(message) => message.encode(),
+            Self::PduSessionAuthenticationComplete(message) => message.encode(),
+            Self::PduSessionAuthenticationResult(message) => message.encode(),
+            Self::PduSessionModificationRequest(message) => message.encode(),
+            Self::PduSessionModificationReject(message) => message.encode(),
+            Self::PduSessionModificationCommand(message) => message.encode(),
+            Self::PduSessionModificationComplete(message) => message.encode(),
+            Self::PduSessionModificationCommandReject(message) => message.encode(),
+            Self::PduSessionReleaseRequest(message) => message.encode(),
+            Self::PduSessionReleaseReject(message) => message.encode(),
+            Self::PduSessionReleaseCommand(message) => message.encode(),
+            Self::PduSessionReleaseComplete(message) => message.encode(),
+            Self::FivegsmStatus(message) => message.encode(),
+        }
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self, NasError> {
+        let (header, _) = Nas5gSmMessageHeader::decode(data)?;
+
+        let (message, decoded) = match header.message_type {
+            SM_MSG_TYPE_PDU_SESSION_ESTABLISHMENT_REQUEST => {
+                let (message, decoded) = PduSessionEstablishmentRequest::decode(data)?;
+                (Self::PduSessionEstablishmentRequest(message), decoded)
+            }
+            SM_MSG_TYPE_PDU_SESSION_ESTABLISHMENT_ACCEPT => {
+                let (message, decoded) = PduSessionEstablishmentAccept::decode(data)?;
+                (Self::PduSessionEstablishmentAccept(message), decoded)
+            }
+            SM_MSG_TYPE_PDU_SESSION_ESTABLISHMENT_REJECT => {
+                let (message, decoded) = PduSessionEstablishmentReject::decode(data)?;
+                (Self::PduSessionEstablishmentReject(message), decoded)
+            }
+            SM_MSG_TYPE_PDU_SESSION_AUTHENTICATION_COMMAND => {
+                let (message, decoded) = PduSessionAuthenticationCommand::decode(data)?;
+                (Self::PduSessionAuthenticationCommand(message), decoded)
+            }
+            SM_MSG_TYPE_PDU_SESSION_AUTHENTICATION_COMPLETE => {
+                let (message, decoded) = PduSessionAuthenticationComplete::decode(data)?;
+                (Self::PduSessionAuthenticationComplete(message), decoded)
+            }
+            SM_MSG_TYPE_PDU_SESSION_AUTHENTICATION_RESULT => {
+                let (message, decoded) = PduSessionAuthenticationResult::decode(data)?;
+                (Self::PduSessionAuthenticationResult(message), decoded)
+            }
+            SM_MSG_TYPE_PDU_SESSION_MODIFICATION_REQUEST => {
+                let (message, decoded) = PduSessionModificationRequest::decode(data)?;
+                (Self::PduSessionModificationRequest(message), decoded)
+            }
+            SM_MSG_TYPE_PDU_SESSION_MODIFICATION_REJECT => {
+                let (message, decoded) = PduSessionModificationReject::decode(data)?;
+                (Self::PduSessionModificationReject(message), decoded)
+            }
+            SM_MSG_TYPE_PDU_SESSION_MODIFICATION_COMMAND => {
+                let (message, decoded) = PduSessionModificationCommand::decode(data)?;
+                (Self::PduSessionModificationCommand(message), decoded)
+            }
+            SM_MSG_TYPE_PDU_SESSION_MODIFICATION_COMPLETE => {
+                let (message, decoded) = PduSessionModificationComplete::decode(data)?;
+                (Self::PduSessionModificationComplete(message), decoded)
+            }
+            SM_MSG_TYPE_PDU_SESSION_MODIFICATION_COMMAND_REJECT => {
+                let (message, decoded) = PduSessionModificationCommandReject::decode(data)?;
+                (Self::PduSessionModificationCommandReject(message), decoded)
+            }
+            SM_MSG_TYPE_PDU_SESSION_RELEASE_REQUEST => {
+                let (message, decoded) = PduSessionReleaseRequest::decode(data)?;
+                (Self::PduSessionReleaseRequest(message), decoded)
+            }
+            SM_MSG_TYPE_PDU_SESSION_RELEASE_REJECT => {
+                let (message, decoded) = PduSessionReleaseReject::decode(data)?;
+                (Self::PduSessionReleaseReject(message), decoded)
+            }
+            SM_MSG_TYPE_PDU_SESSION_RELEASE_COMMAND => {
+                let (message, decoded) = PduSessionReleaseCommand::decode(data)?;
+                (Self::PduSessionReleaseCommand(message), decoded)
+            }
+            SM_MSG_TYPE_PDU_SESSION_RELEASE_COMPLETE => {
+                let (message, decoded) = PduSessionReleaseComplete::decode(data)?;
+                (Self::PduSessionReleaseComplete(message), decoded)
+            }
+            SM_MSG_TYPE_FIVEGSM_STATUS => {
+                let (message, decoded) = FivegsmStatus::decode(data)?;
+                (Self::FivegsmStatus(message), decoded)
+            }
+            message_type => return Err(NasError::UnknownMessageType(message_type)),
+        };
+
+        if decoded != data.len() {
+            Err(NasError::UndecodedOctets {
+                decoded,
+                length: data.len(),
+            })
+        } else {
+            Ok(message)
+        }
+    }
+}
+
+// Decode the 5GSM Message Header and verify that it is for the expected message type.
+fn decode_header(data: &[u8], message_type: u8) -> Result<(Nas5gSmMessageHeader, usize), NasError> {
+    let (header, decoded) = Nas5gSmMessageHeader::decode(data)?;
+    if header.message_type != message_type {
+        return Err(NasError::UnexpectedMessageType {
+            expected: message_type,
+            received: header.message_type,
+        });
+    }
+
+    Ok((header, decoded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    use crate::messages::ies::{
+        PduSessionTypeValue, QosFlowOperationCode, QosRuleOperationCode, SscModeValue,
+    };
+
+    const ESTABLISHMENT_ACCEPT: &str =
+        "2e0501c211000901000631310101ff01060600010600012905010a2d0001220101790006012041010109\
+         7b000880000d0408080808250908696e7465726e6574";
+
+    const MESSAGES: [(&str, &str); 16] = [
+        (
+            "PduSessionEstablishmentRequest",
+            "2e0501c1ffff91a12801005502007b000780000a00000d00",
+        ),
+        ("PduSessionEstablishmentAccept", ESTABLISHMENT_ACCEPT),
+        ("PduSessionEstablishmentReject", "2e0501c31b370121f71d0100"),
+        ("PduSessionAuthenticationCommand", "2e0501c5000401010004"),
+        ("PduSessionAuthenticationComplete", "2e0501c6000402010004"),
+        ("PduSessionAuthenticationResult", "2e0501c778000403010004"),
+        (
+            "PduSessionModificationRequest",
+            "2e0501c928010013ffff7a000401000140",
+        ),
+        ("PduSessionModificationReject", "2e0501ca2b370121"),
+        (
+            "PduSessionModificationCommand",
+            "2e0501cb2a0606000106000156217a0005010002a102790003014000",
+        ),
+        ("PduSessionModificationComplete", "2e0501cc"),
+        ("PduSessionModificationCommandReject", "2e0501cd2b"),
+        ("PduSessionReleaseRequest", "2e0501d15924"),
+        ("PduSessionReleaseReject", "2e0501d22b"),
+        ("PduSessionReleaseCommand", "2e0501d324370121d1"),
+        ("PduSessionReleaseComplete", "2e0501d4"),
+        ("FivegsmStatus", "2e0501d662"),
+    ];
+
+    #[test]
+    fn roundtrip_5gsm_messages() {
+        for (name, message) in MESSAGES {
+            let data = hex::decode(message).unwrap();
+
+            let result = Nas5gSmMessage::decode(&data);
+            assert!(result.is_ok(), "{}: {:?}", name, result.err());
+
+            let decoded = result.unwrap();
+            assert!(format!("{:?}", decoded).starts_with(name), "{}", name);
+            assert_eq!(decoded.encode(), data, "{}", name);
+        }
+    }
+
+    #[test]
+    fn decode_pdu_session_establishment_accept() {
+        let data = hex::decode(ESTABLISHMENT_ACCEPT).unwrap();
+
+        let Ok(Nas5gSmMessage::PduSessionEstablishmentAccept(accept)) =
+            Nas5gSmMessage::decode(&data)
+        else {
+            panic!("Not a PDU Session Establishment Accept");
+        };
+
+        assert_eq!(accept.header.pdu_session_identity, 5);
+        assert_eq!(accept.header.proc_transaction_identity, 1);
+        assert_eq!(
+            accept.selected_pdu_session_type.value,
+            PduSessionTypeValue::Ipv4
+        );
+        assert_eq!(accept.selected_ssc_mode.value, SscModeValue::SscMode1);
+
+        let rule = &accept.authorized_qos_rules.rules[0];
+        assert_eq!(rule.identifier, 1);
+        assert_eq!(rule.operation_code, QosRuleOperationCode::Create);
+        assert!(rule.dqr);
+        assert_eq!(rule.packet_filters.len(), 1);
+        assert_eq!(rule.packet_filters[0].direction, 3);
+        assert_eq!(rule.precedence, Some(0xFF));
+        assert_eq!(rule.qfi, Some(1));
+
+        assert_eq!(accept.session_ambr.downlink, 1);
+        assert_eq!(accept.pdu_address.unwrap().address, [10, 45, 0, 1]);
+
+        let flow = &accept
+            .authorized_qos_flow_descriptions
+            .unwrap()
+            .descriptions[0];
+        assert_eq!(flow.operation_code, QosFlowOperationCode::Create);
+        assert_eq!(flow.parameters[0].contents, [9]);
+
+        let options = accept.extended_protocol_configuration_options.unwrap();
+        assert_eq!(options.options[0].identifier, 0x000D);
+        assert_eq!(options.options[0].contents, [8, 8, 8, 8]);
+    }
+
+    #[test]
+    fn decode_truncated_messages() {
+        // Decoding any prefix of a message must not panic.
+        for (_, message) in MESSAGES {
+            let data = hex::decode(message).unwrap();
+            for length in 0..data.len() {
+                let _ = Nas5gSmMessage::decode(&data[..length]);
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn decode_arbitrary_messages(
+            message_type in SM_MSG_TYPE_PDU_SESSION_ESTABLISHMENT_REQUEST..=SM_MSG_TYPE_FIVEGSM_STATUS,
+            body in proptest::collection::vec(any::<u8>(), 0..64)
+        ) {
+            let mut data = vec![0x2e, 0x05, 0x01, message_type];
+            data.extend(body);
+
+            let _ = Nas5gSmMessage::decode(&data);
+        }
+    }
+
+    #[test]
+    fn decode_errors() {
+        // Unknown message type
+        assert!(Nas5gSmMessage::decode(&[0x2e, 0x05, 0x01, 0xc0]).is_err());
+
+        // Unexpected message type
+        assert!(PduSessionReleaseReject::decode(&[0x2e, 0x05, 0x01, 0xd4]).is_err());
+
+        // Invalid QoS Rule operation code
+        assert_eq!(
+            Nas5gSmMessage::decode(&hex::decode("2e0501c97a0004010001e0").unwrap())
+                .map_err(|e| e.cause()),
+            Err(83)
+        );
+
+        // An octet with the upper half zero is not the IEI of a Type 1 IE. (Here PDU Session Type)
+        assert_eq!(
+            Nas5gSmMessage::decode(&hex::decode("2e0501c1ffff09").unwrap()),
+            Err(NasError::InvalidIei(0x09))
+        );
+    }
+
+    #[test]
+    fn roundtrip_packet_filters_to_delete() {
+        // The Packet Filter Direction bits are spare in the list of packet filters to delete.
+        let data = hex::decode("2e0501cb7a0005010002a131").unwrap();
+
+        let message = Nas5gSmMessage::decode(&data).unwrap();
+        let encoded = message.encode();
+        assert_eq!(encoded, hex::decode("2e0501cb7a0005010002a101").unwrap());
+        assert_eq!(Nas5gSmMessage::decode(&encoded).unwrap(), message);
+    }
+}
@@ -0,0 +1,34 @@
+//! 5G SM 5GSM Status
+
+use crate::messages::{headers::Nas5gSmMessageHeader, ies::FivegsmCause};
+use crate::NasError;
+
+use super::{decode_header, SM_MSG_TYPE_FIVEGSM_STATUS};
+
+/// 5GSM Status: 24.501 (Release 17) Section 8.3.16
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FivegsmStatus {
+    pub header: Nas5gSmMessageHeader,
+    pub cause: FivegsmCause,
+}
+
+impl FivegsmStatus {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+        output.extend(self.cause.encode(false));
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("FivegsmStatus decode.");
+        let (header, mut decoded) = decode_header(data, SM_MSG_TYPE_FIVEGSM_STATUS)?;
+
+        let (cause, cause_decoded) = FivegsmCause::decode(&data[decoded..], false)?;
+        decoded += cause_decoded;
+
+        Ok((Self { header, cause }, decoded))
+    }
+}
@@ -0,0 +1,67 @@
+//! 5G SM PDU Session Authentication Complete
+
+use crate::messages::{
+    headers::Nas5gSmMessageHeader,
+    ies::{optional_iei, skip_unknown_ie, EapMessage, ExtendedProtocolConfigurationOptions},
+};
+use crate::NasError;
+
+use super::{decode_header, SM_MSG_TYPE_PDU_SESSION_AUTHENTICATION_COMPLETE};
+
+/// PDU Session Authentication Complete: 24.501 (Release 17) Section 8.3.5
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PduSessionAuthenticationComplete {
+    pub header: Nas5gSmMessageHeader,
+    pub eap_message: EapMessage,
+    pub extended_protocol_configuration_options: Option<ExtendedProtocolConfigurationOptions>,
+}
+
+impl PduSessionAuthenticationComplete {
+    pub const EXTENDED_PROTOCOL_CONFIGURATION_OPTIONS_IEI: u8 = 0x7B;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+        output.extend(self.eap_message.encode(false));
+
+        if let Some(extended_protocol_configuration_options) =
+            &self.extended_protocol_configuration_options
+        {
+            output.extend(extended_protocol_configuration_options.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("PduSessionAuthenticationComplete decode.");
+        let (header, mut decoded) =
+            decode_header(data, SM_MSG_TYPE_PDU_SESSION_AUTHENTICATION_COMPLETE)?;
+
+        let (eap_message, eap_message_decoded) = EapMessage::decode(&data[decoded..], false)?;
+        decoded += eap_message_decoded;
+
+        let mut extended_protocol_configuration_options = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::EXTENDED_PROTOCOL_CONFIGURATION_OPTIONS_IEI => {
+                    let (ie, ie_decoded) =
+                        ExtendedProtocolConfigurationOptions::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    extended_protocol_configuration_options = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                eap_message,
+                extended_protocol_configuration_options,
+            },
+            decoded,
+        ))
+    }
+}
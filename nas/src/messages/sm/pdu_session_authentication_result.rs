@@ -0,0 +1,73 @@
+//! 5G SM PDU Session Authentication Result
+
+use crate::messages::{
+    headers::Nas5gSmMessageHeader,
+    ies::{optional_iei, skip_unknown_ie, EapMessage, ExtendedProtocolConfigurationOptions},
+};
+use crate::NasError;
+
+use super::{decode_header, SM_MSG_TYPE_PDU_SESSION_AUTHENTICATION_RESULT};
+
+/// PDU Session Authentication Result: 24.501 (Release 17) Section 8.3.6
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PduSessionAuthenticationResult {
+    pub header: Nas5gSmMessageHeader,
+    pub eap_message: Option<EapMessage>,
+    pub extended_protocol_configuration_options: Option<ExtendedProtocolConfigurationOptions>,
+}
+
+impl PduSessionAuthenticationResult {
+    pub const EAP_MESSAGE_IEI: u8 = 0x78;
+    pub const EXTENDED_PROTOCOL_CONFIGURATION_OPTIONS_IEI: u8 = 0x7B;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+
+        if let Some(eap_message) = &self.eap_message {
+            output.extend(eap_message.encode(true));
+        }
+        if let Some(extended_protocol_configuration_options) =
+            &self.extended_protocol_configuration_options
+        {
+            output.extend(extended_protocol_configuration_options.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("PduSessionAuthenticationResult decode.");
+        let (header, mut decoded) =
+            decode_header(data, SM_MSG_TYPE_PDU_SESSION_AUTHENTICATION_RESULT)?;
+
+        let mut eap_message = None;
+        let mut extended_protocol_configuration_options = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::EAP_MESSAGE_IEI => {
+                    let (ie, ie_decoded) = EapMessage::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    eap_message = Some(ie);
+                }
+                Self::EXTENDED_PROTOCOL_CONFIGURATION_OPTIONS_IEI => {
+                    let (ie, ie_decoded) =
+                        ExtendedProtocolConfigurationOptions::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    extended_protocol_configuration_options = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                eap_message,
+                extended_protocol_configuration_options,
+            },
+            decoded,
+        ))
+    }
+}
@@ -0,0 +1,283 @@
+//! 5G SM PDU Session Establishment Accept
+
+use crate::messages::{
+    headers::Nas5gSmMessageHeader,
+    ies::{
+        optional_iei, skip_unknown_ie, AlwaysOnPduSessionIndication, AtsssContainer,
+        ControlPlaneOnlyIndication, Dnn, EapMessage, EthernetHeaderCompressionConfiguration,
+        ExtendedProtocolConfigurationOptions, FivegsmCause, FivegsmNetworkFeatureSupport,
+        GprsTimer, IpHeaderCompressionConfiguration, MappedEpsBearerContexts, PduAddress,
+        PduSessionType, QosFlowDescriptions, QosRules, SNssai, ServingPlmnRateControl, SessionAmbr,
+        SscMode,
+    },
+};
+use crate::NasError;
+
+use super::{decode_header, SM_MSG_TYPE_PDU_SESSION_ESTABLISHMENT_ACCEPT};
+
+/// PDU Session Establishment Accept: 24.501 (Release 17) Section 8.3.2
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PduSessionEstablishmentAccept {
+    pub header: Nas5gSmMessageHeader,
+    pub selected_pdu_session_type: PduSessionType,
+    pub selected_ssc_mode: SscMode,
+    pub authorized_qos_rules: QosRules,
+    pub session_ambr: SessionAmbr,
+    pub cause: Option<FivegsmCause>,
+    pub pdu_address: Option<PduAddress>,
+    pub rq_timer: Option<GprsTimer>,
+    pub s_nssai: Option<SNssai>,
+    pub always_on_pdu_session_indication: Option<AlwaysOnPduSessionIndication>,
+    pub mapped_eps_bearer_contexts: Option<MappedEpsBearerContexts>,
+    pub eap_message: Option<EapMessage>,
+    pub authorized_qos_flow_descriptions: Option<QosFlowDescriptions>,
+    pub extended_protocol_configuration_options: Option<ExtendedProtocolConfigurationOptions>,
+    pub dnn: Option<Dnn>,
+    pub network_feature_support: Option<FivegsmNetworkFeatureSupport>,
+    pub serving_plmn_rate_control: Option<ServingPlmnRateControl>,
+    pub atsss_container: Option<AtsssContainer>,
+    pub control_plane_only_indication: Option<ControlPlaneOnlyIndication>,
+    pub ip_header_compression_configuration: Option<IpHeaderCompressionConfiguration>,
+    pub ethernet_header_compression_configuration: Option<EthernetHeaderCompressionConfiguration>,
+}
+
+impl PduSessionEstablishmentAccept {
+    pub const CAUSE_IEI: u8 = 0x59;
+    pub const PDU_ADDRESS_IEI: u8 = 0x29;
+    pub const RQ_TIMER_IEI: u8 = 0x56;
+    pub const S_NSSAI_IEI: u8 = 0x22;
+    pub const ALWAYS_ON_PDU_SESSION_INDICATION_IEI: u8 = 0x08;
+    pub const MAPPED_EPS_BEARER_CONTEXTS_IEI: u8 = 0x75;
+    pub const EAP_MESSAGE_IEI: u8 = 0x78;
+    pub const AUTHORIZED_QOS_FLOW_DESCRIPTIONS_IEI: u8 = 0x79;
+    pub const EXTENDED_PROTOCOL_CONFIGURATION_OPTIONS_IEI: u8 = 0x7B;
+    pub const DNN_IEI: u8 = 0x25;
+    pub const NETWORK_FEATURE_SUPPORT_IEI: u8 = 0x17;
+    pub const SERVING_PLMN_RATE_CONTROL_IEI: u8 = 0x18;
+    pub const ATSSS_CONTAINER_IEI: u8 = 0x77;
+    pub const CONTROL_PLANE_ONLY_INDICATION_IEI: u8 = 0x0C;
+    pub const IP_HEADER_COMPRESSION_CONFIGURATION_IEI: u8 = 0x66;
+    pub const ETHERNET_HEADER_COMPRESSION_CONFIGURATION_IEI: u8 = 0x1F;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+        output.push(
+            self.selected_pdu_session_type.encode(false, false)[0]
+                | self.selected_ssc_mode.encode(false, true)[0],
+        );
+        output.extend(self.authorized_qos_rules.encode(false));
+        output.extend(self.session_ambr.encode(false));
+
+        if let Some(cause) = &self.cause {
+            output.extend(cause.encode(true));
+        }
+        if let Some(pdu_address) = &self.pdu_address {
+            output.extend(pdu_address.encode(true));
+        }
+        if let Some(rq_timer) = &self.rq_timer {
+            output.extend(rq_timer.encode(true));
+        }
+        if let Some(s_nssai) = &self.s_nssai {
+            output.extend(s_nssai.encode(true));
+        }
+        if let Some(always_on_pdu_session_indication) = &self.always_on_pdu_session_indication {
+            output.extend(always_on_pdu_session_indication.encode(true, false));
+        }
+        if let Some(mapped_eps_bearer_contexts) = &self.mapped_eps_bearer_contexts {
+            output.extend(mapped_eps_bearer_contexts.encode(true));
+        }
+        if let Some(eap_message) = &self.eap_message {
+            output.extend(eap_message.encode(true));
+        }
+        if let Some(authorized_qos_flow_descriptions) = &self.authorized_qos_flow_descriptions {
+            output.extend(authorized_qos_flow_descriptions.encode(true));
+        }
+        if let Some(extended_protocol_configuration_options) =
+            &self.extended_protocol_configuration_options
+        {
+            output.extend(extended_protocol_configuration_options.encode(true));
+        }
+        if let Some(dnn) = &self.dnn {
+            output.extend(dnn.encode(true));
+        }
+        if let Some(network_feature_support) = &self.network_feature_support {
+            output.extend(network_feature_support.encode(true));
+        }
+        if let Some(serving_plmn_rate_control) = &self.serving_plmn_rate_control {
+            output.extend(serving_plmn_rate_control.encode(true));
+        }
+        if let Some(atsss_container) = &self.atsss_container {
+            output.extend(atsss_container.encode(true));
+        }
+        if let Some(control_plane_only_indication) = &self.control_plane_only_indication {
+            output.extend(control_plane_only_indication.encode(true, false));
+        }
+        if let Some(ip_header_compression_configuration) = &self.ip_header_compression_configuration
+        {
+            output.extend(ip_header_compression_configuration.encode(true));
+        }
+        if let Some(ethernet_header_compression_configuration) =
+            &self.ethernet_header_compression_configuration
+        {
+            output.extend(ethernet_header_compression_configuration.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("PduSessionEstablishmentAccept decode.");
+        let (header, mut decoded) =
+            decode_header(data, SM_MSG_TYPE_PDU_SESSION_ESTABLISHMENT_ACCEPT)?;
+
+        let (selected_pdu_session_type, _) =
+            PduSessionType::decode(&data[decoded..], false, false)?;
+        let (selected_ssc_mode, _) = SscMode::decode(&data[decoded..], false, true)?;
+        // Both the above share an octet.
+        decoded += 1;
+
+        let (authorized_qos_rules, authorized_qos_rules_decoded) =
+            QosRules::decode(&data[decoded..], false)?;
+        decoded += authorized_qos_rules_decoded;
+
+        let (session_ambr, session_ambr_decoded) = SessionAmbr::decode(&data[decoded..], false)?;
+        decoded += session_ambr_decoded;
+
+        let mut cause = None;
+        let mut pdu_address = None;
+        let mut rq_timer = None;
+        let mut s_nssai = None;
+        let mut always_on_pdu_session_indication = None;
+        let mut mapped_eps_bearer_contexts = None;
+        let mut eap_message = None;
+        let mut authorized_qos_flow_descriptions = None;
+        let mut extended_protocol_configuration_options = None;
+        let mut dnn = None;
+        let mut network_feature_support = None;
+        let mut serving_plmn_rate_control = None;
+        let mut atsss_container = None;
+        let mut control_plane_only_indication = None;
+        let mut ip_header_compression_configuration = None;
+        let mut ethernet_header_compression_configuration = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::CAUSE_IEI => {
+                    let (ie, ie_decoded) = FivegsmCause::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    cause = Some(ie);
+                }
+                Self::PDU_ADDRESS_IEI => {
+                    let (ie, ie_decoded) = PduAddress::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    pdu_address = Some(ie);
+                }
+                Self::RQ_TIMER_IEI => {
+                    let (ie, ie_decoded) = GprsTimer::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    rq_timer = Some(ie);
+                }
+                Self::S_NSSAI_IEI => {
+                    let (ie, ie_decoded) = SNssai::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    s_nssai = Some(ie);
+                }
+                Self::ALWAYS_ON_PDU_SESSION_INDICATION_IEI => {
+                    let (ie, _) =
+                        AlwaysOnPduSessionIndication::decode(&data[decoded..], true, false)?;
+                    decoded += 1;
+                    always_on_pdu_session_indication = Some(ie);
+                }
+                Self::MAPPED_EPS_BEARER_CONTEXTS_IEI => {
+                    let (ie, ie_decoded) = MappedEpsBearerContexts::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    mapped_eps_bearer_contexts = Some(ie);
+                }
+                Self::EAP_MESSAGE_IEI => {
+                    let (ie, ie_decoded) = EapMessage::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    eap_message = Some(ie);
+                }
+                Self::AUTHORIZED_QOS_FLOW_DESCRIPTIONS_IEI => {
+                    let (ie, ie_decoded) = QosFlowDescriptions::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    authorized_qos_flow_descriptions = Some(ie);
+                }
+                Self::EXTENDED_PROTOCOL_CONFIGURATION_OPTIONS_IEI => {
+                    let (ie, ie_decoded) =
+                        ExtendedProtocolConfigurationOptions::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    extended_protocol_configuration_options = Some(ie);
+                }
+                Self::DNN_IEI => {
+                    let (ie, ie_decoded) = Dnn::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    dnn = Some(ie);
+                }
+                Self::NETWORK_FEATURE_SUPPORT_IEI => {
+                    let (ie, ie_decoded) =
+                        FivegsmNetworkFeatureSupport::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    network_feature_support = Some(ie);
+                }
+                Self::SERVING_PLMN_RATE_CONTROL_IEI => {
+                    let (ie, ie_decoded) = ServingPlmnRateControl::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    serving_plmn_rate_control = Some(ie);
+                }
+                Self::ATSSS_CONTAINER_IEI => {
+                    let (ie, ie_decoded) = AtsssContainer::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    atsss_container = Some(ie);
+                }
+                Self::CONTROL_PLANE_ONLY_INDICATION_IEI => {
+                    let (ie, _) =
+                        ControlPlaneOnlyIndication::decode(&data[decoded..], true, false)?;
+                    decoded += 1;
+                    control_plane_only_indication = Some(ie);
+                }
+                Self::IP_HEADER_COMPRESSION_CONFIGURATION_IEI => {
+                    let (ie, ie_decoded) =
+                        IpHeaderCompressionConfiguration::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    ip_header_compression_configuration = Some(ie);
+                }
+                Self::ETHERNET_HEADER_COMPRESSION_CONFIGURATION_IEI => {
+                    let (ie, ie_decoded) =
+                        EthernetHeaderCompressionConfiguration::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    ethernet_header_compression_configuration = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                selected_pdu_session_type,
+                selected_ssc_mode,
+                authorized_qos_rules,
+                session_ambr,
+                cause,
+                pdu_address,
+                rq_timer,
+                s_nssai,
+                always_on_pdu_session_indication,
+                mapped_eps_bearer_contexts,
+                eap_message,
+                authorized_qos_flow_descriptions,
+                extended_protocol_configuration_options,
+                dnn,
+                network_feature_support,
+                serving_plmn_rate_control,
+                atsss_container,
+                control_plane_only_indication,
+                ip_header_compression_configuration,
+                ethernet_header_compression_configuration,
+            },
+            decoded,
+        ))
+    }
+}
@@ -0,0 +1,132 @@
+//! 5G SM PDU Session Establishment Reject
+
+use crate::messages::{
+    headers::Nas5gSmMessageHeader,
+    ies::{
+        optional_iei, skip_unknown_ie, AllowedSscMode, EapMessage,
+        ExtendedProtocolConfigurationOptions, FivegsmCause, FivegsmCongestionReAttemptIndicator,
+        GprsTimer3, ReAttemptIndicator,
+    },
+};
+use crate::NasError;
+
+use super::{decode_header, SM_MSG_TYPE_PDU_SESSION_ESTABLISHMENT_REJECT};
+
+/// PDU Session Establishment Reject: 24.501 (Release 17) Section 8.3.3
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PduSessionEstablishmentReject {
+    pub header: Nas5gSmMessageHeader,
+    pub cause: FivegsmCause,
+    pub back_off_timer: Option<GprsTimer3>,
+    pub allowed_ssc_mode: Option<AllowedSscMode>,
+    pub eap_message: Option<EapMessage>,
+    pub congestion_re_attempt_indicator: Option<FivegsmCongestionReAttemptIndicator>,
+    pub extended_protocol_configuration_options: Option<ExtendedProtocolConfigurationOptions>,
+    pub re_attempt_indicator: Option<ReAttemptIndicator>,
+}
+
+impl PduSessionEstablishmentReject {
+    pub const BACK_OFF_TIMER_IEI: u8 = 0x37;
+    pub const ALLOWED_SSC_MODE_IEI: u8 = 0x0F;
+    pub const EAP_MESSAGE_IEI: u8 = 0x78;
+    pub const CONGESTION_RE_ATTEMPT_INDICATOR_IEI: u8 = 0x61;
+    pub const EXTENDED_PROTOCOL_CONFIGURATION_OPTIONS_IEI: u8 = 0x7B;
+    pub const RE_ATTEMPT_INDICATOR_IEI: u8 = 0x1D;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+        output.extend(self.cause.encode(false));
+
+        if let Some(back_off_timer) = &self.back_off_timer {
+            output.extend(back_off_timer.encode(true));
+        }
+        if let Some(allowed_ssc_mode) = &self.allowed_ssc_mode {
+            output.extend(allowed_ssc_mode.encode(true, false));
+        }
+        if let Some(eap_message) = &self.eap_message {
+            output.extend(eap_message.encode(true));
+        }
+        if let Some(congestion_re_attempt_indicator) = &self.congestion_re_attempt_indicator {
+            output.extend(congestion_re_attempt_indicator.encode(true));
+        }
+        if let Some(extended_protocol_configuration_options) =
+            &self.extended_protocol_configuration_options
+        {
+            output.extend(extended_protocol_configuration_options.encode(true));
+        }
+        if let Some(re_attempt_indicator) = &self.re_attempt_indicator {
+            output.extend(re_attempt_indicator.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("PduSessionEstablishmentReject decode.");
+        let (header, mut decoded) =
+            decode_header(data, SM_MSG_TYPE_PDU_SESSION_ESTABLISHMENT_REJECT)?;
+
+        let (cause, cause_decoded) = FivegsmCause::decode(&data[decoded..], false)?;
+        decoded += cause_decoded;
+
+        let mut back_off_timer = None;
+        let mut allowed_ssc_mode = None;
+        let mut eap_message = None;
+        let mut congestion_re_attempt_indicator = None;
+        let mut extended_protocol_configuration_options = None;
+        let mut re_attempt_indicator = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::BACK_OFF_TIMER_IEI => {
+                    let (ie, ie_decoded) = GprsTimer3::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    back_off_timer = Some(ie);
+                }
+                Self::ALLOWED_SSC_MODE_IEI => {
+                    let (ie, _) = AllowedSscMode::decode(&data[decoded..], true, false)?;
+                    decoded += 1;
+                    allowed_ssc_mode = Some(ie);
+                }
+                Self::EAP_MESSAGE_IEI => {
+                    let (ie, ie_decoded) = EapMessage::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    eap_message = Some(ie);
+                }
+                Self::CONGESTION_RE_ATTEMPT_INDICATOR_IEI => {
+                    let (ie, ie_decoded) =
+                        FivegsmCongestionReAttemptIndicator::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    congestion_re_attempt_indicator = Some(ie);
+                }
+                Self::EXTENDED_PROTOCOL_CONFIGURATION_OPTIONS_IEI => {
+                    let (ie, ie_decoded) =
+                        ExtendedProtocolConfigurationOptions::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    extended_protocol_configuration_options = Some(ie);
+                }
+                Self::RE_ATTEMPT_INDICATOR_IEI => {
+                    let (ie, ie_decoded) = ReAttemptIndicator::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    re_attempt_indicator = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                cause,
+                back_off_timer,
+                allowed_ssc_mode,
+                eap_message,
+                congestion_re_attempt_indicator,
+                extended_protocol_configuration_options,
+                re_attempt_indicator,
+            },
+            decoded,
+        ))
+    }
+}
@@ -0,0 +1,233 @@
+//! 5G SM PDU Session Establishment Request
+
+use crate::messages::{
+    headers::Nas5gSmMessageHeader,
+    ies::{
+        optional_iei, skip_unknown_ie, AlwaysOnPduSessionRequested, DsTtEthernetPortMacAddress,
+        EthernetHeaderCompressionConfiguration, ExtendedProtocolConfigurationOptions,
+        FivegsmCapability, IntegrityProtectionMaximumDataRate, IpHeaderCompressionConfiguration,
+        MaximumNumberOfSupportedPacketFilters, PduAddress, PduSessionType,
+        PortManagementInformationContainer, SmPduDnRequestContainer, SscMode, UeDsTtResidenceTime,
+    },
+};
+use crate::NasError;
+
+use super::{decode_header, SM_MSG_TYPE_PDU_SESSION_ESTABLISHMENT_REQUEST};
+
+/// PDU Session Establishment Request: 24.501 (Release 17) Section 8.3.1
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PduSessionEstablishmentRequest {
+    pub header: Nas5gSmMessageHeader,
+    pub integrity_protection_maximum_data_rate: IntegrityProtectionMaximumDataRate,
+    pub pdu_session_type: Option<PduSessionType>,
+    pub ssc_mode: Option<SscMode>,
+    pub capability: Option<FivegsmCapability>,
+    pub maximum_number_of_supported_packet_filters: Option<MaximumNumberOfSupportedPacketFilters>,
+    pub always_on_pdu_session_requested: Option<AlwaysOnPduSessionRequested>,
+    pub sm_pdu_dn_request_container: Option<SmPduDnRequestContainer>,
+    pub extended_protocol_configuration_options: Option<ExtendedProtocolConfigurationOptions>,
+    pub ip_header_compression_configuration: Option<IpHeaderCompressionConfiguration>,
+    pub ds_tt_ethernet_port_mac_address: Option<DsTtEthernetPortMacAddress>,
+    pub ue_ds_tt_residence_time: Option<UeDsTtResidenceTime>,
+    pub port_management_information_container: Option<PortManagementInformationContainer>,
+    pub ethernet_header_compression_configuration: Option<EthernetHeaderCompressionConfiguration>,
+    pub suggested_interface_identifier: Option<PduAddress>,
+}
+
+impl PduSessionEstablishmentRequest {
+    pub const PDU_SESSION_TYPE_IEI: u8 = 0x09;
+    pub const SSC_MODE_IEI: u8 = 0x0A;
+    pub const CAPABILITY_IEI: u8 = 0x28;
+    pub const MAXIMUM_NUMBER_OF_SUPPORTED_PACKET_FILTERS_IEI: u8 = 0x55;
+    pub const ALWAYS_ON_PDU_SESSION_REQUESTED_IEI: u8 = 0x0B;
+    pub const SM_PDU_DN_REQUEST_CONTAINER_IEI: u8 = 0x39;
+    pub const EXTENDED_PROTOCOL_CONFIGURATION_OPTIONS_IEI: u8 = 0x7B;
+    pub const IP_HEADER_COMPRESSION_CONFIGURATION_IEI: u8 = 0x66;
+    pub const DS_TT_ETHERNET_PORT_MAC_ADDRESS_IEI: u8 = 0x6E;
+    pub const UE_DS_TT_RESIDENCE_TIME_IEI: u8 = 0x6F;
+    pub const PORT_MANAGEMENT_INFORMATION_CONTAINER_IEI: u8 = 0x74;
+    pub const ETHERNET_HEADER_COMPRESSION_CONFIGURATION_IEI: u8 = 0x1F;
+    pub const SUGGESTED_INTERFACE_IDENTIFIER_IEI: u8 = 0x29;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+        output.extend(self.integrity_protection_maximum_data_rate.encode(false));
+
+        if let Some(pdu_session_type) = &self.pdu_session_type {
+            output.extend(pdu_session_type.encode(true, false));
+        }
+        if let Some(ssc_mode) = &self.ssc_mode {
+            output.extend(ssc_mode.encode(true, false));
+        }
+        if let Some(capability) = &self.capability {
+            output.extend(capability.encode(true));
+        }
+        if let Some(maximum_number_of_supported_packet_filters) =
+            &self.maximum_number_of_supported_packet_filters
+        {
+            output.extend(maximum_number_of_supported_packet_filters.encode(true));
+        }
+        if let Some(always_on_pdu_session_requested) = &self.always_on_pdu_session_requested {
+            output.extend(always_on_pdu_session_requested.encode(true, false));
+        }
+        if let Some(sm_pdu_dn_request_container) = &self.sm_pdu_dn_request_container {
+            output.extend(sm_pdu_dn_request_container.encode(true));
+        }
+        if let Some(extended_protocol_configuration_options) =
+            &self.extended_protocol_configuration_options
+        {
+            output.extend(extended_protocol_configuration_options.encode(true));
+        }
+        if let Some(ip_header_compression_configuration) = &self.ip_header_compression_configuration
+        {
+            output.extend(ip_header_compression_configuration.encode(true));
+        }
+        if let Some(ds_tt_ethernet_port_mac_address) = &self.ds_tt_ethernet_port_mac_address {
+            output.extend(ds_tt_ethernet_port_mac_address.encode(true));
+        }
+        if let Some(ue_ds_tt_residence_time) = &self.ue_ds_tt_residence_time {
+            output.extend(ue_ds_tt_residence_time.encode(true));
+        }
+        if let Some(port_management_information_container) =
+            &self.port_management_information_container
+        {
+            output.extend(port_management_information_container.encode(true));
+        }
+        if let Some(ethernet_header_compression_configuration) =
+            &self.ethernet_header_compression_configuration
+        {
+            output.extend(ethernet_header_compression_configuration.encode(true));
+        }
+        if let Some(suggested_interface_identifier) = &self.suggested_interface_identifier {
+            output.extend(suggested_interface_identifier.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("PduSessionEstablishmentRequest decode.");
+        let (header, mut decoded) =
+            decode_header(data, SM_MSG_TYPE_PDU_SESSION_ESTABLISHMENT_REQUEST)?;
+
+        let (
+            integrity_protection_maximum_data_rate,
+            integrity_protection_maximum_data_rate_decoded,
+        ) = IntegrityProtectionMaximumDataRate::decode(&data[decoded..], false)?;
+        decoded += integrity_protection_maximum_data_rate_decoded;
+
+        let mut pdu_session_type = None;
+        let mut ssc_mode = None;
+        let mut capability = None;
+        let mut maximum_number_of_supported_packet_filters = None;
+        let mut always_on_pdu_session_requested = None;
+        let mut sm_pdu_dn_request_container = None;
+        let mut extended_protocol_configuration_options = None;
+        let mut ip_header_compression_configuration = None;
+        let mut ds_tt_ethernet_port_mac_address = None;
+        let mut ue_ds_tt_residence_time = None;
+        let mut port_management_information_container = None;
+        let mut ethernet_header_compression_configuration = None;
+        let mut suggested_interface_identifier = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::PDU_SESSION_TYPE_IEI => {
+                    let (ie, _) = PduSessionType::decode(&data[decoded..], true, false)?;
+                    decoded += 1;
+                    pdu_session_type = Some(ie);
+                }
+                Self::SSC_MODE_IEI => {
+                    let (ie, _) = SscMode::decode(&data[decoded..], true, false)?;
+                    decoded += 1;
+                    ssc_mode = Some(ie);
+                }
+                Self::CAPABILITY_IEI => {
+                    let (ie, ie_decoded) = FivegsmCapability::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    capability = Some(ie);
+                }
+                Self::MAXIMUM_NUMBER_OF_SUPPORTED_PACKET_FILTERS_IEI => {
+                    let (ie, ie_decoded) =
+                        MaximumNumberOfSupportedPacketFilters::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    maximum_number_of_supported_packet_filters = Some(ie);
+                }
+                Self::ALWAYS_ON_PDU_SESSION_REQUESTED_IEI => {
+                    let (ie, _) =
+                        AlwaysOnPduSessionRequested::decode(&data[decoded..], true, false)?;
+                    decoded += 1;
+                    always_on_pdu_session_requested = Some(ie);
+                }
+                Self::SM_PDU_DN_REQUEST_CONTAINER_IEI => {
+                    let (ie, ie_decoded) = SmPduDnRequestContainer::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    sm_pdu_dn_request_container = Some(ie);
+                }
+                Self::EXTENDED_PROTOCOL_CONFIGURATION_OPTIONS_IEI => {
+                    let (ie, ie_decoded) =
+                        ExtendedProtocolConfigurationOptions::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    extended_protocol_configuration_options = Some(ie);
+                }
+                Self::IP_HEADER_COMPRESSION_CONFIGURATION_IEI => {
+                    let (ie, ie_decoded) =
+                        IpHeaderCompressionConfiguration::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    ip_header_compression_configuration = Some(ie);
+                }
+                Self::DS_TT_ETHERNET_PORT_MAC_ADDRESS_IEI => {
+                    let (ie, ie_decoded) =
+                        DsTtEthernetPortMacAddress::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    ds_tt_ethernet_port_mac_address = Some(ie);
+                }
+                Self::UE_DS_TT_RESIDENCE_TIME_IEI => {
+                    let (ie, ie_decoded) = UeDsTtResidenceTime::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    ue_ds_tt_residence_time = Some(ie);
+                }
+                Self::PORT_MANAGEMENT_INFORMATION_CONTAINER_IEI => {
+                    let (ie, ie_decoded) =
+                        PortManagementInformationContainer::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    port_management_information_container = Some(ie);
+                }
+                Self::ETHERNET_HEADER_COMPRESSION_CONFIGURATION_IEI => {
+                    let (ie, ie_decoded) =
+                        EthernetHeaderCompressionConfiguration::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    ethernet_header_compression_configuration = Some(ie);
+                }
+                Self::SUGGESTED_INTERFACE_IDENTIFIER_IEI => {
+                    let (ie, ie_decoded) = PduAddress::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    suggested_interface_identifier = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                integrity_protection_maximum_data_rate,
+                pdu_session_type,
+                ssc_mode,
+                capability,
+                maximum_number_of_supported_packet_filters,
+                always_on_pdu_session_requested,
+                sm_pdu_dn_request_container,
+                extended_protocol_configuration_options,
+                ip_header_compression_configuration,
+                ds_tt_ethernet_port_mac_address,
+                ue_ds_tt_residence_time,
+                port_management_information_container,
+                ethernet_header_compression_configuration,
+                suggested_interface_identifier,
+            },
+            decoded,
+        ))
+    }
+}
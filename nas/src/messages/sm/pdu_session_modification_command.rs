@@ -0,0 +1,220 @@
+//! 5G SM PDU Session Modification Command
+
+use crate::messages::{
+    headers::Nas5gSmMessageHeader,
+    ies::{
+        optional_iei, skip_unknown_ie, AlwaysOnPduSessionIndication, AtsssContainer,
+        EthernetHeaderCompressionConfiguration, ExtendedProtocolConfigurationOptions, FivegsmCause,
+        GprsTimer, IpHeaderCompressionConfiguration, MappedEpsBearerContexts,
+        PortManagementInformationContainer, QosFlowDescriptions, QosRules, ServingPlmnRateControl,
+        SessionAmbr,
+    },
+};
+use crate::NasError;
+
+use super::{decode_header, SM_MSG_TYPE_PDU_SESSION_MODIFICATION_COMMAND};
+
+/// PDU Session Modification Command: 24.501 (Release 17) Section 8.3.9
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PduSessionModificationCommand {
+    pub header: Nas5gSmMessageHeader,
+    pub cause: Option<FivegsmCause>,
+    pub session_ambr: Option<SessionAmbr>,
+    pub rq_timer: Option<GprsTimer>,
+    pub always_on_pdu_session_indication: Option<AlwaysOnPduSessionIndication>,
+    pub authorized_qos_rules: Option<QosRules>,
+    pub mapped_eps_bearer_contexts: Option<MappedEpsBearerContexts>,
+    pub authorized_qos_flow_descriptions: Option<QosFlowDescriptions>,
+    pub extended_protocol_configuration_options: Option<ExtendedProtocolConfigurationOptions>,
+    pub atsss_container: Option<AtsssContainer>,
+    pub ip_header_compression_configuration: Option<IpHeaderCompressionConfiguration>,
+    pub port_management_information_container: Option<PortManagementInformationContainer>,
+    pub serving_plmn_rate_control: Option<ServingPlmnRateControl>,
+    pub ethernet_header_compression_configuration: Option<EthernetHeaderCompressionConfiguration>,
+}
+
+impl PduSessionModificationCommand {
+    pub const CAUSE_IEI: u8 = 0x59;
+    pub const SESSION_AMBR_IEI: u8 = 0x2A;
+    pub const RQ_TIMER_IEI: u8 = 0x56;
+    pub const ALWAYS_ON_PDU_SESSION_INDICATION_IEI: u8 = 0x08;
+    pub const AUTHORIZED_QOS_RULES_IEI: u8 = 0x7A;
+    pub const MAPPED_EPS_BEARER_CONTEXTS_IEI: u8 = 0x75;
+    pub const AUTHORIZED_QOS_FLOW_DESCRIPTIONS_IEI: u8 = 0x79;
+    pub const EXTENDED_PROTOCOL_CONFIGURATION_OPTIONS_IEI: u8 = 0x7B;
+    pub const ATSSS_CONTAINER_IEI: u8 = 0x77;
+    pub const IP_HEADER_COMPRESSION_CONFIGURATION_IEI: u8 = 0x66;
+    pub const PORT_MANAGEMENT_INFORMATION_CONTAINER_IEI: u8 = 0x74;
+    pub const SERVING_PLMN_RATE_CONTROL_IEI: u8 = 0x1E;
+    pub const ETHERNET_HEADER_COMPRESSION_CONFIGURATION_IEI: u8 = 0x1F;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+
+        if let Some(cause) = &self.cause {
+            output.extend(cause.encode(true));
+        }
+        if let Some(session_ambr) = &self.session_ambr {
+            output.extend(session_ambr.encode(true));
+        }
+        if let Some(rq_timer) = &self.rq_timer {
+            output.extend(rq_timer.encode(true));
+        }
+        if let Some(always_on_pdu_session_indication) = &self.always_on_pdu_session_indication {
+            output.extend(always_on_pdu_session_indication.encode(true, false));
+        }
+        if let Some(authorized_qos_rules) = &self.authorized_qos_rules {
+            output.extend(authorized_qos_rules.encode(true));
+        }
+        if let Some(mapped_eps_bearer_contexts) = &self.mapped_eps_bearer_contexts {
+            output.extend(mapped_eps_bearer_contexts.encode(true));
+        }
+        if let Some(authorized_qos_flow_descriptions) = &self.authorized_qos_flow_descriptions {
+            output.extend(authorized_qos_flow_descriptions.encode(true));
+        }
+        if let Some(extended_protocol_configuration_options) =
+            &self.extended_protocol_configuration_options
+        {
+            output.extend(extended_protocol_configuration_options.encode(true));
+        }
+        if let Some(atsss_container) = &self.atsss_container {
+            output.extend(atsss_container.encode(true));
+        }
+        if let Some(ip_header_compression_configuration) = &self.ip_header_compression_configuration
+        {
+            output.extend(ip_header_compression_configuration.encode(true));
+        }
+        if let Some(port_management_information_container) =
+            &self.port_management_information_container
+        {
+            output.extend(port_management_information_container.encode(true));
+        }
+        if let Some(serving_plmn_rate_control) = &self.serving_plmn_rate_control {
+            output.extend(serving_plmn_rate_control.encode(true));
+        }
+        if let Some(ethernet_header_compression_configuration) =
+            &self.ethernet_header_compression_configuration
+        {
+            output.extend(ethernet_header_compression_configuration.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("PduSessionModificationCommand decode.");
+        let (header, mut decoded) =
+            decode_header(data, SM_MSG_TYPE_PDU_SESSION_MODIFICATION_COMMAND)?;
+
+        let mut cause = None;
+        let mut session_ambr = None;
+        let mut rq_timer = None;
+        let mut always_on_pdu_session_indication = None;
+        let mut authorized_qos_rules = None;
+        let mut mapped_eps_bearer_contexts = None;
+        let mut authorized_qos_flow_descriptions = None;
+        let mut extended_protocol_configuration_options = None;
+        let mut atsss_container = None;
+        let mut ip_header_compression_configuration = None;
+        let mut port_management_information_container = None;
+        let mut serving_plmn_rate_control = None;
+        let mut ethernet_header_compression_configuration = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::CAUSE_IEI => {
+                    let (ie, ie_decoded) = FivegsmCause::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    cause = Some(ie);
+                }
+                Self::SESSION_AMBR_IEI => {
+                    let (ie, ie_decoded) = SessionAmbr::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    session_ambr = Some(ie);
+                }
+                Self::RQ_TIMER_IEI => {
+                    let (ie, ie_decoded) = GprsTimer::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    rq_timer = Some(ie);
+                }
+                Self::ALWAYS_ON_PDU_SESSION_INDICATION_IEI => {
+                    let (ie, _) =
+                        AlwaysOnPduSessionIndication::decode(&data[decoded..], true, false)?;
+                    decoded += 1;
+                    always_on_pdu_session_indication = Some(ie);
+                }
+                Self::AUTHORIZED_QOS_RULES_IEI => {
+                    let (ie, ie_decoded) = QosRules::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    authorized_qos_rules = Some(ie);
+                }
+                Self::MAPPED_EPS_BEARER_CONTEXTS_IEI => {
+                    let (ie, ie_decoded) = MappedEpsBearerContexts::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    mapped_eps_bearer_contexts = Some(ie);
+                }
+                Self::AUTHORIZED_QOS_FLOW_DESCRIPTIONS_IEI => {
+                    let (ie, ie_decoded) = QosFlowDescriptions::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    authorized_qos_flow_descriptions = Some(ie);
+                }
+                Self::EXTENDED_PROTOCOL_CONFIGURATION_OPTIONS_IEI => {
+                    let (ie, ie_decoded) =
+                        ExtendedProtocolConfigurationOptions::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    extended_protocol_configuration_options = Some(ie);
+                }
+                Self::ATSSS_CONTAINER_IEI => {
+                    let (ie, ie_decoded) = AtsssContainer::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    atsss_container = Some(ie);
+                }
+                Self::IP_HEADER_COMPRESSION_CONFIGURATION_IEI => {
+                    let (ie, ie_decoded) =
+                        IpHeaderCompressionConfiguration::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    ip_header_compression_configuration = Some(ie);
+                }
+                Self::PORT_MANAGEMENT_INFORMATION_CONTAINER_IEI => {
+                    let (ie, ie_decoded) =
+                        PortManagementInformationContainer::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    port_management_information_container = Some(ie);
+                }
+                Self::SERVING_PLMN_RATE_CONTROL_IEI => {
+                    let (ie, ie_decoded) = ServingPlmnRateControl::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    serving_plmn_rate_control = Some(ie);
+                }
+                Self::ETHERNET_HEADER_COMPRESSION_CONFIGURATION_IEI => {
+                    let (ie, ie_decoded) =
+                        EthernetHeaderCompressionConfiguration::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    ethernet_header_compression_configuration = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                cause,
+                session_ambr,
+                rq_timer,
+                always_on_pdu_session_indication,
+                authorized_qos_rules,
+                mapped_eps_bearer_contexts,
+                authorized_qos_flow_descriptions,
+                extended_protocol_configuration_options,
+                atsss_container,
+                ip_header_compression_configuration,
+                port_management_information_container,
+                serving_plmn_rate_control,
+                ethernet_header_compression_configuration,
+            },
+            decoded,
+        ))
+    }
+}
@@ -0,0 +1,67 @@
+//! 5G SM PDU Session Modification Command Reject
+
+use crate::messages::{
+    headers::Nas5gSmMessageHeader,
+    ies::{optional_iei, skip_unknown_ie, ExtendedProtocolConfigurationOptions, FivegsmCause},
+};
+use crate::NasError;
+
+use super::{decode_header, SM_MSG_TYPE_PDU_SESSION_MODIFICATION_COMMAND_REJECT};
+
+/// PDU Session Modification Command Reject: 24.501 (Release 17) Section 8.3.11
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PduSessionModificationCommandReject {
+    pub header: Nas5gSmMessageHeader,
+    pub cause: FivegsmCause,
+    pub extended_protocol_configuration_options: Option<ExtendedProtocolConfigurationOptions>,
+}
+
+impl PduSessionModificationCommandReject {
+    pub const EXTENDED_PROTOCOL_CONFIGURATION_OPTIONS_IEI: u8 = 0x7B;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+        output.extend(self.cause.encode(false));
+
+        if let Some(extended_protocol_configuration_options) =
+            &self.extended_protocol_configuration_options
+        {
+            output.extend(extended_protocol_configuration_options.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("PduSessionModificationCommandReject decode.");
+        let (header, mut decoded) =
+            decode_header(data, SM_MSG_TYPE_PDU_SESSION_MODIFICATION_COMMAND_REJECT)?;
+
+        let (cause, cause_decoded) = FivegsmCause::decode(&data[decoded..], false)?;
+        decoded += cause_decoded;
+
+        let mut extended_protocol_configuration_options = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::EXTENDED_PROTOCOL_CONFIGURATION_OPTIONS_IEI => {
+                    let (ie, ie_decoded) =
+                        ExtendedProtocolConfigurationOptions::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    extended_protocol_configuration_options = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                cause,
+                extended_protocol_configuration_options,
+            },
+            decoded,
+        ))
+    }
+}
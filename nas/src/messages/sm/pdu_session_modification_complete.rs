@@ -0,0 +1,79 @@
+//! 5G SM PDU Session Modification Complete
+
+use crate::messages::{
+    headers::Nas5gSmMessageHeader,
+    ies::{
+        optional_iei, skip_unknown_ie, ExtendedProtocolConfigurationOptions,
+        PortManagementInformationContainer,
+    },
+};
+use crate::NasError;
+
+use super::{decode_header, SM_MSG_TYPE_PDU_SESSION_MODIFICATION_COMPLETE};
+
+/// PDU Session Modification Complete: 24.501 (Release 17) Section 8.3.10
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PduSessionModificationComplete {
+    pub header: Nas5gSmMessageHeader,
+    pub extended_protocol_configuration_options: Option<ExtendedProtocolConfigurationOptions>,
+    pub port_management_information_container: Option<PortManagementInformationContainer>,
+}
+
+impl PduSessionModificationComplete {
+    pub const EXTENDED_PROTOCOL_CONFIGURATION_OPTIONS_IEI: u8 = 0x7B;
+    pub const PORT_MANAGEMENT_INFORMATION_CONTAINER_IEI: u8 = 0x74;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+
+        if let Some(extended_protocol_configuration_options) =
+            &self.extended_protocol_configuration_options
+        {
+            output.extend(extended_protocol_configuration_options.encode(true));
+        }
+        if let Some(port_management_information_container) =
+            &self.port_management_information_container
+        {
+            output.extend(port_management_information_container.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("PduSessionModificationComplete decode.");
+        let (header, mut decoded) =
+            decode_header(data, SM_MSG_TYPE_PDU_SESSION_MODIFICATION_COMPLETE)?;
+
+        let mut extended_protocol_configuration_options = None;
+        let mut port_management_information_container = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::EXTENDED_PROTOCOL_CONFIGURATION_OPTIONS_IEI => {
+                    let (ie, ie_decoded) =
+                        ExtendedProtocolConfigurationOptions::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    extended_protocol_configuration_options = Some(ie);
+                }
+                Self::PORT_MANAGEMENT_INFORMATION_CONTAINER_IEI => {
+                    let (ie, ie_decoded) =
+                        PortManagementInformationContainer::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    port_management_information_container = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                extended_protocol_configuration_options,
+                port_management_information_container,
+            },
+            decoded,
+        ))
+    }
+}
@@ -0,0 +1,215 @@
+//! 5G SM PDU Session Modification Request
+
+use crate::messages::{
+    headers::Nas5gSmMessageHeader,
+    ies::{
+        optional_iei, skip_unknown_ie, AlwaysOnPduSessionRequested,
+        EthernetHeaderCompressionConfiguration, ExtendedProtocolConfigurationOptions,
+        FivegsmCapability, FivegsmCause, IntegrityProtectionMaximumDataRate,
+        IpHeaderCompressionConfiguration, MappedEpsBearerContexts,
+        MaximumNumberOfSupportedPacketFilters, PortManagementInformationContainer,
+        QosFlowDescriptions, QosRules,
+    },
+};
+use crate::NasError;
+
+use super::{decode_header, SM_MSG_TYPE_PDU_SESSION_MODIFICATION_REQUEST};
+
+/// PDU Session Modification Request: 24.501 (Release 17) Section 8.3.7
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PduSessionModificationRequest {
+    pub header: Nas5gSmMessageHeader,
+    pub capability: Option<FivegsmCapability>,
+    pub cause: Option<FivegsmCause>,
+    pub maximum_number_of_supported_packet_filters: Option<MaximumNumberOfSupportedPacketFilters>,
+    pub always_on_pdu_session_requested: Option<AlwaysOnPduSessionRequested>,
+    pub integrity_protection_maximum_data_rate: Option<IntegrityProtectionMaximumDataRate>,
+    pub requested_qos_rules: Option<QosRules>,
+    pub requested_qos_flow_descriptions: Option<QosFlowDescriptions>,
+    pub mapped_eps_bearer_contexts: Option<MappedEpsBearerContexts>,
+    pub extended_protocol_configuration_options: Option<ExtendedProtocolConfigurationOptions>,
+    pub port_management_information_container: Option<PortManagementInformationContainer>,
+    pub ip_header_compression_configuration: Option<IpHeaderCompressionConfiguration>,
+    pub ethernet_header_compression_configuration: Option<EthernetHeaderCompressionConfiguration>,
+}
+
+impl PduSessionModificationRequest {
+    pub const CAPABILITY_IEI: u8 = 0x28;
+    pub const CAUSE_IEI: u8 = 0x59;
+    pub const MAXIMUM_NUMBER_OF_SUPPORTED_PACKET_FILTERS_IEI: u8 = 0x55;
+    pub const ALWAYS_ON_PDU_SESSION_REQUESTED_IEI: u8 = 0x0B;
+    pub const INTEGRITY_PROTECTION_MAXIMUM_DATA_RATE_IEI: u8 = 0x13;
+    pub const REQUESTED_QOS_RULES_IEI: u8 = 0x7A;
+    pub const REQUESTED_QOS_FLOW_DESCRIPTIONS_IEI: u8 = 0x79;
+    pub const MAPPED_EPS_BEARER_CONTEXTS_IEI: u8 = 0x75;
+    pub const EXTENDED_PROTOCOL_CONFIGURATION_OPTIONS_IEI: u8 = 0x7B;
+    pub const PORT_MANAGEMENT_INFORMATION_CONTAINER_IEI: u8 = 0x74;
+    pub const IP_HEADER_COMPRESSION_CONFIGURATION_IEI: u8 = 0x66;
+    pub const ETHERNET_HEADER_COMPRESSION_CONFIGURATION_IEI: u8 = 0x1F;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+
+        if let Some(capability) = &self.capability {
+            output.extend(capability.encode(true));
+        }
+        if let Some(cause) = &self.cause {
+            output.extend(cause.encode(true));
+        }
+        if let Some(maximum_number_of_supported_packet_filters) =
+            &self.maximum_number_of_supported_packet_filters
+        {
+            output.extend(maximum_number_of_supported_packet_filters.encode(true));
+        }
+        if let Some(always_on_pdu_session_requested) = &self.always_on_pdu_session_requested {
+            output.extend(always_on_pdu_session_requested.encode(true, false));
+        }
+        if let Some(integrity_protection_maximum_data_rate) =
+            &self.integrity_protection_maximum_data_rate
+        {
+            output.extend(integrity_protection_maximum_data_rate.encode(true));
+        }
+        if let Some(requested_qos_rules) = &self.requested_qos_rules {
+            output.extend(requested_qos_rules.encode(true));
+        }
+        if let Some(requested_qos_flow_descriptions) = &self.requested_qos_flow_descriptions {
+            output.extend(requested_qos_flow_descriptions.encode(true));
+        }
+        if let Some(mapped_eps_bearer_contexts) = &self.mapped_eps_bearer_contexts {
+            output.extend(mapped_eps_bearer_contexts.encode(true));
+        }
+        if let Some(extended_protocol_configuration_options) =
+            &self.extended_protocol_configuration_options
+        {
+            output.extend(extended_protocol_configuration_options.encode(true));
+        }
+        if let Some(port_management_information_container) =
+            &self.port_management_information_container
+        {
+            output.extend(port_management_information_container.encode(true));
+        }
+        if let Some(ip_header_compression_configuration) = &self.ip_header_compression_configuration
+        {
+            output.extend(ip_header_compression_configuration.encode(true));
+        }
+        if let Some(ethernet_header_compression_configuration) =
+            &self.ethernet_header_compression_configuration
+        {
+            output.extend(ethernet_header_compression_configuration.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("PduSessionModificationRequest decode.");
+        let (header, mut decoded) =
+            decode_header(data, SM_MSG_TYPE_PDU_SESSION_MODIFICATION_REQUEST)?;
+
+        let mut capability = None;
+        let mut cause = None;
+        let mut maximum_number_of_supported_packet_filters = None;
+        let mut always_on_pdu_session_requested = None;
+        let mut integrity_protection_maximum_data_rate = None;
+        let mut requested_qos_rules = None;
+        let mut requested_qos_flow_descriptions = None;
+        let mut mapped_eps_bearer_contexts = None;
+        let mut extended_protocol_configuration_options = None;
+        let mut port_management_information_container = None;
+        let mut ip_header_compression_configuration = None;
+        let mut ethernet_header_compression_configuration = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::CAPABILITY_IEI => {
+                    let (ie, ie_decoded) = FivegsmCapability::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    capability = Some(ie);
+                }
+                Self::CAUSE_IEI => {
+                    let (ie, ie_decoded) = FivegsmCause::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    cause = Some(ie);
+                }
+                Self::MAXIMUM_NUMBER_OF_SUPPORTED_PACKET_FILTERS_IEI => {
+                    let (ie, ie_decoded) =
+                        MaximumNumberOfSupportedPacketFilters::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    maximum_number_of_supported_packet_filters = Some(ie);
+                }
+                Self::ALWAYS_ON_PDU_SESSION_REQUESTED_IEI => {
+                    let (ie, _) =
+                        AlwaysOnPduSessionRequested::decode(&data[decoded..], true, false)?;
+                    decoded += 1;
+                    always_on_pdu_session_requested = Some(ie);
+                }
+                Self::INTEGRITY_PROTECTION_MAXIMUM_DATA_RATE_IEI => {
+                    let (ie, ie_decoded) =
+                        IntegrityProtectionMaximumDataRate::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    integrity_protection_maximum_data_rate = Some(ie);
+                }
+                Self::REQUESTED_QOS_RULES_IEI => {
+                    let (ie, ie_decoded) = QosRules::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    requested_qos_rules = Some(ie);
+                }
+                Self::REQUESTED_QOS_FLOW_DESCRIPTIONS_IEI => {
+                    let (ie, ie_decoded) = QosFlowDescriptions::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    requested_qos_flow_descriptions = Some(ie);
+                }
+                Self::MAPPED_EPS_BEARER_CONTEXTS_IEI => {
+                    let (ie, ie_decoded) = MappedEpsBearerContexts::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    mapped_eps_bearer_contexts = Some(ie);
+                }
+                Self::EXTENDED_PROTOCOL_CONFIGURATION_OPTIONS_IEI => {
+                    let (ie, ie_decoded) =
+                        ExtendedProtocolConfigurationOptions::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    extended_protocol_configuration_options = Some(ie);
+                }
+                Self::PORT_MANAGEMENT_INFORMATION_CONTAINER_IEI => {
+                    let (ie, ie_decoded) =
+                        PortManagementInformationContainer::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    port_management_information_container = Some(ie);
+                }
+                Self::IP_HEADER_COMPRESSION_CONFIGURATION_IEI => {
+                    let (ie, ie_decoded) =
+                        IpHeaderCompressionConfiguration::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    ip_header_compression_configuration = Some(ie);
+                }
+                Self::ETHERNET_HEADER_COMPRESSION_CONFIGURATION_IEI => {
+                    let (ie, ie_decoded) =
+                        EthernetHeaderCompressionConfiguration::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    ethernet_header_compression_configuration = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                capability,
+                cause,
+                maximum_number_of_supported_packet_filters,
+                always_on_pdu_session_requested,
+                integrity_protection_maximum_data_rate,
+                requested_qos_rules,
+                requested_qos_flow_descriptions,
+                mapped_eps_bearer_contexts,
+                extended_protocol_configuration_options,
+                port_management_information_container,
+                ip_header_compression_configuration,
+                ethernet_header_compression_configuration,
+            },
+            decoded,
+        ))
+    }
+}
@@ -0,0 +1,72 @@
+//! 5G SM PDU Session Release Complete
+
+use crate::messages::{
+    headers::Nas5gSmMessageHeader,
+    ies::{optional_iei, skip_unknown_ie, ExtendedProtocolConfigurationOptions, FivegsmCause},
+};
+use crate::NasError;
+
+use super::{decode_header, SM_MSG_TYPE_PDU_SESSION_RELEASE_COMPLETE};
+
+/// PDU Session Release Complete: 24.501 (Release 17) Section 8.3.15
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PduSessionReleaseComplete {
+    pub header: Nas5gSmMessageHeader,
+    pub cause: Option<FivegsmCause>,
+    pub extended_protocol_configuration_options: Option<ExtendedProtocolConfigurationOptions>,
+}
+
+impl PduSessionReleaseComplete {
+    pub const CAUSE_IEI: u8 = 0x59;
+    pub const EXTENDED_PROTOCOL_CONFIGURATION_OPTIONS_IEI: u8 = 0x7B;
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = vec![];
+        output.extend(self.header.encode());
+
+        if let Some(cause) = &self.cause {
+            output.extend(cause.encode(true));
+        }
+        if let Some(extended_protocol_configuration_options) =
+            &self.extended_protocol_configuration_options
+        {
+            output.extend(extended_protocol_configuration_options.encode(true));
+        }
+
+        output
+    }
+
+    pub fn decode(data: &[u8]) -> Result<(Self, usize), NasError> {
+        log::trace!("PduSessionReleaseComplete decode.");
+        let (header, mut decoded) = decode_header(data, SM_MSG_TYPE_PDU_SESSION_RELEASE_COMPLETE)?;
+
+        let mut cause = None;
+        let mut extended_protocol_configuration_options = None;
+        while decoded < data.len() {
+            match optional_iei(data[decoded]) {
+                Self::CAUSE_IEI => {
+                    let (ie, ie_decoded) = FivegsmCause::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    cause = Some(ie);
+                }
+                Self::EXTENDED_PROTOCOL_CONFIGURATION_OPTIONS_IEI => {
+                    let (ie, ie_decoded) =
+                        ExtendedProtocolConfigurationOptions::decode(&data[decoded..], true)?;
+                    decoded += ie_decoded;
+                    extended_protocol_configuration_options = Some(ie);
+                }
+                _ => decoded += skip_unknown_ie(&data[decoded..])?,
+            }
+        }
+
+        Ok((
+            Self {
+                header,
+                cause,
+                extended_protocol_configuration_options,
+            },
+            decoded,
+        ))
+    }
+}
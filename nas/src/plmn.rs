@@ -0,0 +1,280 @@
+//! PLMN Identity shared by the NAS IEs, the NGAP and the SBI types.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::NasError;
+
+/// PLMN Identity: The MCC and the 2 or 3 digit MNC. (23.003 Section 2.2)
+///
+/// The number of digits of the MNC is part of the PLMN, "001-01" and "001-001" are different
+/// PLMNs.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Plmn {
+    mcc: u16,
+    mnc: u16,
+    mnc_digits: u8,
+}
+
+impl Plmn {
+    /// PLMN from the MCC and the MNC with `mnc_digits` (2 or 3) digits.
+    pub fn new(mcc: u16, mnc: u16, mnc_digits: u8) -> Result<Self, NasError> {
+        let valid = match mnc_digits {
+            2 => mnc < 100,
+            3 => mnc < 1000,
+            _ => false,
+        };
+        if mcc > 999 || !valid {
+            return Err(NasError::semantically_incorrect(format!(
+                "Invalid PLMN: MCC: {}, MNC: {} ({} digits)",
+                mcc, mnc, mnc_digits
+            )));
+        }
+
+        Ok(Self {
+            mcc,
+            mnc,
+            mnc_digits,
+        })
+    }
+
+    /// PLMN from the MCC and the MNC, where an MNC less than 100 is a 2 digit MNC. This is the
+    /// same as `PLMNIdentity::from_mcc_mnc` in the `ngap` crate.
+    pub fn from_mcc_mnc(mcc: u16, mnc: u16) -> Result<Self, NasError> {
+        Self::new(mcc, mnc, if mnc < 100 { 2 } else { 3 })
+    }
+
+    pub fn mcc(&self) -> u16 {
+        self.mcc
+    }
+
+    pub fn mnc(&self) -> u16 {
+        self.mnc
+    }
+
+    /// Number of digits of the MNC: 2 or 3.
+    pub fn mnc_digits(&self) -> u8 {
+        self.mnc_digits
+    }
+
+    pub fn to_mcc_mnc(&self) -> (u16, u16) {
+        (self.mcc, self.mnc)
+    }
+
+    /// Encode the PLMN as 3 octets of BCD digits. (24.008 Section 10.5.1.13)
+    pub fn encode(&self) -> [u8; 3] {
+        let (mcc1, mcc2, mcc3) = digits(self.mcc);
+        let (mnc1, mnc2, mnc3) = if self.mnc_digits == 2 {
+            let (_, mnc1, mnc2) = digits(self.mnc);
+            (mnc1, mnc2, 0x0F)
+        } else {
+            digits(self.mnc)
+        };
+
+        [mcc2 << 4 | mcc1, mnc3 << 4 | mcc3, mnc2 << 4 | mnc1]
+    }
+
+    /// Decode the PLMN from the first 3 octets of `data`. (24.008 Section 10.5.1.13)
+    pub fn decode(data: &[u8]) -> Result<Self, NasError> {
+        log::trace!("Plmn decode");
+
+        if data.len() < 3 {
+            return Err(NasError::Truncated {
+                expected: 3,
+                available: data.len(),
+            });
+        }
+
+        let (mcc1, mcc2) = (data[0] & 0x0F, data[0] >> 4);
+        let (mcc3, mnc3) = (data[1] & 0x0F, data[1] >> 4);
+        let (mnc1, mnc2) = (data[2] & 0x0F, data[2] >> 4);
+
+        let digits = [mcc1, mcc2, mcc3, mnc1, mnc2];
+        if digits.iter().any(|digit| *digit > 9) || (mnc3 > 9 && mnc3 != 0x0F) {
+            return Err(NasError::semantically_incorrect(format!(
+                "Invalid PLMN: {}",
+                hex::encode(&data[..3])
+            )));
+        }
+
+        let mcc = mcc1 as u16 * 100 + mcc2 as u16 * 10 + mcc3 as u16;
+        if mnc3 == 0x0F {
+            Self::new(mcc, mnc1 as u16 * 10 + mnc2 as u16, 2)
+        } else {
+            Self::new(mcc, mnc1 as u16 * 100 + mnc2 as u16 * 10 + mnc3 as u16, 3)
+        }
+    }
+}
+
+impl fmt::Display for Plmn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:03}-{:0width$}",
+            self.mcc,
+            self.mnc,
+            width = self.mnc_digits as usize
+        )
+    }
+}
+
+/// Parse a PLMN from "MCC-MNC", eg. "901-70".
+impl FromStr for Plmn {
+    type Err = NasError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || NasError::semantically_incorrect(format!("Invalid PLMN: {}", s));
+
+        let (mcc, mnc) = s.split_once('-').ok_or_else(invalid)?;
+        if mcc.len() != 3 || !(2..=3).contains(&mnc.len()) {
+            return Err(invalid());
+        }
+        let mcc = parse_digits(mcc).ok_or_else(invalid)?;
+        let mnc_digits = mnc.len() as u8;
+        let mnc = parse_digits(mnc).ok_or_else(invalid)?;
+
+        Self::new(mcc, mnc, mnc_digits)
+    }
+}
+
+/// Serialized as "MCC-MNC", the same as the `Display`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Plmn {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "ngap")]
+impl From<&Plmn> for ngap::messages::r17::PLMNIdentity {
+    fn from(plmn: &Plmn) -> Self {
+        Self(plmn.encode().to_vec())
+    }
+}
+
+#[cfg(feature = "ngap")]
+impl TryFrom<&ngap::messages::r17::PLMNIdentity> for Plmn {
+    type Error = NasError;
+
+    fn try_from(plmn_identity: &ngap::messages::r17::PLMNIdentity) -> Result<Self, Self::Error> {
+        if plmn_identity.0.len() != 3 {
+            return Err(NasError::InvalidLength {
+                ie: "PLMN Identity",
+                length: plmn_identity.0.len(),
+            });
+        }
+        Self::decode(&plmn_identity.0)
+    }
+}
+
+#[cfg(feature = "sbi")]
+impl From<&Plmn> for sbi5g::models::PlmnId {
+    fn from(plmn: &Plmn) -> Self {
+        Self {
+            mcc: format!("{:03}", plmn.mcc),
+            mnc: format!("{:0width$}", plmn.mnc, width = plmn.mnc_digits as usize),
+        }
+    }
+}
+
+#[cfg(feature = "sbi")]
+impl TryFrom<&sbi5g::models::PlmnId> for Plmn {
+    type Error = NasError;
+
+    fn try_from(plmn_id: &sbi5g::models::PlmnId) -> Result<Self, Self::Error> {
+        format!("{}-{}", plmn_id.mcc, plmn_id.mnc).parse()
+    }
+}
+
+// Hundreds, tens and units digits of a value less than 1000.
+fn digits(value: u16) -> (u8, u8, u8) {
+    (
+        (value / 100) as u8,
+        (value / 10 % 10) as u8,
+        (value % 10) as u8,
+    )
+}
+
+fn parse_digits(digits: &str) -> Option<u16> {
+    if digits.bytes().all(|digit| digit.is_ascii_digit()) {
+        digits.parse().ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plmn() {
+        let plmn = Plmn::decode(&[0x09, 0xF1, 0x07]).unwrap();
+        assert_eq!(plmn.to_mcc_mnc(), (901, 70));
+        assert_eq!(plmn.mnc_digits(), 2);
+        assert_eq!(plmn.to_string(), "901-70");
+        assert_eq!(plmn.encode(), [0x09, 0xF1, 0x07]);
+
+        let plmn = Plmn::decode(&[0x13, 0x00, 0x14]).unwrap();
+        assert_eq!(plmn.to_string(), "310-410");
+        assert_eq!(plmn.encode(), [0x13, 0x00, 0x14]);
+
+        // 2 and 3 digit MNCs with the same value are different PLMNs.
+        let two_digits: Plmn = "001-01".parse().unwrap();
+        let three_digits: Plmn = "001-001".parse().unwrap();
+        assert_ne!(two_digits, three_digits);
+        assert_eq!(two_digits.encode(), [0x00, 0xF1, 0x10]);
+        assert_eq!(three_digits.encode(), [0x00, 0x11, 0x00]);
+        assert_eq!(Plmn::decode(&three_digits.encode()), Ok(three_digits));
+        assert_eq!(three_digits.to_string(), "001-001");
+        assert_eq!(Plmn::from_mcc_mnc(1, 1), Ok(two_digits));
+
+        // Digits other than 0 to 9, except the filler for the MNC digit 3, are invalid.
+        assert!(Plmn::decode(&[0x0A, 0xF1, 0x07]).is_err());
+        assert!(Plmn::decode(&[0x09, 0xE1, 0x07]).is_err());
+        assert!(Plmn::decode(&[0x09, 0xF1]).is_err());
+
+        assert!("901-7".parse::<Plmn>().is_err());
+        assert!("90-170".parse::<Plmn>().is_err());
+        assert!("901-+7".parse::<Plmn>().is_err());
+        assert!(Plmn::new(901, 100, 2).is_err());
+        assert!(Plmn::new(1000, 1, 2).is_err());
+    }
+
+    #[cfg(feature = "ngap")]
+    #[test]
+    fn test_plmn_ngap() {
+        use ngap::messages::r17::PLMNIdentity;
+
+        for plmn in ["901-70", "001-01", "286-101"] {
+            let plmn: Plmn = plmn.parse().unwrap();
+            let plmn_identity = PLMNIdentity::from(&plmn);
+            assert_eq!(
+                plmn_identity,
+                PLMNIdentity::from_mcc_mnc(plmn.mcc(), plmn.mnc())
+            );
+            assert_eq!(plmn_identity.to_mcc_mnc(), Some(plmn.to_mcc_mnc()));
+            assert_eq!(Plmn::try_from(&plmn_identity), Ok(plmn));
+        }
+
+        assert!(Plmn::try_from(&PLMNIdentity(vec![0x09, 0xf1, 0x07, 0x00])).is_err());
+    }
+
+    #[cfg(feature = "sbi")]
+    #[test]
+    fn test_plmn_sbi() {
+        use sbi5g::models::PlmnId;
+
+        let plmn: Plmn = "001-001".parse().unwrap();
+        let plmn_id = PlmnId::from(&plmn);
+        assert_eq!(plmn_id.mcc, "001");
+        assert_eq!(plmn_id.mnc, "001");
+        assert_eq!(Plmn::try_from(&plmn_id), Ok(plmn));
+
+        let plmn_id = PlmnId {
+            mcc: "901".to_string(),
+            mnc: "7".to_string(),
+        };
+        assert!(Plmn::try_from(&plmn_id).is_err());
+    }
+}
@@ -0,0 +1,504 @@
+//! 5G NAS Security Context: Integrity protection and Ciphering of NAS Messages.
+
+use security_3gpp::{constant_time_eq, SecurityKey};
+
+use crate::messages::headers::{
+    ExtProtoDiscriminator, Nas5gSecurityHeader, SecurityProtectedHeader,
+};
+use crate::messages::Nas5gMmMessage;
+use crate::NasError;
+
+use super::{
+    nas_calculate_mac, nas_decrypt_payload, nas_encrypt_payload, nas_encryption_algorithm_key,
+    nas_integrity_algorithm_key, NasEncryptionAlgoIdentity, NasIntegrityAlgoIdentity, NasKey,
+};
+
+// Length of the Security Protected NAS Message Header: EPD, Security Header Type, MAC and
+// Sequence Number. (Section 9.1.1 24.501)
+const SECURITY_PROTECTED_HEADER_LENGTH: usize = 7;
+
+// Offset of the Sequence Number in the Security Protected NAS Message.
+const SEQUENCE_NUMBER_OFFSET: usize = 6;
+
+/// NAS Connection Identifier used as the `BEARER` input of the NAS Security algorithms (Section
+/// 6.4.3.1 33.501)
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NasAccessType {
+    ThreeGpp = 0x00,
+    NonThreeGpp = 0x01,
+}
+
+/// NAS COUNT: 24 bit value made of 16 bit NAS Overflow and 8 bit Sequence Number. (Section
+/// 4.4.3.1 24.501)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct NasCount(u32);
+
+impl NasCount {
+    /// Maximum value of the NAS COUNT.
+    pub const MAX: u32 = 0x00FF_FFFF;
+
+    pub fn new(overflow: u16, sequence_number: u8) -> Self {
+        Self((overflow as u32) << 8 | sequence_number as u32)
+    }
+
+    /// NAS Overflow
+    pub fn overflow(&self) -> u16 {
+        (self.0 >> 8) as u16
+    }
+
+    /// NAS Sequence Number
+    pub fn sequence_number(&self) -> u8 {
+        self.0 as u8
+    }
+
+    /// The 32 bit `COUNT` input for the NAS Security algorithms. (Section 4.4.3.1 24.501)
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+
+    /// Estimate the NAS COUNT of a received NAS message from it's sequence number, where `self`
+    /// is the NAS COUNT expected for the next message. A sequence number lower than the expected
+    /// one means the NAS Overflow has been incremented by the sender.
+    ///
+    /// Returns `None` if the estimated NAS COUNT wraps around (ie. goes beyond `MAX`).
+    pub fn estimate(&self, sequence_number: u8) -> Option<Self> {
+        // `self` may already be beyond `MAX` (See `next`), hence the NAS Overflow is not
+        // truncated to 16 bits here.
+        let mut overflow = self.0 >> 8;
+        if sequence_number < self.sequence_number() {
+            overflow += 1;
+        }
+
+        let count = overflow << 8 | sequence_number as u32;
+        if count > Self::MAX {
+            return None;
+        }
+
+        Some(Self(count))
+    }
+
+    // The NAS COUNT following this one. The NAS COUNT of the last message is allowed to go
+    // beyond `MAX`, so that any further message is rejected.
+    fn next(&self) -> Self {
+        Self(self.0 + 1)
+    }
+}
+
+/// 5G NAS Security Context (Section 6.3.2 33.501)
+///
+/// Holds the keys, the selected algorithms and the NAS COUNTs used to security protect the
+/// downlink NAS messages and to verify and decipher the uplink NAS messages.
+#[derive(Clone)]
+pub struct NasSecurityContext {
+    ngksi: u8,
+    kamf: SecurityKey,
+    k_nas_enc: NasKey,
+    k_nas_int: NasKey,
+    encryption_algo: NasEncryptionAlgoIdentity,
+    integrity_algo: NasIntegrityAlgoIdentity,
+    access_type: NasAccessType,
+    ul_count: NasCount,
+    dl_count: NasCount,
+}
+
+impl std::fmt::Debug for NasSecurityContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Keys are never printed.
+        f.debug_struct("NasSecurityContext")
+            .field("ngksi", &self.ngksi)
+            .field("encryption_algo", &self.encryption_algo)
+            .field("integrity_algo", &self.integrity_algo)
+            .field("access_type", &self.access_type)
+            .field("ul_count", &self.ul_count)
+            .field("dl_count", &self.dl_count)
+            .finish()
+    }
+}
+
+impl NasSecurityContext {
+    /// Create a new NAS Security Context for `KAMF` identified by the `ngksi`. The NAS keys are
+    /// derived from the `KAMF` for the selected algorithms. Both the NAS COUNTs start at zero.
+    pub fn new(
+        ngksi: u8,
+        kamf: SecurityKey,
+        encryption_algo: NasEncryptionAlgoIdentity,
+        integrity_algo: NasIntegrityAlgoIdentity,
+        access_type: NasAccessType,
+    ) -> Self {
+        Self {
+            ngksi,
+            kamf,
+            k_nas_enc: nas_encryption_algorithm_key(kamf, encryption_algo),
+            k_nas_int: nas_integrity_algorithm_key(kamf, integrity_algo),
+            encryption_algo,
+            integrity_algo,
+            access_type,
+            ul_count: NasCount::default(),
+            dl_count: NasCount::default(),
+        }
+    }
+
+    /// Key Set Identifier of the Security Context
+    pub fn ngksi(&self) -> u8 {
+        self.ngksi
+    }
+
+    /// `KAMF` of the Security Context
+    pub fn kamf(&self) -> &SecurityKey {
+        &self.kamf
+    }
+
+    /// Selected NAS Encryption Algorithm
+    pub fn encryption_algo(&self) -> NasEncryptionAlgoIdentity {
+        self.encryption_algo
+    }
+
+    /// Selected NAS Integrity Algorithm
+    pub fn integrity_algo(&self) -> NasIntegrityAlgoIdentity {
+        self.integrity_algo
+    }
+
+    /// NAS COUNT expected for the next Uplink NAS message.
+    pub fn ul_count(&self) -> NasCount {
+        self.ul_count
+    }
+
+    /// NAS COUNT to be used for the next Downlink NAS message.
+    pub fn dl_count(&self) -> NasCount {
+        self.dl_count
+    }
+
+    /// Security protect a plain NAS message for the Downlink using the given Security Header
+    /// Type. The message is ciphered for `IntegrityProtectedAndCiphered` and
+    /// `IntegrityProtectedSecurityModeComplete` Security Header Types.
+    pub fn protect(
+        &mut self,
+        message: &[u8],
+        sec_header_type: Nas5gSecurityHeader,
+    ) -> Result<Vec<u8>, NasError> {
+        if sec_header_type == Nas5gSecurityHeader::PlainText {
+            return Err(NasError::InvalidSecurityHeaderType(sec_header_type as u8));
+        }
+
+        if self.dl_count.value() > NasCount::MAX {
+            return Err(NasError::CountWrapAround);
+        }
+        let count = self.dl_count;
+
+        let payload = if Self::is_ciphered(&sec_header_type) {
+            nas_encrypt_payload(
+                self.k_nas_enc,
+                self.encryption_algo,
+                count.value(),
+                self.access_type as u8,
+                true,
+                message,
+                message.len() as u32 * 8,
+            )
+        } else {
+            message.to_vec()
+        };
+
+        let mut data = Vec::with_capacity(SECURITY_PROTECTED_HEADER_LENGTH + payload.len());
+        data.push(ExtProtoDiscriminator::FivegNasMobilityManagementType as u8);
+        data.push(sec_header_type as u8);
+        data.extend([0_u8; 4]);
+        data.push(count.sequence_number());
+        data.extend(payload);
+
+        let mac = nas_calculate_mac(
+            self.k_nas_int,
+            self.integrity_algo,
+            count.value(),
+            self.access_type as u8,
+            true,
+            &data[SEQUENCE_NUMBER_OFFSET..],
+        );
+        data.splice(2..SEQUENCE_NUMBER_OFFSET, mac);
+
+        self.dl_count = count.next();
+
+        Ok(data)
+    }
+
+    /// Verify and decipher (if ciphered) a security protected Uplink NAS message and return the
+    /// plain NAS message.
+    ///
+    /// The NAS COUNT of the message is estimated from it's sequence number. The message is
+    /// rejected if the MAC verification fails (This includes the replayed messages, since the
+    /// estimated NAS COUNT will be different.). The Uplink NAS COUNT is updated only after a
+    /// successful verification.
+    pub fn unprotect(&mut self, data: &[u8]) -> Result<Vec<u8>, NasError> {
+        let (header, _) = SecurityProtectedHeader::decode(data)?;
+
+        if self.ul_count.value() > NasCount::MAX {
+            return Err(NasError::CountWrapAround);
+        }
+        let sequence_number = header.seq_no;
+        let count = self
+            .ul_count
+            .estimate(sequence_number)
+            .ok_or(NasError::CountWrapAround)?;
+
+        let mac = nas_calculate_mac(
+            self.k_nas_int,
+            self.integrity_algo,
+            count.value(),
+            self.access_type as u8,
+            false,
+            &data[SEQUENCE_NUMBER_OFFSET..],
+        );
+        if !constant_time_eq(&mac, &data[2..SEQUENCE_NUMBER_OFFSET]) {
+            return Err(NasError::MacVerificationFailed {
+                count: count.value(),
+            });
+        }
+
+        let payload = &data[SECURITY_PROTECTED_HEADER_LENGTH..];
+        let message = if Self::is_ciphered(&header.sec_header_type) {
+            nas_decrypt_payload(
+                self.k_nas_enc,
+                self.encryption_algo,
+                count.value(),
+                self.access_type as u8,
+                false,
+                payload,
+                payload.len() as u32 * 8,
+            )
+        } else {
+            payload.to_vec()
+        };
+
+        self.ul_count = count.next();
+
+        Ok(message)
+    }
+
+    /// Verify and decipher a security protected Uplink NAS message and decode the contained 5GMM
+    /// message.
+    ///
+    /// See also [`NasSecurityContext::unprotect`].
+    pub fn unprotect_message(&mut self, data: &[u8]) -> Result<Nas5gMmMessage, NasError> {
+        let message = self.unprotect(data)?;
+
+        Nas5gMmMessage::decode(&message)
+    }
+
+    fn is_ciphered(sec_header_type: &Nas5gSecurityHeader) -> bool {
+        matches!(
+            sec_header_type,
+            Nas5gSecurityHeader::IntegrityProtectedAndCiphered
+                | Nas5gSecurityHeader::IntegrityProtectedSecurityModeComplete
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A Registration Request with SUCI (Null Scheme)
+    const REGISTRATION_REQUEST: &str = "7e004179000d0109f1070000000000000000102e04f0f0f0f0";
+
+    fn context_pair() -> (NasSecurityContext, NasSecurityContext) {
+        let kamf = [0x5A_u8; 32];
+        let context = NasSecurityContext::new(
+            1,
+            kamf,
+            NasEncryptionAlgoIdentity::Nea2,
+            NasIntegrityAlgoIdentity::Nia2,
+            NasAccessType::ThreeGpp,
+        );
+
+        (context.clone(), context)
+    }
+
+    // Protect a message in the Uplink as the UE would.
+    fn ue_protect(
+        ue: &NasSecurityContext,
+        count: NasCount,
+        message: &[u8],
+        sec_header_type: Nas5gSecurityHeader,
+    ) -> Vec<u8> {
+        let payload = if NasSecurityContext::is_ciphered(&sec_header_type) {
+            nas_encrypt_payload(
+                ue.k_nas_enc,
+                ue.encryption_algo,
+                count.value(),
+                0,
+                false,
+                message,
+                message.len() as u32 * 8,
+            )
+        } else {
+            message.to_vec()
+        };
+
+        let mut data = vec![0x7e, sec_header_type as u8, 0, 0, 0, 0];
+        data.push(count.sequence_number());
+        data.extend(payload);
+        let mac = nas_calculate_mac(
+            ue.k_nas_int,
+            ue.integrity_algo,
+            count.value(),
+            0,
+            false,
+            &data[6..],
+        );
+        data.splice(2..6, mac);
+
+        data
+    }
+
+    #[test]
+    fn test_nas_count_estimate() {
+        let expected = NasCount::new(0, 10);
+        assert_eq!(expected.estimate(10), Some(NasCount::new(0, 10)));
+        assert_eq!(expected.estimate(12), Some(NasCount::new(0, 12)));
+        assert_eq!(expected.estimate(9), Some(NasCount::new(1, 9)));
+
+        let expected = NasCount::new(0xFFFF, 0x10);
+        assert_eq!(expected.estimate(0x0F), None);
+
+        // NAS COUNT beyond `MAX` after the last message.
+        let expected = NasCount::new(0xFFFF, 0xFF).next();
+        assert_eq!(expected.estimate(0), None);
+        assert_eq!(expected.estimate(0xFF), None);
+
+        assert_eq!(NasCount::new(0, 0xFF).next(), NasCount::new(1, 0));
+    }
+
+    #[test]
+    fn test_protect_unprotect() {
+        let (mut amf, ue) = context_pair();
+        let message = hex::decode(REGISTRATION_REQUEST).unwrap();
+
+        for sn in 0..3_u8 {
+            let data = ue_protect(
+                &ue,
+                NasCount::new(0, sn),
+                &message,
+                Nas5gSecurityHeader::IntegrityProtectedAndCiphered,
+            );
+            assert_ne!(data[7..], message);
+
+            let result = amf.unprotect(&data);
+            assert!(result.is_ok(), "{:?}", result.err());
+            assert_eq!(result.unwrap(), message);
+        }
+        assert_eq!(amf.ul_count(), NasCount::new(0, 3));
+
+        let data = amf
+            .protect(&message, Nas5gSecurityHeader::IntegrityProtected)
+            .unwrap();
+        assert_eq!(data[7..], message);
+        assert_eq!(data[6], 0);
+
+        let data = amf
+            .protect(&message, Nas5gSecurityHeader::IntegrityProtectedAndCiphered)
+            .unwrap();
+        assert_eq!(data[6], 1);
+        let deciphered = nas_decrypt_payload(
+            ue.k_nas_enc,
+            ue.encryption_algo,
+            1,
+            0,
+            true,
+            &data[7..],
+            message.len() as u32 * 8,
+        );
+        assert_eq!(deciphered, message);
+        assert_eq!(amf.dl_count(), NasCount::new(0, 2));
+
+        let plain = amf.protect(&message, Nas5gSecurityHeader::PlainText);
+        assert!(plain.is_err());
+    }
+
+    #[test]
+    fn test_unprotect_rejects_replay_and_bad_mac() {
+        let (mut amf, ue) = context_pair();
+        let message = hex::decode(REGISTRATION_REQUEST).unwrap();
+
+        let data = ue_protect(
+            &ue,
+            NasCount::new(0, 0),
+            &message,
+            Nas5gSecurityHeader::IntegrityProtected,
+        );
+        assert!(amf.unprotect(&data).is_ok());
+
+        // Replayed message
+        assert!(amf.unprotect(&data).is_err());
+        assert_eq!(amf.ul_count(), NasCount::new(0, 1));
+
+        // Modified message
+        let mut data = ue_protect(
+            &ue,
+            NasCount::new(0, 1),
+            &message,
+            Nas5gSecurityHeader::IntegrityProtected,
+        );
+        let last = data.len() - 1;
+        data[last] ^= 0x01;
+        assert!(amf.unprotect(&data).is_err());
+        assert_eq!(amf.ul_count(), NasCount::new(0, 1));
+
+        // Plain message
+        assert!(amf.unprotect(&message).is_err());
+    }
+
+    #[test]
+    fn test_unprotect_overflow() {
+        let (mut amf, ue) = context_pair();
+        let message = hex::decode(REGISTRATION_REQUEST).unwrap();
+
+        amf.ul_count = NasCount::new(0, 0xFE);
+        for count in [
+            NasCount::new(0, 0xFE),
+            NasCount::new(0, 0xFF),
+            NasCount::new(1, 0),
+        ] {
+            let data = ue_protect(
+                &ue,
+                count,
+                &message,
+                Nas5gSecurityHeader::IntegrityProtectedAndCiphered,
+            );
+            assert!(amf.unprotect(&data).is_ok());
+        }
+        assert_eq!(amf.ul_count(), NasCount::new(1, 1));
+    }
+
+    #[test]
+    fn test_unprotect_wrap_around() {
+        let (mut amf, ue) = context_pair();
+        let message = hex::decode(REGISTRATION_REQUEST).unwrap();
+
+        let first = ue_protect(
+            &ue,
+            NasCount::new(0, 0),
+            &message,
+            Nas5gSecurityHeader::IntegrityProtected,
+        );
+        assert!(amf.unprotect(&first).is_ok());
+
+        amf.ul_count = NasCount::new(0xFFFF, 0xFF);
+        let last = ue_protect(
+            &ue,
+            NasCount::new(0xFFFF, 0xFF),
+            &message,
+            Nas5gSecurityHeader::IntegrityProtected,
+        );
+        assert!(amf.unprotect(&last).is_ok());
+
+        // No message is accepted after the Uplink NAS COUNT wraps around, including the replay
+        // of the message with the sequence number 0.
+        for data in [&first, &last] {
+            assert!(matches!(
+                amf.unprotect(data),
+                Err(NasError::CountWrapAround)
+            ));
+        }
+    }
+}
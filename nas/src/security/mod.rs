@@ -1,5 +1,11 @@
 //! NAS Security related common functions etc.
 
+mod context;
+pub use context::{NasAccessType, NasCount, NasSecurityContext};
+
+use crate::messages::ies::UeSecurityCapability;
+use crate::NasError;
+
 /// Key Derivation Function ID (FC param) (Section A.8 33.501)
 const ALGO_KEY_DERIVE_FC: u8 = 0x69_u8;
 
@@ -15,6 +21,7 @@ pub enum NasAlgorithmType {
 
 /// NAS Encryption Algorithm Identity
 #[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NasEncryptionAlgoIdentity {
     /// - `NAS_ALGO_IDENTITY_NEA0` - Null Encryption Algorithm (Section D.1 33.501)
     Nea0 = 0x00,
@@ -27,6 +34,8 @@ pub enum NasEncryptionAlgoIdentity {
 }
 
 /// NAS Identity Algorithm Identity
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NasIntegrityAlgoIdentity {
     /// - `NAS_ALGO_IDENTITY_NIA0` - Null Integrity Algorithm (Section D.1 33.501)
     Nia0 = 0x00,
@@ -38,6 +47,40 @@ pub enum NasIntegrityAlgoIdentity {
     Nia3 = 0x03,
 }
 
+impl TryFrom<u8> for NasEncryptionAlgoIdentity {
+    type Error = NasError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Nea0),
+            1 => Ok(Self::Nea1),
+            2 => Ok(Self::Nea2),
+            3 => Ok(Self::Nea3),
+            _ => Err(NasError::semantically_incorrect(format!(
+                "Unsupported NAS Encryption Algorithm: {}",
+                value
+            ))),
+        }
+    }
+}
+
+impl TryFrom<u8> for NasIntegrityAlgoIdentity {
+    type Error = NasError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Nia0),
+            1 => Ok(Self::Nia1),
+            2 => Ok(Self::Nia2),
+            3 => Ok(Self::Nia3),
+            _ => Err(NasError::semantically_incorrect(format!(
+                "Unsupported NAS Integrity Algorithm: {}",
+                value
+            ))),
+        }
+    }
+}
+
 /// NAS Key Type: The 128 bit key used by Encryption or Identity Algorithms
 pub type NasKey = [u8; 16];
 
@@ -78,6 +121,24 @@ pub fn nas_integrity_algorithm_key(
     kdf[16..].try_into().unwrap()
 }
 
+/// Select the NAS security algorithms for a UE: the first algorithm of each of the configured
+/// priority lists that is supported by the UE. Returns `None` if the UE supports none of the
+/// configured algorithms of either kind. (Section 6.7.2 33.501)
+pub fn select_nas_algorithms(
+    ue_sec_capability: &UeSecurityCapability,
+    encryption_priority: &[NasEncryptionAlgoIdentity],
+    integrity_priority: &[NasIntegrityAlgoIdentity],
+) -> Option<(NasEncryptionAlgoIdentity, NasIntegrityAlgoIdentity)> {
+    let encryption = encryption_priority
+        .iter()
+        .find(|algo| ue_sec_capability.supports_fiveg_ea(**algo))?;
+    let integrity = integrity_priority
+        .iter()
+        .find(|algo| ue_sec_capability.supports_fiveg_ia(**algo))?;
+
+    Some((*encryption, *integrity))
+}
+
 /// Encrypt a given NAS payload based on Algorithm Identity and given NAS Key and
 /// other parameters
 pub fn nas_encrypt_payload(
@@ -100,21 +161,17 @@ pub fn nas_encrypt_payload(
 
     match algo_identity {
         NasEncryptionAlgoIdentity::Nea0 => payload.to_vec(),
+        NasEncryptionAlgoIdentity::Nea1 => {
+            security_3gpp::encrypt_snow3g_f8(key, count, bearer, downlink, payload, bitlen)
+        }
         NasEncryptionAlgoIdentity::Nea2 => {
             let mut output = security_3gpp::encrypt_aes128_ctr(key, iv, payload);
-            let remaining = 32 - bitlen % 32;
-            if remaining > 0 {
-                let output_len = output.len();
-                // TODO: Case for when bitlen is < 4 bytes
-                let last = output.split_off(output_len - 4);
-                let mut last_u32 = u32::from_be_bytes(last.try_into().unwrap());
-                let last_u32 = last_u32 & (0xFFFFFFFF << remaining);
-                let last = last_u32.to_be_bytes();
-                output.extend(last)
-            }
+            security_3gpp::zero_trailing_bits(&mut output, bitlen);
             output
         }
-        _ => todo!(),
+        NasEncryptionAlgoIdentity::Nea3 => {
+            security_3gpp::encrypt_zuc_eea3(key, count, bearer, downlink, payload, bitlen)
+        }
     }
 }
 
@@ -153,16 +210,99 @@ pub fn nas_calculate_mac(
 
     match algo_identity {
         NasIntegrityAlgoIdentity::Nia0 => [0_u8; 4],
+        NasIntegrityAlgoIdentity::Nia1 => {
+            // FRESH is BEARER followed by 27 zero bits (Section B.2.2 33.401)
+            let fresh = (bearer as u32) << 27;
+            security_3gpp::mac_snow3g_f9(
+                key,
+                count,
+                fresh,
+                downlink,
+                payload,
+                payload.len() as u64 * 8,
+            )
+        }
         NasIntegrityAlgoIdentity::Nia2 => security_3gpp::mac_aes128_cmac(key, &kv)[..4]
             .try_into()
             .unwrap(),
-        _ => todo!(),
+        NasIntegrityAlgoIdentity::Nia3 => security_3gpp::mac_zuc_eia3(
+            key,
+            count,
+            bearer,
+            downlink,
+            payload,
+            payload.len() as u32 * 8,
+        ),
     }
 }
 
 #[cfg(test)]
 mod tests {
 
+    use super::*;
+
+    #[test]
+    fn test_select_nas_algorithms() {
+        // 5G-EA0, 5G-EA1, 5G-EA2 and 5G-IA1, 5G-IA2
+        let ue_sec_capability = UeSecurityCapability {
+            iei: None,
+            fiveg_ea: 0xE0,
+            fiveg_ia: 0x60,
+            eea: None,
+            eia: None,
+            spare: vec![],
+        };
+
+        let encryption_priority = [
+            NasEncryptionAlgoIdentity::Nea3,
+            NasEncryptionAlgoIdentity::Nea2,
+            NasEncryptionAlgoIdentity::Nea0,
+        ];
+        let integrity_priority = [
+            NasIntegrityAlgoIdentity::Nia3,
+            NasIntegrityAlgoIdentity::Nia1,
+            NasIntegrityAlgoIdentity::Nia2,
+        ];
+        assert_eq!(
+            select_nas_algorithms(
+                &ue_sec_capability,
+                &encryption_priority,
+                &integrity_priority
+            ),
+            Some((
+                NasEncryptionAlgoIdentity::Nea2,
+                NasIntegrityAlgoIdentity::Nia1
+            ))
+        );
+
+        // No common integrity algorithm.
+        assert_eq!(
+            select_nas_algorithms(
+                &ue_sec_capability,
+                &encryption_priority,
+                &[
+                    NasIntegrityAlgoIdentity::Nia3,
+                    NasIntegrityAlgoIdentity::Nia0
+                ]
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_algo_identity_from_u8() {
+        assert_eq!(
+            NasEncryptionAlgoIdentity::try_from(2),
+            Ok(NasEncryptionAlgoIdentity::Nea2)
+        );
+        assert_eq!(
+            NasIntegrityAlgoIdentity::try_from(3),
+            Ok(NasIntegrityAlgoIdentity::Nia3)
+        );
+        assert!(NasEncryptionAlgoIdentity::try_from(4).is_err());
+        assert!(NasIntegrityAlgoIdentity::try_from(8).is_err());
+    }
+
     #[test]
     fn test_33_401_c1() {
         struct TestSet<'ts> {
@@ -282,7 +422,7 @@ mod tests {
             );
             // decrypt it and we should get back the payload
             let result = super::nas_decrypt_payload(
-                ts.key.try_into().unwrap(),
+                ts.key,
                 super::NasEncryptionAlgoIdentity::Nea2,
                 ts.count,
                 ts.bearer,
@@ -300,6 +440,112 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_33_401_c3() {
+        // TestSet 1 33.401 C.3.1
+        let key = hex::decode("d3c5d592327fb11c4035c6680af8c6d1")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let payload =
+            hex::decode("981ba6824c1bfb1ab485472029b71d808ce33e2cc3c0b5fc1f3de8a6dc66b1f0")
+                .unwrap();
+        let ciphertext = "5d5bfe75eb04f68ce0a12377ea00b37d47c6a0ba06309155086a859c4341b378";
+
+        let result = super::nas_encrypt_payload(
+            key,
+            super::NasEncryptionAlgoIdentity::Nea1,
+            0x398a59b4_u32,
+            0x15_u8,
+            true,
+            &payload,
+            253,
+        );
+        assert_eq!(hex::encode(&result), ciphertext);
+
+        let result = super::nas_decrypt_payload(
+            key,
+            super::NasEncryptionAlgoIdentity::Nea1,
+            0x398a59b4_u32,
+            0x15_u8,
+            true,
+            &result,
+            253,
+        );
+        assert_eq!(result, payload);
+    }
+
+    #[test]
+    fn test_33_401_c4() {
+        // TestSet 1 33.401 C.4.1
+        let key = hex::decode("2bd6459f82c5b300952c49104881ff48")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let payload = hex::decode("3332346263393861373479").unwrap();
+
+        let result = super::nas_calculate_mac(
+            key,
+            super::NasIntegrityAlgoIdentity::Nia1,
+            0x38a6f056_u32,
+            0x1f_u8,
+            false,
+            &payload,
+        );
+        assert_eq!(hex::encode(result), "731f1165");
+    }
+
+    #[test]
+    fn test_eea3() {
+        // TestSet 1: 128-EEA3 & 128-EIA3 Document 3: Implementor's Test Data
+        let key = hex::decode("173d14ba5003731d7a60049470f00a29")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let payload = hex::decode("6cf65340735552ab0c9752fa6f9025fe0bd675d9005875b200").unwrap();
+        let ciphertext = "a6c85fc66afb8533aafc2518dfe784940ee1e4b030238cc800";
+
+        let result = super::nas_encrypt_payload(
+            key,
+            super::NasEncryptionAlgoIdentity::Nea3,
+            0x66035492_u32,
+            0x0f_u8,
+            false,
+            &payload,
+            193,
+        );
+        assert_eq!(hex::encode(&result), ciphertext);
+    }
+
+    #[test]
+    fn test_eia3() {
+        // TestSet 3: 128-EEA3 & 128-EIA3 Document 3: Implementor's Test Data. The NAS MAC is
+        // calculated over all the octets of the message (576 bits instead of 577 bits of the Test
+        // Set), so the `COUNT`, `BEARER` and `DIRECTION` are verified against `128-EIA3`.
+        let key = hex::decode("c9e6cec4607c72db000aefa88385ab0a")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let payload = hex::decode("983b41d47d780c9e1ad11d7eb70391b1de0b35da2dc62f83e7b78d6306ca0ea07e941b7be91348f9fcb170e2217fecd97f9f68adb16e5d7d21e569d280ed775cebde3f4093c53881").unwrap();
+
+        let result = super::nas_calculate_mac(
+            key,
+            super::NasIntegrityAlgoIdentity::Nia3,
+            0xa94059da_u32,
+            0x0a_u8,
+            true,
+            &payload,
+        );
+        assert_eq!(
+            result,
+            security_3gpp::mac_zuc_eia3(key, 0xa94059da, 0x0a, true, &payload, 576)
+        );
+        assert_ne!(
+            result,
+            security_3gpp::mac_zuc_eia3(key, 0xa94059da, 0x0a, false, &payload, 576)
+        );
+    }
+
     #[test]
     fn test_33_401_c2() {
         struct TestSet<'ts> {
@@ -365,7 +611,7 @@ mod tests {
                 "Failure:{}, Expected: {}, Computed:{}",
                 ts.name,
                 ts.mac,
-                hex::encode(&result)
+                hex::encode(result)
             );
         }
     }
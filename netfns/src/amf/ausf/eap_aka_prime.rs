@@ -0,0 +1,351 @@
+//! EAP-AKA' Packets (RFC 9048)
+//!
+//! Encoding and decoding of the EAP Packets (Section 4 RFC 3748) carrying the EAP-AKA' messages
+//! (Section 8.1 RFC 4187) used for the EAP-AKA' Primary Authentication.
+
+use security_3gpp::{constant_time_eq, mac_eap_aka_prime};
+
+use nas::Plmn;
+
+// EAP Codes (Section 4 RFC 3748)
+pub(in crate::amf) const EAP_CODE_REQUEST: u8 = 1;
+pub(in crate::amf) const EAP_CODE_RESPONSE: u8 = 2;
+pub(in crate::amf) const EAP_CODE_SUCCESS: u8 = 3;
+pub(in crate::amf) const EAP_CODE_FAILURE: u8 = 4;
+
+// EAP Method Type for EAP-AKA' (Section 6 RFC 9048)
+const EAP_TYPE_AKA_PRIME: u8 = 50;
+
+// EAP-AKA' Subtypes (Section 11 RFC 4187)
+pub(in crate::amf) const SUBTYPE_AKA_CHALLENGE: u8 = 1;
+pub(in crate::amf) const SUBTYPE_AKA_SYNCHRONIZATION_FAILURE: u8 = 4;
+// Authentication Reject by the UE is handled like any other unexpected Subtype.
+#[cfg(test)]
+pub(in crate::amf) const SUBTYPE_AKA_AUTHENTICATION_REJECT: u8 = 2;
+
+// EAP-AKA' Attributes (Section 11 RFC 4187, Section 6 RFC 9048)
+pub(in crate::amf) const AT_RAND: u8 = 1;
+pub(in crate::amf) const AT_AUTN: u8 = 2;
+pub(in crate::amf) const AT_RES: u8 = 3;
+pub(in crate::amf) const AT_AUTS: u8 = 4;
+pub(in crate::amf) const AT_MAC: u8 = 11;
+pub(in crate::amf) const AT_KDF_INPUT: u8 = 23;
+pub(in crate::amf) const AT_KDF: u8 = 24;
+
+// Key Derivation Function: The default KDF for EAP-AKA'. (Section 3.1 RFC 9048)
+pub(in crate::amf) const KDF_DEFAULT: u16 = 1;
+
+// Length of the `MAC` in the `AT_MAC`.
+const MAC_LENGTH: usize = 16;
+
+/// An EAP-AKA' Attribute: `value` is the Attribute value without the Type and Length, including
+/// the Reserved or the actual length octets where applicable, padded to a multiple of 4 octets
+/// (minus the 2 octets of Type and Length).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(in crate::amf) struct Attribute {
+    pub(in crate::amf) attr_type: u8,
+    pub(in crate::amf) value: Vec<u8>,
+}
+
+impl Attribute {
+    /// Attribute with the 2 Reserved octets followed by the `data`. (eg. `AT_RAND`, `AT_AUTN`)
+    pub(in crate::amf) fn reserved(attr_type: u8, data: &[u8]) -> Self {
+        let mut value = vec![0, 0];
+        value.extend(data);
+        Self::padded(attr_type, value)
+    }
+
+    /// Attribute with the actual length (in `bits` or octets as applicable) in the first 2
+    /// octets followed by the `data`. (eg. `AT_RES`, `AT_KDF_INPUT`)
+    pub(in crate::amf) fn with_length(attr_type: u8, length: u16, data: &[u8]) -> Self {
+        let mut value = length.to_be_bytes().to_vec();
+        value.extend(data);
+        Self::padded(attr_type, value)
+    }
+
+    /// Attribute with the `data` as it is. (eg. `AT_AUTS`, `AT_KDF`)
+    pub(in crate::amf) fn plain(attr_type: u8, data: &[u8]) -> Self {
+        Self::padded(attr_type, data.to_vec())
+    }
+
+    fn padded(attr_type: u8, mut value: Vec<u8>) -> Self {
+        let padding = (4 - (value.len() + 2) % 4) % 4;
+        value.resize(value.len() + padding, 0);
+        Self { attr_type, value }
+    }
+
+    /// The data of the attribute after the Reserved (or the length) octets.
+    pub(in crate::amf) fn data(&self) -> &[u8] {
+        self.value.get(2..).unwrap_or_default()
+    }
+
+    /// The data of the attribute truncated to the actual length in the first 2 octets. The
+    /// length is in bits if `length_in_bits` is true, else in octets.
+    pub(in crate::amf) fn data_with_length(&self, length_in_bits: bool) -> &[u8] {
+        let data = self.data();
+        let length = match self.value.get(..2) {
+            Some(length) => u16::from_be_bytes([length[0], length[1]]) as usize,
+            None => 0,
+        };
+        let length = if length_in_bits { length / 8 } else { length };
+
+        &data[..length.min(data.len())]
+    }
+}
+
+/// An EAP-AKA' message carried in the EAP Request or Response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(in crate::amf) struct EapAkaPrimeMessage {
+    pub(in crate::amf) subtype: u8,
+    pub(in crate::amf) attributes: Vec<Attribute>,
+}
+
+impl EapAkaPrimeMessage {
+    pub(in crate::amf) fn new(subtype: u8, attributes: Vec<Attribute>) -> Self {
+        Self {
+            subtype,
+            attributes,
+        }
+    }
+
+    pub(in crate::amf) fn attribute(&self, attr_type: u8) -> Option<&Attribute> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.attr_type == attr_type)
+    }
+}
+
+/// An EAP Packet used in the EAP-AKA' Authentication. (Section 4 RFC 3748)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(in crate::amf) struct EapPacket {
+    pub(in crate::amf) code: u8,
+    pub(in crate::amf) identifier: u8,
+
+    // Not present in the EAP Success and the EAP Failure.
+    pub(in crate::amf) message: Option<EapAkaPrimeMessage>,
+}
+
+impl EapPacket {
+    pub(in crate::amf) fn request(identifier: u8, message: EapAkaPrimeMessage) -> Self {
+        Self {
+            code: EAP_CODE_REQUEST,
+            identifier,
+            message: Some(message),
+        }
+    }
+
+    // EAP Responses are sent only by the UE.
+    #[cfg(test)]
+    pub(in crate::amf) fn response(identifier: u8, message: EapAkaPrimeMessage) -> Self {
+        Self {
+            code: EAP_CODE_RESPONSE,
+            identifier,
+            message: Some(message),
+        }
+    }
+
+    pub(in crate::amf) fn success(identifier: u8) -> Self {
+        Self {
+            code: EAP_CODE_SUCCESS,
+            identifier,
+            message: None,
+        }
+    }
+
+    pub(in crate::amf) fn failure(identifier: u8) -> Self {
+        Self {
+            code: EAP_CODE_FAILURE,
+            identifier,
+            message: None,
+        }
+    }
+
+    pub(in crate::amf) fn encode(&self) -> Vec<u8> {
+        let mut data = vec![];
+        if let Some(message) = &self.message {
+            data.extend([EAP_TYPE_AKA_PRIME, message.subtype, 0, 0]);
+            for attribute in &message.attributes {
+                data.push(attribute.attr_type);
+                data.push(((attribute.value.len() + 2) / 4) as u8);
+                data.extend(&attribute.value);
+            }
+        }
+
+        let mut output = vec![self.code, self.identifier];
+        output.extend(((data.len() + 4) as u16).to_be_bytes());
+        output.extend(data);
+
+        output
+    }
+
+    pub(in crate::amf) fn decode(data: &[u8]) -> std::io::Result<Self> {
+        if data.len() < 4 {
+            return Err(invalid_packet("EAP Packet too short."));
+        }
+
+        let code = data[0];
+        let identifier = data[1];
+        let length = u16::from_be_bytes([data[2], data[3]]) as usize;
+        if length < 4 || length > data.len() {
+            return Err(invalid_packet("Invalid EAP Packet length."));
+        }
+
+        let message = match code {
+            EAP_CODE_REQUEST | EAP_CODE_RESPONSE => Some(Self::decode_message(&data[4..length])?),
+            EAP_CODE_SUCCESS | EAP_CODE_FAILURE => None,
+            _ => return Err(invalid_packet("Unknown EAP Code.")),
+        };
+
+        Ok(Self {
+            code,
+            identifier,
+            message,
+        })
+    }
+
+    fn decode_message(data: &[u8]) -> std::io::Result<EapAkaPrimeMessage> {
+        if data.len() < 4 || data[0] != EAP_TYPE_AKA_PRIME {
+            return Err(invalid_packet("Not an EAP-AKA' Message."));
+        }
+
+        let subtype = data[1];
+        let mut attributes = vec![];
+        let mut decoded = 4;
+        while decoded < data.len() {
+            if decoded + 2 > data.len() {
+                return Err(invalid_packet("EAP-AKA' Attribute too short."));
+            }
+            let length = data[decoded + 1] as usize * 4;
+            if length == 0 || decoded + length > data.len() {
+                return Err(invalid_packet("Invalid EAP-AKA' Attribute length."));
+            }
+            attributes.push(Attribute {
+                attr_type: data[decoded],
+                value: data[decoded + 2..decoded + length].to_vec(),
+            });
+            decoded += length;
+        }
+
+        Ok(EapAkaPrimeMessage {
+            subtype,
+            attributes,
+        })
+    }
+
+    /// Add the `AT_MAC` to the message computed over the whole packet using `K_aut`. (Section
+    /// 10.15 RFC 4187)
+    pub(in crate::amf) fn add_mac(&mut self, k_aut: &[u8; 32]) {
+        let message = self.message.as_mut().unwrap();
+        message
+            .attributes
+            .push(Attribute::reserved(AT_MAC, &[0; MAC_LENGTH]));
+
+        let mac = mac_eap_aka_prime(k_aut, &self.encode());
+
+        let message = self.message.as_mut().unwrap();
+        message.attributes.last_mut().unwrap().value = Attribute::reserved(AT_MAC, &mac).value;
+    }
+
+    /// Verify the `AT_MAC` of the message using `K_aut`.
+    pub(in crate::amf) fn verify_mac(&self, k_aut: &[u8; 32]) -> bool {
+        let message = match &self.message {
+            Some(message) => message,
+            None => return false,
+        };
+
+        let mac = match message.attribute(AT_MAC) {
+            Some(attribute) if attribute.data().len() == MAC_LENGTH => attribute.data(),
+            _ => return false,
+        };
+
+        let mut packet = self.clone();
+        for attribute in &mut packet.message.as_mut().unwrap().attributes {
+            if attribute.attr_type == AT_MAC {
+                attribute.value = Attribute::reserved(AT_MAC, &[0; MAC_LENGTH]).value;
+            }
+        }
+
+        constant_time_eq(&mac_eap_aka_prime(k_aut, &packet.encode()), mac)
+    }
+}
+
+/// The identity of the UE used in the EAP-AKA' key derivation: The IMSI based SUPI in the NAI
+/// format with the realm of the `home_network`. (Section 28.7.3 23.003, Annex F 33.501)
+pub(in crate::amf) fn eap_aka_prime_identity(supi: &str, home_network: &Plmn) -> String {
+    format!(
+        "{}@nai.5gc.mnc{:03}.mcc{:03}.3gppnetwork.org",
+        supi.trim_start_matches("imsi-"),
+        home_network.mnc(),
+        home_network.mcc()
+    )
+}
+
+fn invalid_packet(reason: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eap_packet() {
+        let k_aut = [0x5a_u8; 32];
+        let mut request = EapPacket::request(
+            1,
+            EapAkaPrimeMessage::new(
+                SUBTYPE_AKA_CHALLENGE,
+                vec![
+                    Attribute::reserved(AT_RAND, &[0x11; 16]),
+                    Attribute::reserved(AT_AUTN, &[0x22; 16]),
+                    Attribute::plain(AT_KDF, &KDF_DEFAULT.to_be_bytes()),
+                    Attribute::with_length(AT_KDF_INPUT, 4, b"WLAN"),
+                ],
+            ),
+        );
+        request.add_mac(&k_aut);
+
+        let encoded = request.encode();
+        assert_eq!(&encoded[..8], &[1, 1, 0, 80, 50, 1, 0, 0]);
+
+        let decoded = EapPacket::decode(&encoded).unwrap();
+        assert_eq!(decoded, request);
+        assert!(decoded.verify_mac(&k_aut));
+        assert!(!decoded.verify_mac(&[0x5b_u8; 32]));
+
+        let message = decoded.message.unwrap();
+        assert_eq!(message.attribute(AT_RAND).unwrap().data(), &[0x11; 16]);
+        assert_eq!(
+            message
+                .attribute(AT_KDF_INPUT)
+                .unwrap()
+                .data_with_length(false),
+            b"WLAN"
+        );
+
+        // `AT_RES` with the length in bits and padding.
+        let res = Attribute::with_length(AT_RES, 40, &[1, 2, 3, 4, 5]);
+        assert_eq!(res.value.len(), 10);
+        assert_eq!(res.data_with_length(true), &[1, 2, 3, 4, 5]);
+
+        assert_eq!(EapPacket::success(2).encode(), vec![3, 2, 0, 4]);
+        assert_eq!(
+            EapPacket::decode(&[4, 2, 0, 4]).unwrap(),
+            EapPacket::failure(2)
+        );
+    }
+
+    #[test]
+    fn test_eap_packet_errors() {
+        assert!(EapPacket::decode(&[1, 1, 0]).is_err());
+        assert!(EapPacket::decode(&[1, 1, 0, 8, 50, 1, 0, 0]).is_ok());
+        // Length larger than the data.
+        assert!(EapPacket::decode(&[1, 1, 0, 12, 50, 1, 0, 0]).is_err());
+        // Not EAP-AKA'
+        assert!(EapPacket::decode(&[1, 1, 0, 8, 23, 1, 0, 0]).is_err());
+        // Attribute with zero length.
+        assert!(EapPacket::decode(&[2, 1, 0, 12, 50, 1, 0, 0, 1, 0, 0, 0]).is_err());
+        // Unknown Code
+        assert!(EapPacket::decode(&[5, 1, 0, 4]).is_err());
+    }
+}
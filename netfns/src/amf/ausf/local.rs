@@ -0,0 +1,848 @@
+//! AUSF co-located with the AMF
+//!
+//! Implements the AUSF along with the UDM (ARPF) functionality of generating the 5G Home
+//! Environment Authentication Vectors (Section 6.1.3.2 33.501) and the EAP-AKA' Authentication
+//! Vectors (Section 6.1.3.1 33.501) for the subscribers in the AMF configuration. The UE is
+//! identified by the IMSI based SUPI or the SUCI using the Null Scheme.
+
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+
+use sbi5g::models::{
+    AuthResult, AuthType, AuthenticationInfo, Av5gAka, ConfirmationData, ConfirmationDataResponse,
+    EapAuthMethod200Response, EapSession, LinksValueSchema, UeAuthenticationCtx,
+    UeAuthenticationCtx5gAuthData,
+};
+
+use security_3gpp::{
+    constant_time_eq, derive_ck_ik_prime, derive_hres_star, derive_kausf, derive_kseaf,
+    derive_res_star, AuthAlgorithm, AuthMgmtField, AuthVector, EapAkaPrimeKeys, Milenage, Rand,
+    ResStar, SecurityKey, Sqn,
+};
+
+use nas::messages::ies::Suci;
+use nas::Plmn;
+
+use crate::amf::config::AusfConfig;
+
+use super::eap_aka_prime::{
+    eap_aka_prime_identity, Attribute, EapAkaPrimeMessage, EapPacket, AT_AUTN, AT_AUTS, AT_KDF,
+    AT_KDF_INPUT, AT_RAND, AT_RES, EAP_CODE_RESPONSE, KDF_DEFAULT, SUBTYPE_AKA_CHALLENGE,
+    SUBTYPE_AKA_SYNCHRONIZATION_FAILURE,
+};
+use super::{Ausf, AusfError, EapSessionResponse};
+
+// `SQN` is a 48 bit value.
+const SQN_MAX: u64 = (1 << 48) - 1;
+
+// Authentication Management Field with the "separation bit" set. (Annex H 33.102)
+const DEFAULT_AUTH_MGMT_FIELD: AuthMgmtField = [0x80, 0x00];
+
+struct Subscriber {
+    milenage: Milenage,
+    sqn: u64,
+    amf: AuthMgmtField,
+    auth_type: AuthType,
+}
+
+impl Subscriber {
+    // Re-synchronisation: `SQN` is reset to the `SQN_MS` received in the `AUTS`, when the `MAC-S`
+    // is valid. (Section 6.3.5 33.102)
+    fn resynchronize(&mut self, rand: Rand, auts: &[u8]) {
+        match self.milenage.resync_sqn(rand, auts) {
+            Some(sqn_ms) => {
+                self.sqn = sqn_ms.iter().fold(0, |sqn, octet| sqn << 8 | *octet as u64);
+            }
+            None => log::warn!("`MAC-S` verification failed."),
+        }
+    }
+
+    // Generate the Authentication Vector with the next `SQN` and a random `RAND`.
+    fn next_auth_vector(&mut self) -> AuthVector {
+        self.sqn = (self.sqn + 1) & SQN_MAX;
+        let sqn: Sqn = self.sqn.to_be_bytes()[2..].try_into().unwrap();
+        let rand: Rand = rand::random();
+
+        self.milenage.generate_auth_vector(rand, sqn, self.amf)
+    }
+}
+
+// Authentication Context of an ongoing Authentication.
+enum AuthenticationContext {
+    // 5G AKA: Waiting for the confirmation with the `RES*`.
+    FivegAka {
+        supi: String,
+        xres_star: ResStar,
+        kseaf: SecurityKey,
+    },
+
+    // EAP-AKA': Waiting for the EAP Response for the EAP Request sent.
+    EapAkaPrime(Box<EapAkaPrimeContext>),
+}
+
+struct EapAkaPrimeContext {
+    supi: String,
+    serving_network_name: String,
+    identifier: u8,
+    rand: Rand,
+    xres: Vec<u8>,
+    keys: EapAkaPrimeKeys,
+
+    // Re-synchronisation is attempted only once.
+    resynchronized: bool,
+}
+
+impl EapAkaPrimeContext {
+    // Generate the EAP-AKA' Challenge for the Authentication Vector. (Section 6.1.3.1 33.501)
+    fn new(
+        supi: String,
+        serving_network_name: String,
+        identifier: u8,
+        identity: &str,
+        av: AuthVector,
+    ) -> (Self, EapPacket) {
+        let (ck_prime, ik_prime) =
+            derive_ck_ik_prime(&av.ck, &av.ik, &serving_network_name, &av.autn[..6]);
+        let keys = EapAkaPrimeKeys::derive(&ck_prime, &ik_prime, identity);
+
+        let mut request = EapPacket::request(
+            identifier,
+            EapAkaPrimeMessage::new(
+                SUBTYPE_AKA_CHALLENGE,
+                vec![
+                    Attribute::reserved(AT_RAND, &av.rand),
+                    Attribute::reserved(AT_AUTN, &av.autn),
+                    Attribute::plain(AT_KDF, &KDF_DEFAULT.to_be_bytes()),
+                    Attribute::with_length(
+                        AT_KDF_INPUT,
+                        serving_network_name.len() as u16,
+                        serving_network_name.as_bytes(),
+                    ),
+                ],
+            ),
+        );
+        request.add_mac(&keys.k_aut);
+
+        let context = Self {
+            supi,
+            serving_network_name,
+            identifier,
+            rand: av.rand,
+            xres: av.xres,
+            keys,
+            resynchronized: false,
+        };
+
+        (context, request)
+    }
+}
+
+#[derive(Default)]
+struct LocalAusfState {
+    subscribers: HashMap<String, Subscriber>,
+    contexts: HashMap<String, AuthenticationContext>,
+    next_context_id: u64,
+}
+
+impl LocalAusfState {
+    // Link of the resource for the next Authentication Context.
+    fn next_link(&mut self, resource: &str) -> String {
+        let link = format!(
+            "/nausf-auth/v1/ue-authentications/{}/{}",
+            self.next_context_id, resource
+        );
+        self.next_context_id += 1;
+        link
+    }
+}
+
+/// AUSF co-located with the AMF serving the subscribers of the `home_network`.
+pub(in crate::amf) struct LocalAusf {
+    home_network: Plmn,
+    state: Mutex<LocalAusfState>,
+}
+
+impl std::fmt::Debug for LocalAusf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Subscription credentials are never printed.
+        f.debug_struct("LocalAusf")
+            .field("home_network", &self.home_network)
+            .finish()
+    }
+}
+
+impl LocalAusf {
+    /// Create the AUSF for the subscribers in the `config`. The subscriber credentials are
+    /// validated when the config is deserialized.
+    pub(in crate::amf) fn from_config(home_network: Plmn, config: &AusfConfig) -> Self {
+        let mut state = LocalAusfState::default();
+
+        for subscriber in &config.subscribers {
+            let k = hex::decode(&subscriber.k).unwrap();
+            let opc = hex::decode(&subscriber.opc).unwrap();
+            let sqn = subscriber
+                .sqn
+                .as_ref()
+                .map(|sqn| hex::decode(sqn).unwrap())
+                .unwrap_or_default();
+            let amf = subscriber
+                .amf
+                .as_ref()
+                .map(|amf| hex::decode(amf).unwrap().try_into().unwrap())
+                .unwrap_or(DEFAULT_AUTH_MGMT_FIELD);
+
+            state.subscribers.insert(
+                subscriber.supi.clone(),
+                Subscriber {
+                    milenage: Milenage::from_opc(k.try_into().unwrap(), opc.try_into().unwrap()),
+                    sqn: sqn.iter().fold(0, |sqn, octet| sqn << 8 | *octet as u64),
+                    amf,
+                    auth_type: subscriber.auth_method.unwrap_or(AuthType::Variant5GAka),
+                },
+            );
+        }
+
+        Self {
+            home_network,
+            state: Mutex::new(state),
+        }
+    }
+
+    // Get the SUPI from the `supiOrSuci`. The SUCI should be using the Null Scheme, for which the
+    // Scheme Output is the MSIN.
+    fn supi(&self, supi_or_suci: &str) -> Result<String, AusfError> {
+        if supi_or_suci.starts_with("imsi-") {
+            return Ok(supi_or_suci.to_string());
+        }
+
+        let fields: Vec<&str> = supi_or_suci.split('-').collect();
+        match fields[..] {
+            ["suci", "0", mcc, mnc, _routing_indicator, "0", "0", msin] => {
+                Ok(format!("imsi-{}{}{}", mcc, mnc, msin))
+            }
+            _ => Err(AusfError::UserNotFound(supi_or_suci.to_string())),
+        }
+    }
+
+    // The EAP Session is complete: The EAP Success along with the `KSEAF` derived from the
+    // `KAUSF` (Section 6.1.3.1 33.501) or the EAP Failure.
+    fn complete_eap_session(context: EapAkaPrimeContext, success: bool) -> EapSessionResponse {
+        let mut eap_session;
+        if success {
+            let kausf = context.keys.kausf();
+            let kseaf = derive_kseaf(&kausf, &context.serving_network_name);
+
+            let eap_success = EapPacket::success(context.identifier).encode();
+            eap_session = EapSession::new(Some(BASE64.encode(eap_success)));
+            eap_session.auth_result = Some(AuthResult::Success);
+            eap_session.supi = Some(context.supi);
+            eap_session.k_seaf = Some(hex::encode(kseaf));
+        } else {
+            let eap_failure = EapPacket::failure(context.identifier).encode();
+            eap_session = EapSession::new(Some(BASE64.encode(eap_failure)));
+            eap_session.auth_result = Some(AuthResult::Failure);
+        }
+
+        EapSessionResponse::Complete(eap_session)
+    }
+}
+
+#[async_trait]
+impl Ausf for LocalAusf {
+    fn serves(&self, suci: &Suci) -> bool {
+        suci.plmn == self.home_network
+    }
+
+    async fn ue_authentications(
+        &self,
+        authentication_info: AuthenticationInfo,
+    ) -> Result<UeAuthenticationCtx, AusfError> {
+        let serving_network_name = authentication_info.serving_network_name;
+        if !serving_network_name.starts_with("5G:") {
+            return Err(AusfError::ServingNetworkNotAuthorized(serving_network_name));
+        }
+
+        let supi = self.supi(&authentication_info.supi_or_suci)?;
+
+        let mut state = self.state.lock().unwrap();
+        let subscriber = state
+            .subscribers
+            .get_mut(&supi)
+            .ok_or_else(|| AusfError::UserNotFound(supi.clone()))?;
+
+        if let Some(resync_info) = authentication_info.resynchronization_info {
+            let rand: Option<Rand> = hex::decode(&resync_info.rand)
+                .ok()
+                .and_then(|rand| rand.try_into().ok());
+            let auts = hex::decode(&resync_info.auts).ok();
+            let (rand, auts) = rand.zip(auts).ok_or_else(|| {
+                AusfError::InvalidRequest("Invalid `resynchronizationInfo`.".to_string())
+            })?;
+
+            subscriber.resynchronize(rand, &auts);
+        }
+
+        let auth_type = subscriber.auth_type;
+        let av = subscriber.next_auth_vector();
+
+        let (auth_data, resource, link) = if auth_type == AuthType::EapAkaPrime {
+            let identity = eap_aka_prime_identity(&supi, &self.home_network);
+            let (context, request) =
+                EapAkaPrimeContext::new(supi, serving_network_name.clone(), 1, &identity, av);
+
+            let link = state.next_link("eap-session");
+            state.contexts.insert(
+                link.clone(),
+                AuthenticationContext::EapAkaPrime(Box::new(context)),
+            );
+
+            let eap_payload = BASE64.encode(request.encode());
+            (
+                UeAuthenticationCtx5gAuthData::EapPayload(eap_payload),
+                "eap-session",
+                link,
+            )
+        } else {
+            let rand = av.rand;
+            let xres_star = derive_res_star(&av.ck, &av.ik, &serving_network_name, &rand, &av.xres);
+            let hxres_star = derive_hres_star(&rand, &xres_star);
+            let kausf = derive_kausf(&av.ck, &av.ik, &serving_network_name, &av.autn[..6]);
+            let kseaf = derive_kseaf(&kausf, &serving_network_name);
+
+            let link = state.next_link("5g-aka-confirmation");
+            state.contexts.insert(
+                link.clone(),
+                AuthenticationContext::FivegAka {
+                    supi,
+                    xres_star,
+                    kseaf,
+                },
+            );
+
+            let av_5g_aka = Av5gAka::new(
+                hex::encode(rand),
+                hex::encode(hxres_star),
+                hex::encode(&av.autn),
+            );
+            (
+                UeAuthenticationCtx5gAuthData::Av5gAka(av_5g_aka),
+                "5g-aka",
+                link,
+            )
+        };
+
+        let mut links = HashMap::new();
+        links.insert(resource.to_string(), LinksValueSchema { href: Some(link) });
+
+        let mut ue_authentication_ctx = UeAuthenticationCtx::new(auth_type, auth_data, links);
+        ue_authentication_ctx.serving_network_name = Some(serving_network_name);
+
+        Ok(ue_authentication_ctx)
+    }
+
+    async fn confirm_5g_aka(
+        &self,
+        link: &str,
+        confirmation_data: ConfirmationData,
+    ) -> Result<ConfirmationDataResponse, AusfError> {
+        let (supi, xres_star, kseaf) = match self.state.lock().unwrap().contexts.remove(link) {
+            Some(AuthenticationContext::FivegAka {
+                supi,
+                xres_star,
+                kseaf,
+            }) => (supi, xres_star, kseaf),
+            _ => return Err(AusfError::ContextNotFound(link.to_string())),
+        };
+
+        let res_star = confirmation_data
+            .res_star
+            .and_then(|res_star| hex::decode(res_star).ok());
+
+        if res_star.is_some_and(|res_star| constant_time_eq(&res_star, &xres_star)) {
+            let mut response = ConfirmationDataResponse::new(AuthResult::Success);
+            response.supi = Some(supi);
+            response.kseaf = Some(hex::encode(kseaf));
+            Ok(response)
+        } else {
+            Ok(ConfirmationDataResponse::new(AuthResult::Failure))
+        }
+    }
+
+    // The EAP Response to the EAP-AKA' Challenge is verified (Section 6.1.3.1 33.501), the
+    // Synchronization Failure is handled by sending a new Challenge after the Re-synchronisation.
+    // Any other EAP Response results in the EAP Failure.
+    async fn eap_session(
+        &self,
+        link: &str,
+        eap_session: EapSession,
+    ) -> Result<EapSessionResponse, AusfError> {
+        let mut state = self.state.lock().unwrap();
+        let context = match state.contexts.remove(link) {
+            Some(AuthenticationContext::EapAkaPrime(context)) => *context,
+            _ => return Err(AusfError::ContextNotFound(link.to_string())),
+        };
+
+        let response = eap_session
+            .eap_payload
+            .and_then(|payload| BASE64.decode(payload).ok())
+            .and_then(|payload| EapPacket::decode(&payload).ok())
+            .filter(|response| {
+                response.code == EAP_CODE_RESPONSE && response.identifier == context.identifier
+            });
+        let response = match response {
+            Some(response) if response.message.is_some() => response,
+            _ => {
+                log::warn!("Invalid EAP Response for SUPI: {}", context.supi);
+                return Ok(Self::complete_eap_session(context, false));
+            }
+        };
+        let message = response.message.as_ref().unwrap();
+
+        match message.subtype {
+            SUBTYPE_AKA_CHALLENGE => {
+                let res = message
+                    .attribute(AT_RES)
+                    .map(|res| res.data_with_length(true));
+                let success = response.verify_mac(&context.keys.k_aut)
+                    && res.is_some_and(|res| constant_time_eq(res, &context.xres));
+
+                Ok(Self::complete_eap_session(context, success))
+            }
+            SUBTYPE_AKA_SYNCHRONIZATION_FAILURE if !context.resynchronized => {
+                let auts = message.attribute(AT_AUTS).map(|auts| auts.value.clone());
+                let subscriber = match (auts, state.subscribers.get_mut(&context.supi)) {
+                    (Some(auts), Some(subscriber)) => {
+                        subscriber.resynchronize(context.rand, &auts);
+                        subscriber
+                    }
+                    _ => return Ok(Self::complete_eap_session(context, false)),
+                };
+
+                let av = subscriber.next_auth_vector();
+                let identity = eap_aka_prime_identity(&context.supi, &self.home_network);
+                let (mut context, request) = EapAkaPrimeContext::new(
+                    context.supi,
+                    context.serving_network_name,
+                    context.identifier.wrapping_add(1),
+                    &identity,
+                    av,
+                );
+                context.resynchronized = true;
+                state.contexts.insert(
+                    link.to_string(),
+                    AuthenticationContext::EapAkaPrime(Box::new(context)),
+                );
+
+                let mut links = HashMap::new();
+                links.insert(
+                    "eap-session".to_string(),
+                    LinksValueSchema {
+                        href: Some(link.to_string()),
+                    },
+                );
+                Ok(EapSessionResponse::Ongoing(EapAuthMethod200Response::new(
+                    Some(BASE64.encode(request.encode())),
+                    links,
+                )))
+            }
+            // Authentication Reject, Client Error or a repeated Synchronization Failure.
+            subtype => {
+                log::warn!(
+                    "EAP-AKA' failed for SUPI: {}, Subtype: {}",
+                    context.supi,
+                    subtype
+                );
+                Ok(Self::complete_eap_session(context, false))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use security_3gpp::serving_network_name;
+
+    use super::super::eap_aka_prime::{EAP_CODE_FAILURE, SUBTYPE_AKA_AUTHENTICATION_REJECT};
+
+    const SUBSCRIBERS: &str = "subscribers:\n - supi: imsi-999990000000001\n   k: 465b5ce8b199b49faa5f0a2ee238a6bc\n   opc: cd63cb71954a9f4e48a5994e37a02baf\n   sqn: 000000000020\n - supi: imsi-999990000000003\n   k: 465b5ce8b199b49faa5f0a2ee238a6bc\n   opc: cd63cb71954a9f4e48a5994e37a02baf\n   sqn: 000000000020\n   auth_method: EAP_AKA_PRIME";
+
+    fn local_ausf() -> LocalAusf {
+        let config: AusfConfig = serde_yaml::from_str(SUBSCRIBERS).unwrap();
+        LocalAusf::from_config("999-99".parse().unwrap(), &config)
+    }
+
+    fn milenage() -> Milenage {
+        Milenage::from_opc(
+            hex::decode("465b5ce8b199b49faa5f0a2ee238a6bc")
+                .unwrap()
+                .try_into()
+                .unwrap(),
+            hex::decode("cd63cb71954a9f4e48a5994e37a02baf")
+                .unwrap()
+                .try_into()
+                .unwrap(),
+        )
+    }
+
+    fn av_5g_aka(ctx: &UeAuthenticationCtx) -> &Av5gAka {
+        match &*ctx.param_5g_auth_data {
+            UeAuthenticationCtx5gAuthData::Av5gAka(av_5g_aka) => av_5g_aka,
+            _ => panic!("Not a 5G AKA Authentication Vector."),
+        }
+    }
+
+    fn eap_payload(ctx: &UeAuthenticationCtx) -> &str {
+        match &*ctx.param_5g_auth_data {
+            UeAuthenticationCtx5gAuthData::EapPayload(eap_payload) => eap_payload,
+            _ => panic!("Not an EAP Payload."),
+        }
+    }
+
+    // Decode the EAP Request from the `eapPayload`.
+    fn eap_request(eap_payload: &str) -> EapPacket {
+        let request = EapPacket::decode(&BASE64.decode(eap_payload).unwrap()).unwrap();
+        assert_eq!(
+            request.message.as_ref().unwrap().subtype,
+            SUBTYPE_AKA_CHALLENGE
+        );
+        request
+    }
+
+    // Verify the EAP-AKA' Challenge as the UE would for the given `SQN` and return the keys along
+    // with the Authentication Vector.
+    fn verify_challenge(
+        request: &EapPacket,
+        sqn: Sqn,
+        sn_name: &str,
+    ) -> (AuthVector, EapAkaPrimeKeys) {
+        let message = request.message.as_ref().unwrap();
+        let rand: Rand = message
+            .attribute(AT_RAND)
+            .unwrap()
+            .data()
+            .try_into()
+            .unwrap();
+        let av = milenage().generate_auth_vector(rand, sqn, [0x80, 0x00]);
+        assert_eq!(message.attribute(AT_AUTN).unwrap().data(), &av.autn[..]);
+        assert_eq!(
+            message
+                .attribute(AT_KDF_INPUT)
+                .unwrap()
+                .data_with_length(false),
+            sn_name.as_bytes()
+        );
+
+        let (ck_prime, ik_prime) = derive_ck_ik_prime(&av.ck, &av.ik, sn_name, &av.autn[..6]);
+        let keys = EapAkaPrimeKeys::derive(
+            &ck_prime,
+            &ik_prime,
+            "999990000000003@nai.5gc.mnc099.mcc999.3gppnetwork.org",
+        );
+        assert!(request.verify_mac(&keys.k_aut));
+
+        (av, keys)
+    }
+
+    fn eap_session(ausf: &LocalAusf, link: &str, response: EapPacket) -> EapSessionResponse {
+        let eap_session = EapSession::new(Some(BASE64.encode(response.encode())));
+        futures::executor::block_on(ausf.eap_session(link, eap_session)).unwrap()
+    }
+
+    fn challenge_response(identifier: u8, res: &[u8], k_aut: &[u8; 32]) -> EapPacket {
+        let mut response = EapPacket::response(
+            identifier,
+            EapAkaPrimeMessage::new(
+                SUBTYPE_AKA_CHALLENGE,
+                vec![Attribute::with_length(AT_RES, (res.len() * 8) as u16, res)],
+            ),
+        );
+        response.add_mac(k_aut);
+        response
+    }
+
+    #[test]
+    fn test_5g_aka() {
+        let ausf = local_ausf();
+        let sn_name = serving_network_name(999, 99);
+
+        let info = AuthenticationInfo::new(
+            "suci-0-999-99-0000-0-0-0000000001".to_string(),
+            sn_name.clone(),
+        );
+        let ctx = futures::executor::block_on(ausf.ue_authentications(info)).unwrap();
+        assert_eq!(ctx.auth_type, AuthType::Variant5GAka);
+
+        // RES* computed by the UE. The `SQN` is the next one after the configured `SQN`.
+        let rand: Rand = hex::decode(&av_5g_aka(&ctx).rand)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let milenage = milenage();
+        let av = milenage.generate_auth_vector(rand, [0, 0, 0, 0, 0, 0x21], [0x80, 0x00]);
+        assert_eq!(hex::encode(&av.autn), av_5g_aka(&ctx).autn);
+
+        let res_star = derive_res_star(&av.ck, &av.ik, &sn_name, &rand, &av.xres);
+        assert_eq!(
+            hex::encode(derive_hres_star(&rand, &res_star)),
+            av_5g_aka(&ctx).hxres_star
+        );
+
+        let link = ctx._links["5g-aka"].href.clone().unwrap();
+        let data = ConfirmationData::new(Some(hex::encode(res_star)));
+        let response = futures::executor::block_on(ausf.confirm_5g_aka(&link, data)).unwrap();
+        assert_eq!(response.auth_result, AuthResult::Success);
+        assert_eq!(response.supi.as_deref(), Some("imsi-999990000000001"));
+
+        let kausf = derive_kausf(&av.ck, &av.ik, &sn_name, &av.autn[..6]);
+        assert_eq!(
+            response.kseaf,
+            Some(hex::encode(derive_kseaf(&kausf, &sn_name)))
+        );
+
+        // The context is removed after the confirmation.
+        let data = ConfirmationData::new(Some(hex::encode(res_star)));
+        assert!(futures::executor::block_on(ausf.confirm_5g_aka(&link, data)).is_err());
+    }
+
+    #[test]
+    fn test_resynchronization() {
+        let ausf = local_ausf();
+        let sn_name = serving_network_name(999, 99);
+
+        // `AUTS` for `SQN_MS` of 0x100 (Section 6.3.3 33.102)
+        let milenage = milenage();
+        let rand = [0x23_u8; 16];
+        let sqn_ms = [0, 0, 0, 0, 0x01, 0x00];
+        let mut auts: Vec<u8> = sqn_ms
+            .iter()
+            .zip(milenage.f5_star(rand))
+            .map(|(s, a)| s ^ a)
+            .collect();
+        auts.extend(milenage.f1_star(rand, sqn_ms, [0, 0]));
+
+        let mut info = AuthenticationInfo::new("imsi-999990000000001".to_string(), sn_name);
+        info.resynchronization_info = Some(Box::new(sbi5g::models::ResynchronizationInfo::new(
+            hex::encode(rand),
+            hex::encode(auts),
+        )));
+        let ctx = futures::executor::block_on(ausf.ue_authentications(info)).unwrap();
+
+        let rand: Rand = hex::decode(&av_5g_aka(&ctx).rand)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let av = milenage.generate_auth_vector(rand, [0, 0, 0, 0, 0x01, 0x01], [0x80, 0x00]);
+        assert_eq!(hex::encode(&av.autn), av_5g_aka(&ctx).autn);
+    }
+
+    #[test]
+    fn test_eap_aka_prime() {
+        let ausf = local_ausf();
+        let sn_name = serving_network_name(999, 99);
+
+        let info = AuthenticationInfo::new(
+            "suci-0-999-99-0000-0-0-0000000003".to_string(),
+            sn_name.clone(),
+        );
+        let ctx = futures::executor::block_on(ausf.ue_authentications(info)).unwrap();
+        assert_eq!(ctx.auth_type, AuthType::EapAkaPrime);
+        let request = eap_request(eap_payload(&ctx));
+        let (av, keys) = verify_challenge(&request, [0, 0, 0, 0, 0, 0x21], &sn_name);
+
+        // 5G AKA confirmation is not allowed for the EAP-AKA' context.
+        let link = ctx._links["eap-session"].href.clone().unwrap();
+        let data = ConfirmationData::new(None);
+        assert!(futures::executor::block_on(ausf.confirm_5g_aka(&link, data)).is_err());
+
+        let ctx = futures::executor::block_on(ausf.ue_authentications(AuthenticationInfo::new(
+            "imsi-999990000000003".to_string(),
+            sn_name.clone(),
+        )))
+        .unwrap();
+        let request = eap_request(eap_payload(&ctx));
+        let (av_new, keys_new) = verify_challenge(&request, [0, 0, 0, 0, 0, 0x22], &sn_name);
+        assert_ne!(av_new, av);
+        assert_ne!(keys_new, keys);
+
+        let link = ctx._links["eap-session"].href.clone().unwrap();
+        let response = challenge_response(request.identifier, &av_new.xres, &keys_new.k_aut);
+        let eap_session = match eap_session(&ausf, &link, response) {
+            EapSessionResponse::Complete(eap_session) => eap_session,
+            _ => panic!("EAP Session not complete."),
+        };
+        assert_eq!(eap_session.auth_result, Some(AuthResult::Success));
+        assert_eq!(eap_session.supi.as_deref(), Some("imsi-999990000000003"));
+        assert_eq!(
+            eap_session.k_seaf,
+            Some(hex::encode(derive_kseaf(&keys_new.kausf(), &sn_name)))
+        );
+        let success = BASE64.decode(eap_session.eap_payload.unwrap()).unwrap();
+        assert_eq!(
+            EapPacket::decode(&success).unwrap(),
+            EapPacket::success(request.identifier)
+        );
+
+        // The context is removed after the EAP Session is complete.
+        let response = challenge_response(request.identifier, &av_new.xres, &keys_new.k_aut);
+        let eap_session = EapSession::new(Some(BASE64.encode(response.encode())));
+        assert!(futures::executor::block_on(ausf.eap_session(&link, eap_session)).is_err());
+    }
+
+    #[test]
+    fn test_eap_aka_prime_failures() {
+        let ausf = local_ausf();
+        let sn_name = serving_network_name(999, 99);
+
+        let start = || {
+            let info = AuthenticationInfo::new("imsi-999990000000003".to_string(), sn_name.clone());
+            let ctx = futures::executor::block_on(ausf.ue_authentications(info)).unwrap();
+            let request = eap_request(eap_payload(&ctx));
+            (ctx._links["eap-session"].href.clone().unwrap(), request)
+        };
+        let failed = |response: EapSessionResponse| match response {
+            EapSessionResponse::Complete(eap_session) => {
+                assert_eq!(eap_session.auth_result, Some(AuthResult::Failure));
+                assert!(eap_session.k_seaf.is_none());
+                let failure = BASE64.decode(eap_session.eap_payload.unwrap()).unwrap();
+                assert_eq!(EapPacket::decode(&failure).unwrap().code, EAP_CODE_FAILURE);
+            }
+            _ => panic!("EAP Session not complete."),
+        };
+
+        // Wrong `RES`
+        let (link, request) = start();
+        let (av, keys) = verify_challenge(&request, [0, 0, 0, 0, 0, 0x21], &sn_name);
+        let mut res = av.xres.clone();
+        res[0] ^= 0xff;
+        failed(eap_session(
+            &ausf,
+            &link,
+            challenge_response(request.identifier, &res, &keys.k_aut),
+        ));
+
+        // Wrong `AT_MAC`
+        let (link, request) = start();
+        let (av, _) = verify_challenge(&request, [0, 0, 0, 0, 0, 0x22], &sn_name);
+        failed(eap_session(
+            &ausf,
+            &link,
+            challenge_response(request.identifier, &av.xres, &[0; 32]),
+        ));
+
+        // Wrong Identifier
+        let (link, request) = start();
+        let (av, keys) = verify_challenge(&request, [0, 0, 0, 0, 0, 0x23], &sn_name);
+        failed(eap_session(
+            &ausf,
+            &link,
+            challenge_response(request.identifier + 1, &av.xres, &keys.k_aut),
+        ));
+
+        // Authentication Reject by the UE
+        let (link, request) = start();
+        failed(eap_session(
+            &ausf,
+            &link,
+            EapPacket::response(
+                request.identifier,
+                EapAkaPrimeMessage::new(SUBTYPE_AKA_AUTHENTICATION_REJECT, vec![]),
+            ),
+        ));
+
+        // Synchronization Failure with the `AUTS` for `SQN_MS` of 0x100, followed by a new
+        // Challenge. A second Synchronization Failure is not accepted.
+        let (link, request) = start();
+        let milenage = milenage();
+        let rand: Rand = request
+            .message
+            .as_ref()
+            .unwrap()
+            .attribute(AT_RAND)
+            .unwrap()
+            .data()
+            .try_into()
+            .unwrap();
+        let sqn_ms = [0, 0, 0, 0, 0x01, 0x00];
+        let mut auts: Vec<u8> = sqn_ms
+            .iter()
+            .zip(milenage.f5_star(rand))
+            .map(|(s, a)| s ^ a)
+            .collect();
+        auts.extend(milenage.f1_star(rand, sqn_ms, [0, 0]));
+        let synchronization_failure = |identifier| {
+            EapPacket::response(
+                identifier,
+                EapAkaPrimeMessage::new(
+                    SUBTYPE_AKA_SYNCHRONIZATION_FAILURE,
+                    vec![Attribute::plain(AT_AUTS, &auts)],
+                ),
+            )
+        };
+
+        let request = match eap_session(&ausf, &link, synchronization_failure(request.identifier)) {
+            EapSessionResponse::Ongoing(response) => {
+                assert_eq!(
+                    response._links["eap-session"].href.as_deref(),
+                    Some(&link[..])
+                );
+                eap_request(response.eap_payload.as_ref().unwrap())
+            }
+            _ => panic!("EAP Session complete."),
+        };
+        assert_eq!(request.identifier, 2);
+        verify_challenge(&request, [0, 0, 0, 0, 0x01, 0x01], &sn_name);
+
+        failed(eap_session(
+            &ausf,
+            &link,
+            synchronization_failure(request.identifier),
+        ));
+
+        // The new `SQN` is used for the next Authentication.
+        let (_, request) = start();
+        verify_challenge(&request, [0, 0, 0, 0, 0x01, 0x02], &sn_name);
+
+        // EAP Success is not a valid EAP Response.
+        let (link, _) = start();
+        failed(eap_session(&ausf, &link, EapPacket::success(1)));
+    }
+
+    #[test]
+    fn test_errors() {
+        let ausf = local_ausf();
+
+        let info = AuthenticationInfo::new(
+            "imsi-999990000000002".to_string(),
+            serving_network_name(999, 99),
+        );
+        assert!(matches!(
+            futures::executor::block_on(ausf.ue_authentications(info)),
+            Err(AusfError::UserNotFound(_))
+        ));
+
+        // SUCI not using the Null Scheme.
+        let info = AuthenticationInfo::new(
+            "suci-0-999-99-0000-1-1-00112233".to_string(),
+            serving_network_name(999, 99),
+        );
+        assert!(matches!(
+            futures::executor::block_on(ausf.ue_authentications(info)),
+            Err(AusfError::UserNotFound(_))
+        ));
+
+        let info = AuthenticationInfo::new(
+            "imsi-999990000000001".to_string(),
+            "mnc099.mcc999.3gppnetwork.org".to_string(),
+        );
+        assert!(matches!(
+            futures::executor::block_on(ausf.ue_authentications(info)),
+            Err(AusfError::ServingNetworkNotAuthorized(_))
+        ));
+    }
+}
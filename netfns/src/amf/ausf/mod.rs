@@ -0,0 +1,125 @@
+//! Consumer of the `Nausf_UEAuthentication` Service of the AUSF (29.509 Release 17)
+//!
+//! The AMF (SEAF) uses the service for the Primary Authentication of the UE using 5G AKA or
+//! EAP-AKA'. (Section 6.1.3 33.501)
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use sbi5g::models::{
+    AuthenticationInfo, ConfirmationData, ConfirmationDataResponse, EapAuthMethod200Response,
+    EapSession, UeAuthenticationCtx,
+};
+
+use nas::messages::ies::Suci;
+
+use super::config::AmfConfig;
+
+mod local;
+pub(in crate::amf) use local::LocalAusf;
+
+pub(in crate::amf) mod eap_aka_prime;
+
+/// Errors returned by the `Nausf_UEAuthentication` Service operations. (Section 6.1.7.3 29.509)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(in crate::amf) enum AusfError {
+    /// The Serving Network is not authorized. (`SERVING_NETWORK_NOT_AUTHORIZED`)
+    ServingNetworkNotAuthorized(String),
+
+    /// The UE identified by the SUPI or SUCI is not known. (`USER_NOT_FOUND`)
+    UserNotFound(String),
+
+    /// The Authentication Context resource is not known.
+    ContextNotFound(String),
+
+    /// The request is not valid. (`MANDATORY_IE_INCORRECT` etc.)
+    InvalidRequest(String),
+}
+
+impl std::fmt::Display for AusfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ServingNetworkNotAuthorized(name) => {
+                write!(f, "Serving Network Not Authorized: {}", name)
+            }
+            Self::UserNotFound(id) => write!(f, "User Not Found: {}", id),
+            Self::ContextNotFound(link) => {
+                write!(f, "Authentication Context Not Found: {}", link)
+            }
+            Self::InvalidRequest(reason) => write!(f, "Invalid Request: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for AusfError {}
+
+impl From<AusfError> for std::io::Error {
+    fn from(error: AusfError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::Other, error)
+    }
+}
+
+/// Response of the EAP Session operation. (Section 5.2.2.2.3 29.509)
+#[derive(Debug, Clone, PartialEq)]
+pub(in crate::amf) enum EapSessionResponse {
+    /// The EAP Session is ongoing: The next EAP Request to be sent to the UE.
+    Ongoing(EapAuthMethod200Response),
+
+    /// The EAP Session is complete: The Authentication Result along with the EAP Success (or the
+    /// EAP Failure) to be sent to the UE.
+    Complete(EapSession),
+}
+
+/// `Nausf_UEAuthentication` Service operations used by the AMF.
+#[async_trait]
+pub(in crate::amf) trait Ausf: std::fmt::Debug + Send + Sync {
+    /// Whether the AUSF serves the UE identified by the SUCI. Used for the AUSF selection
+    /// based on the Home Network Identifier and the Routing Indicator. (Section 6.3.4 23.501)
+    fn serves(&self, suci: &Suci) -> bool;
+
+    /// Authenticate: `POST /ue-authentications` (Section 5.2.2.2.2 29.509)
+    ///
+    /// The returned context contains the Authentication Vector and the link to the resource
+    /// used for the confirmation.
+    async fn ue_authentications(
+        &self,
+        authentication_info: AuthenticationInfo,
+    ) -> Result<UeAuthenticationCtx, AusfError>;
+
+    /// 5G AKA Confirmation: `PUT /ue-authentications/{authCtxId}/5g-aka-confirmation` (Section
+    /// 5.2.2.2.2 29.509). `link` is the link received in the [`UeAuthenticationCtx`].
+    async fn confirm_5g_aka(
+        &self,
+        link: &str,
+        confirmation_data: ConfirmationData,
+    ) -> Result<ConfirmationDataResponse, AusfError>;
+
+    /// EAP Session: `POST /ue-authentications/{authCtxId}/eap-session` (Section 5.2.2.2.3
+    /// 29.509). `link` is the link received in the [`UeAuthenticationCtx`] or in the last
+    /// [`EapSessionResponse::Ongoing`] response.
+    async fn eap_session(
+        &self,
+        link: &str,
+        eap_session: EapSession,
+    ) -> Result<EapSessionResponse, AusfError>;
+}
+
+/// Get the AUSFs that can be selected by the AMF from the AMF Configuration.
+pub(in crate::amf) fn ausfs_from_config(config: &AmfConfig) -> Vec<Arc<dyn Ausf>> {
+    let mut ausfs: Vec<Arc<dyn Ausf>> = vec![];
+
+    if let Some(ausf_config) = &config.ausf {
+        ausfs.push(Arc::new(LocalAusf::from_config(
+            config.plmn.plmn(),
+            ausf_config,
+        )));
+    }
+
+    ausfs
+}
+
+/// Select the AUSF for the UE identified by the SUCI. (Section 6.3.4 23.501)
+pub(in crate::amf) fn select_ausf(ausfs: &[Arc<dyn Ausf>], suci: &Suci) -> Option<Arc<dyn Ausf>> {
+    ausfs.iter().find(|ausf| ausf.serves(suci)).cloned()
+}
@@ -1,3 +1,4 @@
+use std::convert::{TryFrom, TryInto};
 use std::net::IpAddr;
 
 use serde::{
@@ -5,6 +6,17 @@ use serde::{
     Deserialize, Serialize,
 };
 
+use nas::messages::ies::{FivegGuti, Nssai, PartialTaiList, SNssai, TaiList};
+use nas::security::{NasEncryptionAlgoIdentity, NasIntegrityAlgoIdentity};
+use nas::Plmn;
+
+use sbi5g::models::AuthType;
+
+use security_3gpp::serving_network_name;
+
+// Maximum number of TAIs in a TAI List. (24.501 Section 9.11.3.9)
+const MAX_TAIS_IN_TAI_LIST: usize = 16;
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(remote = "Self")]
 pub struct AmfIdConfig {
@@ -58,6 +70,19 @@ impl<'de> Deserialize<'de> for PlmnConfig {
     }
 }
 
+impl PlmnConfig {
+    /// PLMN of the AMF. An `mnc` less than 100 is a 2 digit MNC.
+    pub(crate) fn plmn(&self) -> Plmn {
+        // Values are validated when the config is deserialized.
+        Plmn::from_mcc_mnc(self.mcc, self.mnc).unwrap()
+    }
+
+    /// Serving Network Name of the AMF used in the Authentication and the Key Derivations.
+    pub(crate) fn serving_network_name(&self) -> String {
+        serving_network_name(self.mcc, self.mnc)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 pub struct NgapConfig {
     pub(crate) addrs: Vec<IpAddr>,
@@ -66,14 +91,227 @@ pub struct NgapConfig {
     pub(crate) input_streams: Option<u16>,
 }
 
+/// Subscription Credentials of a Subscriber: The Subscriber Key `K`, `OPc` and the last used
+/// `SQN` for the MILENAGE Algorithm set. Values are hex strings. The `auth_method` is either
+/// `5G_AKA` (default) or `EAP_AKA_PRIME`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(remote = "Self")]
+pub struct SubscriberConfig {
+    pub(crate) supi: String,
+    pub(crate) k: String,
+    pub(crate) opc: String,
+    pub(crate) sqn: Option<String>,
+    pub(crate) amf: Option<String>,
+    pub(crate) auth_method: Option<AuthType>,
+}
+
+impl<'de> Deserialize<'de> for SubscriberConfig {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let this = Self::deserialize(deserializer)?;
+
+        let imsi = this.supi.strip_prefix("imsi-").unwrap_or_default();
+        if imsi.len() < 6 || imsi.len() > 15 || !imsi.bytes().all(|d| d.is_ascii_digit()) {
+            return Err(D::Error::custom(format!(
+                "Invalid `supi` {}, only IMSI based SUPI (`imsi-<IMSI>`) is supported.",
+                this.supi
+            )));
+        }
+
+        if let Some(auth_method) = this.auth_method {
+            if auth_method != AuthType::Variant5GAka && auth_method != AuthType::EapAkaPrime {
+                return Err(D::Error::custom(format!(
+                    "Unsupported `auth_method` {}.",
+                    auth_method.to_string()
+                )));
+            }
+        }
+
+        let values = [
+            ("k", Some(&this.k), 16),
+            ("opc", Some(&this.opc), 16),
+            ("sqn", this.sqn.as_ref(), 6),
+            ("amf", this.amf.as_ref(), 2),
+        ];
+        for (name, value, length) in values {
+            if let Some(value) = value {
+                match hex::decode(value) {
+                    Ok(decoded) if decoded.len() == length => {}
+                    _ => {
+                        return Err(D::Error::custom(format!(
+                            "`{}` should be a hex string of {} octets.",
+                            name, length
+                        )))
+                    }
+                }
+            }
+        }
+
+        Ok(this)
+    }
+}
+
+/// Configuration of the AUSF co-located with the AMF.
+///
+/// The AUSF (along with the UDM) is co-located with the AMF, when no other AUSF is available (eg.
+/// for testing), and authenticates the `subscribers` of the AMF's PLMN.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AusfConfig {
+    pub(crate) subscribers: Vec<SubscriberConfig>,
+}
+
+/// NAS Security Algorithms in the order of priority, used for selecting the algorithms for a UE.
+/// (Section 6.7.2 33.501) The algorithms are `NEA0` - `NEA3` and `NIA1` - `NIA3`. `NIA0` is not
+/// allowed, since it is used only for the unauthenticated emergency services. (Section 5.5.2
+/// 33.501)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct NasSecurityConfig {
+    #[serde(deserialize_with = "deserialize_encryption_algorithms")]
+    pub(crate) encryption_algorithms: Vec<NasEncryptionAlgoIdentity>,
+    #[serde(deserialize_with = "deserialize_integrity_algorithms")]
+    pub(crate) integrity_algorithms: Vec<NasIntegrityAlgoIdentity>,
+}
+
+impl Default for NasSecurityConfig {
+    fn default() -> Self {
+        Self {
+            encryption_algorithms: vec![
+                NasEncryptionAlgoIdentity::Nea2,
+                NasEncryptionAlgoIdentity::Nea1,
+                NasEncryptionAlgoIdentity::Nea3,
+                NasEncryptionAlgoIdentity::Nea0,
+            ],
+            integrity_algorithms: vec![
+                NasIntegrityAlgoIdentity::Nia2,
+                NasIntegrityAlgoIdentity::Nia1,
+                NasIntegrityAlgoIdentity::Nia3,
+            ],
+        }
+    }
+}
+
+// Algorithm identities from the names with the given `prefix` (eg. `NEA2` is 2 for `NEA`).
+fn algorithm_identities<'de, D>(deserializer: D, prefix: &str) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let names = Vec::<String>::deserialize(deserializer)?;
+    if names.is_empty() {
+        return Err(D::Error::custom(format!(
+            "At least one `{}` algorithm should be configured.",
+            prefix
+        )));
+    }
+
+    names
+        .iter()
+        .map(|name| {
+            name.strip_prefix(prefix)
+                .and_then(|identity| identity.parse::<u8>().ok())
+                .ok_or_else(|| D::Error::custom(format!("Invalid algorithm `{}`.", name)))
+        })
+        .collect()
+}
+
+fn deserialize_encryption_algorithms<'de, D>(
+    deserializer: D,
+) -> Result<Vec<NasEncryptionAlgoIdentity>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    algorithm_identities(deserializer, "NEA")?
+        .into_iter()
+        .map(|identity| NasEncryptionAlgoIdentity::try_from(identity).map_err(D::Error::custom))
+        .collect()
+}
+
+fn deserialize_integrity_algorithms<'de, D>(
+    deserializer: D,
+) -> Result<Vec<NasIntegrityAlgoIdentity>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    algorithm_identities(deserializer, "NIA")?
+        .into_iter()
+        .map(
+            |identity| match NasIntegrityAlgoIdentity::try_from(identity) {
+                Ok(NasIntegrityAlgoIdentity::Nia0) => {
+                    Err(D::Error::custom("`NIA0` is not allowed."))
+                }
+                result => result.map_err(D::Error::custom),
+            },
+        )
+        .collect()
+}
+
+/// Network Slice (S-NSSAI) supported by the AMF: The Slice/Service Type (`sst`) and an optional
+/// Slice Differentiator (`sd`) as a hex string of 3 octets. (Section 5.15.2 23.501)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(remote = "Self")]
+pub struct SliceConfig {
+    pub(crate) sst: u8,
+    pub(crate) sd: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for SliceConfig {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let this = Self::deserialize(deserializer)?;
+
+        if let Some(sd) = &this.sd {
+            match hex::decode(sd) {
+                Ok(decoded) if decoded.len() == 3 => {}
+                _ => return Err(D::Error::custom("`sd` should be a hex string of 3 octets.")),
+            }
+        }
+
+        Ok(this)
+    }
+}
+
+impl SliceConfig {
+    fn s_nssai(&self) -> SNssai {
+        // Values are validated when the config is deserialized.
+        let sd = self
+            .sd
+            .as_ref()
+            .map(|sd| hex::decode(sd).unwrap().try_into().unwrap());
+        SNssai::new(self.sst, sd)
+    }
+}
+
+// By default only eMBB (SST 1) is supported.
+fn default_slices() -> Vec<SliceConfig> {
+    vec![SliceConfig { sst: 1, sd: None }]
+}
+
+/// NAS level Mobility Management Congestion Control: The Registration of the UEs is rejected when
+/// the AMF is serving `max_ues` UEs, the UEs back-off for `back_off` seconds (T3346) before
+/// trying again. (Section 5.3.9 24.501)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CongestionConfig {
+    pub(crate) max_ues: usize,
+    pub(crate) back_off: u32,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(remote = "Self")]
 pub struct AmfConfig {
     pub(crate) ngap: NgapConfig,
     pub(crate) plmn: PlmnConfig,
-    pub(crate) tacs: Vec<u32>, // TODO: Validate Max value is 24 bit.
+    pub(crate) tacs: Vec<u32>,
     pub(crate) amf_id: AmfIdConfig,
     pub(crate) amf_name: String,
+    pub(crate) ausf: Option<AusfConfig>,
+    #[serde(default)]
+    pub(crate) nas_security: NasSecurityConfig,
+    pub(crate) congestion: Option<CongestionConfig>,
+    #[serde(default = "default_slices")]
+    pub(crate) slices: Vec<SliceConfig>,
 }
 
 impl<'de> Deserialize<'de> for AmfConfig {
@@ -93,18 +331,246 @@ impl<'de> Deserialize<'de> for AmfConfig {
             }
         }
 
+        if this.slices.is_empty() {
+            return Err(D::Error::custom("At least one slice should be configured."));
+        }
+
         Ok(this)
     }
 }
 
+impl AmfConfig {
+    /// TAI List with the TAIs served by the AMF (at the most 16) to be sent to the UE. (Section
+    /// 5.3.4 24.501) The TAI the UE is currently in (`tac`) is always included, as the first TAI.
+    pub(crate) fn tai_list(&self, tac: Option<u32>) -> TaiList {
+        let tacs: Vec<u32> = tac
+            .into_iter()
+            .chain(self.tacs.iter().copied().filter(|t| Some(*t) != tac))
+            .take(MAX_TAIS_IN_TAI_LIST)
+            .collect();
+
+        let mut partial_lists = vec![];
+        if !tacs.is_empty() {
+            partial_lists.push(PartialTaiList::NonConsecutiveTacs {
+                plmn: self.plmn.plmn(),
+                tacs,
+            });
+        }
+
+        TaiList {
+            iei: None,
+            partial_lists,
+        }
+    }
+
+    /// 5G-GUTI for the 5G-TMSI allocated by the AMF: The GUAMI of the AMF followed by the
+    /// 5G-TMSI. (Section 2.10.1 23.003)
+    pub(crate) fn guti(&self, tmsi: u32) -> FivegGuti {
+        FivegGuti {
+            plmn: self.plmn.plmn(),
+            amf_region_id: self.amf_id.region,
+            amf_set_id: self.amf_id.set,
+            amf_pointer: self.amf_id.pointer,
+            tmsi,
+        }
+    }
+
+    /// S-NSSAIs supported by the AMF: The configured `slices`.
+    pub(crate) fn supported_nssai(&self) -> Nssai {
+        Nssai {
+            iei: None,
+            s_nssais: self.slices.iter().map(SliceConfig::s_nssai).collect(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
+    const CONFIG: &str = "ngap:\n addrs:\n - 127.0.0.1\nplmn:\n mcc: 999\n mnc: 99\ntacs: [ 1, 2, 3]\namf_id:\n pointer: 63\n set: 10\n region: 1\namf_name: taxila-amf";
+
     #[test]
     fn works() {
-        let config_str =
-            "ngap:\n addrs:\n - 127.0.0.1 \n - ::1 \nport: 38413\nplmn:\n mcc: 999\n mnc: 99\ntacs: [ 1, 2, 3]\namf_id:\n pointer: 63\n set: 10\n region: 1\namf_name: taxila-amf";
+        let config_str = CONFIG.replace(" - 127.0.0.1", " - 127.0.0.1 \n - ::1 \n port: 38413");
+        let amf_config: Result<super::AmfConfig, _> = serde_yaml::from_str(&config_str);
+        assert!(amf_config.is_ok(), "{:#?}", amf_config.err().unwrap());
+    }
+
+    #[test]
+    fn config_file() {
+        let config_str = include_str!("../../../../configs/amf.yaml");
         let amf_config: Result<super::AmfConfig, _> = serde_yaml::from_str(config_str);
         assert!(amf_config.is_ok(), "{:#?}", amf_config.err().unwrap());
+        let amf_config = amf_config.unwrap();
+        assert_eq!(amf_config.ausf.unwrap().subscribers.len(), 1);
+        assert_eq!(amf_config.slices.len(), 2);
+    }
+
+    #[test]
+    fn ausf() {
+        let config_str = format!(
+            "{}\nausf:\n subscribers:\n - supi: imsi-999990000000001\n   k: 465b5ce8b199b49faa5f0a2ee238a6bc\n   opc: cd63cb71954a9f4e48a5994e37a02baf\n   sqn: ff9bb4d0b607",
+            CONFIG
+        );
+        let amf_config: super::AmfConfig = serde_yaml::from_str(&config_str).unwrap();
+        let ausf = amf_config.ausf.unwrap();
+        assert_eq!(ausf.subscribers.len(), 1);
+        assert_eq!(ausf.subscribers[0].amf, None);
+        assert_eq!(ausf.subscribers[0].auth_method, None);
+
+        let eap_aka_prime = format!("{}\n   auth_method: EAP_AKA_PRIME", config_str);
+        let amf_config: super::AmfConfig = serde_yaml::from_str(&eap_aka_prime).unwrap();
+        assert_eq!(
+            amf_config.ausf.unwrap().subscribers[0].auth_method,
+            Some(sbi5g::models::AuthType::EapAkaPrime)
+        );
+
+        // EAP-TLS is not supported.
+        let invalid = format!("{}\n   auth_method: EAP_TLS", config_str);
+        let amf_config: Result<super::AmfConfig, _> = serde_yaml::from_str(&invalid);
+        assert!(amf_config.is_err());
+
+        // Short `k`
+        let invalid = config_str.replace("k: 465b5ce8b199b49faa5f0a2ee238a6bc", "k: 465b5ce8");
+        let amf_config: Result<super::AmfConfig, _> = serde_yaml::from_str(&invalid);
+        assert!(amf_config.is_err());
+
+        // Not an IMSI
+        let invalid = config_str.replace("imsi-999990000000001", "nai-user@example.com");
+        let amf_config: Result<super::AmfConfig, _> = serde_yaml::from_str(&invalid);
+        assert!(amf_config.is_err());
+    }
+
+    #[test]
+    fn nas_security() {
+        use nas::security::{NasEncryptionAlgoIdentity, NasIntegrityAlgoIdentity};
+
+        let amf_config: super::AmfConfig = serde_yaml::from_str(CONFIG).unwrap();
+        assert_eq!(amf_config.nas_security, super::NasSecurityConfig::default());
+
+        let nas_security = format!(
+            "{}\nnas_security:\n encryption_algorithms: [ NEA0, NEA2 ]\n integrity_algorithms: [ NIA1 ]",
+            CONFIG
+        );
+        let amf_config: super::AmfConfig = serde_yaml::from_str(&nas_security).unwrap();
+        assert_eq!(
+            amf_config.nas_security.encryption_algorithms,
+            vec![
+                NasEncryptionAlgoIdentity::Nea0,
+                NasEncryptionAlgoIdentity::Nea2
+            ]
+        );
+        assert_eq!(
+            amf_config.nas_security.integrity_algorithms,
+            vec![NasIntegrityAlgoIdentity::Nia1]
+        );
+
+        for invalid in [
+            "[ NEA2 ]\n integrity_algorithms: [ NIA0 ]",
+            "[ NEA4 ]\n integrity_algorithms: [ NIA2 ]",
+            "[ EEA2 ]\n integrity_algorithms: [ NIA2 ]",
+            "[]\n integrity_algorithms: [ NIA2 ]",
+        ] {
+            let invalid = format!(
+                "{}\nnas_security:\n encryption_algorithms: {}",
+                CONFIG, invalid
+            );
+            let amf_config: Result<super::AmfConfig, _> = serde_yaml::from_str(&invalid);
+            assert!(amf_config.is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn congestion() {
+        let amf_config: super::AmfConfig = serde_yaml::from_str(CONFIG).unwrap();
+        assert_eq!(amf_config.congestion, None);
+
+        let congestion = format!("{}\ncongestion:\n max_ues: 1000\n back_off: 60", CONFIG);
+        let amf_config: super::AmfConfig = serde_yaml::from_str(&congestion).unwrap();
+        assert_eq!(
+            amf_config.congestion,
+            Some(super::CongestionConfig {
+                max_ues: 1000,
+                back_off: 60
+            })
+        );
+    }
+
+    #[test]
+    fn slices() {
+        use nas::messages::ies::SNssai;
+
+        let amf_config: super::AmfConfig = serde_yaml::from_str(CONFIG).unwrap();
+        assert_eq!(
+            amf_config.supported_nssai().s_nssais,
+            vec![SNssai::new(1, None)]
+        );
+
+        let slices = format!("{}\nslices:\n - sst: 1\n - sst: 2\n   sd: 0a0b0c", CONFIG);
+        let amf_config: super::AmfConfig = serde_yaml::from_str(&slices).unwrap();
+        assert_eq!(
+            amf_config.supported_nssai().s_nssais,
+            vec![
+                SNssai::new(1, None),
+                SNssai::new(2, Some([0x0a, 0x0b, 0x0c]))
+            ]
+        );
+
+        for invalid in ["[]", "\n - sst: 256", "\n - sst: 1\n   sd: 0a0b"] {
+            let invalid = format!("{}\nslices: {}", CONFIG, invalid);
+            let amf_config: Result<super::AmfConfig, _> = serde_yaml::from_str(&invalid);
+            assert!(amf_config.is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn tai_list() {
+        use nas::messages::ies::PartialTaiList;
+
+        let amf_config: super::AmfConfig = serde_yaml::from_str(CONFIG).unwrap();
+
+        let tai_list = amf_config.tai_list(None);
+        assert_eq!(
+            tai_list.partial_lists,
+            vec![PartialTaiList::NonConsecutiveTacs {
+                plmn: "999-99".parse().unwrap(),
+                tacs: vec![1, 2, 3]
+            }]
+        );
+
+        // The TAC of the UE is the first.
+        let tai_list = amf_config.tai_list(Some(3));
+        assert_eq!(
+            tai_list.partial_lists,
+            vec![PartialTaiList::NonConsecutiveTacs {
+                plmn: "999-99".parse().unwrap(),
+                tacs: vec![3, 1, 2]
+            }]
+        );
+
+        // At the most 16 TACs, always including the TAC of the UE.
+        let tacs = (1..=20).map(|tac| tac.to_string()).collect::<Vec<_>>();
+        let config_str = CONFIG.replace("[ 1, 2, 3]", &format!("[ {} ]", tacs.join(", ")));
+        let amf_config: super::AmfConfig = serde_yaml::from_str(&config_str).unwrap();
+        let tai_list = amf_config.tai_list(Some(20));
+        assert_eq!(
+            tai_list.partial_lists,
+            vec![PartialTaiList::NonConsecutiveTacs {
+                plmn: "999-99".parse().unwrap(),
+                tacs: std::iter::once(20).chain(1..=15).collect()
+            }]
+        );
+    }
+
+    #[test]
+    fn tacs() {
+        let config_str = CONFIG.replace("[ 1, 2, 3]", "[ 1, 16777215 ]");
+        let amf_config: Result<super::AmfConfig, _> = serde_yaml::from_str(&config_str);
+        assert!(amf_config.is_ok());
+
+        // TACs are 24 bits.
+        let config_str = CONFIG.replace("[ 1, 2, 3]", "[ 1, 16777216 ]");
+        let amf_config: Result<super::AmfConfig, _> = serde_yaml::from_str(&config_str);
+        assert!(amf_config.is_err());
     }
 }
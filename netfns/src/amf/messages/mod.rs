@@ -14,6 +14,8 @@ pub(crate) struct NasPduMessage {
     pub(crate) id: u64,
     pub(crate) pdu: NAS_PDU,
     pub(crate) initial_ue: bool,
+    // TAC of the UE's location, received in the Initial UE Message.
+    pub(crate) tac: Option<u32>,
 }
 
 // Message sent to NGAP by RAN Connection Task
@@ -32,6 +34,7 @@ pub(crate) struct ReceivedDataMessage {
 #[derive(Debug, Clone)]
 pub(crate) enum AmfToNgapMessage {
     Signal(i32),
+    NasPduMessage(NasPduMessage),
 }
 
 // Message sent to Ran Connection task by NGAP Task.
@@ -54,6 +57,8 @@ pub(crate) enum AmfToNasMessage {
     NasPduMessage(NasPduMessage),
 }
 
-// Message Sent from NAS to AMF
+// Message Sent from NAS to AMF: The Downlink NAS PDUs are sent to the NGAP Task by the AMF.
 #[derive(Debug, Clone)]
-pub(crate) enum NasToAmfMessage {}
+pub(crate) enum NasToAmfMessage {
+    NasPduMessage(NasPduMessage),
+}
@@ -7,3 +7,5 @@ pub(in crate::amf) mod messages;
 pub(in crate::amf) mod config;
 
 pub(in crate::amf) mod nas;
+
+pub(in crate::amf) mod ausf;
@@ -3,65 +3,368 @@
 use ngap::messages::r17::NAS_PDU;
 
 use nas::messages::{
-    headers::{Nas5gMmMessageHeader, Nas5gSecurityHeader, NasMessageHeader},
-    Nas5gMmMessage, RegistrationRequest,
+    headers::{ExtProtoDiscriminator, Nas5gMmMessageHeader, Nas5gSecurityHeader, NasMessageHeader},
+    ies::{FivegGuti, Suci},
+    Nas5gMmMessage, Nas5gSmMessage, RegistrationAccept, RegistrationRequest,
 };
+use nas::security::NasSecurityContext;
+
+use tokio::time::Instant;
+
+use super::nas_manager::NasContext;
+use super::timer::{NasTimer, NasTimerId};
 
 mod registration_procedure;
 
+mod registration_reject;
+pub(in crate::amf) use registration_reject::RegistrationFailure;
+
+mod authentication_procedure;
+pub(in crate::amf) use authentication_procedure::{Authentication, PartialSecurityContext};
+
+mod security_mode_control_procedure;
+pub(in crate::amf) use security_mode_control_procedure::SecurityModeControl;
+
 #[derive(Debug, Clone)]
 pub(in crate::amf) struct AmfUe {
     pub(in crate::amf) amf_ue_ngap_id: u64,
+    pub(in crate::amf) tac: Option<u32>,
+    pub(in crate::amf) security_context: Option<NasSecurityContext>,
+    pub(in crate::amf) partial_security_context: Option<PartialSecurityContext>,
+    pub(in crate::amf) suci: Option<Suci>,
+    pub(in crate::amf) supi: Option<String>,
+    pub(in crate::amf) pei: Option<String>,
+    pub(in crate::amf) guti: Option<FivegGuti>,
+    pub(in crate::amf) registration_request: Option<RegistrationRequest>,
+    pub(in crate::amf) authentication: Option<Authentication>,
+    pub(in crate::amf) security_mode_control: Option<SecurityModeControl>,
+    pub(in crate::amf) registration_accept: Option<RegistrationAccept>,
+    pub(in crate::amf) registered: bool,
+    pub(in crate::amf) timer: Option<NasTimer>,
+}
+
+// Header of a plain 5GMM Message.
+pub(super) fn nas_5gmm_header(message_type: u8) -> Nas5gMmMessageHeader {
+    Nas5gMmMessageHeader {
+        extended_protocol_disc: ExtProtoDiscriminator::FivegNasMobilityManagementType,
+        sec_header_type: Nas5gSecurityHeader::PlainText,
+        message_type,
+    }
 }
 
 impl AmfUe {
     pub(in crate::amf) fn new_amf_ue(amf_ue_ngap_id: u64) -> Self {
-        Self { amf_ue_ngap_id }
+        Self {
+            amf_ue_ngap_id,
+            tac: None,
+            security_context: None,
+            partial_security_context: None,
+            suci: None,
+            supi: None,
+            pei: None,
+            guti: None,
+            registration_request: None,
+            authentication: None,
+            security_mode_control: None,
+            registration_accept: None,
+            registered: false,
+            timer: None,
+        }
     }
 
     // Handle Initial NAS MM Message
-    pub(in crate::amf) fn handle_initial_nas_message(
+    pub(in crate::amf) async fn handle_initial_nas_message(
         &mut self,
         nas_pdu: NAS_PDU,
+        context: &NasContext,
     ) -> std::io::Result<()> {
-        let (header, decoded) = NasMessageHeader::decode(&nas_pdu.0)?;
-        match header {
+        let (header, _decoded) = NasMessageHeader::decode(&nas_pdu.0)?;
+        let result = match header {
             NasMessageHeader::SecurityProtected(_) => {
-                self.handle_security_protected_initial_nas_message(nas_pdu)
+                self.handle_security_protected_initial_nas_message(nas_pdu, context)
+                    .await
+            }
+            NasMessageHeader::Nas5gMm(_) => {
+                self.handle_initial_nas_5gmm_message(nas_pdu, context).await
             }
-            NasMessageHeader::Nas5gMm(_) => self.handle_initial_nas_5gmm_message(nas_pdu),
             NasMessageHeader::Nas5gSm(_) => self.handle_initial_nas_5gsm_message(nas_pdu),
-        }
+        };
+
+        self.reject_registration_on_failure(result, context).await
     }
 
-    pub(in crate::amf) fn handle_nas_message(&mut self, nas_pdu: NAS_PDU) -> std::io::Result<()> {
-        todo!();
+    // Handle a NAS Message received after the Initial NAS Message (in the Uplink NAS
+    // Transport). Once the Security Context is established, only the integrity protected NAS
+    // Messages are processed, before that only the messages allowed without the integrity
+    // protection are processed. (Section 4.4.4.3 24.501)
+    pub(in crate::amf) async fn handle_nas_message(
+        &mut self,
+        nas_pdu: NAS_PDU,
+        context: &NasContext,
+    ) -> std::io::Result<()> {
+        let (header, _decoded) = NasMessageHeader::decode(&nas_pdu.0)?;
+        let message = match header {
+            NasMessageHeader::SecurityProtected(_) => match self.current_security_context() {
+                Some(security_context) => security_context.unprotect_message(&nas_pdu.0)?,
+                None => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "Security Protected NAS Message without a Security Context.",
+                    ))
+                }
+            },
+            NasMessageHeader::Nas5gMm(_) => {
+                let message = Nas5gMmMessage::decode(&nas_pdu.0)?;
+                if self.security_context.is_some() || !Self::allowed_without_protection(&message) {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "NAS Message is not integrity protected.",
+                    ));
+                }
+                message
+            }
+            // 5GSM Messages are carried in the UL NAS Transport. (Section 5.4.5 24.501)
+            NasMessageHeader::Nas5gSm(_) => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "5GSM Message not carried in the UL NAS Transport.",
+                ))
+            }
+        };
+
+        let result = self.handle_nas_5gmm_message(message, context).await;
+        self.reject_registration_on_failure(result, context).await
     }
 
-    pub(in crate::amf) fn handle_security_protected_initial_nas_message(
+    // Handle Initial NAS Message that is security protected. If we have a valid security context
+    // for the UE, the message is verified (and deciphered) using it, else the message is handled
+    // as if it was not integrity protected (Section 4.4.4.3 24.501). A ciphered message cannot
+    // be handled without the security context.
+    pub(in crate::amf) async fn handle_security_protected_initial_nas_message(
         &mut self,
         nas_pdu: NAS_PDU,
+        context: &NasContext,
     ) -> std::io::Result<()> {
-        todo!();
+        let message = match self.security_context.as_mut() {
+            Some(security_context) => security_context.unprotect(&nas_pdu.0)?,
+            None => {
+                let (header, decoded) = NasMessageHeader::decode(&nas_pdu.0)?;
+                match header {
+                    NasMessageHeader::SecurityProtected(header)
+                        if header.sec_header_type == Nas5gSecurityHeader::IntegrityProtected =>
+                    {
+                        nas_pdu.0[decoded..].to_vec()
+                    }
+                    _ => {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            "Ciphered Initial NAS Message without a Security Context.",
+                        ))
+                    }
+                }
+            }
+        };
+
+        self.handle_initial_nas_5gmm_message(NAS_PDU(message), context)
+            .await
     }
 
-    pub(in crate::amf) fn handle_initial_nas_5gmm_message(
+    pub(in crate::amf) async fn handle_initial_nas_5gmm_message(
         &mut self,
         nas_pdu: NAS_PDU,
+        context: &NasContext,
     ) -> std::io::Result<()> {
-        let message = Nas5gMmMessage::decode(&nas_pdu.0)?;
+        let message =
+            Nas5gMmMessage::decode(&nas_pdu.0).map_err(RegistrationFailure::InvalidMessage)?;
 
         match message {
             Nas5gMmMessage::RegistrationRequest(reg_request) => {
-                self.registration_procedure(reg_request, true)
+                self.registration_procedure(*reg_request, true, context)
+                    .await
             }
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Unexpected Initial NAS Message.",
+            )),
         }
     }
 
+    // 5GSM Messages are always carried in the UL NAS Transport and are never an Initial NAS
+    // Message. (Section 5.4.5 24.501)
     pub(in crate::amf) fn handle_initial_nas_5gsm_message(
         &mut self,
         nas_pdu: NAS_PDU,
     ) -> std::io::Result<()> {
-        todo!();
+        let message = Nas5gSmMessage::decode(&nas_pdu.0)?;
+        log::warn!(
+            "Received 5GSM Message as Initial NAS Message: {:?}",
+            message
+        );
+
+        Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "Unexpected Initial NAS Message.",
+        ))
+    }
+
+    // Handle the 5GMM Message (after verifying and deciphering if security protected) of an
+    // ongoing 5GMM procedure.
+    async fn handle_nas_5gmm_message(
+        &mut self,
+        message: Nas5gMmMessage,
+        context: &NasContext,
+    ) -> std::io::Result<()> {
+        match message {
+            Nas5gMmMessage::AuthenticationResponse(response) => {
+                self.handle_authentication_response(response, context).await
+            }
+            Nas5gMmMessage::AuthenticationFailure(failure) => {
+                self.handle_authentication_failure(failure, context).await
+            }
+            Nas5gMmMessage::SecurityModeComplete(complete) => {
+                self.handle_security_mode_complete(complete, context).await
+            }
+            Nas5gMmMessage::SecurityModeReject(reject) => {
+                self.handle_security_mode_reject(reject, context).await
+            }
+            Nas5gMmMessage::RegistrationComplete(complete) => {
+                self.handle_registration_complete(complete, context).await
+            }
+            _ => {
+                log::warn!("Unsupported 5GMM Message: {:?}", message);
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "Unexpected NAS Message.",
+                ))
+            }
+        }
+    }
+
+    // The Security Context used for the Uplink NAS Messages: The new Security Context during the
+    // Security Mode Control procedure, since the Security Mode Complete is protected using it.
+    // (Section 5.4.2.3 24.501)
+    fn current_security_context(&mut self) -> Option<&mut NasSecurityContext> {
+        match self.security_mode_control.as_mut() {
+            Some(security_mode_control) => Some(&mut security_mode_control.security_context),
+            None => self.security_context.as_mut(),
+        }
+    }
+
+    // Messages processed without the integrity protection, when there is no Security Context
+    // (Section 4.4.4.3 24.501).
+    fn allowed_without_protection(message: &Nas5gMmMessage) -> bool {
+        matches!(
+            message,
+            Nas5gMmMessage::RegistrationRequest(_)
+                | Nas5gMmMessage::IdentityResponse(_)
+                | Nas5gMmMessage::AuthenticationResponse(_)
+                | Nas5gMmMessage::AuthenticationFailure(_)
+                | Nas5gMmMessage::SecurityModeReject(_)
+                | Nas5gMmMessage::DeregistrationRequestFromUe(_)
+                | Nas5gMmMessage::DeregistrationAcceptToUe(_)
+                | Nas5gMmMessage::ServiceRequest(_)
+        )
+    }
+
+    // Send a 5GMM Message to the UE. The message is integrity protected and ciphered when the
+    // Security Context is established.
+    pub(in crate::amf) async fn send_nas_message(
+        &mut self,
+        message: Nas5gMmMessage,
+        context: &NasContext,
+    ) -> std::io::Result<()> {
+        let message = message.encode();
+        let pdu = match self.security_context.as_mut() {
+            Some(security_context) => security_context
+                .protect(&message, Nas5gSecurityHeader::IntegrityProtectedAndCiphered)?,
+            None => message,
+        };
+
+        context
+            .send_nas_pdu(self.amf_ue_ngap_id, NAS_PDU(pdu))
+            .await
+    }
+
+    fn start_timer(&mut self, id: NasTimerId) {
+        self.timer = Some(NasTimer::start(id));
+    }
+
+    fn stop_timer(&mut self) {
+        self.timer = None;
+    }
+
+    /// Expiry time of the running NAS Timer of the UE if any.
+    pub(in crate::amf) fn timer_expires_at(&self) -> Option<Instant> {
+        self.timer.as_ref().map(|timer| timer.expires_at)
+    }
+
+    // Handle the expiry of the running NAS Timer: The message of the ongoing procedure is
+    // retransmitted, until the procedure is aborted on the fifth expiry.
+    pub(in crate::amf) async fn handle_timer_expiry(
+        &mut self,
+        context: &NasContext,
+    ) -> std::io::Result<()> {
+        let timer = match self.timer.as_mut() {
+            Some(timer) => timer,
+            None => return Ok(()),
+        };
+        let id = timer.id;
+
+        if !timer.restart() {
+            log::warn!(
+                "{:?} expired for the UE with AMF_UE_NGAP_ID: {}, aborting the procedure.",
+                id,
+                self.amf_ue_ngap_id
+            );
+            self.abort_procedures();
+            return Ok(());
+        }
+
+        match id {
+            NasTimerId::T3560 if self.authentication.is_some() => {
+                self.resend_authentication_request(context).await
+            }
+            NasTimerId::T3560 if self.security_mode_control.is_some() => {
+                self.resend_security_mode_command(context).await
+            }
+            NasTimerId::T3550 if self.registration_accept.is_some() => {
+                self.resend_registration_accept(context).await
+            }
+            NasTimerId::T3560 | NasTimerId::T3550 => {
+                self.stop_timer();
+                Ok(())
+            }
+        }
+    }
+
+    // Abort the ongoing 5GMM procedures of the UE. An allocated 5G-GUTI is retained. (Section
+    // 5.4.1.3.7, 5.4.2.7 and 5.5.1.2.8 24.501)
+    fn abort_procedures(&mut self) {
+        self.stop_timer();
+        self.authentication = None;
+        self.security_mode_control = None;
+        self.partial_security_context = None;
+        self.registration_request = None;
+        self.registration_accept = None;
+    }
+
+    /// Whether the UE is registered or it's Registration is ongoing. The UEs whose Registration
+    /// is rejected or aborted are not served by the AMF.
+    pub(in crate::amf) fn is_served(&self) -> bool {
+        self.registered || self.registration_request.is_some() || self.procedure_ongoing()
+    }
+
+    // Whether a 5GMM procedure is waiting for a message from the UE.
+    fn procedure_ongoing(&self) -> bool {
+        self.authentication.is_some()
+            || self.security_mode_control.is_some()
+            || self.registration_accept.is_some()
+    }
+
+    /// Release the resources allocated to the UE, when the `AmfUe` is removed.
+    pub(in crate::amf) fn release(self, context: &NasContext) {
+        if let Some(guti) = self.guti {
+            context.release_tmsi(guti.tmsi);
+        }
     }
 }
@@ -0,0 +1,895 @@
+//! Primary Authentication and Key Agreement Procedure
+//!
+//! Section 5.4.1 24.501 (5G AKA and EAP based Primary Authentication)
+//!
+//! Also: Section 6.1.3 33.501 Release 17.
+
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+
+use sbi5g::models::{
+    AuthResult, AuthType, AuthenticationInfo, ConfirmationData, EapSession, ResynchronizationInfo,
+    UeAuthenticationCtx5gAuthData,
+};
+
+use nas::messages::ies::{
+    Abba, AuthenticationParameterAutn, AuthenticationParameterRand, EapMessage, NasKeySetId,
+    SecurityContextType,
+};
+use nas::messages::{
+    AuthenticationFailure, AuthenticationReject, AuthenticationRequest, AuthenticationResponse,
+    AuthenticationResult, Nas5gMmMessage, MM_MSG_TYPE_AUTHENTICATION_REJECT,
+    MM_MSG_TYPE_AUTHENTICATION_REQUEST, MM_MSG_TYPE_AUTHENTICATION_RESULT,
+};
+
+use security_3gpp::{derive_hres_star, derive_kamf, Rand, ResStar, SecurityKey};
+
+use crate::amf::ausf::{select_ausf, Ausf, EapSessionResponse};
+use crate::amf::nas::nas_manager::NasContext;
+use crate::amf::nas::timer::NasTimerId;
+
+use super::{nas_5gmm_header, AmfUe, RegistrationFailure};
+
+// ABBA for the Release 15 onwards security features. (Section A.7.1 33.501)
+const ABBA: [u8; 2] = [0x00, 0x00];
+
+// ngKSI value indicating no key is available. (Section 9.11.3.32 24.501)
+const NGKSI_NO_KEY_AVAILABLE: u8 = 0x07;
+
+// 5GMM Causes in the Authentication Failure. (Section 9.11.3.2 24.501)
+const CAUSE_MAC_FAILURE: u8 = 20;
+const CAUSE_SYNCH_FAILURE: u8 = 21;
+const CAUSE_NGKSI_ALREADY_IN_USE: u8 = 71;
+
+/// Security Context established by a successful Primary Authentication, before the NAS Security
+/// Mode Control procedure activates it.
+#[derive(Clone)]
+pub(in crate::amf) struct PartialSecurityContext {
+    pub(in crate::amf) ngksi: u8,
+    pub(in crate::amf) kamf: SecurityKey,
+}
+
+impl std::fmt::Debug for PartialSecurityContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Keys are never printed.
+        f.debug_struct("PartialSecurityContext")
+            .field("ngksi", &self.ngksi)
+            .finish()
+    }
+}
+
+/// State of an ongoing Primary Authentication.
+#[derive(Debug, Clone)]
+pub(in crate::amf) struct Authentication {
+    ausf: Arc<dyn Ausf>,
+    ngksi: u8,
+    method: AuthenticationMethod,
+    link: String,
+
+    // Re-synchronisation is attempted only once for an Authentication.
+    resynchronized: bool,
+}
+
+// Authentication data received from the AUSF for the Authentication Method selected for the UE.
+#[derive(Debug, Clone)]
+enum AuthenticationMethod {
+    FivegAka {
+        rand: Rand,
+        autn: [u8; 16],
+        hxres_star: ResStar,
+    },
+
+    // The EAP Request to be relayed to the UE. The Re-synchronisation is handled by the AUSF.
+    EapAkaPrime {
+        eap_request: Vec<u8>,
+    },
+}
+
+fn decode_hex<const N: usize>(value: &str) -> std::io::Result<[u8; N]> {
+    hex::decode(value)
+        .ok()
+        .and_then(|value| value.try_into().ok())
+        .ok_or_else(|| invalid_authentication_data(value))
+}
+
+fn decode_eap_payload(value: &str) -> std::io::Result<Vec<u8>> {
+    BASE64
+        .decode(value)
+        .map_err(|_| invalid_authentication_data(value))
+}
+
+fn invalid_authentication_data(value: &str) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("Invalid Authentication Data: {}", value),
+    )
+}
+
+fn missing_link(link: &str) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("Missing '{}' link in the Authentication Context.", link),
+    )
+}
+
+impl AmfUe {
+    // Start the Primary Authentication of the UE identified by the SUCI, using an AUSF selected
+    // for the UE. (Section 5.4.1.3.2 24.501)
+    pub(super) async fn authentication_procedure(
+        &mut self,
+        context: &NasContext,
+    ) -> std::io::Result<()> {
+        let suci = self.suci.as_ref().unwrap();
+        // The UEs cannot be authenticated without an AUSF, this is not a failure of the UE.
+        if context.ausfs.is_empty() {
+            log::error!(
+                "No AUSF configured, the UE with SUCI: {} cannot be authenticated.",
+                suci
+            );
+            return Err(
+                RegistrationFailure::NetworkFailure("No AUSF configured.".to_string()).into(),
+            );
+        }
+
+        // No AUSF serves the Home Network of the UE.
+        let ausf = select_ausf(&context.ausfs, suci).ok_or_else(|| {
+            RegistrationFailure::PlmnNotAllowed(format!("No AUSF available for the UE: {}", suci))
+        })?;
+
+        let info =
+            AuthenticationInfo::new(suci.to_string(), context.config.plmn.serving_network_name());
+
+        // The ngKSI allocated should be different from the one indicated by the UE.
+        let ue_ngksi = self
+            .registration_request
+            .as_ref()
+            .map(|request| request.ngksi.identifier)
+            .unwrap_or(NGKSI_NO_KEY_AVAILABLE);
+        let ngksi = if ue_ngksi == NGKSI_NO_KEY_AVAILABLE {
+            0
+        } else {
+            (ue_ngksi + 1) % NGKSI_NO_KEY_AVAILABLE
+        };
+
+        self.start_authentication(ausf, info, ngksi, false, context)
+            .await
+    }
+
+    // Get the Authentication Vector for the UE from the AUSF and send the Authentication Request.
+    async fn start_authentication(
+        &mut self,
+        ausf: Arc<dyn Ausf>,
+        info: AuthenticationInfo,
+        ngksi: u8,
+        resynchronized: bool,
+        context: &NasContext,
+    ) -> std::io::Result<()> {
+        let ue_authentication_ctx = ausf.ue_authentications(info).await?;
+
+        let (method, link) = match (
+            ue_authentication_ctx.auth_type,
+            ue_authentication_ctx.param_5g_auth_data.as_ref(),
+        ) {
+            (AuthType::Variant5GAka, UeAuthenticationCtx5gAuthData::Av5gAka(av_5g_aka)) => (
+                AuthenticationMethod::FivegAka {
+                    rand: decode_hex(&av_5g_aka.rand)?,
+                    autn: decode_hex(&av_5g_aka.autn)?,
+                    hxres_star: decode_hex(&av_5g_aka.hxres_star)?,
+                },
+                "5g-aka",
+            ),
+            (AuthType::EapAkaPrime, UeAuthenticationCtx5gAuthData::EapPayload(eap_payload)) => (
+                AuthenticationMethod::EapAkaPrime {
+                    eap_request: decode_eap_payload(eap_payload)?,
+                },
+                "eap-session",
+            ),
+            (auth_type, _) => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    format!("Unsupported Authentication Method: {:?}", auth_type),
+                ));
+            }
+        };
+
+        let link = ue_authentication_ctx
+            ._links
+            .get(link)
+            .and_then(|href| href.href.clone())
+            .ok_or_else(|| missing_link(link))?;
+
+        self.authentication = Some(Authentication {
+            ausf,
+            ngksi,
+            method,
+            link,
+            resynchronized,
+        });
+
+        self.send_authentication_request(context).await
+    }
+
+    async fn send_authentication_request(&mut self, context: &NasContext) -> std::io::Result<()> {
+        self.start_timer(NasTimerId::T3560);
+        self.resend_authentication_request(context).await
+    }
+
+    // Send the Authentication Request for the ongoing Authentication. Also used for the
+    // retransmission on the expiry of T3560. (Section 5.4.1.3.7 24.501)
+    pub(super) async fn resend_authentication_request(
+        &mut self,
+        context: &NasContext,
+    ) -> std::io::Result<()> {
+        let authentication = self.authentication.as_ref().unwrap();
+
+        let mut request = AuthenticationRequest {
+            header: nas_5gmm_header(MM_MSG_TYPE_AUTHENTICATION_REQUEST),
+            ngksi: NasKeySetId {
+                iei: None,
+                sec_context: SecurityContextType::Native,
+                identifier: authentication.ngksi,
+            },
+            abba: Abba {
+                iei: None,
+                value: ABBA.to_vec(),
+            },
+            rand: None,
+            autn: None,
+            eap_message: None,
+        };
+
+        match &authentication.method {
+            AuthenticationMethod::FivegAka { rand, autn, .. } => {
+                request.rand = Some(AuthenticationParameterRand {
+                    iei: Some(AuthenticationRequest::RAND_IEI),
+                    rand: *rand,
+                });
+                request.autn = Some(AuthenticationParameterAutn {
+                    iei: Some(AuthenticationRequest::AUTN_IEI),
+                    autn: *autn,
+                });
+            }
+            AuthenticationMethod::EapAkaPrime { eap_request } => {
+                request.eap_message = Some(EapMessage {
+                    iei: Some(AuthenticationRequest::EAP_MESSAGE_IEI),
+                    value: eap_request.clone(),
+                });
+            }
+        }
+
+        self.send_nas_message(Nas5gMmMessage::AuthenticationRequest(request), context)
+            .await
+    }
+
+    // Handle the Authentication Response from the UE for the ongoing Authentication.
+    pub(super) async fn handle_authentication_response(
+        &mut self,
+        response: AuthenticationResponse,
+        context: &NasContext,
+    ) -> std::io::Result<()> {
+        let authentication = self.authentication.take().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Authentication Response without an ongoing Authentication.",
+            )
+        })?;
+        self.stop_timer();
+
+        match authentication.method {
+            AuthenticationMethod::FivegAka {
+                rand, hxres_star, ..
+            } => {
+                self.confirm_5g_aka(authentication, rand, hxres_star, response, context)
+                    .await
+            }
+            AuthenticationMethod::EapAkaPrime { .. } => {
+                self.relay_eap_response(authentication, response, context)
+                    .await
+            }
+        }
+    }
+
+    // The `RES*` is first verified against the `HXRES*` by the AMF and then confirmed by the
+    // AUSF. On success the `KAMF` is derived from the `KSEAF`. (Section 6.1.3.2 33.501)
+    async fn confirm_5g_aka(
+        &mut self,
+        authentication: Authentication,
+        rand: Rand,
+        hxres_star: ResStar,
+        response: AuthenticationResponse,
+        context: &NasContext,
+    ) -> std::io::Result<()> {
+        let res_star = match response.response_parameter {
+            Some(parameter) => parameter.res_star,
+            None => {
+                log::warn!("Authentication Response without the RES*.");
+                return self.authentication_failed(None, context).await;
+            }
+        };
+
+        if derive_hres_star(&rand, &res_star) != hxres_star {
+            log::warn!("HRES* does not match HXRES*.");
+            return self.authentication_failed(None, context).await;
+        }
+
+        let confirmation = authentication
+            .ausf
+            .confirm_5g_aka(
+                &authentication.link,
+                ConfirmationData::new(Some(hex::encode(res_star))),
+            )
+            .await?;
+
+        let supi_kseaf = confirmation.supi.zip(confirmation.kseaf);
+        match (confirmation.auth_result, supi_kseaf) {
+            (AuthResult::Success, Some((supi, kseaf))) => {
+                self.authenticated(supi, &kseaf, authentication.ngksi)?;
+                self.security_mode_control_procedure(context).await
+            }
+            _ => {
+                log::warn!("5G AKA Confirmation failed: {:?}", confirmation.auth_result);
+                self.authentication_failed(None, context).await
+            }
+        }
+    }
+
+    // The EAP Response from the UE is relayed to the AUSF. The AUSF either sends the next EAP
+    // Request, relayed to the UE in the Authentication Request, or completes the EAP Session. On
+    // success the `KAMF` is derived from the `KSEAF` and the EAP Success is sent to the UE in the
+    // Authentication Result. (Section 6.1.3.1 33.501, Section 5.4.1.2 24.501)
+    async fn relay_eap_response(
+        &mut self,
+        authentication: Authentication,
+        response: AuthenticationResponse,
+        context: &NasContext,
+    ) -> std::io::Result<()> {
+        let eap_response = match response.eap_message {
+            Some(eap_message) => eap_message.value,
+            None => {
+                log::warn!("Authentication Response without the EAP Message.");
+                return self.authentication_failed(None, context).await;
+            }
+        };
+
+        let eap_session = EapSession::new(Some(BASE64.encode(eap_response)));
+        let eap_session_response = authentication
+            .ausf
+            .eap_session(&authentication.link, eap_session)
+            .await?;
+
+        match eap_session_response {
+            EapSessionResponse::Ongoing(response) => {
+                let eap_request = response
+                    .eap_payload
+                    .as_deref()
+                    .map(decode_eap_payload)
+                    .transpose()?
+                    .ok_or_else(|| invalid_authentication_data("eapPayload"))?;
+                let link = response
+                    ._links
+                    .get("eap-session")
+                    .and_then(|link| link.href.clone())
+                    .ok_or_else(|| missing_link("eap-session"))?;
+
+                self.authentication = Some(Authentication {
+                    method: AuthenticationMethod::EapAkaPrime { eap_request },
+                    link,
+                    ..authentication
+                });
+                self.send_authentication_request(context).await
+            }
+            EapSessionResponse::Complete(eap_session) => {
+                let eap_message = eap_session
+                    .eap_payload
+                    .as_deref()
+                    .map(decode_eap_payload)
+                    .transpose()?;
+
+                let supi_kseaf = eap_session.supi.zip(eap_session.k_seaf);
+                match (eap_session.auth_result, supi_kseaf, eap_message) {
+                    (Some(AuthResult::Success), Some((supi, kseaf)), Some(eap_success)) => {
+                        self.authenticated(supi, &kseaf, authentication.ngksi)?;
+                        self.send_authentication_result(authentication.ngksi, eap_success, context)
+                            .await?;
+                        self.security_mode_control_procedure(context).await
+                    }
+                    (auth_result, _, eap_message) => {
+                        log::warn!("EAP Authentication failed: {:?}", auth_result);
+                        self.authentication_failed(eap_message, context).await
+                    }
+                }
+            }
+        }
+    }
+
+    // The UE is authenticated: The partial Security Context is created with the `KAMF` derived
+    // from the `KSEAF`. (Section A.7 33.501)
+    fn authenticated(&mut self, supi: String, kseaf: &str, ngksi: u8) -> std::io::Result<()> {
+        let kseaf: SecurityKey = decode_hex(kseaf)?;
+        let supi_value = supi.strip_prefix("imsi-").unwrap_or(&supi);
+        let kamf = derive_kamf(&kseaf, supi_value, &ABBA);
+
+        log::info!("UE Authenticated: SUPI: {}", supi);
+        self.supi = Some(supi);
+        self.partial_security_context = Some(PartialSecurityContext { ngksi, kamf });
+
+        Ok(())
+    }
+
+    // Send the EAP Success to the UE. (Section 5.4.1.2.2.7 24.501)
+    async fn send_authentication_result(
+        &mut self,
+        ngksi: u8,
+        eap_success: Vec<u8>,
+        context: &NasContext,
+    ) -> std::io::Result<()> {
+        let result = AuthenticationResult {
+            header: nas_5gmm_header(MM_MSG_TYPE_AUTHENTICATION_RESULT),
+            ngksi: NasKeySetId {
+                iei: None,
+                sec_context: SecurityContextType::Native,
+                identifier: ngksi,
+            },
+            eap_message: EapMessage {
+                iei: None,
+                value: eap_success,
+            },
+            abba: Some(Abba {
+                iei: Some(AuthenticationResult::ABBA_IEI),
+                value: ABBA.to_vec(),
+            }),
+        };
+
+        self.send_nas_message(Nas5gMmMessage::AuthenticationResult(result), context)
+            .await
+    }
+
+    // Handle the Authentication Failure from the UE. (Section 5.4.1.3.7 24.501)
+    pub(super) async fn handle_authentication_failure(
+        &mut self,
+        failure: AuthenticationFailure,
+        context: &NasContext,
+    ) -> std::io::Result<()> {
+        let mut authentication = self.authentication.take().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Authentication Failure without an ongoing Authentication.",
+            )
+        })?;
+        self.stop_timer();
+
+        log::warn!("Authentication Failure: Cause: {}", failure.cause.cause);
+
+        match (failure.cause.cause, failure.failure_parameter) {
+            // Re-synchronise the `SQN` using the `AUTS` and restart the Authentication with the
+            // new Authentication Vector.
+            (CAUSE_SYNCH_FAILURE, Some(parameter)) if !authentication.resynchronized => {
+                let rand = match authentication.method {
+                    AuthenticationMethod::FivegAka { rand, .. } => rand,
+                    AuthenticationMethod::EapAkaPrime { .. } => {
+                        return self.authentication_failed(None, context).await;
+                    }
+                };
+
+                let mut info = AuthenticationInfo::new(
+                    self.suci.as_ref().unwrap().to_string(),
+                    context.config.plmn.serving_network_name(),
+                );
+                info.resynchronization_info = Some(Box::new(ResynchronizationInfo::new(
+                    hex::encode(rand),
+                    hex::encode(parameter.auts),
+                )));
+
+                self.start_authentication(
+                    authentication.ausf,
+                    info,
+                    authentication.ngksi,
+                    true,
+                    context,
+                )
+                .await
+            }
+            // Resend the Authentication Request with a different ngKSI.
+            (CAUSE_NGKSI_ALREADY_IN_USE, _) => {
+                authentication.ngksi = (authentication.ngksi + 1) % NGKSI_NO_KEY_AVAILABLE;
+                self.authentication = Some(authentication);
+                self.send_authentication_request(context).await
+            }
+            // `CAUSE_MAC_FAILURE`, a repeated synch failure or the non-5G authentication being
+            // unacceptable: The network is not authenticated by the UE.
+            (cause, _) => {
+                if cause == CAUSE_MAC_FAILURE {
+                    log::warn!("UE failed to authenticate the network.");
+                }
+                self.authentication_failed(None, context).await
+            }
+        }
+    }
+
+    // The Authentication is not successful, the Authentication Reject is sent to the UE along
+    // with the EAP Failure for the EAP based Authentication. The Registration is aborted as well.
+    // (Section 5.4.1.3.5 and 5.4.1.2.2.11 24.501)
+    async fn authentication_failed(
+        &mut self,
+        eap_failure: Option<Vec<u8>>,
+        context: &NasContext,
+    ) -> std::io::Result<()> {
+        self.abort_procedures();
+
+        let reject = AuthenticationReject {
+            header: nas_5gmm_header(MM_MSG_TYPE_AUTHENTICATION_REJECT),
+            eap_message: eap_failure.map(|value| EapMessage {
+                iei: Some(AuthenticationReject::EAP_MESSAGE_IEI),
+                value,
+            }),
+        };
+
+        log::warn!(
+            "Authentication of the UE with AMF_UE_NGAP_ID: {} failed.",
+            self.amf_ue_ngap_id
+        );
+        self.send_nas_message(Nas5gMmMessage::AuthenticationReject(reject), context)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::sync::mpsc::Receiver;
+
+    use ngap::messages::r17::NAS_PDU;
+
+    use nas::messages::ies::{AuthenticationFailureParameter, FivegmmCause};
+    use nas::messages::{MM_MSG_TYPE_AUTHENTICATION_FAILURE, MM_MSG_TYPE_AUTHENTICATION_RESPONSE};
+
+    use security_3gpp::{
+        derive_ck_ik_prime, derive_kseaf, AuthAlgorithm, AuthVector, EapAkaPrimeKeys,
+    };
+
+    use crate::amf::ausf::eap_aka_prime::{
+        eap_aka_prime_identity, Attribute, EapAkaPrimeMessage, EapPacket, AT_AUTN, AT_AUTS,
+        AT_RAND, AT_RES, EAP_CODE_FAILURE, SUBTYPE_AKA_CHALLENGE,
+        SUBTYPE_AKA_SYNCHRONIZATION_FAILURE,
+    };
+    use crate::amf::messages::NasToAmfMessage;
+    use crate::amf::nas::tests_util::{
+        authentication_response, milenage, network_sqn, received_authentication_request,
+        received_message, received_security_mode_command, setup, setup_with_config, ue_5g_aka,
+        CONFIG, REGISTRATION_REQUEST,
+    };
+
+    fn received_eap_request(rx: &mut Receiver<NasToAmfMessage>) -> EapPacket {
+        match received_message(rx) {
+            Nas5gMmMessage::AuthenticationRequest(request) => {
+                assert!(request.rand.is_none() && request.autn.is_none());
+                EapPacket::decode(&request.eap_message.unwrap().value).unwrap()
+            }
+            message => panic!("Unexpected Message: {:?}", message),
+        }
+    }
+
+    // `RAND` and `AUTN` in the EAP-AKA' Challenge.
+    fn challenge_rand_autn(request: &EapPacket) -> (Rand, Vec<u8>) {
+        let message = request.message.as_ref().unwrap();
+        assert_eq!(message.subtype, SUBTYPE_AKA_CHALLENGE);
+        (
+            message
+                .attribute(AT_RAND)
+                .unwrap()
+                .data()
+                .try_into()
+                .unwrap(),
+            message.attribute(AT_AUTN).unwrap().data().to_vec(),
+        )
+    }
+
+    // Verify the EAP-AKA' Challenge as the UE would and return the Authentication Vector along
+    // with the EAP-AKA' keys.
+    fn verify_eap_request(
+        request: &EapPacket,
+        context: &NasContext,
+    ) -> (AuthVector, EapAkaPrimeKeys) {
+        let (rand, autn) = challenge_rand_autn(request);
+        let av = milenage().generate_auth_vector(rand, network_sqn(rand, &autn), [0x80, 0x00]);
+        assert_eq!(av.autn, autn);
+
+        let sn_name = context.config.plmn.serving_network_name();
+        let (ck_prime, ik_prime) = derive_ck_ik_prime(&av.ck, &av.ik, &sn_name, &av.autn[..6]);
+        let identity = eap_aka_prime_identity("imsi-901700000000001", &"901-70".parse().unwrap());
+        let keys = EapAkaPrimeKeys::derive(&ck_prime, &ik_prime, &identity);
+        assert!(request.verify_mac(&keys.k_aut));
+
+        (av, keys)
+    }
+
+    fn eap_respond(
+        ue: &mut AmfUe,
+        context: &NasContext,
+        eap_response: EapPacket,
+    ) -> std::io::Result<()> {
+        let response = AuthenticationResponse {
+            header: nas_5gmm_header(MM_MSG_TYPE_AUTHENTICATION_RESPONSE),
+            response_parameter: None,
+            eap_message: Some(EapMessage {
+                iei: Some(AuthenticationResponse::EAP_MESSAGE_IEI),
+                value: eap_response.encode(),
+            }),
+        };
+        let pdu = NAS_PDU(Nas5gMmMessage::AuthenticationResponse(response).encode());
+        futures::executor::block_on(ue.handle_nas_message(pdu, context))
+    }
+
+    fn challenge_response(identifier: u8, res: &[u8], k_aut: &[u8; 32]) -> EapPacket {
+        let mut response = EapPacket::response(
+            identifier,
+            EapAkaPrimeMessage::new(
+                SUBTYPE_AKA_CHALLENGE,
+                vec![Attribute::with_length(AT_RES, (res.len() * 8) as u16, res)],
+            ),
+        );
+        response.add_mac(k_aut);
+        response
+    }
+
+    fn start(ue: &mut AmfUe, context: &NasContext) {
+        let pdu = NAS_PDU(hex::decode(REGISTRATION_REQUEST).unwrap());
+        futures::executor::block_on(ue.handle_initial_nas_message(pdu, context)).unwrap();
+    }
+
+    fn respond(ue: &mut AmfUe, context: &NasContext, res_star: ResStar) -> std::io::Result<()> {
+        let pdu = authentication_response(res_star);
+        futures::executor::block_on(ue.handle_nas_message(pdu, context))
+    }
+
+    fn fail(
+        ue: &mut AmfUe,
+        context: &NasContext,
+        cause: u8,
+        auts: Option<[u8; 14]>,
+    ) -> std::io::Result<()> {
+        let failure = AuthenticationFailure {
+            header: nas_5gmm_header(MM_MSG_TYPE_AUTHENTICATION_FAILURE),
+            cause: FivegmmCause { iei: None, cause },
+            failure_parameter: auts.map(|auts| AuthenticationFailureParameter {
+                iei: Some(AuthenticationFailure::FAILURE_PARAMETER_IEI),
+                auts,
+            }),
+        };
+        let pdu = NAS_PDU(Nas5gMmMessage::AuthenticationFailure(failure).encode());
+        futures::executor::block_on(ue.handle_nas_message(pdu, context))
+    }
+
+    #[test]
+    fn test_authentication_success() {
+        let (mut ue, context, mut rx) = setup();
+        start(&mut ue, &context);
+        assert_eq!(
+            ue.suci.as_ref().unwrap().to_string(),
+            "suci-0-901-70-0000-0-0-0000000001"
+        );
+
+        let (rand, autn) = received_authentication_request(&mut rx);
+        assert_eq!(network_sqn(rand, &autn), [0, 0, 0, 0, 0, 0x21]);
+
+        let (res_star, kamf) = ue_5g_aka(rand, &autn, &context);
+        assert!(respond(&mut ue, &context, res_star).is_ok());
+
+        assert_eq!(ue.supi.as_deref(), Some("imsi-901700000000001"));
+        let partial_security_context = ue.partial_security_context.as_ref().unwrap();
+        assert_eq!(partial_security_context.ngksi, 0);
+        assert_eq!(partial_security_context.kamf, kamf);
+        assert!(ue.authentication.is_none());
+
+        // The Security Mode Control starts after the Authentication.
+        received_security_mode_command(&mut rx, kamf);
+        assert!(ue.security_mode_control.is_some());
+        assert!(rx.try_recv().is_err());
+
+        // A repeated Authentication Response does not affect the Security Mode Control.
+        assert!(respond(&mut ue, &context, res_star).is_err());
+        assert!(ue.security_mode_control.is_some());
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_authentication_ausf_error() {
+        let (mut ue, context, mut rx) = setup();
+        start(&mut ue, &context);
+
+        let (rand, autn) = received_authentication_request(&mut rx);
+        let (res_star, _) = ue_5g_aka(rand, &autn, &context);
+
+        // The Authentication Context is not known to the AUSF: The Registration is rejected with
+        // `Protocol error, unspecified`, instead of being stalled.
+        ue.authentication
+            .as_mut()
+            .unwrap()
+            .link
+            .push_str("-unknown");
+        assert!(respond(&mut ue, &context, res_star).is_ok());
+        match received_message(&mut rx) {
+            Nas5gMmMessage::RegistrationReject(reject) => assert_eq!(reject.cause.cause, 111),
+            message => panic!("Unexpected Message: {:?}", message),
+        }
+        assert!(!ue.is_served());
+        assert!(ue.timer.is_none());
+    }
+
+    #[test]
+    fn test_authentication_request_retransmission() {
+        let (mut ue, context, mut rx) = setup();
+        start(&mut ue, &context);
+        let (rand, autn) = received_authentication_request(&mut rx);
+        assert_eq!(ue.timer.as_ref().unwrap().id, NasTimerId::T3560);
+
+        // The same Authentication Request is retransmitted on each expiry of T3560.
+        for expirations in 1..=4 {
+            assert!(futures::executor::block_on(ue.handle_timer_expiry(&context)).is_ok());
+            assert_eq!(
+                received_authentication_request(&mut rx),
+                (rand, autn.clone())
+            );
+            assert_eq!(ue.timer.as_ref().unwrap().expirations, expirations);
+        }
+
+        // Aborted on the fifth expiry.
+        assert!(futures::executor::block_on(ue.handle_timer_expiry(&context)).is_ok());
+        assert!(rx.try_recv().is_err());
+        assert!(ue.authentication.is_none());
+        assert!(ue.timer.is_none());
+    }
+
+    #[test]
+    fn test_authentication_synch_failure() {
+        let (mut ue, context, mut rx) = setup();
+        start(&mut ue, &context);
+        let (rand, _) = received_authentication_request(&mut rx);
+
+        // `AUTS` for `SQN_MS` of 0x100 (Section 6.3.3 33.102)
+        let milenage = milenage();
+        let sqn_ms = [0, 0, 0, 0, 0x01, 0x00];
+        let mut auts: Vec<u8> = sqn_ms
+            .iter()
+            .zip(milenage.f5_star(rand))
+            .map(|(s, a)| s ^ a)
+            .collect();
+        auts.extend(milenage.f1_star(rand, sqn_ms, [0, 0]));
+
+        let auts: [u8; 14] = auts.try_into().unwrap();
+        assert!(fail(&mut ue, &context, CAUSE_SYNCH_FAILURE, Some(auts)).is_ok());
+
+        // New Authentication Request with the `SQN` after `SQN_MS`.
+        let (rand, autn) = received_authentication_request(&mut rx);
+        assert_eq!(network_sqn(rand, &autn), [0, 0, 0, 0, 0x01, 0x01]);
+
+        // Synch Failure again is treated as an Authentication failure.
+        assert!(fail(&mut ue, &context, CAUSE_SYNCH_FAILURE, Some(auts)).is_ok());
+        assert!(matches!(
+            received_message(&mut rx),
+            Nas5gMmMessage::AuthenticationReject(_)
+        ));
+    }
+
+    #[test]
+    fn test_authentication_mac_failure() {
+        let (mut ue, context, mut rx) = setup();
+        start(&mut ue, &context);
+        let _ = received_authentication_request(&mut rx);
+
+        assert!(fail(&mut ue, &context, CAUSE_MAC_FAILURE, None).is_ok());
+        assert!(matches!(
+            received_message(&mut rx),
+            Nas5gMmMessage::AuthenticationReject(_)
+        ));
+        assert!(ue.authentication.is_none());
+        assert!(ue.partial_security_context.is_none());
+    }
+
+    #[test]
+    fn test_authentication_invalid_res_star() {
+        let (mut ue, context, mut rx) = setup();
+        start(&mut ue, &context);
+        let _ = received_authentication_request(&mut rx);
+
+        assert!(respond(&mut ue, &context, [0_u8; 16]).is_ok());
+        assert!(matches!(
+            received_message(&mut rx),
+            Nas5gMmMessage::AuthenticationReject(_)
+        ));
+        assert!(ue.supi.is_none());
+        assert!(ue.partial_security_context.is_none());
+        assert!(ue.registration_request.is_none());
+        assert!(!ue.is_served());
+
+        // Authentication Response without an ongoing Authentication.
+        assert!(respond(&mut ue, &context, [0_u8; 16]).is_err());
+    }
+
+    #[test]
+    fn test_eap_authentication_success() {
+        let (mut ue, context, mut rx) =
+            setup_with_config(&format!("{}\n   auth_method: EAP_AKA_PRIME", CONFIG));
+        start(&mut ue, &context);
+
+        let request = received_eap_request(&mut rx);
+        let (av, keys) = verify_eap_request(&request, &context);
+        let response = challenge_response(request.identifier, &av.xres, &keys.k_aut);
+        assert!(eap_respond(&mut ue, &context, response).is_ok());
+
+        match received_message(&mut rx) {
+            Nas5gMmMessage::AuthenticationResult(result) => {
+                assert_eq!(result.ngksi.identifier, 0);
+                assert_eq!(result.abba.unwrap().value, ABBA.to_vec());
+                assert_eq!(
+                    EapPacket::decode(&result.eap_message.value).unwrap(),
+                    EapPacket::success(request.identifier)
+                );
+            }
+            message => panic!("Unexpected Message: {:?}", message),
+        }
+
+        // `KAMF` derived by the UE.
+        let sn_name = context.config.plmn.serving_network_name();
+        let kseaf = derive_kseaf(&keys.kausf(), &sn_name);
+        let kamf = derive_kamf(&kseaf, "901700000000001", &ABBA);
+
+        assert_eq!(ue.supi.as_deref(), Some("imsi-901700000000001"));
+        assert_eq!(ue.partial_security_context.as_ref().unwrap().kamf, kamf);
+        assert!(ue.authentication.is_none());
+
+        received_security_mode_command(&mut rx, kamf);
+        assert!(ue.security_mode_control.is_some());
+    }
+
+    #[test]
+    fn test_eap_authentication_failure() {
+        let (mut ue, context, mut rx) =
+            setup_with_config(&format!("{}\n   auth_method: EAP_AKA_PRIME", CONFIG));
+        start(&mut ue, &context);
+
+        // Synchronization Failure: The AUSF sends a new EAP Request after the
+        // Re-synchronisation, that is relayed to the UE.
+        let request = received_eap_request(&mut rx);
+        let (rand, _) = challenge_rand_autn(&request);
+        let milenage = milenage();
+        let sqn_ms = [0, 0, 0, 0, 0x01, 0x00];
+        let mut auts: Vec<u8> = sqn_ms
+            .iter()
+            .zip(milenage.f5_star(rand))
+            .map(|(s, a)| s ^ a)
+            .collect();
+        auts.extend(milenage.f1_star(rand, sqn_ms, [0, 0]));
+        let synchronization_failure = EapPacket::response(
+            request.identifier,
+            EapAkaPrimeMessage::new(
+                SUBTYPE_AKA_SYNCHRONIZATION_FAILURE,
+                vec![Attribute::plain(AT_AUTS, &auts)],
+            ),
+        );
+        assert!(eap_respond(&mut ue, &context, synchronization_failure).is_ok());
+
+        let request = received_eap_request(&mut rx);
+        let (rand, autn) = challenge_rand_autn(&request);
+        assert_eq!(network_sqn(rand, &autn), [0, 0, 0, 0, 0x01, 0x01]);
+
+        // Wrong `RES`: Authentication Reject with the EAP Failure.
+        let (av, keys) = verify_eap_request(&request, &context);
+        let response = challenge_response(request.identifier, &av.xres[1..], &keys.k_aut);
+        assert!(eap_respond(&mut ue, &context, response).is_ok());
+
+        match received_message(&mut rx) {
+            Nas5gMmMessage::AuthenticationReject(reject) => {
+                let eap_failure = EapPacket::decode(&reject.eap_message.unwrap().value).unwrap();
+                assert_eq!(eap_failure.code, EAP_CODE_FAILURE);
+            }
+            message => panic!("Unexpected Message: {:?}", message),
+        }
+        assert!(ue.supi.is_none());
+        assert!(ue.partial_security_context.is_none());
+        assert!(ue.authentication.is_none());
+    }
+}
@@ -5,34 +5,425 @@
 //!
 //! Also: Section 4.2.2.2 from 23.502 Release 17. (Note: General Registration Only).
 //!
-use super::AmfUe;
-use nas::messages::RegistrationRequest;
+use super::{nas_5gmm_header, AmfUe, RegistrationFailure};
+use nas::messages::{
+    ies::{
+        FivegsMobileIdentity, FivegsNetworkFeatureSupport, FivegsRegistrationResult, GprsTimer3,
+        GprsTimer3Unit, MobileIdentity, Nssai, SNssai, TaiList,
+    },
+    Nas5gMmMessage, RegistrationAccept, RegistrationComplete, RegistrationRequest,
+    MM_MSG_TYPE_REGISTRATION_ACCEPT,
+};
+
+use crate::amf::nas::nas_manager::NasContext;
+use crate::amf::nas::timer::NasTimerId;
+
+// 5GS Registration Result: 3GPP Access. (Section 9.11.3.6 24.501)
+const REGISTRATION_RESULT_3GPP_ACCESS: u8 = 0x01;
+
+// T3512 (Periodic Registration Update Timer) of 1 hour. The default value of 54 minutes cannot be
+// encoded in the GPRS Timer 3. (Section 10.2 24.501)
+const T3512_UNIT: GprsTimer3Unit = GprsTimer3Unit::OneHour;
+const T3512_VALUE: u8 = 1;
+
+// 5GS Network Feature Support: IMS Voice over PS Session, Emergency Services and Interworking
+// without N26 are not supported. (Section 9.11.3.5 24.501)
+const NETWORK_FEATURE_SUPPORT: [u8; 2] = [0x00, 0x00];
 
 impl AmfUe {
-    pub(super) fn registration_procedure(
+    pub(super) async fn registration_procedure(
         &mut self,
         reg_request: RegistrationRequest,
         initial: bool,
+        context: &NasContext,
     ) -> std::io::Result<()> {
         if initial {
-            self.initial_registration_procedure(reg_request)
+            self.initial_registration_procedure(reg_request, context)
+                .await
         } else {
-            self.periodic_or_mobility_registration_procedure(reg_request)
+            self.periodic_or_mobility_registration_procedure(reg_request, context)
+                .await
         }
     }
 
-    fn initial_registration_procedure(
+    // The UE identified by the SUCI is authenticated first. The UEs in the Tracking Areas not
+    // served by the AMF are rejected. (Section 5.5.1.2.2 24.501)
+    async fn initial_registration_procedure(
         &mut self,
         reg_request: RegistrationRequest,
+        context: &NasContext,
     ) -> std::io::Result<()> {
         log::debug!("Registration Request: {:#?}", reg_request);
+
+        if let Some(tac) = self.tac {
+            if !context.config.tacs.contains(&tac) {
+                return Err(RegistrationFailure::TaNotAllowed(tac).into());
+            }
+        }
+
+        // The 5G-GUTI is not known, since the AMF does not keep the UE contexts after the UE is
+        // released, so the UE is identified only by the SUCI.
+        let suci = match &reg_request.identity.identity {
+            MobileIdentity::Suci(suci) => suci.clone(),
+            identity => {
+                return Err(RegistrationFailure::UeIdentityCannotBeDerived(format!(
+                    "{:?}",
+                    identity
+                ))
+                .into())
+            }
+        };
+
+        self.suci = Some(suci);
+        self.registration_request = Some(reg_request);
+
+        self.authentication_procedure(context).await
+    }
+
+    // Accept the Registration of the authenticated UE, once the NAS Security is activated: A new
+    // 5G-GUTI is allocated to the UE and is used after the Registration Complete is received.
+    // (Section 5.5.1.2.4 24.501)
+    pub(super) async fn accept_registration(
+        &mut self,
+        context: &NasContext,
+    ) -> std::io::Result<()> {
+        let requested_nssai = self
+            .registration_request
+            .as_ref()
+            .and_then(|request| request.requested_nssai.as_ref());
+        let allowed_nssai = allowed_nssai(requested_nssai, &context.config.supported_nssai())
+            .ok_or(RegistrationFailure::NoNetworkSlicesAvailable)?;
+
+        let tmsi = context.allocate_tmsi()?;
+        if let Some(old_guti) = self.guti.replace(context.config.guti(tmsi)) {
+            context.release_tmsi(old_guti.tmsi);
+        }
+        let guti = self.guti.clone().unwrap();
+
+        let accept = RegistrationAccept {
+            header: nas_5gmm_header(MM_MSG_TYPE_REGISTRATION_ACCEPT),
+            registration_result: FivegsRegistrationResult {
+                iei: None,
+                disaster_roaming_registration_result: false,
+                emergency_registered: false,
+                nssaa_to_be_performed: false,
+                sms_allowed: false,
+                result: REGISTRATION_RESULT_3GPP_ACCESS,
+            },
+            guti: Some(FivegsMobileIdentity {
+                iei: Some(RegistrationAccept::GUTI_IEI),
+                length: 11,
+                identity: MobileIdentity::FivegGuti(guti),
+            }),
+            equivalent_plmns: None,
+            tai_list: Some(TaiList {
+                iei: Some(RegistrationAccept::TAI_LIST_IEI),
+                ..context.config.tai_list(self.tac)
+            }),
+            allowed_nssai: Some(Nssai {
+                iei: Some(RegistrationAccept::ALLOWED_NSSAI_IEI),
+                ..allowed_nssai
+            }),
+            rejected_nssai: None,
+            configured_nssai: None,
+            network_feature_support: Some(FivegsNetworkFeatureSupport {
+                iei: Some(RegistrationAccept::NETWORK_FEATURE_SUPPORT_IEI),
+                value: NETWORK_FEATURE_SUPPORT.to_vec(),
+            }),
+            pdu_session_status: None,
+            pdu_session_reactivation_result: None,
+            pdu_session_reactivation_result_error_cause: None,
+            ladn_information: None,
+            mico_indication: None,
+            network_slicing_indication: None,
+            service_area_list: None,
+            t3512: Some(GprsTimer3 {
+                iei: Some(RegistrationAccept::T3512_IEI),
+                unit: T3512_UNIT,
+                value: T3512_VALUE,
+            }),
+            non_3gpp_deregistration_timer: None,
+            t3502: None,
+            emergency_number_list: None,
+            extended_emergency_number_list: None,
+            sor_transparent_container: None,
+            eap_message: None,
+            nssai_inclusion_mode: None,
+            operator_defined_access_category_definitions: None,
+        };
+        self.registration_accept = Some(accept);
+
+        self.start_timer(NasTimerId::T3550);
+        self.resend_registration_accept(context).await
+    }
+
+    // Send the Registration Accept. Also used for the retransmission on the expiry of T3550.
+    // (Section 5.5.1.2.8 24.501)
+    pub(super) async fn resend_registration_accept(
+        &mut self,
+        context: &NasContext,
+    ) -> std::io::Result<()> {
+        let accept = self.registration_accept.clone().unwrap();
+
+        self.send_nas_message(Nas5gMmMessage::RegistrationAccept(accept), context)
+            .await
+    }
+
+    // The UE is registered, the new 5G-GUTI is used by the UE from now on. (Section 5.5.1.2.4
+    // 24.501)
+    pub(super) async fn handle_registration_complete(
+        &mut self,
+        _complete: RegistrationComplete,
+        _context: &NasContext,
+    ) -> std::io::Result<()> {
+        if self.registration_accept.take().is_none() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Registration Complete without an ongoing Registration.",
+            ));
+        }
+        self.stop_timer();
+        self.registered = true;
+
+        log::info!(
+            "UE Registered: SUPI: {}, 5G-GUTI: {:?}",
+            self.supi.as_deref().unwrap_or_default(),
+            self.guti.as_ref().unwrap()
+        );
+
         Ok(())
     }
 
-    fn periodic_or_mobility_registration_procedure(
+    async fn periodic_or_mobility_registration_procedure(
         &mut self,
-        reg_request: RegistrationRequest,
+        _reg_request: RegistrationRequest,
+        _context: &NasContext,
     ) -> std::io::Result<()> {
         todo!()
     }
 }
+
+// The Allowed NSSAI: The S-NSSAIs in the Requested NSSAI that are supported by the AMF. All the
+// supported S-NSSAIs are allowed, if the UE does not request any S-NSSAI. Returns `None` if none of
+// the requested S-NSSAIs is supported. (Section 5.15.5.2.1 23.501)
+fn allowed_nssai(requested_nssai: Option<&Nssai>, supported_nssai: &Nssai) -> Option<Nssai> {
+    let requested_nssai = match requested_nssai {
+        Some(requested_nssai) => requested_nssai,
+        None => return Some(supported_nssai.clone()),
+    };
+
+    let s_nssais: Vec<SNssai> = requested_nssai
+        .s_nssais
+        .iter()
+        .filter(|s_nssai| {
+            supported_nssai
+                .s_nssais
+                .iter()
+                .any(|supported| supported.sst == s_nssai.sst && supported.sd == s_nssai.sd)
+        })
+        .map(|s_nssai| SNssai::new(s_nssai.sst, s_nssai.sd))
+        .collect();
+
+    if s_nssais.is_empty() {
+        None
+    } else {
+        Some(Nssai {
+            iei: None,
+            s_nssais,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use nas::messages::headers::Nas5gSecurityHeader;
+    use nas::messages::ies::{FivegGuti, PartialTaiList};
+    use nas::messages::MM_MSG_TYPE_REGISTRATION_COMPLETE;
+
+    use tokio::sync::mpsc::Receiver;
+
+    use crate::amf::messages::NasToAmfMessage;
+    use crate::amf::nas::tests_util::{
+        received_pdu, register, register_with_request, setup, setup_with_config, UeSecurityContext,
+        CONFIG, REGISTRATION_REQUEST,
+    };
+
+    fn received_registration_accept(
+        rx: &mut Receiver<NasToAmfMessage>,
+        ue_security_context: &UeSecurityContext,
+    ) -> RegistrationAccept {
+        let pdu = received_pdu(rx);
+        assert_eq!(
+            pdu[1],
+            Nas5gSecurityHeader::IntegrityProtectedAndCiphered as u8
+        );
+        match ue_security_context.unprotect(&pdu) {
+            Nas5gMmMessage::RegistrationAccept(accept) => accept,
+            message => panic!("Unexpected Message: {:?}", message),
+        }
+    }
+
+    #[test]
+    fn test_initial_registration() {
+        let (mut ue, context, mut rx) = setup();
+        ue.tac = Some(2);
+        let mut ue_security_context = register(&mut ue, &context, &mut rx);
+
+        let accept = received_registration_accept(&mut rx, &ue_security_context);
+        assert_eq!(
+            accept.registration_result.result,
+            REGISTRATION_RESULT_3GPP_ACCESS
+        );
+        let guti = match accept.guti.unwrap().identity {
+            MobileIdentity::FivegGuti(guti) => guti,
+            identity => panic!("Unexpected Mobile Identity: {:?}", identity),
+        };
+        assert_eq!(
+            guti,
+            FivegGuti {
+                plmn: "901-70".parse().unwrap(),
+                amf_region_id: 1,
+                amf_set_id: 2,
+                amf_pointer: 3,
+                tmsi: guti.tmsi,
+            }
+        );
+        assert_eq!(guti.tmsi >> 26, 3);
+        assert_eq!(ue.guti.as_ref(), Some(&guti));
+        // The TAI of the UE is the first in the TAI List.
+        assert_eq!(
+            accept.tai_list.unwrap().partial_lists,
+            vec![PartialTaiList::NonConsecutiveTacs {
+                plmn: "901-70".parse().unwrap(),
+                tacs: vec![2, 1]
+            }]
+        );
+        assert_eq!(
+            accept.allowed_nssai.unwrap().s_nssais,
+            vec![SNssai::new(1, None)]
+        );
+        let t3512 = accept.t3512.unwrap();
+        assert_eq!((t3512.unit, t3512.value), (T3512_UNIT, T3512_VALUE));
+        assert!(accept.network_feature_support.is_some());
+        assert!(!ue.registered);
+
+        let complete = RegistrationComplete {
+            header: nas_5gmm_header(MM_MSG_TYPE_REGISTRATION_COMPLETE),
+            sor_transparent_container: None,
+        };
+        let pdu = ue_security_context.protect(
+            Nas5gMmMessage::RegistrationComplete(complete),
+            Nas5gSecurityHeader::IntegrityProtectedAndCiphered,
+        );
+        assert!(futures::executor::block_on(ue.handle_nas_message(pdu, &context)).is_ok());
+
+        assert!(ue.registered);
+        assert!(ue.registration_accept.is_none());
+        assert!(ue.timer.is_none());
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_registration_accept_retransmission() {
+        let (mut ue, context, mut rx) = setup();
+        let ue_security_context = register(&mut ue, &context, &mut rx);
+        let accept = received_registration_accept(&mut rx, &ue_security_context);
+
+        // The same Registration Accept is retransmitted on each expiry of T3550.
+        for expirations in 1..=4 {
+            assert!(futures::executor::block_on(ue.handle_timer_expiry(&context)).is_ok());
+            assert_eq!(
+                received_registration_accept(&mut rx, &ue_security_context),
+                accept
+            );
+            assert_eq!(ue.timer.as_ref().unwrap().id, NasTimerId::T3550);
+            assert_eq!(ue.timer.as_ref().unwrap().expirations, expirations);
+        }
+
+        // Aborted on the fifth expiry, the 5G-GUTI is retained.
+        assert!(futures::executor::block_on(ue.handle_timer_expiry(&context)).is_ok());
+        assert!(rx.try_recv().is_err());
+        assert!(ue.registration_accept.is_none());
+        assert!(ue.guti.is_some());
+        assert!(!ue.registered);
+        assert!(ue.timer.is_none());
+    }
+
+    #[test]
+    fn test_no_network_slices_available() {
+        let (mut ue, context, mut rx) = setup();
+
+        // Registration Request with the Requested NSSAI of the SST 2, that is not supported.
+        let request = format!("{}2f020102", REGISTRATION_REQUEST);
+        let ue_security_context = register_with_request(&mut ue, &context, &mut rx, &request);
+
+        // The Registration Reject is protected with the NAS Security Context.
+        match ue_security_context.unprotect(&received_pdu(&mut rx)) {
+            Nas5gMmMessage::RegistrationReject(reject) => assert_eq!(reject.cause.cause, 62),
+            message => panic!("Unexpected Message: {:?}", message),
+        }
+        assert!(ue.guti.is_none());
+        assert!(ue.timer.is_none());
+    }
+
+    #[test]
+    fn test_configured_slices() {
+        let (mut ue, context, mut rx) =
+            setup_with_config(&format!("{}\nslices:\n - sst: 1\n - sst: 2", CONFIG));
+
+        // Registration Request with the Requested NSSAI of the SST 2.
+        let request = format!("{}2f020102", REGISTRATION_REQUEST);
+        let ue_security_context = register_with_request(&mut ue, &context, &mut rx, &request);
+
+        let accept = received_registration_accept(&mut rx, &ue_security_context);
+        assert_eq!(
+            accept.allowed_nssai.unwrap().s_nssais,
+            vec![SNssai::new(2, None)]
+        );
+    }
+
+    #[test]
+    fn test_unexpected_registration_complete() {
+        let (mut ue, context, _rx) = setup();
+        let complete = RegistrationComplete {
+            header: nas_5gmm_header(MM_MSG_TYPE_REGISTRATION_COMPLETE),
+            sor_transparent_container: None,
+        };
+        assert!(
+            futures::executor::block_on(ue.handle_registration_complete(complete, &context))
+                .is_err()
+        );
+        assert!(!ue.registered);
+    }
+
+    #[test]
+    fn test_allowed_nssai() {
+        let supported = Nssai {
+            iei: None,
+            s_nssais: vec![SNssai::new(1, None), SNssai::new(2, Some([0, 0, 1]))],
+        };
+
+        // All the supported S-NSSAIs without the Requested NSSAI.
+        assert_eq!(allowed_nssai(None, &supported), Some(supported.clone()));
+
+        let requested = Nssai {
+            iei: Some(RegistrationRequest::REQUESTED_NSSAI_IEI),
+            s_nssais: vec![SNssai::new(2, Some([0, 0, 1])), SNssai::new(3, None)],
+        };
+        assert_eq!(
+            allowed_nssai(Some(&requested), &supported)
+                .unwrap()
+                .s_nssais,
+            vec![SNssai::new(2, Some([0, 0, 1]))]
+        );
+
+        // None of the requested S-NSSAIs are supported.
+        let requested = Nssai {
+            iei: Some(RegistrationRequest::REQUESTED_NSSAI_IEI),
+            s_nssais: vec![SNssai::new(2, None)],
+        };
+        assert_eq!(allowed_nssai(Some(&requested), &supported), None);
+    }
+}
@@ -0,0 +1,341 @@
+//! Registration Reject
+//!
+//! Section 5.5.1.2.5 24.501: The AMF rejects the Registration of the UE with the 5GMM Cause
+//! indicating the reason. The failures during the Registration procedure are mapped to the 5GMM
+//! Causes by the [`RegistrationFailure`].
+
+use nas::messages::ies::{FivegmmCause, GprsTimer2, GprsTimerUnit};
+use nas::messages::{Nas5gMmMessage, RegistrationReject, MM_MSG_TYPE_REGISTRATION_REJECT};
+use nas::{error::CAUSE_PROTOCOL_ERROR_UNSPECIFIED, NasError};
+
+use crate::amf::ausf::AusfError;
+use crate::amf::nas::nas_manager::NasContext;
+
+use super::{nas_5gmm_header, AmfUe};
+
+// 5GMM Causes. (Section 9.11.3.2 24.501)
+const CAUSE_ILLEGAL_UE: u8 = 3;
+const CAUSE_UE_IDENTITY_CANNOT_BE_DERIVED: u8 = 9;
+const CAUSE_PLMN_NOT_ALLOWED: u8 = 11;
+const CAUSE_TA_NOT_ALLOWED: u8 = 12;
+const CAUSE_CONGESTION: u8 = 22;
+const CAUSE_UE_SECURITY_CAPABILITIES_MISMATCH: u8 = 23;
+const CAUSE_NO_NETWORK_SLICES_AVAILABLE: u8 = 62;
+
+/// Failures of the Registration procedure, for which the Registration of the UE is rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(in crate::amf) enum RegistrationFailure {
+    /// The UE is not known (eg. the SUPI is not known to the AUSF) or fails the authentication.
+    IllegalUe(String),
+
+    /// The identity of the UE cannot be derived (eg. an unknown 5G-GUTI).
+    UeIdentityCannotBeDerived(String),
+
+    /// The UE is not allowed in the PLMN of the AMF (eg. no AUSF serves the Home Network of the
+    /// UE).
+    PlmnNotAllowed(String),
+
+    /// The UE is in a Tracking Area not served by the AMF.
+    TaNotAllowed(u32),
+
+    /// The AMF is congested: The UE backs off for the given number of seconds (T3346).
+    Congestion(u32),
+
+    /// None of the NAS Security Algorithms supported by the UE are allowed.
+    UeSecurityCapabilitiesMismatch(String),
+
+    /// None of the S-NSSAIs requested by the UE are available.
+    NoNetworkSlicesAvailable,
+
+    /// The Registration Request is not valid.
+    InvalidMessage(NasError),
+
+    /// The Registration cannot be completed due to a failure in the network (eg. no AUSF is
+    /// configured). The `Protocol error, unspecified` cause is used, so that the UE retries
+    /// later instead of considering the PLMN forbidden.
+    NetworkFailure(String),
+}
+
+impl RegistrationFailure {
+    /// The 5GMM Cause sent to the UE in the Registration Reject.
+    pub(in crate::amf) fn cause(&self) -> u8 {
+        match self {
+            Self::IllegalUe(_) => CAUSE_ILLEGAL_UE,
+            Self::UeIdentityCannotBeDerived(_) => CAUSE_UE_IDENTITY_CANNOT_BE_DERIVED,
+            Self::PlmnNotAllowed(_) => CAUSE_PLMN_NOT_ALLOWED,
+            Self::TaNotAllowed(_) => CAUSE_TA_NOT_ALLOWED,
+            Self::Congestion(_) => CAUSE_CONGESTION,
+            Self::UeSecurityCapabilitiesMismatch(_) => CAUSE_UE_SECURITY_CAPABILITIES_MISMATCH,
+            Self::NoNetworkSlicesAvailable => CAUSE_NO_NETWORK_SLICES_AVAILABLE,
+            Self::InvalidMessage(error) => error.cause(),
+            Self::NetworkFailure(_) => CAUSE_PROTOCOL_ERROR_UNSPECIFIED,
+        }
+    }
+
+    /// The Registration Failure for an error returned by the Registration procedure. Errors not
+    /// caused by the UE (eg. the AUSF not reachable) are not mapped.
+    pub(in crate::amf) fn from_error(error: &std::io::Error) -> Option<Self> {
+        let inner = error.get_ref()?;
+        if let Some(failure) = inner.downcast_ref::<Self>() {
+            return Some(failure.clone());
+        }
+
+        match inner.downcast_ref::<AusfError>()? {
+            AusfError::UserNotFound(id) => Some(Self::IllegalUe(format!("Unknown UE: {}", id))),
+            AusfError::ServingNetworkNotAuthorized(name) => Some(Self::PlmnNotAllowed(format!(
+                "Serving Network Not Authorized: {}",
+                name
+            ))),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for RegistrationFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IllegalUe(reason) => write!(f, "Illegal UE: {}", reason),
+            Self::UeIdentityCannotBeDerived(identity) => {
+                write!(f, "UE Identity Cannot Be Derived: {}", identity)
+            }
+            Self::PlmnNotAllowed(reason) => write!(f, "PLMN Not Allowed: {}", reason),
+            Self::TaNotAllowed(tac) => write!(f, "TA Not Allowed: TAC: {}", tac),
+            Self::Congestion(back_off) => write!(f, "Congestion: Back-off: {} seconds", back_off),
+            Self::UeSecurityCapabilitiesMismatch(reason) => {
+                write!(f, "UE Security Capabilities Mismatch: {}", reason)
+            }
+            Self::NoNetworkSlicesAvailable => write!(f, "No Network Slices Available"),
+            Self::InvalidMessage(error) => write!(f, "Invalid Message: {}", error),
+            Self::NetworkFailure(reason) => write!(f, "Network Failure: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for RegistrationFailure {}
+
+impl From<RegistrationFailure> for std::io::Error {
+    fn from(failure: RegistrationFailure) -> Self {
+        std::io::Error::new(std::io::ErrorKind::Other, failure)
+    }
+}
+
+// GPRS Timer 2 value for the given number of seconds, in the largest unit needed for the value.
+// Values larger than the maximum value (31 decihours) are encoded as the maximum value. (Section
+// 10.5.7.4 24.008)
+fn gprs_timer2_value(seconds: u32) -> u8 {
+    let (unit, value) = if seconds <= 31 * 2 {
+        (GprsTimerUnit::TwoSeconds, seconds / 2)
+    } else if seconds <= 31 * 60 {
+        (GprsTimerUnit::OneMinute, seconds / 60)
+    } else {
+        (GprsTimerUnit::Decihours, std::cmp::min(seconds / 360, 31))
+    };
+
+    (unit as u8) << 5 | value as u8
+}
+
+impl AmfUe {
+    // Reject the Registration of the UE and abort the ongoing procedures. The Registration Reject
+    // is integrity protected and ciphered if the NAS Security Context is established, else it is
+    // sent without the protection. (Section 4.4.4.2 and 5.5.1.2.5 24.501)
+    pub(in crate::amf) async fn reject_registration(
+        &mut self,
+        failure: RegistrationFailure,
+        context: &NasContext,
+    ) -> std::io::Result<()> {
+        log::warn!(
+            "Registration of the UE with AMF_UE_NGAP_ID: {} rejected: {}",
+            self.amf_ue_ngap_id,
+            failure
+        );
+        self.abort_procedures();
+
+        let t3346 = match failure {
+            RegistrationFailure::Congestion(back_off) => Some(GprsTimer2 {
+                iei: Some(RegistrationReject::T3346_IEI),
+                value: gprs_timer2_value(back_off),
+            }),
+            _ => None,
+        };
+        let reject = RegistrationReject {
+            header: nas_5gmm_header(MM_MSG_TYPE_REGISTRATION_REJECT),
+            cause: FivegmmCause {
+                iei: None,
+                cause: failure.cause(),
+            },
+            t3346,
+            t3502: None,
+            eap_message: None,
+            rejected_nssai: None,
+        };
+
+        self.send_nas_message(Nas5gMmMessage::RegistrationReject(reject), context)
+            .await
+    }
+
+    // Reject the Registration, if the procedures during the Registration failed for a reason to
+    // be indicated to the UE. Other errors (eg. the AUSF not reachable or an invalid response
+    // from the AUSF) leave the Registration stalled, if no procedure is waiting for the UE, the
+    // Registration is rejected with a temporary cause then. Else (eg. an unexpected message from
+    // the UE) the error is returned as is and the ongoing procedure continues.
+    pub(super) async fn reject_registration_on_failure(
+        &mut self,
+        result: std::io::Result<()>,
+        context: &NasContext,
+    ) -> std::io::Result<()> {
+        match result {
+            Err(error) if !self.registered => match RegistrationFailure::from_error(&error) {
+                Some(failure) => self.reject_registration(failure, context).await,
+                None if self.registration_request.is_some() && !self.procedure_ongoing() => {
+                    let failure = RegistrationFailure::NetworkFailure(error.to_string());
+                    self.reject_registration(failure, context).await
+                }
+                None => Err(error),
+            },
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::sync::mpsc::Receiver;
+
+    use ngap::messages::r17::NAS_PDU;
+
+    use crate::amf::messages::NasToAmfMessage;
+    use crate::amf::nas::tests_util::{
+        received_message, setup, setup_with_config, CONFIG, REGISTRATION_REQUEST,
+    };
+
+    // Handle the Registration Request and return the 5GMM Cause of the Registration Reject sent
+    // without the protection.
+    fn rejected_cause(
+        ue: &mut AmfUe,
+        context: &NasContext,
+        rx: &mut Receiver<NasToAmfMessage>,
+        request: &str,
+    ) -> u8 {
+        let pdu = NAS_PDU(hex::decode(request).unwrap());
+        assert!(futures::executor::block_on(ue.handle_initial_nas_message(pdu, context)).is_ok());
+
+        match received_message(rx) {
+            Nas5gMmMessage::RegistrationReject(reject) => {
+                assert!(reject.t3346.is_none());
+                reject.cause.cause
+            }
+            message => panic!("Unexpected Message: {:?}", message),
+        }
+    }
+
+    #[test]
+    fn test_gprs_timer2_value() {
+        assert_eq!(gprs_timer2_value(10), 0x05);
+        assert_eq!(gprs_timer2_value(62), 0x1f);
+        assert_eq!(gprs_timer2_value(60 * 5), 0x25);
+        assert_eq!(gprs_timer2_value(60 * 60), 0x4a);
+        assert_eq!(gprs_timer2_value(24 * 60 * 60), 0x5f);
+    }
+
+    #[test]
+    fn test_from_error() {
+        let error = RegistrationFailure::TaNotAllowed(3).into();
+        assert_eq!(
+            RegistrationFailure::from_error(&error),
+            Some(RegistrationFailure::TaNotAllowed(3))
+        );
+
+        let error = AusfError::UserNotFound("imsi-901700000000002".to_string()).into();
+        assert_eq!(
+            RegistrationFailure::from_error(&error).map(|failure| failure.cause()),
+            Some(CAUSE_ILLEGAL_UE)
+        );
+
+        let error = AusfError::ServingNetworkNotAuthorized("5G:mnc070.mcc901".to_string()).into();
+        assert_eq!(
+            RegistrationFailure::from_error(&error).map(|failure| failure.cause()),
+            Some(CAUSE_PLMN_NOT_ALLOWED)
+        );
+
+        let error = std::io::Error::new(std::io::ErrorKind::Other, "AUSF not reachable.");
+        assert_eq!(RegistrationFailure::from_error(&error), None);
+    }
+
+    #[test]
+    fn test_unknown_ue() {
+        let config = CONFIG.replace("imsi-901700000000001", "imsi-901700000000002");
+        let (mut ue, context, mut rx) = setup_with_config(&config);
+
+        let cause = rejected_cause(&mut ue, &context, &mut rx, REGISTRATION_REQUEST);
+        assert_eq!(cause, CAUSE_ILLEGAL_UE);
+        assert!(ue.timer_expires_at().is_none());
+    }
+
+    #[test]
+    fn test_plmn_not_allowed() {
+        let (mut ue, context, mut rx) = setup();
+
+        // SUCI with the Home Network `901-71`.
+        let request = REGISTRATION_REQUEST.replace("09f107", "09f117");
+        let cause = rejected_cause(&mut ue, &context, &mut rx, &request);
+        assert_eq!(cause, CAUSE_PLMN_NOT_ALLOWED);
+    }
+
+    #[test]
+    fn test_no_ausf_configured() {
+        let config = &CONFIG[..CONFIG.find("\nausf:").unwrap()];
+        let (mut ue, context, mut rx) = setup_with_config(config);
+
+        let cause = rejected_cause(&mut ue, &context, &mut rx, REGISTRATION_REQUEST);
+        assert_eq!(cause, CAUSE_PROTOCOL_ERROR_UNSPECIFIED);
+    }
+
+    #[test]
+    fn test_ta_not_allowed() {
+        let (mut ue, context, mut rx) = setup();
+        ue.tac = Some(3);
+
+        let cause = rejected_cause(&mut ue, &context, &mut rx, REGISTRATION_REQUEST);
+        assert_eq!(cause, CAUSE_TA_NOT_ALLOWED);
+    }
+
+    #[test]
+    fn test_ue_identity_cannot_be_derived() {
+        let (mut ue, context, mut rx) = setup();
+
+        // Registration Request with an unknown 5G-GUTI.
+        let request = "7e004179000bf209f107010041000000012e04f0f0f0f0";
+        let cause = rejected_cause(&mut ue, &context, &mut rx, request);
+        assert_eq!(cause, CAUSE_UE_IDENTITY_CANNOT_BE_DERIVED);
+    }
+
+    #[test]
+    fn test_congestion() {
+        let (mut ue, context, mut rx) = setup();
+
+        let failure = RegistrationFailure::Congestion(60);
+        assert!(futures::executor::block_on(ue.reject_registration(failure, &context)).is_ok());
+
+        match received_message(&mut rx) {
+            Nas5gMmMessage::RegistrationReject(reject) => {
+                assert_eq!(reject.cause.cause, CAUSE_CONGESTION);
+                assert_eq!(reject.t3346.unwrap().value, 0x1e);
+            }
+            message => panic!("Unexpected Message: {:?}", message),
+        }
+    }
+
+    #[test]
+    fn test_registered_ue_not_rejected() {
+        let (mut ue, context, mut rx) = setup();
+        ue.registered = true;
+
+        let result = Err(RegistrationFailure::NoNetworkSlicesAvailable.into());
+        let result =
+            futures::executor::block_on(ue.reject_registration_on_failure(result, &context));
+        assert!(result.is_err());
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -0,0 +1,496 @@
+//! NAS Security Mode Control Procedure
+//!
+//! Section 5.4.2 24.501
+//!
+//! Also: Section 6.7.2 33.501 Release 17.
+
+use ngap::messages::r17::NAS_PDU;
+
+use nas::messages::headers::Nas5gSecurityHeader;
+use nas::messages::ies::{
+    Additional5gSecurityInformation, ImeisvRequest, MobileIdentity, NasKeySetId,
+    NasSecurityAlgorithms, SecurityContextType, UeSecurityCapability,
+};
+use nas::messages::{
+    Nas5gMmMessage, SecurityModeCommand, SecurityModeComplete, SecurityModeReject,
+    MM_MSG_TYPE_SECURITY_MODE_COMMAND,
+};
+use nas::security::{select_nas_algorithms, NasAccessType, NasSecurityContext};
+use nas::{error::CAUSE_SEMANTICALLY_INCORRECT_MESSAGE, NasError};
+
+use crate::amf::nas::nas_manager::NasContext;
+use crate::amf::nas::timer::NasTimerId;
+
+use super::{nas_5gmm_header, AmfUe, RegistrationFailure};
+
+// IMEISV Request value: IMEISV requested. (Section 9.11.3.28 24.501)
+const IMEISV_REQUESTED: u8 = 0x01;
+
+/// State of an ongoing Security Mode Control procedure: The new Security Context is taken into
+/// use only after the Security Mode Complete is received.
+#[derive(Debug, Clone)]
+pub(in crate::amf) struct SecurityModeControl {
+    pub(super) security_context: NasSecurityContext,
+    command: SecurityModeCommand,
+}
+
+impl AmfUe {
+    // Activate the NAS Security Context created by the Primary Authentication with the NAS
+    // Security Algorithms selected for the UE. (Section 5.4.2.2 24.501)
+    pub(super) async fn security_mode_control_procedure(
+        &mut self,
+        context: &NasContext,
+    ) -> std::io::Result<()> {
+        let partial_security_context = self.partial_security_context.as_ref().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "Security Mode Control without an Authenticated Security Context.",
+            )
+        })?;
+
+        let ue_sec_capability = self
+            .registration_request
+            .as_ref()
+            .and_then(|request| request.ue_sec_capability.clone())
+            .ok_or_else(|| {
+                RegistrationFailure::InvalidMessage(NasError::SemanticallyIncorrect {
+                    cause: CAUSE_SEMANTICALLY_INCORRECT_MESSAGE,
+                    reason: "UE Security Capability not received from the UE.".to_string(),
+                })
+            })?;
+
+        let nas_security = &context.config.nas_security;
+        let (encryption_algo, integrity_algo) = select_nas_algorithms(
+            &ue_sec_capability,
+            &nas_security.encryption_algorithms,
+            &nas_security.integrity_algorithms,
+        )
+        .ok_or_else(|| {
+            RegistrationFailure::UeSecurityCapabilitiesMismatch(format!(
+                "No configured NAS Security Algorithms supported by the UE: {:?}",
+                ue_sec_capability
+            ))
+        })?;
+
+        let security_context = NasSecurityContext::new(
+            partial_security_context.ngksi,
+            partial_security_context.kamf,
+            encryption_algo,
+            integrity_algo,
+            NasAccessType::ThreeGpp,
+        );
+
+        // The UE Security Capability is replayed and the IMEISV is requested. The complete
+        // Initial NAS Message is requested, since it was received without integrity protection.
+        let command = SecurityModeCommand {
+            header: nas_5gmm_header(MM_MSG_TYPE_SECURITY_MODE_COMMAND),
+            selected_nas_security_algorithms: NasSecurityAlgorithms {
+                iei: None,
+                ciphering: encryption_algo as u8,
+                integrity: integrity_algo as u8,
+            },
+            ngksi: NasKeySetId {
+                iei: None,
+                sec_context: SecurityContextType::Native,
+                identifier: partial_security_context.ngksi,
+            },
+            replayed_ue_security_capability: UeSecurityCapability {
+                iei: None,
+                ..ue_sec_capability
+            },
+            imeisv_request: Some(ImeisvRequest {
+                iei: Some(SecurityModeCommand::IMEISV_REQUEST_IEI),
+                value: IMEISV_REQUESTED,
+            }),
+            additional_5g_security_information: Some(Additional5gSecurityInformation {
+                iei: Some(SecurityModeCommand::ADDITIONAL_5G_SECURITY_INFORMATION_IEI),
+                rinmr: true,
+                hdp: false,
+            }),
+            eap_message: None,
+            abba: None,
+        };
+
+        log::debug!(
+            "Selected NAS Security Algorithms: {:?}, {:?}",
+            encryption_algo,
+            integrity_algo
+        );
+        self.security_mode_control = Some(SecurityModeControl {
+            security_context,
+            command,
+        });
+
+        self.send_security_mode_command(context).await
+    }
+
+    async fn send_security_mode_command(&mut self, context: &NasContext) -> std::io::Result<()> {
+        self.start_timer(NasTimerId::T3560);
+        self.resend_security_mode_command(context).await
+    }
+
+    // Send the Security Mode Command integrity protected with the new Security Context. Also
+    // used for the retransmission on the expiry of T3560. (Section 5.4.2.7 24.501)
+    pub(super) async fn resend_security_mode_command(
+        &mut self,
+        context: &NasContext,
+    ) -> std::io::Result<()> {
+        let security_mode_control = self.security_mode_control.as_mut().unwrap();
+
+        let message =
+            Nas5gMmMessage::SecurityModeCommand(security_mode_control.command.clone()).encode();
+        let pdu = security_mode_control.security_context.protect(
+            &message,
+            Nas5gSecurityHeader::IntegrityProtectedSecurityModeCommand,
+        )?;
+
+        context
+            .send_nas_pdu(self.amf_ue_ngap_id, NAS_PDU(pdu))
+            .await
+    }
+
+    // The new Security Context is used from now on. The complete Registration Request in the NAS
+    // Message Container replaces the one received in the Initial NAS Message. (Section 5.4.2.3
+    // 24.501)
+    pub(super) async fn handle_security_mode_complete(
+        &mut self,
+        complete: SecurityModeComplete,
+        context: &NasContext,
+    ) -> std::io::Result<()> {
+        let security_mode_control = self.security_mode_control.take().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Security Mode Complete without an ongoing Security Mode Control.",
+            )
+        })?;
+        self.stop_timer();
+
+        self.security_context = Some(security_mode_control.security_context);
+        self.partial_security_context = None;
+
+        if let Some(MobileIdentity::Imeisv(imeisv)) = complete.imeisv.map(|imeisv| imeisv.identity)
+        {
+            self.pei = Some(format!("imeisv-{}", imeisv));
+        }
+
+        // The Registration is rejected (with the protection) for an invalid NAS Message
+        // Container, since the Registration cannot proceed without the Registration Request.
+        if let Some(container) = complete.nas_message_container {
+            match Nas5gMmMessage::decode(&container.value)
+                .map_err(RegistrationFailure::InvalidMessage)?
+            {
+                Nas5gMmMessage::RegistrationRequest(reg_request) => {
+                    self.registration_request = Some(*reg_request);
+                }
+                message => {
+                    return Err(RegistrationFailure::InvalidMessage(
+                        NasError::SemanticallyIncorrect {
+                            cause: CAUSE_SEMANTICALLY_INCORRECT_MESSAGE,
+                            reason: format!(
+                                "Unexpected NAS Message in the Container: {:?}",
+                                message
+                            ),
+                        },
+                    )
+                    .into())
+                }
+            }
+        }
+
+        log::info!(
+            "NAS Security activated for the UE with AMF_UE_NGAP_ID: {}",
+            self.amf_ue_ngap_id
+        );
+
+        self.accept_registration(context).await
+    }
+
+    // The UE rejected the Security Mode Command: The procedure that initiated the Security Mode
+    // Control is aborted. (Section 5.4.2.5 24.501)
+    pub(super) async fn handle_security_mode_reject(
+        &mut self,
+        reject: SecurityModeReject,
+        _context: &NasContext,
+    ) -> std::io::Result<()> {
+        if self.security_mode_control.take().is_none() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Security Mode Reject without an ongoing Security Mode Control.",
+            ));
+        }
+        self.stop_timer();
+
+        log::warn!(
+            "Security Mode Reject from the UE with AMF_UE_NGAP_ID: {}, Cause: {}",
+            self.amf_ue_ngap_id,
+            reject.cause.cause
+        );
+        self.partial_security_context = None;
+        self.registration_request = None;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::sync::mpsc::Receiver;
+
+    use nas::messages::ies::{FivegmmCause, FivegsMobileIdentity, Imeisv, NasMessageContainer};
+    use nas::messages::{
+        RegistrationRequest, MM_MSG_TYPE_SECURITY_MODE_COMPLETE, MM_MSG_TYPE_SECURITY_MODE_REJECT,
+    };
+    use nas::security::{NasEncryptionAlgoIdentity, NasIntegrityAlgoIdentity};
+
+    use crate::amf::messages::NasToAmfMessage;
+    use crate::amf::nas::amf_ue::PartialSecurityContext;
+    use crate::amf::nas::tests_util::{
+        nas_context, received_pdu, received_security_mode_command, CONFIG, REGISTRATION_REQUEST,
+    };
+
+    const KAMF: [u8; 32] = [0x5a; 32];
+
+    fn registration_request() -> RegistrationRequest {
+        match Nas5gMmMessage::decode(&hex::decode(REGISTRATION_REQUEST).unwrap()).unwrap() {
+            Nas5gMmMessage::RegistrationRequest(request) => *request,
+            message => panic!("Unexpected Message: {:?}", message),
+        }
+    }
+
+    // An authenticated UE, with the Security Mode Command sent.
+    fn setup(config: &str) -> (AmfUe, NasContext, Receiver<NasToAmfMessage>) {
+        let (context, rx) = nas_context(config);
+
+        let mut ue = AmfUe::new_amf_ue(1);
+        ue.registration_request = Some(registration_request());
+        ue.partial_security_context = Some(PartialSecurityContext {
+            ngksi: 1,
+            kamf: KAMF,
+        });
+        assert!(futures::executor::block_on(ue.security_mode_control_procedure(&context)).is_ok());
+
+        (ue, context, rx)
+    }
+
+    #[test]
+    fn test_security_mode_complete() {
+        let (mut ue, context, mut rx) = setup(CONFIG);
+
+        let (mut ue_security_context, command) = received_security_mode_command(&mut rx, KAMF);
+        assert_eq!(
+            command.selected_nas_security_algorithms.ciphering,
+            NasEncryptionAlgoIdentity::Nea2 as u8
+        );
+        assert_eq!(
+            command.selected_nas_security_algorithms.integrity,
+            NasIntegrityAlgoIdentity::Nia2 as u8
+        );
+        assert_eq!(command.ngksi.identifier, 1);
+        assert_eq!(
+            command.replayed_ue_security_capability,
+            UeSecurityCapability {
+                iei: None,
+                ..registration_request().ue_sec_capability.unwrap()
+            }
+        );
+        assert_eq!(
+            command.imeisv_request.as_ref().unwrap().value,
+            IMEISV_REQUESTED
+        );
+        assert!(
+            command
+                .additional_5g_security_information
+                .as_ref()
+                .unwrap()
+                .rinmr
+        );
+        assert_eq!(ue.timer.as_ref().unwrap().id, NasTimerId::T3560);
+
+        let complete = SecurityModeComplete {
+            header: nas_5gmm_header(MM_MSG_TYPE_SECURITY_MODE_COMPLETE),
+            imeisv: Some(FivegsMobileIdentity {
+                iei: Some(SecurityModeComplete::IMEISV_IEI),
+                length: 9,
+                identity: MobileIdentity::Imeisv(Imeisv::new("4370816125816151").unwrap()),
+            }),
+            nas_message_container: Some(NasMessageContainer {
+                iei: Some(SecurityModeComplete::NAS_MESSAGE_CONTAINER_IEI),
+                value: hex::decode(REGISTRATION_REQUEST).unwrap(),
+            }),
+            non_imeisv_pei: None,
+        };
+        let pdu = ue_security_context.protect(
+            Nas5gMmMessage::SecurityModeComplete(complete),
+            Nas5gSecurityHeader::IntegrityProtectedSecurityModeComplete,
+        );
+        assert!(futures::executor::block_on(ue.handle_nas_message(pdu, &context)).is_ok());
+
+        let security_context = ue.security_context.as_ref().unwrap();
+        assert_eq!(security_context.ngksi(), 1);
+        assert_eq!(
+            security_context.encryption_algo(),
+            NasEncryptionAlgoIdentity::Nea2
+        );
+        assert_eq!(ue.pei.as_deref(), Some("imeisv-4370816125816151"));
+        assert_eq!(ue.registration_request, Some(registration_request()));
+        assert!(ue.security_mode_control.is_none());
+        assert!(ue.partial_security_context.is_none());
+
+        // The Registration is accepted, protected with the new Security Context.
+        let NasToAmfMessage::NasPduMessage(message) = rx.try_recv().unwrap();
+        assert_eq!(
+            message.pdu.0[1],
+            Nas5gSecurityHeader::IntegrityProtectedAndCiphered as u8
+        );
+        assert!(ue.registration_accept.is_some());
+        assert_eq!(ue.timer.as_ref().unwrap().id, NasTimerId::T3550);
+    }
+
+    #[test]
+    fn test_security_mode_complete_invalid_container() {
+        let security_mode_complete = Nas5gMmMessage::SecurityModeComplete(SecurityModeComplete {
+            header: nas_5gmm_header(MM_MSG_TYPE_SECURITY_MODE_COMPLETE),
+            imeisv: None,
+            nas_message_container: None,
+            non_imeisv_pei: None,
+        });
+
+        // A NAS Message that cannot be decoded and a NAS Message other than the Registration
+        // Request.
+        for (container, cause) in [
+            (vec![0x7e, 0x00, 0x41], None),
+            (
+                security_mode_complete.encode(),
+                Some(CAUSE_SEMANTICALLY_INCORRECT_MESSAGE),
+            ),
+        ] {
+            let (mut ue, context, mut rx) = setup(CONFIG);
+            let (mut ue_security_context, _) = received_security_mode_command(&mut rx, KAMF);
+
+            let complete = SecurityModeComplete {
+                header: nas_5gmm_header(MM_MSG_TYPE_SECURITY_MODE_COMPLETE),
+                imeisv: None,
+                nas_message_container: Some(NasMessageContainer {
+                    iei: Some(SecurityModeComplete::NAS_MESSAGE_CONTAINER_IEI),
+                    value: container,
+                }),
+                non_imeisv_pei: None,
+            };
+            let pdu = ue_security_context.protect(
+                Nas5gMmMessage::SecurityModeComplete(complete),
+                Nas5gSecurityHeader::IntegrityProtectedSecurityModeComplete,
+            );
+            assert!(futures::executor::block_on(ue.handle_nas_message(pdu, &context)).is_ok());
+
+            // The Registration Reject is protected with the new Security Context.
+            match ue_security_context.unprotect(&received_pdu(&mut rx)) {
+                Nas5gMmMessage::RegistrationReject(reject) => {
+                    if let Some(cause) = cause {
+                        assert_eq!(reject.cause.cause, cause);
+                    }
+                }
+                message => panic!("Unexpected Message: {:?}", message),
+            }
+            assert!(ue.registration_accept.is_none());
+            assert!(!ue.is_served());
+        }
+    }
+
+    #[test]
+    fn test_security_mode_complete_invalid_mac() {
+        let (mut ue, context, mut rx) = setup(CONFIG);
+        let (mut ue_security_context, _) = received_security_mode_command(&mut rx, KAMF);
+
+        let complete = SecurityModeComplete {
+            header: nas_5gmm_header(MM_MSG_TYPE_SECURITY_MODE_COMPLETE),
+            imeisv: None,
+            nas_message_container: None,
+            non_imeisv_pei: None,
+        };
+        let NAS_PDU(mut pdu) = ue_security_context.protect(
+            Nas5gMmMessage::SecurityModeComplete(complete),
+            Nas5gSecurityHeader::IntegrityProtectedSecurityModeComplete,
+        );
+        pdu[2] ^= 0x01;
+        assert!(
+            futures::executor::block_on(ue.handle_nas_message(NAS_PDU(pdu), &context)).is_err()
+        );
+
+        // The Security Mode Control is still ongoing.
+        assert!(ue.security_context.is_none());
+        assert!(ue.security_mode_control.is_some());
+    }
+
+    #[test]
+    fn test_security_mode_reject() {
+        let (mut ue, context, mut rx) = setup(CONFIG);
+        received_security_mode_command(&mut rx, KAMF);
+
+        // Security Mode Reject is sent without integrity protection.
+        let reject = SecurityModeReject {
+            header: nas_5gmm_header(MM_MSG_TYPE_SECURITY_MODE_REJECT),
+            cause: FivegmmCause {
+                iei: None,
+                cause: 24,
+            },
+        };
+        let pdu = NAS_PDU(Nas5gMmMessage::SecurityModeReject(reject).encode());
+        assert!(futures::executor::block_on(ue.handle_nas_message(pdu.clone(), &context)).is_ok());
+
+        assert!(ue.security_context.is_none());
+        assert!(ue.security_mode_control.is_none());
+        assert!(ue.partial_security_context.is_none());
+        assert!(ue.registration_request.is_none());
+        assert!(ue.timer.is_none());
+
+        // Not expected anymore.
+        assert!(futures::executor::block_on(ue.handle_nas_message(pdu, &context)).is_err());
+    }
+
+    #[test]
+    fn test_no_common_nas_algorithms() {
+        let config = format!(
+            "{}\nnas_security:\n encryption_algorithms: [ NEA2 ]\n integrity_algorithms: [ NIA2 ]",
+            CONFIG
+        );
+        let (context, mut rx) = nas_context(&config);
+
+        // UE Security Capability: 5G-EA0 and 5G-IA0 only.
+        let mut ue = AmfUe::new_amf_ue(1);
+        let mut request = registration_request();
+        request.ue_sec_capability.as_mut().unwrap().fiveg_ea = 0x80;
+        request.ue_sec_capability.as_mut().unwrap().fiveg_ia = 0x80;
+        ue.registration_request = Some(request);
+        ue.partial_security_context = Some(PartialSecurityContext {
+            ngksi: 1,
+            kamf: KAMF,
+        });
+
+        assert!(futures::executor::block_on(ue.security_mode_control_procedure(&context)).is_err());
+        assert!(ue.security_mode_control.is_none());
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_security_mode_command_retransmission() {
+        let (mut ue, context, mut rx) = setup(CONFIG);
+        let (_, command) = received_security_mode_command(&mut rx, KAMF);
+
+        // The same Security Mode Command is retransmitted on each expiry of T3560, the NAS COUNT
+        // is incremented for each retransmission.
+        for expirations in 1..=4 {
+            assert!(futures::executor::block_on(ue.handle_timer_expiry(&context)).is_ok());
+            assert_eq!(received_security_mode_command(&mut rx, KAMF).1, command);
+            assert_eq!(ue.timer.as_ref().unwrap().expirations, expirations);
+        }
+
+        // Aborted on the fifth expiry.
+        assert!(futures::executor::block_on(ue.handle_timer_expiry(&context)).is_ok());
+        assert!(rx.try_recv().is_err());
+        assert!(ue.security_mode_control.is_none());
+        assert!(ue.partial_security_context.is_none());
+        assert!(ue.timer.is_none());
+    }
+}
@@ -1,2 +1,7 @@
 pub(in crate::amf) mod amf_ue;
 pub(in crate::amf) mod nas_manager;
+pub(in crate::amf) mod timer;
+pub(in crate::amf) mod tmsi;
+
+#[cfg(test)]
+mod tests_util;
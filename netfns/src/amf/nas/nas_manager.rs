@@ -4,26 +4,88 @@
 //! UEs.
 
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::time::{sleep_until, Instant};
 
-use nas::messages::headers::ExtProtoDiscriminator;
+use ngap::messages::r17::NAS_PDU;
 
+use crate::amf::ausf::{ausfs_from_config, Ausf};
 use crate::amf::config::AmfConfig;
 use crate::amf::messages::{AmfToNasMessage, NasPduMessage, NasToAmfMessage};
 
-use super::amf_ue::AmfUe;
+use super::amf_ue::{AmfUe, RegistrationFailure};
+use super::tmsi::TmsiAllocator;
+
+// `NasContext`: Configuration and the Network Functions used by the NAS procedures of all the
+// UEs, along with the 5G-TMSIs allocated to the UEs. Downlink NAS PDUs are sent to the `Amf`
+// using the `nas_to_amf_tx`.
+#[derive(Debug, Clone)]
+pub(in crate::amf) struct NasContext {
+    pub(in crate::amf) config: AmfConfig,
+    pub(in crate::amf) ausfs: Vec<Arc<dyn Ausf>>,
+    pub(in crate::amf) tmsi_allocator: Arc<Mutex<TmsiAllocator>>,
+    pub(in crate::amf) nas_to_amf_tx: Option<Sender<NasToAmfMessage>>,
+}
+
+impl NasContext {
+    pub(in crate::amf) fn from_config(config: AmfConfig) -> Self {
+        Self {
+            ausfs: ausfs_from_config(&config),
+            tmsi_allocator: Arc::new(Mutex::new(TmsiAllocator::new(&config.amf_id))),
+            config,
+            nas_to_amf_tx: None,
+        }
+    }
+
+    pub(in crate::amf) fn allocate_tmsi(&self) -> std::io::Result<u32> {
+        self.tmsi_allocator
+            .lock()
+            .unwrap()
+            .allocate()
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::Other, "No 5G-TMSI available."))
+    }
+
+    pub(in crate::amf) fn release_tmsi(&self, tmsi: u32) {
+        self.tmsi_allocator.lock().unwrap().release(tmsi);
+    }
+
+    // Send the Downlink NAS PDU for the UE identified by the `amf_ue_ngap_id`.
+    pub(in crate::amf) async fn send_nas_pdu(
+        &self,
+        amf_ue_ngap_id: u64,
+        pdu: NAS_PDU,
+    ) -> std::io::Result<()> {
+        let message = NasToAmfMessage::NasPduMessage(NasPduMessage {
+            id: amf_ue_ngap_id,
+            pdu,
+            initial_ue: false,
+            tac: None,
+        });
+
+        let tx = self.nas_to_amf_tx.as_ref().unwrap();
+        if let Err(e) = tx.send(message).await {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("Error Tx: `NasMgr` -> `Amf`:{}", e),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub(in crate::amf) struct NasManager {
-    pub(crate) config: AmfConfig,
+    pub(crate) context: NasContext,
     pub(crate) amf_ues: HashMap<u64, AmfUe>,
 }
 
 impl NasManager {
     pub(in crate::amf) fn from_config(config: AmfConfig) -> std::io::Result<Self> {
         Ok(Self {
-            config,
+            context: NasContext::from_config(config),
             amf_ues: HashMap::new(),
         })
     }
@@ -31,9 +93,18 @@ impl NasManager {
     pub(in crate::amf) async fn run(
         mut self,
         mut amf_to_nas_rx: Receiver<AmfToNasMessage>,
-        _nas_to_amf_tx: Sender<NasToAmfMessage>,
+        nas_to_amf_tx: Sender<NasToAmfMessage>,
     ) -> std::io::Result<()> {
+        let _ = self.context.nas_to_amf_tx.replace(nas_to_amf_tx);
+
         loop {
+            // Earliest expiry of the running NAS Timers of all the UEs.
+            let next_expiry = self
+                .amf_ues
+                .values()
+                .filter_map(AmfUe::timer_expires_at)
+                .min();
+
             tokio::select! {
                 Some(msg) = amf_to_nas_rx.recv() => {
                     match msg {
@@ -44,10 +115,16 @@ impl NasManager {
                         AmfToNasMessage::NasPduMessage(msg) => {
                             // First Octet is Extended Protocol Identity, Use it to call
                             // appropriate function to decode (and handle) the rest of the message.
-                            self.handle_nas_message(msg)?;
+                            if let Err(e) = self.handle_nas_message(msg).await {
+                                log::error!("Error Processing NAS Message: {}", e);
+                            }
                         }
                     }
                 }
+                _ = sleep_until(next_expiry.unwrap_or_else(Instant::now)),
+                    if next_expiry.is_some() => {
+                    self.handle_timer_expiries().await;
+                }
             }
         }
 
@@ -55,9 +132,24 @@ impl NasManager {
         Ok(())
     }
 
+    // Handle the expiry of the NAS Timers of all the UEs whose timers have expired.
+    async fn handle_timer_expiries(&mut self) {
+        let now = Instant::now();
+        for amf_ue in self.amf_ues.values_mut() {
+            if amf_ue
+                .timer_expires_at()
+                .is_some_and(|expires_at| expires_at <= now)
+            {
+                if let Err(e) = amf_ue.handle_timer_expiry(&self.context).await {
+                    log::error!("Error Handling NAS Timer Expiry: {}", e);
+                }
+            }
+        }
+    }
+
     // Decode the received NAS Message. The received NAS message may be a plain-text message or an
     // integrity protected and/or ciphered message.
-    fn handle_nas_message(&mut self, msg: NasPduMessage) -> std::io::Result<()> {
+    async fn handle_nas_message(&mut self, msg: NasPduMessage) -> std::io::Result<()> {
         if msg.initial_ue {
             // First get the `AmfUe` for the given `id`.
             let amf_ue = self.amf_ues.get(&msg.id);
@@ -65,9 +157,26 @@ impl NasManager {
                 // initial UE Message and we still have an `AmfUe` Entry somewhere? Right now just
                 // log a warning and remove this entry!
                 log::warn!("Initial UE Message and exisitng `AmfUe`. Deleting it...");
-                let _ = self.amf_ues.remove_entry(&msg.id);
+                if let Some((_, amf_ue)) = self.amf_ues.remove_entry(&msg.id) {
+                    amf_ue.release(&self.context);
+                }
+            }
+
+            let mut amf_ue = AmfUe::new_amf_ue(msg.id);
+            amf_ue.tac = msg.tac;
+
+            // NAS level Mobility Management Congestion Control: The Registration of the UE is
+            // rejected, when the AMF is serving the configured maximum number of UEs. (Only the
+            // Registration Request is handled as the Initial NAS Message.) (Section 5.3.9 24.501)
+            if let Some(congestion) = &self.context.config.congestion {
+                let served_ues = self.amf_ues.values().filter(|ue| ue.is_served()).count();
+                if served_ues >= congestion.max_ues {
+                    let failure = RegistrationFailure::Congestion(congestion.back_off);
+                    return amf_ue.reject_registration(failure, &self.context).await;
+                }
             }
-            self.amf_ues.insert(msg.id, AmfUe::new_amf_ue(msg.id));
+
+            self.amf_ues.insert(msg.id, amf_ue);
         };
         // Get the AMF UE corresponding to the `amf_ngap_ue_id`.
         let amf_ue = self.amf_ues.get_mut(&msg.id);
@@ -81,12 +190,124 @@ impl NasManager {
             return Err(std::io::Error::new(std::io::ErrorKind::Other, err_string));
         }
 
-        let mut amf_ue = amf_ue.unwrap();
+        let amf_ue = amf_ue.unwrap();
 
         if msg.initial_ue {
-            amf_ue.handle_initial_nas_message(msg.pdu)
+            amf_ue
+                .handle_initial_nas_message(msg.pdu, &self.context)
+                .await
         } else {
-            amf_ue.handle_nas_message(msg.pdu)
+            amf_ue.handle_nas_message(msg.pdu, &self.context).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use nas::messages::Nas5gMmMessage;
+
+    use crate::amf::nas::tests_util::{
+        authentication_response, nas_context, CONFIG, REGISTRATION_REQUEST,
+    };
+
+    // NAS Manager of the AMF serving at the most one UE.
+    fn setup() -> (NasManager, Receiver<NasToAmfMessage>) {
+        let (context, rx) = nas_context(&format!(
+            "{}\ncongestion:\n max_ues: 1\n back_off: 60",
+            CONFIG
+        ));
+        let nas_manager = NasManager {
+            context,
+            amf_ues: HashMap::new(),
+        };
+
+        (nas_manager, rx)
+    }
+
+    fn initial_ue_message(id: u64) -> NasPduMessage {
+        NasPduMessage {
+            id,
+            pdu: NAS_PDU(hex::decode(REGISTRATION_REQUEST).unwrap()),
+            initial_ue: true,
+            tac: Some(1),
         }
     }
+
+    // Handle the NAS Message and return the Downlink NAS Message sent to the UE.
+    fn handle(
+        nas_manager: &mut NasManager,
+        rx: &mut Receiver<NasToAmfMessage>,
+        msg: NasPduMessage,
+    ) -> Nas5gMmMessage {
+        let id = msg.id;
+        assert!(futures::executor::block_on(nas_manager.handle_nas_message(msg)).is_ok());
+
+        let NasToAmfMessage::NasPduMessage(message) = rx.try_recv().unwrap();
+        assert_eq!(message.id, id);
+        Nas5gMmMessage::decode(&message.pdu.0).unwrap()
+    }
+
+    #[test]
+    fn test_congestion() {
+        let (mut nas_manager, mut rx) = setup();
+
+        assert!(matches!(
+            handle(&mut nas_manager, &mut rx, initial_ue_message(1)),
+            Nas5gMmMessage::AuthenticationRequest(_)
+        ));
+
+        // The Registration of the second UE is rejected, with the back-off timer T3346 of 60
+        // seconds.
+        match handle(&mut nas_manager, &mut rx, initial_ue_message(2)) {
+            Nas5gMmMessage::RegistrationReject(reject) => {
+                assert_eq!(reject.cause.cause, 22);
+                assert_eq!(reject.t3346.unwrap().value, 0x1e);
+            }
+            message => panic!("Unexpected Message: {:?}", message),
+        }
+        assert_eq!(nas_manager.amf_ues.len(), 1);
+        assert!(!nas_manager.amf_ues.contains_key(&2));
+
+        // A UE whose Registration is rejected is no longer served.
+        let amf_ue = nas_manager.amf_ues.get_mut(&1).unwrap();
+        let failure = RegistrationFailure::IllegalUe("Authentication Failure".to_string());
+        let result = amf_ue.reject_registration(failure, &nas_manager.context);
+        assert!(futures::executor::block_on(result).is_ok());
+        assert!(rx.try_recv().is_ok());
+
+        assert!(matches!(
+            handle(&mut nas_manager, &mut rx, initial_ue_message(3)),
+            Nas5gMmMessage::AuthenticationRequest(_)
+        ));
+    }
+
+    #[test]
+    fn test_congestion_authentication_failure() {
+        let (mut nas_manager, mut rx) = setup();
+
+        assert!(matches!(
+            handle(&mut nas_manager, &mut rx, initial_ue_message(1)),
+            Nas5gMmMessage::AuthenticationRequest(_)
+        ));
+
+        // Wrong `RES*`: The Authentication of the UE fails.
+        let response = NasPduMessage {
+            id: 1,
+            pdu: authentication_response([0_u8; 16]),
+            initial_ue: false,
+            tac: None,
+        };
+        assert!(matches!(
+            handle(&mut nas_manager, &mut rx, response),
+            Nas5gMmMessage::AuthenticationReject(_)
+        ));
+
+        // The UE that failed the Authentication is no longer served.
+        assert!(matches!(
+            handle(&mut nas_manager, &mut rx, initial_ue_message(2)),
+            Nas5gMmMessage::AuthenticationRequest(_)
+        ));
+    }
 }
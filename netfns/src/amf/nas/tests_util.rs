@@ -0,0 +1,300 @@
+//! Fixtures shared by the tests of the NAS procedures: The AMF configuration, the `NasContext`
+//! and the UE side of the NAS procedures (5G AKA and the NAS Security).
+
+use std::convert::{TryFrom, TryInto};
+
+use tokio::sync::mpsc::{channel, Receiver};
+
+use ngap::messages::r17::NAS_PDU;
+
+use nas::messages::headers::Nas5gSecurityHeader;
+use nas::messages::ies::AuthenticationResponseParameter;
+use nas::messages::{
+    AuthenticationResponse, Nas5gMmMessage, SecurityModeCommand, SecurityModeComplete,
+    MM_MSG_TYPE_AUTHENTICATION_RESPONSE, MM_MSG_TYPE_SECURITY_MODE_COMPLETE,
+};
+use nas::security::{
+    nas_calculate_mac, nas_decrypt_payload, nas_encrypt_payload, nas_encryption_algorithm_key,
+    nas_integrity_algorithm_key, NasEncryptionAlgoIdentity, NasIntegrityAlgoIdentity, NasKey,
+};
+
+use security_3gpp::{
+    derive_kamf, derive_kausf, derive_kseaf, derive_res_star, AuthAlgorithm, Milenage, Rand,
+    ResStar, SecurityKey, Sqn,
+};
+
+use crate::amf::config::AmfConfig;
+use crate::amf::messages::NasToAmfMessage;
+
+use super::amf_ue::{nas_5gmm_header, AmfUe};
+use super::nas_manager::NasContext;
+
+/// AMF serving the TACs 1 and 2 of the PLMN `901-70`, with a single subscriber of the co-located
+/// AUSF. The subscriber is the last entry, so that it's `auth_method` can be appended.
+pub(in crate::amf) const CONFIG: &str = "ngap:\n addrs:\n - 127.0.0.1\nplmn:\n mcc: 901\n mnc: 70\ntacs: [ 1, 2 ]\namf_id:\n pointer: 3\n set: 2\n region: 1\namf_name: taxila-amf\nausf:\n subscribers:\n - supi: imsi-901700000000001\n   k: 465b5ce8b199b49faa5f0a2ee238a6bc\n   opc: cd63cb71954a9f4e48a5994e37a02baf\n   sqn: 000000000020";
+
+/// Registration Request with the SUCI (Null Scheme) for the SUPI `imsi-901700000000001` and the
+/// UE Security Capability: 5G-EA0-7 and 5G-IA0-7.
+pub(in crate::amf) const REGISTRATION_REQUEST: &str =
+    "7e004179000d0109f1070000000000000000102e04f0f0f0f0";
+
+/// The `NasContext` for the `config`, along with the receiver of the Downlink NAS PDUs.
+pub(in crate::amf) fn nas_context(config: &str) -> (NasContext, Receiver<NasToAmfMessage>) {
+    let config: AmfConfig = serde_yaml::from_str(config).unwrap();
+    let (tx, rx) = channel(8);
+    let mut context = NasContext::from_config(config);
+    context.nas_to_amf_tx = Some(tx);
+
+    (context, rx)
+}
+
+/// A new UE with the AMF_UE_NGAP_ID 1, served by the AMF with the [`CONFIG`].
+pub(in crate::amf) fn setup() -> (AmfUe, NasContext, Receiver<NasToAmfMessage>) {
+    setup_with_config(CONFIG)
+}
+
+pub(in crate::amf) fn setup_with_config(
+    config: &str,
+) -> (AmfUe, NasContext, Receiver<NasToAmfMessage>) {
+    let (context, rx) = nas_context(config);
+
+    (AmfUe::new_amf_ue(1), context, rx)
+}
+
+/// MILENAGE Algorithm set of the subscriber.
+pub(in crate::amf) fn milenage() -> Milenage {
+    Milenage::from_opc(
+        hex::decode("465b5ce8b199b49faa5f0a2ee238a6bc")
+            .unwrap()
+            .try_into()
+            .unwrap(),
+        hex::decode("cd63cb71954a9f4e48a5994e37a02baf")
+            .unwrap()
+            .try_into()
+            .unwrap(),
+    )
+}
+
+/// `SQN` of the network from the `AUTN`.
+pub(in crate::amf) fn network_sqn(rand: Rand, autn: &[u8]) -> Sqn {
+    let ak = milenage().f5(rand);
+    let mut sqn = [0_u8; 6];
+    for (i, (s, a)) in autn[..6].iter().zip(ak).enumerate() {
+        sqn[i] = s ^ a;
+    }
+    sqn
+}
+
+/// The 5G AKA as the UE would: Verify the `AUTN` and return the `RES*` along with the `KAMF`.
+pub(in crate::amf) fn ue_5g_aka(
+    rand: Rand,
+    autn: &[u8],
+    context: &NasContext,
+) -> (ResStar, SecurityKey) {
+    let milenage = milenage();
+    let av = milenage.generate_auth_vector(rand, network_sqn(rand, autn), [0x80, 0x00]);
+    assert_eq!(av.autn, autn);
+
+    let sn_name = context.config.plmn.serving_network_name();
+    let res_star = derive_res_star(&av.ck, &av.ik, &sn_name, &rand, &av.xres);
+    let kausf = derive_kausf(&av.ck, &av.ik, &sn_name, &av.autn[..6]);
+    let kamf = derive_kamf(&derive_kseaf(&kausf, &sn_name), "901700000000001", &[0, 0]);
+
+    (res_star, kamf)
+}
+
+/// The Downlink NAS PDU sent to the UE.
+pub(in crate::amf) fn received_pdu(rx: &mut Receiver<NasToAmfMessage>) -> Vec<u8> {
+    let NasToAmfMessage::NasPduMessage(message) = rx.try_recv().unwrap();
+    assert_eq!(message.id, 1);
+    message.pdu.0
+}
+
+/// The Downlink NAS Message sent to the UE without the protection.
+pub(in crate::amf) fn received_message(rx: &mut Receiver<NasToAmfMessage>) -> Nas5gMmMessage {
+    Nas5gMmMessage::decode(&received_pdu(rx)).unwrap()
+}
+
+/// `RAND` and `AUTN` of the Authentication Request for the 5G AKA.
+pub(in crate::amf) fn received_authentication_request(
+    rx: &mut Receiver<NasToAmfMessage>,
+) -> (Rand, Vec<u8>) {
+    match received_message(rx) {
+        Nas5gMmMessage::AuthenticationRequest(request) => {
+            assert_eq!(request.abba.value, vec![0, 0]);
+            (
+                request.rand.unwrap().rand,
+                request.autn.unwrap().autn.to_vec(),
+            )
+        }
+        message => panic!("Unexpected Message: {:?}", message),
+    }
+}
+
+/// Authentication Response with the `RES*` for the 5G AKA.
+pub(in crate::amf) fn authentication_response(res_star: ResStar) -> NAS_PDU {
+    let response = AuthenticationResponse {
+        header: nas_5gmm_header(MM_MSG_TYPE_AUTHENTICATION_RESPONSE),
+        response_parameter: Some(AuthenticationResponseParameter {
+            iei: Some(AuthenticationResponse::RESPONSE_PARAMETER_IEI),
+            res_star,
+        }),
+        eap_message: None,
+    };
+    NAS_PDU(Nas5gMmMessage::AuthenticationResponse(response).encode())
+}
+
+/// The NAS Security Context of the UE, to verify and decipher the Downlink NAS Messages and
+/// protect the Uplink NAS Messages as the UE would.
+pub(in crate::amf) struct UeSecurityContext {
+    k_nas_enc: NasKey,
+    k_nas_int: NasKey,
+    encryption_algo: NasEncryptionAlgoIdentity,
+    integrity_algo: NasIntegrityAlgoIdentity,
+    ul_count: u8,
+}
+
+impl UeSecurityContext {
+    /// Security Context with the NAS Security Algorithms selected in the Security Mode Command.
+    pub(in crate::amf) fn new(kamf: SecurityKey, command: &SecurityModeCommand) -> Self {
+        let algorithms = &command.selected_nas_security_algorithms;
+        let encryption_algo = NasEncryptionAlgoIdentity::try_from(algorithms.ciphering).unwrap();
+        let integrity_algo = NasIntegrityAlgoIdentity::try_from(algorithms.integrity).unwrap();
+        Self {
+            k_nas_enc: nas_encryption_algorithm_key(kamf, encryption_algo),
+            k_nas_int: nas_integrity_algorithm_key(kamf, integrity_algo),
+            encryption_algo,
+            integrity_algo,
+            ul_count: 0,
+        }
+    }
+
+    /// Verify the MAC of the Downlink NAS PDU and decipher it (if ciphered).
+    pub(in crate::amf) fn unprotect(&self, pdu: &[u8]) -> Nas5gMmMessage {
+        let count = pdu[6] as u32;
+        let mac = nas_calculate_mac(
+            self.k_nas_int,
+            self.integrity_algo,
+            count,
+            0,
+            true,
+            &pdu[6..],
+        );
+        assert_eq!(mac, pdu[2..6]);
+
+        let message = if pdu[1] == Nas5gSecurityHeader::IntegrityProtectedAndCiphered as u8 {
+            nas_decrypt_payload(
+                self.k_nas_enc,
+                self.encryption_algo,
+                count,
+                0,
+                true,
+                &pdu[7..],
+                (pdu.len() - 7) as u32 * 8,
+            )
+        } else {
+            pdu[7..].to_vec()
+        };
+        Nas5gMmMessage::decode(&message).unwrap()
+    }
+
+    /// Integrity protect and cipher the Uplink NAS Message.
+    pub(in crate::amf) fn protect(
+        &mut self,
+        message: Nas5gMmMessage,
+        sec_header_type: Nas5gSecurityHeader,
+    ) -> NAS_PDU {
+        let count = self.ul_count;
+        self.ul_count += 1;
+
+        let message = message.encode();
+        let mut pdu = vec![0x7e, sec_header_type as u8, 0, 0, 0, 0, count];
+        pdu.extend(nas_encrypt_payload(
+            self.k_nas_enc,
+            self.encryption_algo,
+            count as u32,
+            0,
+            false,
+            &message,
+            message.len() as u32 * 8,
+        ));
+        let mac = nas_calculate_mac(
+            self.k_nas_int,
+            self.integrity_algo,
+            count as u32,
+            0,
+            false,
+            &pdu[6..],
+        );
+        pdu.splice(2..6, mac);
+
+        NAS_PDU(pdu)
+    }
+}
+
+/// Verify the Security Mode Command (integrity protected with the new Security Context) as the
+/// UE would and return it along with the UE's Security Context.
+pub(in crate::amf) fn received_security_mode_command(
+    rx: &mut Receiver<NasToAmfMessage>,
+    kamf: SecurityKey,
+) -> (UeSecurityContext, SecurityModeCommand) {
+    let pdu = received_pdu(rx);
+    assert_eq!(
+        pdu[1],
+        Nas5gSecurityHeader::IntegrityProtectedSecurityModeCommand as u8
+    );
+
+    let command = match Nas5gMmMessage::decode(&pdu[7..]).unwrap() {
+        Nas5gMmMessage::SecurityModeCommand(command) => command,
+        message => panic!("Unexpected Message: {:?}", message),
+    };
+    let ue_security_context = UeSecurityContext::new(kamf, &command);
+    assert_eq!(
+        ue_security_context.unprotect(&pdu),
+        Nas5gMmMessage::SecurityModeCommand(command.clone())
+    );
+
+    (ue_security_context, command)
+}
+
+/// Authenticate the UE and activate the NAS Security as the UE would, until the Registration
+/// Accept is sent.
+pub(in crate::amf) fn register(
+    ue: &mut AmfUe,
+    context: &NasContext,
+    rx: &mut Receiver<NasToAmfMessage>,
+) -> UeSecurityContext {
+    register_with_request(ue, context, rx, REGISTRATION_REQUEST)
+}
+
+pub(in crate::amf) fn register_with_request(
+    ue: &mut AmfUe,
+    context: &NasContext,
+    rx: &mut Receiver<NasToAmfMessage>,
+    request: &str,
+) -> UeSecurityContext {
+    let pdu = NAS_PDU(hex::decode(request).unwrap());
+    assert!(futures::executor::block_on(ue.handle_initial_nas_message(pdu, context)).is_ok());
+
+    // 5G AKA
+    let (rand, autn) = received_authentication_request(rx);
+    let (res_star, kamf) = ue_5g_aka(rand, &autn, context);
+    let pdu = authentication_response(res_star);
+    assert!(futures::executor::block_on(ue.handle_nas_message(pdu, context)).is_ok());
+
+    // Security Mode Control
+    let (mut ue_security_context, _) = received_security_mode_command(rx, kamf);
+    let complete = SecurityModeComplete {
+        header: nas_5gmm_header(MM_MSG_TYPE_SECURITY_MODE_COMPLETE),
+        imeisv: None,
+        nas_message_container: None,
+        non_imeisv_pei: None,
+    };
+    let pdu = ue_security_context.protect(
+        Nas5gMmMessage::SecurityModeComplete(complete),
+        Nas5gSecurityHeader::IntegrityProtectedSecurityModeComplete,
+    );
+    assert!(futures::executor::block_on(ue.handle_nas_message(pdu, context)).is_ok());
+
+    ue_security_context
+}
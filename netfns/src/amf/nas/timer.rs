@@ -0,0 +1,57 @@
+//! NAS Timers
+//!
+//! The 5GMM Timers on the network side (Table 10.2.2 24.501). A running timer is kept in the
+//! `AmfUe` along with the number of times it has expired, the `NasManager` handles the expiry of
+//! the timers of all the UEs.
+
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+/// The message is retransmitted on the expiry of the timer (eg. T3560), at the most four times.
+/// The procedure is aborted on the fifth expiry. (Section 5.4.1.3.7, 5.4.2.7 and 5.5.1.2.8
+/// 24.501)
+pub(in crate::amf) const MAX_RETRANSMISSIONS: u8 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(in crate::amf) enum NasTimerId {
+    /// Started when the Authentication Request or the Security Mode Command is sent.
+    T3560,
+
+    /// Started when the Registration Accept with a new 5G-GUTI is sent.
+    T3550,
+}
+
+impl NasTimerId {
+    fn duration(&self) -> Duration {
+        match self {
+            Self::T3560 | Self::T3550 => Duration::from_secs(6),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(in crate::amf) struct NasTimer {
+    pub(in crate::amf) id: NasTimerId,
+    pub(in crate::amf) expires_at: Instant,
+    pub(in crate::amf) expirations: u8,
+}
+
+impl NasTimer {
+    pub(in crate::amf) fn start(id: NasTimerId) -> Self {
+        Self {
+            id,
+            expires_at: Instant::now() + id.duration(),
+            expirations: 0,
+        }
+    }
+
+    /// Restart the timer after it has expired. Returns `false` if the timer has expired more
+    /// than `MAX_RETRANSMISSIONS` times, when the procedure is to be aborted.
+    pub(in crate::amf) fn restart(&mut self) -> bool {
+        self.expirations += 1;
+        self.expires_at = Instant::now() + self.id.duration();
+
+        self.expirations <= MAX_RETRANSMISSIONS
+    }
+}
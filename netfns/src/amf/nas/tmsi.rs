@@ -0,0 +1,73 @@
+//! 5G-TMSI Allocation
+//!
+//! The 5G-TMSI is unique within the AMF (Section 2.10.1 23.003). The AMF Pointer of the AMF is
+//! used as the most significant bits of the 5G-TMSI, so that the 5G-TMSIs allocated by the AMFs
+//! of an AMF Set are unique within the AMF Set. (The 5G-S-TMSI is made of the AMF Set ID, AMF
+//! Pointer and the 5G-TMSI.) The remaining bits are allocated randomly, so that the 5G-TMSI is
+//! not predictable. (Section 6.12.3 33.501)
+
+use std::collections::HashSet;
+
+use crate::amf::config::AmfIdConfig;
+
+// Number of bits of the 5G-TMSI allocated by the AMF, the remaining 6 bits are the AMF Pointer.
+const TMSI_VALUE_BITS: u32 = 26;
+const TMSI_VALUE_MASK: u32 = (1 << TMSI_VALUE_BITS) - 1;
+
+#[derive(Debug, Clone)]
+pub(in crate::amf) struct TmsiAllocator {
+    prefix: u32,
+    allocated: HashSet<u32>,
+}
+
+impl TmsiAllocator {
+    pub(in crate::amf) fn new(amf_id: &AmfIdConfig) -> Self {
+        Self {
+            prefix: (amf_id.pointer as u32) << TMSI_VALUE_BITS,
+            allocated: HashSet::new(),
+        }
+    }
+
+    /// Allocate a 5G-TMSI not in use. Returns `None` if all the 5G-TMSIs are in use.
+    pub(in crate::amf) fn allocate(&mut self) -> Option<u32> {
+        if self.allocated.len() > TMSI_VALUE_MASK as usize {
+            return None;
+        }
+
+        loop {
+            let tmsi = self.prefix | (rand::random::<u32>() & TMSI_VALUE_MASK);
+            if self.allocated.insert(tmsi) {
+                return Some(tmsi);
+            }
+        }
+    }
+
+    /// Release the 5G-TMSI, so that it can be allocated again.
+    pub(in crate::amf) fn release(&mut self, tmsi: u32) {
+        let _ = self.allocated.remove(&tmsi);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocate_release() {
+        let amf_id = AmfIdConfig {
+            pointer: 0x2a,
+            set: 1,
+            region: 1,
+        };
+        let mut allocator = TmsiAllocator::new(&amf_id);
+
+        let tmsis: HashSet<u32> = (0..1000).map(|_| allocator.allocate().unwrap()).collect();
+        assert_eq!(tmsis.len(), 1000);
+        assert!(tmsis.iter().all(|tmsi| tmsi >> TMSI_VALUE_BITS == 0x2a));
+
+        let tmsi = *tmsis.iter().next().unwrap();
+        allocator.release(tmsi);
+        assert!(!allocator.allocated.contains(&tmsi));
+        assert_eq!(allocator.allocated.len(), 999);
+    }
+}
@@ -8,7 +8,7 @@ use ngap::messages::r17::{Criticality, ProcedureCode, ProtocolIE_ID, NGAP_PDU};
 // Initial UE Message Types
 use ngap::messages::r17::InitialUEMessage;
 use ngap::messages::r17::InitialUEMessageProtocolIEs_EntryValue as InitialIEValue;
-use ngap::messages::r17::{Cause, CauseProtocol};
+use ngap::messages::r17::{Cause, CauseProtocol, UserLocationInformation};
 use ngap::messages::r17::{
     ErrorIndication, ErrorIndicationProtocolIEs, ErrorIndicationProtocolIEs_Entry,
     ErrorIndicationProtocolIEs_EntryValue, InitiatingMessage, InitiatingMessageValue, ID_CAUSE,
@@ -78,6 +78,14 @@ impl NgapManager {
             log::warn!("Missing mandatory `RRCEstablishmentCause IE`.");
         }
 
+        // TAC of the UE's location, for the NAS procedures. (Only the NR access is supported.)
+        let tac = match user_location.as_ref().unwrap() {
+            UserLocationInformation::UserLocationInformationNR(location) => {
+                Some(location.tai.tac.to_u32())
+            }
+            _ => None,
+        };
+
         // Store the received information in the `NgapRanUe` and then pass the PDU for NAS
         // processing.
         //
@@ -101,6 +109,7 @@ impl NgapManager {
             id,
             pdu,
             initial_ue: true,
+            tac,
         });
         let _ = self.ngap_to_amf_tx.as_ref().unwrap().send(message).await;
 
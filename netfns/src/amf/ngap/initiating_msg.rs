@@ -19,6 +19,10 @@ impl NgapManager {
                 self.process_initial_ue_message(id, sid, initial_ue_message)
                     .await
             }
+            InitiatingMessageValue::Id_UplinkNASTransport(uplink_nas_transport) => {
+                self.process_uplink_nas_transport(id, sid, uplink_nas_transport)
+                    .await
+            }
             _ => {
                 log::error!("Unsupported Message received: {:?}", init.procedure_code);
                 Err(std::io::Error::new(
@@ -10,3 +10,5 @@ mod unsuccessful_outcome;
 mod ngsetup;
 
 mod initial_ue;
+
+mod nas_transport;
@@ -0,0 +1,142 @@
+//! Handling of the NAS Transport Messages (Section 8.6 38.413)
+
+use sctp_rs::AssociationId;
+
+// Common NGAP Types
+use ngap::messages::r17::{
+    Criticality, InitiatingMessage, InitiatingMessageValue, ProcedureCode, ProtocolIE_ID,
+    AMF_UE_NGAP_ID, NGAP_PDU, RAN_UE_NGAP_ID,
+};
+use ngap::messages::r17::{ID_AMF_UE_NGAP_ID, ID_NAS_PDU, ID_RAN_UE_NGAP_ID};
+
+// NAS Transport Message Types
+use ngap::messages::r17::UplinkNASTransport;
+use ngap::messages::r17::UplinkNASTransportProtocolIEs_EntryValue as UplinkIEValue;
+use ngap::messages::r17::{
+    DownlinkNASTransport, DownlinkNASTransportProtocolIEs, DownlinkNASTransportProtocolIEs_Entry,
+    DownlinkNASTransportProtocolIEs_EntryValue, ID_DOWNLINK_NAS_TRANSPORT,
+};
+
+use crate::amf::messages::{NasPduMessage, NgapToAmfMessage};
+
+use super::ngap_manager::NgapManager;
+
+impl NgapManager {
+    // The NAS PDU received in the Uplink NAS Transport is sent for NAS processing of the UE
+    // identified by the `AMF_UE_NGAP_ID`.
+    pub(super) async fn process_uplink_nas_transport(
+        &mut self,
+        id: AssociationId,
+        sid: u16,
+        uplink_nas_transport: UplinkNASTransport,
+    ) -> std::io::Result<()> {
+        log::debug!(
+            "Processing 'UplinkNASTransport' received on AssociationID: {}, Stream ID: {}",
+            id,
+            sid
+        );
+
+        let mut amf_ue_ngap_id = None;
+        let mut ran_ue_ngap_id = None;
+        let mut nas_pdu = None;
+        for ie in uplink_nas_transport.protocol_i_es.0 {
+            match ie.value {
+                UplinkIEValue::Id_AMF_UE_NGAP_ID(a) => {
+                    amf_ue_ngap_id.replace(a.0);
+                }
+                UplinkIEValue::Id_RAN_UE_NGAP_ID(r) => {
+                    ran_ue_ngap_id.replace(r.0);
+                }
+                UplinkIEValue::Id_NAS_PDU(inner_nas_pdu) => {
+                    nas_pdu.replace(inner_nas_pdu);
+                }
+                _ => {
+                    log::trace!("Ignoring IE: {:?}", ie);
+                }
+            }
+        }
+
+        if amf_ue_ngap_id.is_none() || ran_ue_ngap_id.is_none() || nas_pdu.is_none() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Missing mandatory IEs in 'UplinkNASTransport'.",
+            ));
+        }
+
+        let amf_ue_ngap_id = amf_ue_ngap_id.unwrap();
+        match self.amf_ues.get(&amf_ue_ngap_id) {
+            Some((assoc_id, ran_id)) if *assoc_id == id && Some(*ran_id) == ran_ue_ngap_id => {}
+            _ => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!(
+                        "Unknown UE: AMF_UE_NGAP_ID: {}, RAN_UE_NGAP_ID: {}",
+                        amf_ue_ngap_id,
+                        ran_ue_ngap_id.unwrap()
+                    ),
+                ))
+            }
+        }
+
+        let message = NgapToAmfMessage::NasPduMessage(NasPduMessage {
+            id: amf_ue_ngap_id,
+            pdu: nas_pdu.unwrap(),
+            initial_ue: false,
+            tac: None,
+        });
+        let _ = self.ngap_to_amf_tx.as_ref().unwrap().send(message).await;
+
+        Ok(())
+    }
+
+    // Send the NAS PDU from NAS to the UE in the Downlink NAS Transport.
+    pub(super) async fn send_downlink_nas_transport(
+        &self,
+        nas_pdu_msg: NasPduMessage,
+    ) -> std::io::Result<()> {
+        let (id, ran_ue_ngap_id) = match self.amf_ues.get(&nas_pdu_msg.id) {
+            Some(ids) => *ids,
+            None => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    format!("Unknown UE: AMF_UE_NGAP_ID: {}", nas_pdu_msg.id),
+                ))
+            }
+        };
+
+        let amf_ue_id_ie = DownlinkNASTransportProtocolIEs_Entry {
+            id: ProtocolIE_ID(ID_AMF_UE_NGAP_ID),
+            criticality: Criticality(Criticality::REJECT),
+            value: DownlinkNASTransportProtocolIEs_EntryValue::Id_AMF_UE_NGAP_ID(AMF_UE_NGAP_ID(
+                nas_pdu_msg.id,
+            )),
+        };
+
+        let ran_ue_id_ie = DownlinkNASTransportProtocolIEs_Entry {
+            id: ProtocolIE_ID(ID_RAN_UE_NGAP_ID),
+            criticality: Criticality(Criticality::REJECT),
+            value: DownlinkNASTransportProtocolIEs_EntryValue::Id_RAN_UE_NGAP_ID(RAN_UE_NGAP_ID(
+                ran_ue_ngap_id,
+            )),
+        };
+
+        let nas_pdu_ie = DownlinkNASTransportProtocolIEs_Entry {
+            id: ProtocolIE_ID(ID_NAS_PDU),
+            criticality: Criticality(Criticality::REJECT),
+            value: DownlinkNASTransportProtocolIEs_EntryValue::Id_NAS_PDU(nas_pdu_msg.pdu),
+        };
+
+        let ies = vec![amf_ue_id_ie, ran_ue_id_ie, nas_pdu_ie];
+
+        let downlink_nas_transport = DownlinkNASTransport {
+            protocol_i_es: DownlinkNASTransportProtocolIEs(ies),
+        };
+        let pdu = NGAP_PDU::InitiatingMessage(InitiatingMessage {
+            procedure_code: ProcedureCode(ID_DOWNLINK_NAS_TRANSPORT),
+            criticality: Criticality(Criticality::IGNORE),
+            value: InitiatingMessageValue::Id_DownlinkNASTransport(downlink_nas_transport),
+        });
+
+        self.ngap_send_pdu(id, pdu, Some(ran_ue_ngap_id)).await
+    }
+}
@@ -179,7 +179,13 @@ impl NgapManager {
                     )) = rx.recv() => {
                     let mut codec_data =
                     PerCodecData::from_slice_aper(&rxdata.payload);
-                    let pdu = NGAP_PDU::aper_decode(&mut codec_data).unwrap();
+                    let pdu = match NGAP_PDU::aper_decode(&mut codec_data) {
+                        Ok(pdu) => pdu,
+                        Err(e) => {
+                            log::error!("Error Decoding NGAP PDU: {:#?}", e);
+                            continue;
+                        }
+                    };
                     let sid = if rxdata.rcv_info.is_some() {
                         rxdata.rcv_info.as_ref().unwrap().sid
                     } else {
@@ -199,13 +205,22 @@ impl NgapManager {
                         log::error!("Error Processing NGAP Message: {:#?}" , result.err().unwrap());
                     }
                 }
-                Some(_amf_data) = amf_to_ngap_rx.recv() => {
-                    log::warn!("Signal Received from AMF.");
-                    log::debug!("Sending close to all RAN Connections.");
-                    for (_k, v) in self.ran_connections {
-                        let _ = v.send(NgapMgrToRanConnMessage::Signal(15)).await;
+                Some(amf_data) = amf_to_ngap_rx.recv() => {
+                    match amf_data {
+                        AmfToNgapMessage::NasPduMessage(nas_pdu_msg) => {
+                            if let Err(e) = self.send_downlink_nas_transport(nas_pdu_msg).await {
+                                log::error!("Error Sending Downlink NAS Transport: {}", e);
+                            }
+                        }
+                        AmfToNgapMessage::Signal(_) => {
+                            log::warn!("Signal Received from AMF.");
+                            log::debug!("Sending close to all RAN Connections.");
+                            for (_k, v) in self.ran_connections {
+                                let _ = v.send(NgapMgrToRanConnMessage::Signal(15)).await;
+                            }
+                            break ;
+                        }
                     }
-                    break ;
                 }
             }
             log::debug!("select loop completed..");
@@ -25,7 +25,7 @@ use ngap::messages::r17::{
     NGSetupResponseProtocolIEs_EntryValue as ResponseIEValue, PLMNIdentity, PLMNSupportItem,
     PLMNSupportList, RelativeAMFCapacity, ServedGUAMIItem, ServedGUAMIList, SliceSupportItem,
     SliceSupportList, GUAMI, ID_AMF_NAME, ID_PLMN_SUPPORT_LIST, ID_RELATIVE_AMF_CAPACITY,
-    ID_SERVED_GUAMI_LIST, SST, S_NSSAI,
+    ID_SERVED_GUAMI_LIST, S_NSSAI,
 };
 
 // Types related to NGSetupFailure
@@ -218,17 +218,16 @@ impl NgapManager {
 
         // PLMN Support List
         let mut plmns = vec![];
-        let mut slices = vec![];
-
-        let slice_item = SliceSupportItem {
-            s_nssai: S_NSSAI {
-                sst: SST(vec![1]),
-                sd: None,
+        let slices = self
+            .config
+            .supported_nssai()
+            .s_nssais
+            .iter()
+            .map(|s_nssai| SliceSupportItem {
+                s_nssai: S_NSSAI::from(s_nssai),
                 ie_extensions: None,
-            },
-            ie_extensions: None,
-        };
-        slices.push(slice_item);
+            })
+            .collect();
 
         let plmn_support_item = PLMNSupportItem {
             plmn_identity: PLMNIdentity::from_mcc_mnc(self.config.plmn.mcc, self.config.plmn.mnc),
@@ -34,9 +34,8 @@ impl Amf {
     /// Runs individual tasks and handles signals sent by the user.
     pub async fn run(mut self) -> std::io::Result<()> {
         log::info!(
-            "Started AMF: PLMN: [MCC:({}), MNC:({})], TACs:{:?}",
-            self.config.plmn.mcc,
-            self.config.plmn.mnc,
+            "Started AMF: PLMN: {}, TACs:{:?}",
+            self.config.plmn.plmn(),
             self.config.tacs
         );
 
@@ -71,7 +70,13 @@ impl Amf {
                         _ => {}
                     }
                 }
-                Some(_) = nas_to_amf_rx.recv() => {
+                Some(msg) = nas_to_amf_rx.recv() => {
+                    match msg {
+                        NasToAmfMessage::NasPduMessage(nas_pdu_msg) => {
+                            let msg = AmfToNgapMessage::NasPduMessage(nas_pdu_msg);
+                            let _ = self.amf_to_ngap_tx.as_ref().unwrap().send(msg).await;
+                        }
+                    }
                 }
                 _ = sigterm.recv() => {
                     log::warn!("Received SIGTERM Sending to all threads.");
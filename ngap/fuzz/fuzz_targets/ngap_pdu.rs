@@ -0,0 +1,11 @@
+#![no_main]
+
+use asn1_codecs::{aper::AperCodec, PerCodecData};
+use libfuzzer_sys::fuzz_target;
+
+use ngap::messages::r17::NGAP_PDU;
+
+fuzz_target!(|data: &[u8]| {
+    let mut codec_data = PerCodecData::from_slice_aper(data);
+    let _ = NGAP_PDU::aper_decode(&mut codec_data);
+});
@@ -0,0 +1,29 @@
+#![no_main]
+
+use asn1_codecs::{aper::AperCodec, PerCodecData};
+use libfuzzer_sys::fuzz_target;
+
+use ngap::messages::r17::NGAP_PDU;
+
+fn encode(pdu: &NGAP_PDU) -> Vec<u8> {
+    let mut codec_data = PerCodecData::new_aper();
+    pdu.aper_encode(&mut codec_data)
+        .expect("Decoded PDU failed to encode.");
+    codec_data
+        .get_inner()
+        .expect("Failed to get the encoded PDU.")
+}
+
+// Input may carry non-canonical encodings (eg. padding bits that are not zero), so the encoding of
+// a decoded PDU is compared with the encoding of that PDU after it is decoded again.
+fuzz_target!(|data: &[u8]| {
+    let mut codec_data = PerCodecData::from_slice_aper(data);
+    if let Ok(pdu) = NGAP_PDU::aper_decode(&mut codec_data) {
+        let encoded = encode(&pdu);
+
+        let mut codec_data = PerCodecData::from_slice_aper(&encoded);
+        let decoded =
+            NGAP_PDU::aper_decode(&mut codec_data).expect("Encoded PDU failed to decode.");
+        assert_eq!(encode(&decoded), encoded);
+    }
+});
@@ -5,3 +5,5 @@ mod plmn_identity;
 mod supported_ta_list;
 
 mod guami;
+
+mod tai;
@@ -12,17 +12,16 @@ impl PLMNIdentity {
         let mcc2 = (mcc % 100) / 10;
         let mcc1 = (mcc % 1000) / 100;
 
-        let mnc3 = mnc % 10;
-        let mnc2 = (mnc % 100) / 10;
-        let mut mnc1 = (mnc % 1000) / 100;
-
-        if mnc1 == 0 {
-            mnc1 = 0xf;
-        }
+        // A 2 digit MNC has the filler `0xf` for the MNC digit 3.
+        let (mnc1, mnc2, mnc3) = if mnc < 100 {
+            (mnc / 10, mnc % 10, 0xf)
+        } else {
+            ((mnc % 1000) / 100, (mnc % 100) / 10, mnc % 10)
+        };
 
         let byte0 = mcc2 << 4 | mcc1;
-        let byte1 = mnc1 << 4 | mcc3;
-        let byte2 = mnc3 << 4 | mnc2;
+        let byte1 = mnc3 << 4 | mcc3;
+        let byte2 = mnc2 << 4 | mnc1;
 
         Self(vec![
             byte0.try_into().unwrap(),
@@ -30,6 +29,30 @@ impl PLMNIdentity {
             byte2.try_into().unwrap(),
         ])
     }
+
+    /// Get the `mcc` and `mnc` values from the `PLMNIdentity`.
+    ///
+    /// Returns `None` if the `PLMNIdentity` is not 3 octets or any of the digits is not a decimal
+    /// digit. The number of digits of the MNC is not returned, `nas::Plmn` keeps it if it is
+    /// required.
+    pub fn to_mcc_mnc(&self) -> Option<(u16, u16)> {
+        let octets: &[u8; 3] = self.0.as_slice().try_into().ok()?;
+        let digit = |nibble: u8| (nibble <= 9).then_some(nibble as u16);
+
+        let mcc1 = digit(octets[0] & 0xf)?;
+        let mcc2 = digit(octets[0] >> 4)?;
+        let mcc3 = digit(octets[1] & 0xf)?;
+        let mnc1 = digit(octets[2] & 0xf)?;
+        let mnc2 = digit(octets[2] >> 4)?;
+
+        let mcc = mcc1 * 100 + mcc2 * 10 + mcc3;
+        let mnc = match octets[1] >> 4 {
+            0xf => mnc1 * 10 + mnc2,
+            mnc3 => mnc1 * 100 + mnc2 * 10 + digit(mnc3)?,
+        };
+
+        Some((mcc, mnc))
+    }
 }
 
 #[cfg(test)]
@@ -59,7 +82,7 @@ mod tests {
             PlmnIdentityTestValues {
                 mcc: 286,
                 mnc: 101,
-                id: PLMNIdentity(vec![130, 22, 16]),
+                id: PLMNIdentity(vec![130, 22, 1]),
             },
         ];
 
@@ -73,4 +96,22 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn plmn_identity_to_mcc_mnc() {
+        for (mcc, mnc) in [(901, 70), (286, 1), (286, 101), (310, 410), (1, 1)] {
+            assert_eq!(
+                PLMNIdentity::from_mcc_mnc(mcc, mnc).to_mcc_mnc(),
+                Some((mcc, mnc))
+            );
+        }
+
+        assert_eq!(
+            PLMNIdentity(vec![0x13, 0x00, 0x14]).to_mcc_mnc(),
+            Some((310, 410))
+        );
+        assert_eq!(PLMNIdentity(vec![0x0a, 0xf1, 0x07]).to_mcc_mnc(), None);
+        assert_eq!(PLMNIdentity(vec![0x09, 0xe1, 0x07]).to_mcc_mnc(), None);
+        assert_eq!(PLMNIdentity(vec![0x09, 0xf1]).to_mcc_mnc(), None);
+    }
 }
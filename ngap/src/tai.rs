@@ -0,0 +1,45 @@
+use crate::messages::r17::{PLMNIdentity, TAC, TAI};
+
+impl TAC {
+    /// Get a `TAC` from the 24 bit `tac` value.
+    pub fn from_u32(tac: u32) -> Self {
+        Self(tac.to_be_bytes()[1..].to_vec())
+    }
+
+    /// Get the TAC value as an integer.
+    pub fn to_u32(&self) -> u32 {
+        self.0.iter().fold(0, |tac, byte| tac << 8 | *byte as u32)
+    }
+}
+
+impl TAI {
+    /// TAI From the PLMN ID (MCC, MNC) and the TAC.
+    pub fn from_plmn_tac(mcc: u16, mnc: u16, tac: u32) -> Self {
+        Self {
+            plmn_identity: PLMNIdentity::from_mcc_mnc(mcc, mnc),
+            tac: TAC::from_u32(tac),
+            ie_extensions: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn tac_to_from_u32() {
+        let tac = TAC::from_u32(0x010203);
+        assert_eq!(tac.0, vec![1, 2, 3]);
+        assert_eq!(tac.to_u32(), 0x010203);
+        assert!(tac == 0x010203_u32);
+    }
+
+    #[test]
+    fn tai_from_plmn_tac() {
+        let tai = TAI::from_plmn_tac(901, 70, 1);
+        assert_eq!(tai.plmn_identity.0, vec![9, 241, 7]);
+        assert_eq!(tai.tac.0, vec![0, 0, 1]);
+    }
+}
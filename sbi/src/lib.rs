@@ -18,4 +18,4 @@ extern crate serde;
 extern crate serde_json;
 extern crate url;
 
-mod models;
+pub mod models;
@@ -0,0 +1,7 @@
+//! Generated models that are edited by hand.
+//!
+//! `ue_authentication_ctx_5g_auth_data`: The `5gAuthData` is `oneOf` `Av5gAka` or `EapPayload`,
+//! the generated model only supported the `Av5gAka`.
+
+pub mod ue_authentication_ctx_5g_auth_data;
+pub use self::ue_authentication_ctx_5g_auth_data::UeAuthenticationCtx5gAuthData;
@@ -0,0 +1,24 @@
+/*
+ * AUSF API
+ *
+ * AUSF UE Authentication Service. © 2022, 3GPP Organizational Partners (ARIB, ATIS, CCSA, ETSI, TSDSI, TTA, TTC). All rights reserved.
+ *
+ * The version of the OpenAPI document: 1.2.2
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+/// UeAuthenticationCtx5gAuthData : The 5G AKA Authentication Vector or the EAP payload (containing
+/// an EAP packet encoded in base64) depending upon the authentication method.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum UeAuthenticationCtx5gAuthData {
+    Av5gAka(crate::models::Av5gAka),
+    EapPayload(String),
+}
+
+impl Default for UeAuthenticationCtx5gAuthData {
+    fn default() -> UeAuthenticationCtx5gAuthData {
+        Self::Av5gAka(crate::models::Av5gAka::default())
+    }
+}
@@ -62,5 +62,6 @@ pub mod trace_depth;
 pub use self::trace_depth::TraceDepth;
 pub mod ue_authentication_ctx;
 pub use self::ue_authentication_ctx::UeAuthenticationCtx;
-pub mod ue_authentication_ctx_5g_auth_data;
-pub use self::ue_authentication_ctx_5g_auth_data::UeAuthenticationCtx5gAuthData;
+
+pub mod edited;
+pub use self::edited::UeAuthenticationCtx5gAuthData;
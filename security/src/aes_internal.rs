@@ -7,6 +7,7 @@ use ctr::cipher::{KeyIvInit, StreamCipher};
 pub type AesKey = [u8; 16];
 pub type AesIv = [u8; 16];
 pub type AesMac = [u8; 16];
+pub type AesBlock = [u8; 16];
 
 type Aes128Ctr64BE = ctr::Ctr64BE<aes::Aes128>;
 
@@ -19,6 +20,17 @@ pub fn encrypt_aes128_ctr(key: AesKey, iv: AesIv, payload: &[u8]) -> Vec<u8> {
     out
 }
 
+/// Encrypt a single block using AES 128 (Used as a kernel function by eg. MILENAGE 35.206)
+pub fn encrypt_aes128_block(key: AesKey, block: AesBlock) -> AesBlock {
+    use aes::cipher::{BlockEncrypt, KeyInit};
+
+    let cipher = aes::Aes128::new(&key.into());
+    let mut block = block.into();
+    cipher.encrypt_block(&mut block);
+
+    block.into()
+}
+
 /// Calculate MAC using AES 128 - CMAC
 pub fn mac_aes128_cmac(key: AesKey, message: &[u8]) -> AesMac {
     let mut mac = Cmac::<aes::Aes128>::new_from_slice(&key).unwrap();
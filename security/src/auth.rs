@@ -0,0 +1,100 @@
+//! Common definitions for the Authentication and Key Generation Algorithm sets.
+//!
+//! The Algorithm sets (eg. MILENAGE 35.206 or TUAK 35.231) implement the functions `f1`, `f1*`,
+//! `f2`, `f3`, `f4`, `f5` and `f5*` used for the Authentication and Key Agreement as specified in
+//! 33.102. The [`AuthAlgorithm`] trait allows the Authentication Vectors to be generated using the
+//! algorithm set configured for a given subscriber.
+
+use crate::utils::constant_time_eq;
+
+/// Random Challenge `RAND`
+pub type Rand = [u8; 16];
+
+/// Sequence Number `SQN`
+pub type Sqn = [u8; 6];
+
+/// Authentication Management Field `AMF`
+pub type AuthMgmtField = [u8; 2];
+
+/// Anonymity Key `AK`
+pub type Ak = [u8; 6];
+
+/// Authentication Vector generated for a given `RAND` and `SQN`. (Section 6.3.2 33.102)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthVector {
+    pub rand: Rand,
+    pub xres: Vec<u8>,
+    pub ck: Vec<u8>,
+    pub ik: Vec<u8>,
+    pub ak: Ak,
+    pub autn: Vec<u8>,
+}
+
+/// Authentication and Key Generation functions implemented by an Algorithm set.
+///
+/// The lengths of the `MAC`, `RES`, `CK` and `IK` depend upon the Algorithm set (and it's
+/// configuration), hence these are returned as a `Vec<u8>`.
+pub trait AuthAlgorithm {
+    /// Network Authentication function `f1`: Returns the `MAC-A`.
+    fn f1(&self, rand: Rand, sqn: Sqn, amf: AuthMgmtField) -> Vec<u8>;
+
+    /// Re-synchronisation Message Authentication function `f1*`: Returns the `MAC-S`.
+    fn f1_star(&self, rand: Rand, sqn: Sqn, amf: AuthMgmtField) -> Vec<u8>;
+
+    /// User Authentication function `f2`: Returns the `RES`.
+    fn f2(&self, rand: Rand) -> Vec<u8>;
+
+    /// Cipher Key derivation function `f3`: Returns the `CK`.
+    fn f3(&self, rand: Rand) -> Vec<u8>;
+
+    /// Integrity Key derivation function `f4`: Returns the `IK`.
+    fn f4(&self, rand: Rand) -> Vec<u8>;
+
+    /// Anonymity Key derivation function `f5`: Returns the `AK`.
+    fn f5(&self, rand: Rand) -> Ak;
+
+    /// Anonymity Key derivation function `f5*` used for Re-synchronisation: Returns the `AK`.
+    fn f5_star(&self, rand: Rand) -> Ak;
+
+    /// Generate the Authentication Vector for the given `RAND`, `SQN` and `AMF`.
+    ///
+    /// The `AUTN` is computed as `SQN XOR AK || AMF || MAC-A`.
+    fn generate_auth_vector(&self, rand: Rand, sqn: Sqn, amf: AuthMgmtField) -> AuthVector {
+        let ak = self.f5(rand);
+
+        let mut autn: Vec<u8> = sqn.iter().zip(ak).map(|(s, a)| s ^ a).collect();
+        autn.extend(amf);
+        autn.extend(self.f1(rand, sqn, amf));
+
+        AuthVector {
+            rand,
+            xres: self.f2(rand),
+            ck: self.f3(rand),
+            ik: self.f4(rand),
+            ak,
+            autn,
+        }
+    }
+
+    /// Verify the `AUTS` received from the UE during Re-synchronisation and return the `SQN_MS`
+    /// if the `MAC-S` is valid. (Section 6.3.3 and 6.3.5 33.102)
+    ///
+    /// The `AUTS` is `SQN_MS XOR AK || MAC-S`, where the length of the `MAC-S` is that of the
+    /// output of `f1*` (eg. 64 bits for MILENAGE, 64, 128 or 256 bits for TUAK).
+    fn resync_sqn(&self, rand: Rand, auts: &[u8]) -> Option<Sqn> {
+        let (concealed_sqn_ms, mac_s) = (auts.get(..6)?, &auts[6..]);
+        let ak = self.f5_star(rand);
+
+        let mut sqn_ms = [0_u8; 6];
+        for (i, (c, a)) in concealed_sqn_ms.iter().zip(ak).enumerate() {
+            sqn_ms[i] = c ^ a;
+        }
+
+        // The `AMF` used for calculating `MAC-S` is a dummy value of all zeros.
+        if constant_time_eq(&self.f1_star(rand, sqn_ms, [0_u8; 2]), mac_s) {
+            Some(sqn_ms)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! Key Derivation functions for EAP-AKA'
+//!
+//! The keys `CK'` and `IK'` are derived from `CK` and `IK` (Section A.2 33.402, Section 3.3 RFC
+//! 9048) and the EAP-AKA' keys are derived from them using the `PRF'` (Section 3.4.1 RFC 9048).
+//! For the 5G Primary Authentication, the Access Network Name is the Serving Network Name and the
+//! `KAUSF` is derived from the `EMSK`. (Annex F 33.501)
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::kdf::{kdf_common, KdfParam, SecurityKey};
+
+/// FC Value for `CK'` and `IK'` derivation (Section A.2 33.402)
+const CK_IK_PRIME_DERIVE_FC: u8 = 0x20;
+
+/// Total length of the keys derived using the `PRF'`: `K_encr`, `K_aut`, `K_re`, `MSK` and `EMSK`.
+const MK_LENGTH: usize = 16 + 32 + 32 + 64 + 64;
+
+/// `CK'` or `IK'`: 128 bit values.
+pub type CkIkPrime = [u8; 16];
+
+/// Derive `CK'` and `IK'` from `CK`, `IK` (Section A.2 33.402). The `network_name` is the Serving
+/// Network Name for the 5G Primary Authentication.
+pub fn derive_ck_ik_prime(
+    ck: &[u8],
+    ik: &[u8],
+    network_name: &str,
+    sqn_xor_ak: &[u8],
+) -> (CkIkPrime, CkIkPrime) {
+    let mut key = ck.to_vec();
+    key.extend(ik);
+
+    let output = kdf_common(
+        key,
+        CK_IK_PRIME_DERIVE_FC,
+        &[
+            KdfParam::from_str(network_name),
+            KdfParam::from_bytes(sqn_xor_ak),
+        ],
+    );
+
+    (
+        output[..16].try_into().unwrap(),
+        output[16..].try_into().unwrap(),
+    )
+}
+
+/// Keys derived for the EAP-AKA' Authentication. (Section 3.3 RFC 9048)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EapAkaPrimeKeys {
+    pub k_encr: [u8; 16],
+    pub k_aut: [u8; 32],
+    pub k_re: [u8; 32],
+    pub msk: [u8; 64],
+    pub emsk: [u8; 64],
+}
+
+impl EapAkaPrimeKeys {
+    /// Derive the keys from `CK'`, `IK'` and the `identity` used by the UE (the SUPI in the NAI
+    /// format for the 5G Primary Authentication).
+    pub fn derive(ck_prime: &CkIkPrime, ik_prime: &CkIkPrime, identity: &str) -> Self {
+        let mut key = ik_prime.to_vec();
+        key.extend(ck_prime);
+
+        let mut s = b"EAP-AKA'".to_vec();
+        s.extend(identity.as_bytes());
+
+        let mk = prf_prime(&key, &s, MK_LENGTH);
+
+        Self {
+            k_encr: mk[..16].try_into().unwrap(),
+            k_aut: mk[16..48].try_into().unwrap(),
+            k_re: mk[48..80].try_into().unwrap(),
+            msk: mk[80..144].try_into().unwrap(),
+            emsk: mk[144..].try_into().unwrap(),
+        }
+    }
+
+    /// `KAUSF` is the most significant 256 bits of the `EMSK`. (Section 6.1.3.1 33.501)
+    pub fn kausf(&self) -> SecurityKey {
+        self.emsk[..32].try_into().unwrap()
+    }
+}
+
+/// Compute the `AT_MAC` value of an EAP-AKA' packet using `K_aut`. (Section 3.4.2 RFC 9048)
+///
+/// The `packet` is the whole EAP packet with the `MAC` field of the `AT_MAC` attribute set to
+/// zero.
+pub fn mac_eap_aka_prime(k_aut: &[u8; 32], packet: &[u8]) -> [u8; 16] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(k_aut).expect("HMAC can take key of any size");
+    mac.update(packet);
+
+    mac.finalize().into_bytes()[..16].try_into().unwrap()
+}
+
+// `PRF'(K, S) = T1 | T2 | T3 | ...`, where `T1 = HMAC-SHA-256(K, S | 0x01)` and
+// `Tn = HMAC-SHA-256(K, Tn-1 | S | n)`. (Section 3.4.1 RFC 9048)
+fn prf_prime(key: &[u8], s: &[u8], length: usize) -> Vec<u8> {
+    let mut output = vec![];
+    let mut t = vec![];
+    let mut n = 1_u8;
+
+    while output.len() < length {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC can take key of any size");
+        mac.update(&t);
+        mac.update(s);
+        mac.update(&[n]);
+        t = mac.finalize().into_bytes().to_vec();
+
+        output.extend(&t);
+        n += 1;
+    }
+
+    output.truncate(length);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode<const N: usize>(s: &str) -> [u8; N] {
+        hex::decode(s).unwrap().try_into().unwrap()
+    }
+
+    // Test Case 1: Appendix C RFC 9048
+    #[test]
+    fn test_eap_aka_prime_keys() {
+        let ck = hex::decode("5349fbe098649f948f5d2e973a81c00f").unwrap();
+        let ik = hex::decode("9744871ad32bf9bbd1dd5ce54e3e2e5a").unwrap();
+        let autn = hex::decode("bb52e91c747ac3ab2a5c23d15ee351d5").unwrap();
+
+        let (ck_prime, ik_prime) = derive_ck_ik_prime(&ck, &ik, "WLAN", &autn[..6]);
+        assert_eq!(ck_prime, decode("0093962d0dd84aa5684b045c9edffa04"));
+        assert_eq!(ik_prime, decode("ccfc230ca74fcc96c0a5d61164f5a76c"));
+
+        let keys = EapAkaPrimeKeys::derive(&ck_prime, &ik_prime, "0555444333222111");
+        assert_eq!(keys.k_encr, decode("766fa0a6c317174b812d52fbcd11a179"));
+        assert_eq!(
+            keys.k_aut,
+            decode("0842ea722ff6835bfa2032499fc3ec23c2f0e388b4f07543ffc677f1696d71ea")
+        );
+        assert_eq!(
+            keys.k_re,
+            decode("cf83aa8bc7e0aced892acc98e76a9b2095b558c7795c7094715cb3393aa7d17a")
+        );
+        assert_eq!(
+            keys.msk,
+            decode("67c42d9aa56c1b79e295e3459fc3d187d42be0bf818d3070e362c5e967a4d544e8ecfe19358ab3039aff03b7c930588c055babee58a02650b067ec4e9347c75a")
+        );
+        assert_eq!(
+            keys.emsk,
+            decode("f861703cd775590e16c7679ea3874ada866311de290764d760cf76df647ea01c313f69924bdd7650ca9bac141ea075c4ef9e8029c0e290cdbad5638b63bc23fb")
+        );
+        assert_eq!(keys.kausf()[..], keys.emsk[..32]);
+    }
+
+    #[test]
+    fn test_mac_eap_aka_prime() {
+        // HMAC-SHA-256 truncated to 128 bits, independently computed.
+        let mac = mac_eap_aka_prime(&[0x0b_u8; 32], b"Hi There");
+        assert_eq!(mac, decode("198a607eb44bfbc69903a0f1cf2bbdc5"));
+    }
+}
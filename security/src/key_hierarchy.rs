@@ -0,0 +1,260 @@
+//! Key Derivation functions for the 5G Key Hierarchy.
+//!
+//! Derivation of the keys in the 5G Key Hierarchy as specified in the Annex A of 33.501 using the
+//! common Key Derivation function [`kdf_common`]. The keys for the NAS Integrity and Ciphering
+//! algorithms are derived by the NAS implementation.
+
+use sha2::{Digest, Sha256};
+
+use crate::kdf::{kdf_common, KdfParam, SecurityKey};
+
+/// FC Value for `KAUSF` derivation (Section A.2 33.501)
+const KAUSF_DERIVE_FC: u8 = 0x6A;
+
+/// FC Value for `RES*` and `XRES*` derivation (Section A.4 33.501)
+const RES_STAR_DERIVE_FC: u8 = 0x6B;
+
+/// FC Value for `KSEAF` derivation (Section A.6 33.501)
+const KSEAF_DERIVE_FC: u8 = 0x6C;
+
+/// FC Value for `KAMF` derivation (Section A.7 33.501)
+const KAMF_DERIVE_FC: u8 = 0x6D;
+
+/// FC Value for `KgNB` and `KN3IWF` derivation (Section A.9 33.501)
+const KGNB_DERIVE_FC: u8 = 0x6E;
+
+/// FC Value for `NH` derivation (Section A.10 33.501)
+const NH_DERIVE_FC: u8 = 0x6F;
+
+/// FC Value for `KAMF'` derivation during mobility (Section A.13 33.501)
+const KAMF_PRIME_DERIVE_FC: u8 = 0x72;
+
+/// `RES*`, `XRES*` or `HXRES*`: 128 bit values.
+pub type ResStar = [u8; 16];
+
+/// Access Type Distinguisher used for `KgNB` and `KN3IWF` derivation. (Table A.9-1 33.501)
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessTypeDistinguisher {
+    ThreeGpp = 0x01,
+    NonThreeGpp = 0x02,
+}
+
+/// Direction used for `KAMF'` derivation. (Section A.13 33.501)
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KamfPrimeDirection {
+    /// Mobility Registration Update: Uplink NAS COUNT is used.
+    MobilityRegistration = 0x00,
+    /// N2 Handover: Downlink NAS COUNT is used.
+    Handover = 0x01,
+}
+
+/// Serving Network Name used in the Key Derivations. (Section 6.1.1.4 33.501)
+///
+/// The MNC is always encoded as three digits.
+pub fn serving_network_name(mcc: u16, mnc: u16) -> String {
+    format!("5G:mnc{:03}.mcc{:03}.3gppnetwork.org", mnc, mcc)
+}
+
+/// Derive `KAUSF` from `CK`, `IK` (Section A.2 33.501)
+pub fn derive_kausf(ck: &[u8], ik: &[u8], sn_name: &str, sqn_xor_ak: &[u8]) -> SecurityKey {
+    let key = [ck, ik].concat();
+
+    kdf_common(
+        key,
+        KAUSF_DERIVE_FC,
+        &[
+            KdfParam::from_str(sn_name),
+            KdfParam::from_bytes(sqn_xor_ak),
+        ],
+    )
+}
+
+/// Derive `RES*` (in the UE) or `XRES*` (in the Home Network) from `CK`, `IK`. (Section A.4
+/// 33.501)
+pub fn derive_res_star(ck: &[u8], ik: &[u8], sn_name: &str, rand: &[u8], res: &[u8]) -> ResStar {
+    let key = [ck, ik].concat();
+
+    let kdf = kdf_common(
+        key,
+        RES_STAR_DERIVE_FC,
+        &[
+            KdfParam::from_str(sn_name),
+            KdfParam::from_bytes(rand),
+            KdfParam::from_bytes(res),
+        ],
+    );
+
+    kdf[16..].try_into().unwrap()
+}
+
+/// Derive `HRES*` (in the SEAF) or `HXRES*` (in the AUSF) from `RES*` or `XRES*` (Section A.5
+/// 33.501)
+pub fn derive_hres_star(rand: &[u8], res_star: &ResStar) -> ResStar {
+    let mut hasher = Sha256::new();
+    hasher.update(rand);
+    hasher.update(res_star);
+
+    hasher.finalize()[16..].try_into().unwrap()
+}
+
+/// Derive `KSEAF` from `KAUSF` (Section A.6 33.501)
+pub fn derive_kseaf(kausf: &SecurityKey, sn_name: &str) -> SecurityKey {
+    kdf_common(kausf, KSEAF_DERIVE_FC, &[KdfParam::from_str(sn_name)])
+}
+
+/// Derive `KAMF` from `KSEAF` (Section A.7 33.501)
+///
+/// `supi` is the value of the SUPI without the type prefix. (eg. The IMSI digits for IMSI based
+/// SUPI.)
+pub fn derive_kamf(kseaf: &SecurityKey, supi: &str, abba: &[u8]) -> SecurityKey {
+    kdf_common(
+        kseaf,
+        KAMF_DERIVE_FC,
+        &[KdfParam::from_str(supi), KdfParam::from_bytes(abba)],
+    )
+}
+
+/// Derive `KgNB` (or `KN3IWF`) from `KAMF` (Section A.9 33.501)
+pub fn derive_kgnb(
+    kamf: &SecurityKey,
+    uplink_nas_count: u32,
+    access_type: AccessTypeDistinguisher,
+) -> SecurityKey {
+    let count = uplink_nas_count.to_be_bytes();
+    let access_type = [access_type as u8];
+
+    kdf_common(
+        kamf,
+        KGNB_DERIVE_FC,
+        &[
+            KdfParam::from_bytes(&count),
+            KdfParam::from_bytes(&access_type),
+        ],
+    )
+}
+
+/// Derive `KN3IWF` from `KAMF` (Section A.9 33.501)
+pub fn derive_kn3iwf(kamf: &SecurityKey, uplink_nas_count: u32) -> SecurityKey {
+    derive_kgnb(kamf, uplink_nas_count, AccessTypeDistinguisher::NonThreeGpp)
+}
+
+/// Derive Next Hop `NH` from `KAMF` (Section A.10 33.501)
+///
+/// `sync_input` is the `KgNB` for the first `NH` derived after the initial context setup and the
+/// previous `NH` otherwise.
+pub fn derive_nh(kamf: &SecurityKey, sync_input: &SecurityKey) -> SecurityKey {
+    kdf_common(kamf, NH_DERIVE_FC, &[KdfParam::from_bytes(sync_input)])
+}
+
+/// Derive `KAMF'` from `KAMF` during mobility (Horizontal Key Derivation) (Section A.13 33.501)
+pub fn derive_kamf_prime(
+    kamf: &SecurityKey,
+    direction: KamfPrimeDirection,
+    count: u32,
+) -> SecurityKey {
+    let direction = [direction as u8];
+    let count = count.to_be_bytes();
+
+    kdf_common(
+        kamf,
+        KAMF_PRIME_DERIVE_FC,
+        &[
+            KdfParam::from_bytes(&direction),
+            KdfParam::from_bytes(&count),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 33.501 does not provide test data for Annex A. The inputs are outputs of the 35.208 Test Set
+    // 1 and the expected values are independently computed using HMAC-SHA-256 as per 33.220 B.2.
+    const CK: &str = "b40ba9a3c58b2a05bbf0d987b21bf8cb";
+    const IK: &str = "f769bcd751044604127672711c6d3441";
+    const RAND: &str = "23553cbe9637a89d218ae64dae47bf35";
+    const RES: &str = "a54211d5e3ba50bf";
+    const SQN_XOR_AK: &str = "55f328b43577";
+
+    fn decode<const N: usize>(s: &str) -> [u8; N] {
+        hex::decode(s).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_serving_network_name() {
+        assert_eq!(
+            serving_network_name(1, 1),
+            "5G:mnc001.mcc001.3gppnetwork.org"
+        );
+        assert_eq!(
+            serving_network_name(310, 410),
+            "5G:mnc410.mcc310.3gppnetwork.org"
+        );
+    }
+
+    #[test]
+    fn test_5g_aka_key_hierarchy() {
+        let ck = hex::decode(CK).unwrap();
+        let ik = hex::decode(IK).unwrap();
+        let rand = hex::decode(RAND).unwrap();
+        let res = hex::decode(RES).unwrap();
+        let sqn_xor_ak = hex::decode(SQN_XOR_AK).unwrap();
+        let sn_name = serving_network_name(1, 1);
+
+        let kausf = derive_kausf(&ck, &ik, &sn_name, &sqn_xor_ak);
+        assert_eq!(
+            kausf,
+            decode("474698caf02cc715db2ec0726510cfee6caa5bb1a649cb01224f2e23af94de1b")
+        );
+
+        let res_star = derive_res_star(&ck, &ik, &sn_name, &rand, &res);
+        assert_eq!(res_star, decode("f236a7417272bfb2d66d4d670733b527"));
+
+        let hres_star = derive_hres_star(&rand, &res_star);
+        assert_eq!(hres_star, decode("20a71900b01776bfd773e8c15a825446"));
+
+        let kseaf = derive_kseaf(&kausf, &sn_name);
+        assert_eq!(
+            kseaf,
+            decode("8dff166c02edd5b177950d50cdd3fe93756cc53951856a95cb5ee9aabd35e220")
+        );
+
+        let kamf = derive_kamf(&kseaf, "001010000000001", &[0x00, 0x00]);
+        assert_eq!(
+            kamf,
+            decode("daae216bc3dc9c6e0db9e56d2b744ea247d67eed51fdf2411847d056ec45a666")
+        );
+    }
+
+    #[test]
+    fn test_kamf_derived_keys() {
+        let kamf = decode("daae216bc3dc9c6e0db9e56d2b744ea247d67eed51fdf2411847d056ec45a666");
+
+        let kgnb = derive_kgnb(&kamf, 0, AccessTypeDistinguisher::ThreeGpp);
+        assert_eq!(
+            kgnb,
+            decode("d5b4598dcce4a0ce1232001e8ebe0d4d312226c08928239324639f0865d7ea9d")
+        );
+
+        let kn3iwf = derive_kn3iwf(&kamf, 0);
+        assert_eq!(
+            kn3iwf,
+            decode("4a44c908a581664ac63771e2b911b5eb494036469d37dd0da91376d44c64d892")
+        );
+
+        let nh = derive_nh(&kamf, &kgnb);
+        assert_eq!(
+            nh,
+            decode("eb2ee43f2f9278c7b9076cf011cfadff447065db65a1f5d52ecf433eab9a7dd6")
+        );
+
+        let kamf_prime = derive_kamf_prime(&kamf, KamfPrimeDirection::MobilityRegistration, 5);
+        assert_eq!(
+            kamf_prime,
+            decode("9a6c34d54c0c75440911409c57653d4148af67d2980ecb1fd64c2c17ca46ba30")
+        );
+    }
+}
@@ -7,4 +7,39 @@ mod kdf;
 pub use kdf::{kdf_common, KdfParam, SecurityKey};
 
 mod aes_internal;
-pub use aes_internal::{encrypt_aes128_ctr, mac_aes128_cmac, AesIv, AesKey};
+pub use aes_internal::{
+    encrypt_aes128_block, encrypt_aes128_ctr, mac_aes128_cmac, AesBlock, AesIv, AesKey,
+};
+
+mod snow3g;
+pub use snow3g::{
+    encrypt_snow3g_f8, mac_snow3g_f9, Snow3g, Snow3gIv, Snow3gKey, Snow3gKeyWords, Snow3gMac,
+};
+
+mod zuc;
+pub use zuc::{encrypt_zuc_eea3, mac_zuc_eia3, Zuc, ZucIv, ZucKey, ZucMac};
+
+mod auth;
+pub use auth::{Ak, AuthAlgorithm, AuthMgmtField, AuthVector, Rand, Sqn};
+
+mod milenage;
+pub use milenage::{milenage_opc, Milenage, MilenageKey, Op};
+
+mod tuak;
+pub use tuak::{tuak_topc, Top, Tuak, TuakKey, TuakKeyLength, TuakMacLength, TuakResLength};
+
+mod key_hierarchy;
+pub use key_hierarchy::{
+    derive_hres_star, derive_kamf, derive_kamf_prime, derive_kausf, derive_kgnb, derive_kn3iwf,
+    derive_kseaf, derive_nh, derive_res_star, serving_network_name, AccessTypeDistinguisher,
+    KamfPrimeDirection, ResStar,
+};
+
+mod eap_aka_prime;
+pub use eap_aka_prime::{derive_ck_ik_prime, mac_eap_aka_prime, CkIkPrime, EapAkaPrimeKeys};
+
+mod sidf;
+pub use sidf::{HomeNetworkKeyStore, HomeNetworkPrivateKey, ProtectionSchemeId, SidfError};
+
+mod utils;
+pub use utils::{constant_time_eq, zero_trailing_bits};
@@ -0,0 +1,260 @@
+//! MILENAGE Authentication and Key Generation functions.
+//!
+//! Implementation of the functions `f1`, `f1*`, `f2`, `f3`, `f4`, `f5` and `f5*` as specified in
+//! 3GPP 35.206 using AES 128 as the kernel function. Also provides the derivation of `OPc` from
+//! the `OP`.
+
+use crate::aes_internal::encrypt_aes128_block;
+use crate::auth::{Ak, AuthAlgorithm, AuthMgmtField, Rand, Sqn};
+
+/// Subscriber Key `K`
+pub type MilenageKey = [u8; 16];
+
+/// Operator Variant Algorithm Configuration Field `OP` (or `OPc` derived from it.)
+pub type Op = [u8; 16];
+
+// Constants `c1` to `c5` and rotations `r1` to `r5` (in octets) (Section 4.1 35.206)
+const C: [u8; 5] = [0x00, 0x01, 0x02, 0x04, 0x08];
+const R: [usize; 5] = [8, 0, 4, 8, 12];
+
+/// Derive `OPc` from `OP` and the Subscriber Key `K`. (Section 4.1 35.206)
+pub fn milenage_opc(key: MilenageKey, op: Op) -> Op {
+    xor16(encrypt_aes128_block(key, op), op)
+}
+
+/// MILENAGE Algorithm Set for a subscriber identified by the Subscriber Key and `OPc`.
+#[derive(Debug, Clone)]
+pub struct Milenage {
+    key: MilenageKey,
+    opc: Op,
+}
+
+impl Milenage {
+    /// Create the Algorithm set using `K` and `OP`. The `OPc` is derived from the `OP`.
+    pub fn from_op(key: MilenageKey, op: Op) -> Self {
+        Self {
+            key,
+            opc: milenage_opc(key, op),
+        }
+    }
+
+    /// Create the Algorithm set using `K` and `OPc`.
+    pub fn from_opc(key: MilenageKey, opc: Op) -> Self {
+        Self { key, opc }
+    }
+
+    /// Get the `OPc` used by this Algorithm set.
+    pub fn opc(&self) -> Op {
+        self.opc
+    }
+
+    // TEMP = E[RAND XOR OPc]K
+    fn temp(&self, rand: Rand) -> [u8; 16] {
+        encrypt_aes128_block(self.key, xor16(rand, self.opc))
+    }
+
+    // OUT1 = E[TEMP XOR rot(IN1 XOR OPc, r1) XOR c1]K XOR OPc
+    fn out1(&self, rand: Rand, sqn: Sqn, amf: AuthMgmtField) -> [u8; 16] {
+        let mut in1 = [0_u8; 16];
+        in1[0..6].copy_from_slice(&sqn);
+        in1[6..8].copy_from_slice(&amf);
+        in1.copy_within(0..8, 8);
+
+        let mut input = rotate(xor16(in1, self.opc), R[0]);
+        input[15] ^= C[0];
+        let input = xor16(self.temp(rand), input);
+
+        xor16(encrypt_aes128_block(self.key, input), self.opc)
+    }
+
+    // OUTn = E[rot(TEMP XOR OPc, rn) XOR cn]K XOR OPc, for n = 2..5 (`idx` = n - 1)
+    fn out(&self, rand: Rand, idx: usize) -> [u8; 16] {
+        let mut input = rotate(xor16(self.temp(rand), self.opc), R[idx]);
+        input[15] ^= C[idx];
+
+        xor16(encrypt_aes128_block(self.key, input), self.opc)
+    }
+}
+
+impl AuthAlgorithm for Milenage {
+    fn f1(&self, rand: Rand, sqn: Sqn, amf: AuthMgmtField) -> Vec<u8> {
+        self.out1(rand, sqn, amf)[..8].to_vec()
+    }
+
+    fn f1_star(&self, rand: Rand, sqn: Sqn, amf: AuthMgmtField) -> Vec<u8> {
+        self.out1(rand, sqn, amf)[8..].to_vec()
+    }
+
+    fn f2(&self, rand: Rand) -> Vec<u8> {
+        self.out(rand, 1)[8..].to_vec()
+    }
+
+    fn f3(&self, rand: Rand) -> Vec<u8> {
+        self.out(rand, 2).to_vec()
+    }
+
+    fn f4(&self, rand: Rand) -> Vec<u8> {
+        self.out(rand, 3).to_vec()
+    }
+
+    fn f5(&self, rand: Rand) -> Ak {
+        self.out(rand, 1)[..6].try_into().unwrap()
+    }
+
+    fn f5_star(&self, rand: Rand) -> Ak {
+        self.out(rand, 4)[..6].try_into().unwrap()
+    }
+}
+
+fn xor16(a: [u8; 16], b: [u8; 16]) -> [u8; 16] {
+    let mut out = a;
+    for (o, b) in out.iter_mut().zip(b) {
+        *o ^= b;
+    }
+    out
+}
+
+// Cyclic rotation of the 128 bit value towards the most significant bit by `octets` octets.
+fn rotate(input: [u8; 16], octets: usize) -> [u8; 16] {
+    let mut out = input;
+    out.rotate_left(octets);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::AuthAlgorithm;
+
+    #[test]
+    fn test_35_208_test_sets() {
+        struct TestSet<'ts> {
+            name: &'ts str,
+            key: &'ts str,
+            rand: &'ts str,
+            sqn: &'ts str,
+            amf: &'ts str,
+            op: &'ts str,
+            opc: &'ts str,
+            f1: &'ts str,
+            f1_star: &'ts str,
+            f2: &'ts str,
+            f5: &'ts str,
+            f3: &'ts str,
+            f4: &'ts str,
+            f5_star: &'ts str,
+        }
+
+        // 35.208 Section 4.3
+        let testsets = vec![
+            TestSet {
+                name: "TestSet 1",
+                key: "465b5ce8b199b49faa5f0a2ee238a6bc",
+                rand: "23553cbe9637a89d218ae64dae47bf35",
+                sqn: "ff9bb4d0b607",
+                amf: "b9b9",
+                op: "cdc202d5123e20f62b6d676ac72cb318",
+                opc: "cd63cb71954a9f4e48a5994e37a02baf",
+                f1: "4a9ffac354dfafb3",
+                f1_star: "01cfaf9ec4e871e9",
+                f2: "a54211d5e3ba50bf",
+                f5: "aa689c648370",
+                f3: "b40ba9a3c58b2a05bbf0d987b21bf8cb",
+                f4: "f769bcd751044604127672711c6d3441",
+                f5_star: "451e8beca43b",
+            },
+            TestSet {
+                name: "TestSet 2",
+                key: "0396eb317b6d1c36f19c1c84cd6ffd16",
+                rand: "c00d603103dcee52c4478119494202e8",
+                sqn: "fd8eef40df7d",
+                amf: "af17",
+                op: "ff53bade17df5d4e793073ce9d7579fa",
+                opc: "53c15671c60a4b731c55b4a441c0bde2",
+                f1: "5df5b31807e258b0",
+                f1_star: "a8c016e51ef4a343",
+                f2: "d3a628ed988620f0",
+                f5: "c47783995f72",
+                f3: "58c433ff7a7082acd424220f2b67c556",
+                f4: "21a8c1f929702adb3e738488b9f5c5da",
+                f5_star: "30f1197061c1",
+            },
+        ];
+
+        for ts in testsets {
+            let key = hex::decode(ts.key).unwrap().try_into().unwrap();
+            let op = hex::decode(ts.op).unwrap().try_into().unwrap();
+            let rand = hex::decode(ts.rand).unwrap().try_into().unwrap();
+            let sqn = hex::decode(ts.sqn).unwrap().try_into().unwrap();
+            let amf = hex::decode(ts.amf).unwrap().try_into().unwrap();
+
+            let milenage = super::Milenage::from_op(key, op);
+
+            assert_eq!(hex::encode(milenage.opc()), ts.opc, "{}: OPc", ts.name);
+            assert_eq!(
+                hex::encode(milenage.f1(rand, sqn, amf)),
+                ts.f1,
+                "{}: f1",
+                ts.name
+            );
+            assert_eq!(
+                hex::encode(milenage.f1_star(rand, sqn, amf)),
+                ts.f1_star,
+                "{}: f1*",
+                ts.name
+            );
+            assert_eq!(hex::encode(milenage.f2(rand)), ts.f2, "{}: f2", ts.name);
+            assert_eq!(hex::encode(milenage.f3(rand)), ts.f3, "{}: f3", ts.name);
+            assert_eq!(hex::encode(milenage.f4(rand)), ts.f4, "{}: f4", ts.name);
+            assert_eq!(hex::encode(milenage.f5(rand)), ts.f5, "{}: f5", ts.name);
+            assert_eq!(
+                hex::encode(milenage.f5_star(rand)),
+                ts.f5_star,
+                "{}: f5*",
+                ts.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_auth_vector_and_resync() {
+        let key = hex::decode("465b5ce8b199b49faa5f0a2ee238a6bc")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let opc = hex::decode("cd63cb71954a9f4e48a5994e37a02baf")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let rand = hex::decode("23553cbe9637a89d218ae64dae47bf35")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let sqn = hex::decode("ff9bb4d0b607").unwrap().try_into().unwrap();
+
+        let milenage = super::Milenage::from_opc(key, opc);
+        let av = milenage.generate_auth_vector(rand, sqn, [0xb9, 0xb9]);
+
+        assert_eq!(hex::encode(av.autn), "55f328b43577b9b94a9ffac354dfafb3");
+        assert_eq!(hex::encode(av.xres), "a54211d5e3ba50bf");
+
+        // AUTS = SQN_MS XOR AK (f5*) || MAC-S (f1* with AMF of all zeros)
+        let mut auts = [0_u8; 14];
+        for (i, (s, a)) in sqn.iter().zip(milenage.f5_star(rand)).enumerate() {
+            auts[i] = s ^ a;
+        }
+        auts[6..].copy_from_slice(&milenage.f1_star(rand, sqn, [0, 0]));
+
+        assert_eq!(milenage.resync_sqn(rand, &auts), Some(sqn));
+
+        auts[13] ^= 0x01;
+        assert_eq!(milenage.resync_sqn(rand, &auts), None);
+
+        // `AUTS` with a `MAC-S` longer than that of MILENAGE.
+        let mut auts = auts.to_vec();
+        auts[13] ^= 0x01;
+        auts.extend([0_u8; 8]);
+        assert_eq!(milenage.resync_sqn(rand, &auts), None);
+        assert_eq!(milenage.resync_sqn(rand, &auts[..4]), None);
+    }
+}
@@ -0,0 +1,387 @@
+//! Subscription Identifier De-concealing Function (SIDF)
+//!
+//! De-concealment of the SUCI Scheme Output using the ECIES Protection Schemes (Profile A and
+//! Profile B) and the Null Scheme as specified in the Annex C of 33.501.
+
+use std::collections::HashMap;
+
+use ctr::cipher::{KeyIvInit, StreamCipher};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+type Aes128Ctr128BE = ctr::Ctr128BE<aes::Aes128>;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// Length of the ECIES Ephemeral Encryption Key (Table C.3.4.1-1/C.3.4.2-1 33.501)
+const ENC_KEY_LENGTH: usize = 16;
+
+// Length of the ECIES Initial Counter Block
+const ICB_LENGTH: usize = 16;
+
+// Length of the ECIES Ephemeral MAC Key
+const MAC_KEY_LENGTH: usize = 32;
+
+// Length of the ECIES MAC Tag
+const MAC_TAG_LENGTH: usize = 8;
+
+// Length of the X25519 Ephemeral Public Key (Profile A)
+const PROFILE_A_PUBLIC_KEY_LENGTH: usize = 32;
+
+// Length of the compressed secp256r1 Ephemeral Public Key (Profile B)
+const PROFILE_B_PUBLIC_KEY_LENGTH: usize = 33;
+
+/// SUCI Protection Scheme Identifier (Section 5.3.2 23.003, Annex C.1 33.501)
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProtectionSchemeId {
+    NullScheme = 0x00,
+    EciesProfileA = 0x01,
+    EciesProfileB = 0x02,
+}
+
+impl ProtectionSchemeId {
+    /// Get the `ProtectionSchemeId` from it's encoded value. `None` for Reserved or Operator
+    /// Specific schemes.
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0x00 => Some(Self::NullScheme),
+            0x01 => Some(Self::EciesProfileA),
+            0x02 => Some(Self::EciesProfileB),
+            _ => None,
+        }
+    }
+}
+
+/// Errors during de-concealment of the SUCI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SidfError {
+    /// Protection Scheme is not supported.
+    UnsupportedProtectionScheme(u8),
+
+    /// No Home Network Private Key for the given Home Network Public Key Identifier.
+    UnknownHomeNetworkKey(u8),
+
+    /// Home Network Public Key Identifier is not valid for the Protection Scheme. (eg. Non zero
+    /// identifier for the Null Scheme.)
+    InvalidHomeNetworkKey(u8),
+
+    /// Scheme Output is not well formed. (Too short or invalid Ephemeral Public Key.)
+    InvalidSchemeOutput,
+
+    /// MAC Tag verification of the Scheme Output failed.
+    MacVerificationFailed,
+}
+
+impl std::fmt::Display for SidfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnsupportedProtectionScheme(id) => {
+                write!(f, "Unsupported Protection Scheme: {}", id)
+            }
+            Self::UnknownHomeNetworkKey(id) => write!(f, "Unknown Home Network Key: {}", id),
+            Self::InvalidHomeNetworkKey(id) => write!(f, "Invalid Home Network Key: {}", id),
+            Self::InvalidSchemeOutput => write!(f, "Invalid Scheme Output"),
+            Self::MacVerificationFailed => write!(f, "MAC Verification Failed"),
+        }
+    }
+}
+
+impl std::error::Error for SidfError {}
+
+/// Home Network Private Key used for de-concealment of the SUCI.
+#[derive(Clone)]
+pub enum HomeNetworkPrivateKey {
+    /// Curve25519 Private Key for ECIES Profile A
+    ProfileA(x25519_dalek::StaticSecret),
+
+    /// secp256r1 Private Key for ECIES Profile B
+    ProfileB(p256::SecretKey),
+}
+
+impl std::fmt::Debug for HomeNetworkPrivateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Never print the Private Key.
+        match self {
+            Self::ProfileA(_) => write!(f, "ProfileA(..)"),
+            Self::ProfileB(_) => write!(f, "ProfileB(..)"),
+        }
+    }
+}
+
+impl HomeNetworkPrivateKey {
+    /// Create a Profile A Private Key from it's 32 octets.
+    pub fn profile_a(private_key: [u8; 32]) -> Self {
+        Self::ProfileA(x25519_dalek::StaticSecret::from(private_key))
+    }
+
+    /// Create a Profile B Private Key from it's 32 octets. Returns `None` if the scalar is not
+    /// valid for the curve.
+    pub fn profile_b(private_key: &[u8]) -> Option<Self> {
+        p256::SecretKey::from_slice(private_key)
+            .ok()
+            .map(Self::ProfileB)
+    }
+
+    /// The Protection Scheme for this Private Key.
+    pub fn protection_scheme(&self) -> ProtectionSchemeId {
+        match self {
+            Self::ProfileA(_) => ProtectionSchemeId::EciesProfileA,
+            Self::ProfileB(_) => ProtectionSchemeId::EciesProfileB,
+        }
+    }
+
+    /// Home Network Public Key corresponding to this Private Key. (For provisioning in the
+    /// USIM/ME.) Profile B Public Key is in the compressed form.
+    pub fn public_key(&self) -> Vec<u8> {
+        match self {
+            Self::ProfileA(secret) => x25519_dalek::PublicKey::from(secret).as_bytes().to_vec(),
+            Self::ProfileB(secret) => {
+                use p256::elliptic_curve::sec1::ToEncodedPoint;
+
+                secret
+                    .public_key()
+                    .to_encoded_point(true)
+                    .as_bytes()
+                    .to_vec()
+            }
+        }
+    }
+
+    // Length of the Ephemeral Public Key in the Scheme Output.
+    fn ephemeral_key_length(&self) -> usize {
+        match self {
+            Self::ProfileA(_) => PROFILE_A_PUBLIC_KEY_LENGTH,
+            Self::ProfileB(_) => PROFILE_B_PUBLIC_KEY_LENGTH,
+        }
+    }
+
+    // Calculate the ECDH shared key using the Ephemeral Public Key of the UE.
+    fn shared_key(&self, public_key: &[u8]) -> Result<Vec<u8>, SidfError> {
+        match self {
+            Self::ProfileA(secret) => {
+                let public_key: [u8; PROFILE_A_PUBLIC_KEY_LENGTH] = public_key
+                    .try_into()
+                    .map_err(|_| SidfError::InvalidSchemeOutput)?;
+                let public_key = x25519_dalek::PublicKey::from(public_key);
+                Ok(secret.diffie_hellman(&public_key).as_bytes().to_vec())
+            }
+            Self::ProfileB(secret) => {
+                let public_key = p256::PublicKey::from_sec1_bytes(public_key)
+                    .map_err(|_| SidfError::InvalidSchemeOutput)?;
+                let shared =
+                    p256::ecdh::diffie_hellman(secret.to_nonzero_scalar(), public_key.as_affine());
+                Ok(shared.raw_secret_bytes().to_vec())
+            }
+        }
+    }
+
+    /// De-conceal the Scheme Output of an ECIES protected SUCI and return the Scheme Input (eg.
+    /// The BCD encoded MSIN for an IMSI based SUPI.) (Section C.3.3 33.501)
+    pub fn deconceal(&self, scheme_output: &[u8]) -> Result<Vec<u8>, SidfError> {
+        let public_key_length = self.ephemeral_key_length();
+        if scheme_output.len() < public_key_length + MAC_TAG_LENGTH {
+            return Err(SidfError::InvalidSchemeOutput);
+        }
+
+        let (public_key, rest) = scheme_output.split_at(public_key_length);
+        let (ciphertext, mac_tag) = rest.split_at(rest.len() - MAC_TAG_LENGTH);
+
+        let shared_key = self.shared_key(public_key)?;
+
+        let keys = ansi_x963_kdf(
+            &shared_key,
+            public_key,
+            ENC_KEY_LENGTH + ICB_LENGTH + MAC_KEY_LENGTH,
+        );
+        let (enc_key, rest) = keys.split_at(ENC_KEY_LENGTH);
+        let (icb, mac_key) = rest.split_at(ICB_LENGTH);
+
+        let mut mac = HmacSha256::new_from_slice(mac_key).unwrap();
+        mac.update(ciphertext);
+        mac.verify_truncated_left(mac_tag)
+            .map_err(|_| SidfError::MacVerificationFailed)?;
+
+        let mut plaintext = ciphertext.to_vec();
+        let mut cipher = Aes128Ctr128BE::new(enc_key.into(), icb.into());
+        cipher.apply_keystream(&mut plaintext);
+
+        Ok(plaintext)
+    }
+}
+
+/// Store of the Home Network Private Keys identified by the Home Network Public Key Identifier.
+#[derive(Debug, Default, Clone)]
+pub struct HomeNetworkKeyStore {
+    keys: HashMap<u8, HomeNetworkPrivateKey>,
+}
+
+impl HomeNetworkKeyStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a Private Key for the given Home Network Public Key Identifier. Returns the previous
+    /// key if any for the identifier.
+    pub fn add_key(
+        &mut self,
+        home_network_pki: u8,
+        key: HomeNetworkPrivateKey,
+    ) -> Option<HomeNetworkPrivateKey> {
+        self.keys.insert(home_network_pki, key)
+    }
+
+    /// Remove the Private Key for the given Home Network Public Key Identifier.
+    pub fn remove_key(&mut self, home_network_pki: u8) -> Option<HomeNetworkPrivateKey> {
+        self.keys.remove(&home_network_pki)
+    }
+
+    /// Get the Private Key for the given Home Network Public Key Identifier.
+    pub fn get_key(&self, home_network_pki: u8) -> Option<&HomeNetworkPrivateKey> {
+        self.keys.get(&home_network_pki)
+    }
+
+    /// De-conceal the Scheme Output using the given Protection Scheme and Home Network Public Key
+    /// Identifier and return the Scheme Input.
+    ///
+    /// For the Null Scheme, the Scheme Output is returned as it is.
+    pub fn deconceal(
+        &self,
+        protection_scheme: u8,
+        home_network_pki: u8,
+        scheme_output: &[u8],
+    ) -> Result<Vec<u8>, SidfError> {
+        let scheme = ProtectionSchemeId::from_u8(protection_scheme)
+            .ok_or(SidfError::UnsupportedProtectionScheme(protection_scheme))?;
+
+        if scheme == ProtectionSchemeId::NullScheme {
+            if home_network_pki != 0 {
+                return Err(SidfError::InvalidHomeNetworkKey(home_network_pki));
+            }
+            return Ok(scheme_output.to_vec());
+        }
+
+        let key = self
+            .get_key(home_network_pki)
+            .ok_or(SidfError::UnknownHomeNetworkKey(home_network_pki))?;
+        if key.protection_scheme() != scheme {
+            return Err(SidfError::InvalidHomeNetworkKey(home_network_pki));
+        }
+
+        key.deconceal(scheme_output)
+    }
+}
+
+// ANSI-X9.63 Key Derivation Function using SHA-256 (Section C.3.2 33.501)
+fn ansi_x963_kdf(shared_key: &[u8], shared_info: &[u8], length: usize) -> Vec<u8> {
+    let mut output = Vec::with_capacity(length);
+    let mut counter = 1_u32;
+
+    while output.len() < length {
+        let mut hasher = Sha256::new();
+        hasher.update(shared_key);
+        hasher.update(counter.to_be_bytes());
+        hasher.update(shared_info);
+        output.extend_from_slice(&hasher.finalize());
+        counter += 1;
+    }
+    output.truncate(length);
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test Data from Annex C.4 33.501
+    const MSIN: &str = "00012080f6";
+
+    const PROFILE_A_PRIVATE_KEY: &str =
+        "c53c22208b61860b06c62e5406a7b330c2b577aa5558981510d128247d38bd1d";
+    const PROFILE_A_PUBLIC_KEY: &str =
+        "5a8d38864820197c3394b92613b20b91633cbd897119273bf8e4a6f4eec0a650";
+    const PROFILE_A_SCHEME_OUTPUT: &str = "b2e92f836055a255837debf850b528997ce0201cb82adfe4be1f587d07d8457dcb02352410cddd9e730ef3fa87";
+
+    const PROFILE_B_PRIVATE_KEY: &str =
+        "f1ab1074477ebcc7f554ea1c5fc368b1616730155e0041ac447d6301975fecda";
+    const PROFILE_B_PUBLIC_KEY: &str =
+        "0272da71976234ce833a6907425867b82e074d44ef907dfb4b3e21c1c2256ebcd1";
+    const PROFILE_B_SCHEME_OUTPUT: &str = "039aab8376597021e855679a9778ea0b67396e68c66df32c0f41e9acca2da9b9d146a33fc2716ac7dae96aa30a4d";
+
+    fn key_store() -> HomeNetworkKeyStore {
+        let mut store = HomeNetworkKeyStore::new();
+
+        let key_a = hex::decode(PROFILE_A_PRIVATE_KEY).unwrap();
+        let key_a = HomeNetworkPrivateKey::profile_a(key_a.try_into().unwrap());
+        assert_eq!(hex::encode(key_a.public_key()), PROFILE_A_PUBLIC_KEY);
+        store.add_key(1, key_a);
+
+        let key_b = hex::decode(PROFILE_B_PRIVATE_KEY).unwrap();
+        let key_b = HomeNetworkPrivateKey::profile_b(&key_b).unwrap();
+        assert_eq!(hex::encode(key_b.public_key()), PROFILE_B_PUBLIC_KEY);
+        store.add_key(2, key_b);
+
+        store
+    }
+
+    #[test]
+    fn test_c_4_3_profile_a() {
+        let store = key_store();
+        let scheme_output = hex::decode(PROFILE_A_SCHEME_OUTPUT).unwrap();
+
+        let msin = store.deconceal(1, 1, &scheme_output);
+        assert_eq!(msin, Ok(hex::decode(MSIN).unwrap()));
+    }
+
+    #[test]
+    fn test_c_4_4_profile_b() {
+        let store = key_store();
+        let scheme_output = hex::decode(PROFILE_B_SCHEME_OUTPUT).unwrap();
+
+        let msin = store.deconceal(2, 2, &scheme_output);
+        assert_eq!(msin, Ok(hex::decode(MSIN).unwrap()));
+    }
+
+    #[test]
+    fn test_null_scheme() {
+        let store = key_store();
+        let scheme_output = hex::decode(MSIN).unwrap();
+
+        assert_eq!(store.deconceal(0, 0, &scheme_output), Ok(scheme_output));
+        assert_eq!(
+            store.deconceal(0, 1, &[]),
+            Err(SidfError::InvalidHomeNetworkKey(1))
+        );
+    }
+
+    #[test]
+    fn test_deconceal_errors() {
+        let store = key_store();
+        let mut scheme_output = hex::decode(PROFILE_A_SCHEME_OUTPUT).unwrap();
+
+        assert_eq!(
+            store.deconceal(1, 3, &scheme_output),
+            Err(SidfError::UnknownHomeNetworkKey(3))
+        );
+        assert_eq!(
+            store.deconceal(2, 1, &scheme_output),
+            Err(SidfError::InvalidHomeNetworkKey(1))
+        );
+        assert_eq!(
+            store.deconceal(5, 1, &scheme_output),
+            Err(SidfError::UnsupportedProtectionScheme(5))
+        );
+        assert_eq!(
+            store.deconceal(1, 1, &scheme_output[..32]),
+            Err(SidfError::InvalidSchemeOutput)
+        );
+
+        let last = scheme_output.len() - 1;
+        scheme_output[last] ^= 0x01;
+        assert_eq!(
+            store.deconceal(1, 1, &scheme_output),
+            Err(SidfError::MacVerificationFailed)
+        );
+    }
+}
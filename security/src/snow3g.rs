@@ -0,0 +1,485 @@
+//! SNOW 3G based Encryption and Integrity functions.
+//!
+//! The SNOW 3G Keystream generator and the `f8` (UEA2) and `f9` (UIA2) functions as specified by
+//! the ETSI/SAGE "Specification of the 3GPP Confidentiality and Integrity Algorithms UEA2 & UIA2"
+//! Documents 1 and 2. These are used as 128-EEA1/128-EIA1 (33.401) and 128-NEA1/128-NIA1
+//! (33.501).
+
+use crate::utils::zero_trailing_bits;
+
+pub type Snow3gKey = [u8; 16];
+pub type Snow3gKeyWords = [u32; 4];
+pub type Snow3gIv = [u32; 4];
+pub type Snow3gMac = [u8; 4];
+
+// Rijndael S-Box `SR` (Section 3.3.1 SNOW 3G Specification)
+const SR: [u8; 256] = [
+    0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+    0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+    0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+    0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+    0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+    0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+    0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+    0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+    0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+    0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+    0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+    0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+    0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+    0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+    0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+    0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,
+];
+
+// S-Box `SQ` based on Dickson Polynomial (Section 3.3.2 SNOW 3G Specification)
+const SQ: [u8; 256] = [
+    0x25, 0x24, 0x73, 0x67, 0xd7, 0xae, 0x5c, 0x30, 0xa4, 0xee, 0x6e, 0xcb, 0x7d, 0xb5, 0x82, 0xdb,
+    0xe4, 0x8e, 0x48, 0x49, 0x4f, 0x5d, 0x6a, 0x78, 0x70, 0x88, 0xe8, 0x5f, 0x5e, 0x84, 0x65, 0xe2,
+    0xd8, 0xe9, 0xcc, 0xed, 0x40, 0x2f, 0x11, 0x28, 0x57, 0xd2, 0xac, 0xe3, 0x4a, 0x15, 0x1b, 0xb9,
+    0xb2, 0x80, 0x85, 0xa6, 0x2e, 0x02, 0x47, 0x29, 0x07, 0x4b, 0x0e, 0xc1, 0x51, 0xaa, 0x89, 0xd4,
+    0xca, 0x01, 0x46, 0xb3, 0xef, 0xdd, 0x44, 0x7b, 0xc2, 0x7f, 0xbe, 0xc3, 0x9f, 0x20, 0x4c, 0x64,
+    0x83, 0xa2, 0x68, 0x42, 0x13, 0xb4, 0x41, 0xcd, 0xba, 0xc6, 0xbb, 0x6d, 0x4d, 0x71, 0x21, 0xf4,
+    0x8d, 0xb0, 0xe5, 0x93, 0xfe, 0x8f, 0xe6, 0xcf, 0x43, 0x45, 0x31, 0x22, 0x37, 0x36, 0x96, 0xfa,
+    0xbc, 0x0f, 0x08, 0x52, 0x1d, 0x55, 0x1a, 0xc5, 0x4e, 0x23, 0x69, 0x7a, 0x92, 0xff, 0x5b, 0x5a,
+    0xeb, 0x9a, 0x1c, 0xa9, 0xd1, 0x7e, 0x0d, 0xfc, 0x50, 0x8a, 0xb6, 0x62, 0xf5, 0x0a, 0xf8, 0xdc,
+    0x03, 0x3c, 0x0c, 0x39, 0xf1, 0xb8, 0xf3, 0x3d, 0xf2, 0xd5, 0x97, 0x66, 0x81, 0x32, 0xa0, 0x00,
+    0x06, 0xce, 0xf6, 0xea, 0xb7, 0x17, 0xf7, 0x8c, 0x79, 0xd6, 0xa7, 0xbf, 0x8b, 0x3f, 0x1f, 0x53,
+    0x63, 0x75, 0x35, 0x2c, 0x60, 0xfd, 0x27, 0xd3, 0x94, 0xa5, 0x7c, 0xa1, 0x05, 0x58, 0x2d, 0xbd,
+    0xd9, 0xc7, 0xaf, 0x6b, 0x54, 0x0b, 0xe0, 0x38, 0x04, 0xc8, 0x9d, 0xe7, 0x14, 0xb1, 0x87, 0x9c,
+    0xdf, 0x6f, 0xf9, 0xda, 0x2a, 0xc4, 0x59, 0x16, 0x74, 0x91, 0xab, 0x26, 0x61, 0x76, 0x34, 0x2b,
+    0xad, 0x99, 0xfb, 0x72, 0xec, 0x33, 0x12, 0xde, 0x98, 0x3b, 0xc0, 0x9b, 0x3e, 0x18, 0x10, 0x3a,
+    0x56, 0xe1, 0x77, 0xc9, 0x1e, 0x9e, 0x95, 0xa3, 0x90, 0x19, 0xa8, 0x6c, 0x09, 0xd0, 0xf0, 0x86,
+];
+
+/// SNOW 3G Keystream Generator.
+///
+/// Initialized with a 128 bit key and a 128 bit IV, generates the keystream as 32 bit words.
+#[derive(Debug, Clone)]
+pub struct Snow3g {
+    lfsr: [u32; 16],
+    r1: u32,
+    r2: u32,
+    r3: u32,
+}
+
+impl Snow3g {
+    /// Initialize the Generator using the `key` and `iv`. (Section 4.1 SNOW 3G Specification)
+    ///
+    /// The `key` is given as `[k0, k1, k2, k3]` and the `iv` is given as `[iv0, iv1, iv2, iv3]`.
+    pub fn new(k: Snow3gKeyWords, iv: Snow3gIv) -> Self {
+        let lfsr = [
+            k[0] ^ 0xFFFFFFFF,
+            k[1] ^ 0xFFFFFFFF,
+            k[2] ^ 0xFFFFFFFF,
+            k[3] ^ 0xFFFFFFFF,
+            k[0],
+            k[1],
+            k[2],
+            k[3],
+            k[0] ^ 0xFFFFFFFF,
+            k[1] ^ 0xFFFFFFFF ^ iv[3],
+            k[2] ^ 0xFFFFFFFF ^ iv[2],
+            k[3] ^ 0xFFFFFFFF,
+            k[0] ^ iv[1],
+            k[1],
+            k[2],
+            k[3] ^ iv[0],
+        ];
+
+        let mut this = Self {
+            lfsr,
+            r1: 0,
+            r2: 0,
+            r3: 0,
+        };
+
+        for _ in 0..32 {
+            let f = this.clock_fsm();
+            this.clock_lfsr(f);
+        }
+
+        // Clock once and discard the output. (Section 4.2 SNOW 3G Specification)
+        let _ = this.clock_fsm();
+        this.clock_lfsr(0);
+
+        this
+    }
+
+    /// Generate the next `n` words of the Keystream.
+    pub fn keystream(&mut self, n: usize) -> Vec<u32> {
+        let mut out = Vec::with_capacity(n);
+        for _ in 0..n {
+            let f = self.clock_fsm();
+            out.push(f ^ self.lfsr[0]);
+            self.clock_lfsr(0);
+        }
+        out
+    }
+
+    // Clocks the LFSR. During the initialization the FSM output `f` is fed back, in the
+    // Keystream mode `f` is `0`.
+    fn clock_lfsr(&mut self, f: u32) {
+        let s0 = self.lfsr[0];
+        let s11 = self.lfsr[11];
+        let v = (s0 << 8)
+            ^ mul_alpha((s0 >> 24) as u8)
+            ^ self.lfsr[2]
+            ^ (s11 >> 8)
+            ^ div_alpha(s11 as u8)
+            ^ f;
+
+        self.lfsr.copy_within(1.., 0);
+        self.lfsr[15] = v;
+    }
+
+    fn clock_fsm(&mut self) -> u32 {
+        let f = self.lfsr[15].wrapping_add(self.r1) ^ self.r2;
+        let r = self.r2.wrapping_add(self.r3 ^ self.lfsr[5]);
+        self.r3 = s2(self.r2);
+        self.r2 = s1(self.r1);
+        self.r1 = r;
+
+        f
+    }
+}
+
+// Both `f8` and `f9` load the key such that the first four bytes of the key form `k3` and the last
+// four bytes form `k0`. (Section 3.4 UEA2 Specification and Section 4.3 UIA2 Specification)
+fn key_words(key: Snow3gKey) -> Snow3gKeyWords {
+    let mut k = [0_u32; 4];
+    for (i, chunk) in key.chunks(4).enumerate() {
+        k[3 - i] = u32::from_be_bytes(chunk.try_into().unwrap());
+    }
+    k
+}
+
+fn mul_x(v: u8, c: u8) -> u8 {
+    if v & 0x80 == 0x80 {
+        (v << 1) ^ c
+    } else {
+        v << 1
+    }
+}
+
+fn mul_x_pow(v: u8, i: u8, c: u8) -> u8 {
+    (0..i).fold(v, |acc, _| mul_x(acc, c))
+}
+
+fn mul_alpha(c: u8) -> u32 {
+    u32::from_be_bytes([
+        mul_x_pow(c, 23, 0xA9),
+        mul_x_pow(c, 245, 0xA9),
+        mul_x_pow(c, 48, 0xA9),
+        mul_x_pow(c, 239, 0xA9),
+    ])
+}
+
+fn div_alpha(c: u8) -> u32 {
+    u32::from_be_bytes([
+        mul_x_pow(c, 16, 0xA9),
+        mul_x_pow(c, 39, 0xA9),
+        mul_x_pow(c, 6, 0xA9),
+        mul_x_pow(c, 64, 0xA9),
+    ])
+}
+
+// Common to `S1` and `S2` boxes: Byte wise substitution using the `sbox` followed by mixing of
+// the bytes using `mul_x` with the given constant.
+fn s_box_mix(w: u32, sbox: &[u8; 256], c: u8) -> u32 {
+    let [w0, w1, w2, w3] = w.to_be_bytes();
+    let (s0, s1, s2, s3) = (
+        sbox[w0 as usize],
+        sbox[w1 as usize],
+        sbox[w2 as usize],
+        sbox[w3 as usize],
+    );
+
+    let r0 = mul_x(s0, c) ^ s1 ^ s2 ^ mul_x(s3, c) ^ s3;
+    let r1 = mul_x(s0, c) ^ s0 ^ mul_x(s1, c) ^ s2 ^ s3;
+    let r2 = s0 ^ mul_x(s1, c) ^ s1 ^ mul_x(s2, c) ^ s3;
+    let r3 = s0 ^ s1 ^ mul_x(s2, c) ^ s2 ^ mul_x(s3, c);
+
+    u32::from_be_bytes([r0, r1, r2, r3])
+}
+
+fn s1(w: u32) -> u32 {
+    s_box_mix(w, &SR, 0x1B)
+}
+
+fn s2(w: u32) -> u32 {
+    s_box_mix(w, &SQ, 0x69)
+}
+
+/// Confidentiality function `f8` (UEA2)
+///
+/// Encrypts (or decrypts) the first `bitlen` bits of the `payload`. The bits in the output beyond
+/// `bitlen` are set to zero. A `bitlen` beyond the length of the `payload` is limited to the length
+/// of the `payload`.
+pub fn encrypt_snow3g_f8(
+    key: Snow3gKey,
+    count: u32,
+    bearer: u8,
+    downlink: bool,
+    payload: &[u8],
+    bitlen: u32,
+) -> Vec<u8> {
+    let direction = downlink as u32;
+    let iv2 = ((bearer as u32) << 27) | (direction << 26);
+    let iv = [iv2, count, iv2, count];
+
+    let mut snow3g = Snow3g::new(key_words(key), iv);
+
+    let bitlen = std::cmp::min(bitlen as u64, payload.len() as u64 * 8) as u32;
+    let bytes = (bitlen as usize).div_ceil(8);
+    let mut out = payload[..bytes].to_vec();
+    let keystream = snow3g.keystream(bytes.div_ceil(4));
+    for (chunk, z) in out.chunks_mut(4).zip(keystream) {
+        for (byte, k) in chunk.iter_mut().zip(z.to_be_bytes()) {
+            *byte ^= k;
+        }
+    }
+
+    zero_trailing_bits(&mut out, bitlen);
+
+    out
+}
+
+/// Integrity function `f9` (UIA2)
+///
+/// Calculates the 32 bit MAC over the first `bitlen` bits of the `message`. A `bitlen` beyond the
+/// length of the `message` is limited to the length of the `message`.
+pub fn mac_snow3g_f9(
+    key: Snow3gKey,
+    count: u32,
+    fresh: u32,
+    downlink: bool,
+    message: &[u8],
+    bitlen: u64,
+) -> Snow3gMac {
+    let bitlen = std::cmp::min(bitlen, message.len() as u64 * 8);
+
+    let direction = downlink as u32;
+    let iv = [
+        fresh ^ (direction << 15),
+        count ^ (direction << 31),
+        fresh,
+        count,
+    ];
+
+    let mut snow3g = Snow3g::new(key_words(key), iv);
+    let z = snow3g.keystream(5);
+
+    let p = ((z[0] as u64) << 32) | z[1] as u64;
+    let q = ((z[2] as u64) << 32) | z[3] as u64;
+
+    let mut eval = 0_u64;
+    let blocks = bitlen.div_ceil(64) as usize;
+    for i in 0..blocks {
+        let mut block = [0_u8; 8];
+        let start = i * 8;
+        let end = std::cmp::min(start + 8, message.len());
+        block[..end - start].copy_from_slice(&message[start..end]);
+        let mut m = u64::from_be_bytes(block);
+
+        // Only the `bitlen` bits are used from the last block.
+        let remaining = bitlen - (i as u64 * 64);
+        if remaining < 64 {
+            m &= !(u64::MAX >> remaining);
+        }
+
+        eval = mul64(eval ^ m, p, 0x1B);
+    }
+
+    eval ^= bitlen;
+    eval = mul64(eval, q, 0x1B);
+
+    (((eval >> 32) as u32) ^ z[4]).to_be_bytes()
+}
+
+fn mul64x(v: u64, c: u64) -> u64 {
+    if v & 0x8000000000000000 == 0x8000000000000000 {
+        (v << 1) ^ c
+    } else {
+        v << 1
+    }
+}
+
+// Multiplication of `v` and `p` in GF(2^64) (Section 3.1.3 UIA2 Specification)
+fn mul64(v: u64, p: u64, c: u64) -> u64 {
+    let mut result = 0;
+    let mut v_pow = v;
+    for i in 0..64 {
+        if (p >> i) & 0x01 == 0x01 {
+            result ^= v_pow;
+        }
+        v_pow = mul64x(v_pow, c);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn test_snow3g_keystream() {
+        // Test Set 1: SNOW 3G Specification Document 4: Design Conformance Test Data
+        let key = [0x2bd6459f, 0x82c5b300, 0x952c4910, 0x4881ff48];
+        let iv = [0xea024714, 0xad5c4d84, 0xdf1f9b25, 0x1c0bf45f];
+
+        let mut snow3g = super::Snow3g::new(key, iv);
+        let keystream = snow3g.keystream(2);
+
+        assert_eq!(keystream, vec![0xabee9704, 0x7ac31373]);
+    }
+
+    #[test]
+    fn test_snow3g_f8() {
+        struct TestSet<'ts> {
+            name: &'ts str,
+            key: &'ts str,
+            count: u32,
+            bearer: u8,
+            downlink: bool,
+            payload: &'ts str,
+            bitlen: u32,
+            ciphertext: &'ts str,
+        }
+
+        let testsets = vec![
+            // 33.401 C.3.1
+            TestSet {
+                name: "TestSet 1",
+                key: "d3c5d592327fb11c4035c6680af8c6d1",
+                count: 0x398a59b4,
+                bearer: 0x15,
+                downlink: true,
+                payload: "981ba6824c1bfb1ab485472029b71d808ce33e2cc3c0b5fc1f3de8a6dc66b1f0",
+                bitlen: 253,
+                ciphertext: "5d5bfe75eb04f68ce0a12377ea00b37d47c6a0ba06309155086a859c4341b378",
+            },
+            // 33.401 C.3.2
+            TestSet {
+                name: "TestSet 2",
+                key: "2bd6459f82c5b300952c49104881ff48",
+                count: 0x72a4f20f,
+                bearer: 0x0c,
+                downlink: true,
+                payload: "7ec61272743bf1614726446a6c38ced166f6ca76eb5430044286346cef130f92922b03450d3a9975e5bd2ea0eb55ad8e1b199e3ec4316020e9a1b285e762795359b7bdfd39bef4b2484583d5afe082aee638bf5fd5a606193901a08f4ab41aab9b134880",
+                bitlen: 798,
+                ciphertext: "8ceba62943dced3a0990b06ea1b0a2c4fb3cedc71b369f42ba64c1eb6665e72aa1c9bb0deaa20fe86058b8baee2c2e7f0becce48b52932a53c9d5f931a3a7c532259af4325e2a65e3084ad5f6a513b7bddc1b65f0aa0d97a053db55a88c4c4f9605e4140",
+            },
+            // 33.401 C.3.3
+            TestSet {
+                name: "TestSet 3",
+                key: "0a8b6bd8d9b08b08d64e32d1817777fb",
+                count: 0x544d49cd,
+                bearer: 0x04,
+                downlink: false,
+                payload: "fd40a41d370a1f65745095687d47ba1d36d2349e23f644392c8ea9c49d40c13271aff264d0f248",
+                bitlen: 310,
+                ciphertext: "48148e5452a210c05f46bc80dc6f73495b02048c1b958b026102ca97280279a4c18d2ee308921c",
+            },
+        ];
+
+        for ts in testsets {
+            let key = hex::decode(ts.key).unwrap().try_into().unwrap();
+            let payload = hex::decode(ts.payload).unwrap();
+
+            let result = super::encrypt_snow3g_f8(
+                key,
+                ts.count,
+                ts.bearer,
+                ts.downlink,
+                &payload,
+                ts.bitlen,
+            );
+            assert!(
+                ts.ciphertext == hex::encode(&result),
+                "Failure:{}, Expected: {}, Computed:{}",
+                ts.name,
+                ts.ciphertext,
+                hex::encode(&result)
+            );
+
+            let result =
+                super::encrypt_snow3g_f8(key, ts.count, ts.bearer, ts.downlink, &result, ts.bitlen);
+            assert!(result == payload, "Failure:{}", ts.name);
+        }
+    }
+
+    #[test]
+    fn test_snow3g_f9() {
+        struct TestSet<'ts> {
+            name: &'ts str,
+            key: &'ts str,
+            count: u32,
+            fresh: u32,
+            downlink: bool,
+            message: &'ts str,
+            bitlen: u64,
+            mac: &'ts str,
+        }
+
+        let testsets = vec![
+            // UIA2 Specification Document 3: Implementors' Test Data
+            TestSet {
+                name: "TestSet 1",
+                key: "2bd6459f82c5b300952c49104881ff48",
+                count: 0x38a6f056,
+                fresh: 0x05d2ec49,
+                downlink: false,
+                message: "6b227737296f393c8079353edc87e2e805d2ec49a4f2d8e0",
+                bitlen: 189,
+                mac: "2bce1820",
+            },
+            // 33.401 C.4.1: `FRESH` is the `BEARER` (0x1f) followed by 27 zero bits.
+            TestSet {
+                name: "EIA1 TestSet 1",
+                key: "2bd6459f82c5b300952c49104881ff48",
+                count: 0x38a6f056,
+                fresh: 0xf8000000,
+                downlink: false,
+                message: "3332346263393861373479",
+                bitlen: 88,
+                mac: "731f1165",
+            },
+        ];
+
+        for ts in testsets {
+            let key = hex::decode(ts.key).unwrap().try_into().unwrap();
+            let message = hex::decode(ts.message).unwrap();
+
+            let result =
+                super::mac_snow3g_f9(key, ts.count, ts.fresh, ts.downlink, &message, ts.bitlen);
+            assert!(
+                ts.mac == hex::encode(result),
+                "Failure:{}, Expected: {}, Computed:{}",
+                ts.name,
+                ts.mac,
+                hex::encode(result)
+            );
+        }
+    }
+
+    #[test]
+    fn test_snow3g_bitlen_beyond_input() {
+        let key = hex::decode("d3c5d592327fb11c4035c6680af8c6d1")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let payload = hex::decode("981ba6824c1bfb1a").unwrap();
+
+        let result = super::encrypt_snow3g_f8(key, 0x398a59b4, 0x15, true, &payload, 1024);
+        assert_eq!(
+            result,
+            super::encrypt_snow3g_f8(key, 0x398a59b4, 0x15, true, &payload, 64)
+        );
+
+        let result = super::mac_snow3g_f9(key, 0x398a59b4, 0, false, &payload, 1024);
+        assert_eq!(
+            result,
+            super::mac_snow3g_f9(key, 0x398a59b4, 0, false, &payload, 64)
+        );
+    }
+}
@@ -0,0 +1,426 @@
+//! TUAK Authentication and Key Generation functions.
+//!
+//! Implementation of the functions `f1`, `f1*`, `f2`, `f3`, `f4`, `f5` and `f5*` as specified in
+//! 3GPP 35.231 using the Keccak-f\[1600\] permutation. Also provides the derivation of `TOPc`
+//! from the `TOP`.
+
+use crate::auth::{Ak, AuthAlgorithm, AuthMgmtField, Rand, Sqn};
+
+/// Operator Variant Algorithm Configuration Field `TOP` (or `TOPc` derived from it.)
+pub type Top = [u8; 32];
+
+// Algorithm Name used as an input to all the functions (Section 6.2 35.231)
+const ALGONAME: &[u8; 7] = b"TUAK1.0";
+
+// Offsets (in octets) of the fields in the Keccak State, the fields are stored with the least
+// significant octet first. (Section 6.2 - 6.5 35.231)
+const TOP_OFFSET: usize = 0;
+const INSTANCE_OFFSET: usize = 32;
+const ALGONAME_OFFSET: usize = 33;
+const RAND_OFFSET: usize = 40;
+const AMF_OFFSET: usize = 56;
+const SQN_OFFSET: usize = 58;
+const KEY_OFFSET: usize = 64;
+const PADDING_OFFSET: usize = 96;
+const PADDING_END_OFFSET: usize = 135;
+
+// Offsets (in octets) of the outputs in the Keccak State.
+const CK_OFFSET: usize = 32;
+const IK_OFFSET: usize = 64;
+const AK_OFFSET: usize = 96;
+
+/// Subscriber Key `K`: TUAK supports 128 bit and 256 bit keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TuakKey {
+    Key128([u8; 16]),
+    Key256([u8; 32]),
+}
+
+/// Length of the `MAC-A` and `MAC-S` generated by `f1` and `f1*`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TuakMacLength {
+    Bits64,
+    Bits128,
+    Bits256,
+}
+
+/// Length of the `RES` generated by `f2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TuakResLength {
+    Bits32,
+    Bits64,
+    Bits128,
+    Bits256,
+}
+
+/// Length of the `CK` and `IK` generated by `f3` and `f4`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TuakKeyLength {
+    Bits128,
+    Bits256,
+}
+
+impl TuakKey {
+    // Key as 256 bit value. 128 bit keys are prefixed with zeros.
+    fn as_256_bits(&self) -> [u8; 32] {
+        match self {
+            Self::Key128(k) => {
+                let mut key = [0_u8; 32];
+                key[16..].copy_from_slice(k);
+                key
+            }
+            Self::Key256(k) => *k,
+        }
+    }
+
+    fn is_256_bits(&self) -> bool {
+        matches!(self, Self::Key256(_))
+    }
+}
+
+impl TuakMacLength {
+    fn octets(&self) -> usize {
+        match self {
+            Self::Bits64 => 8,
+            Self::Bits128 => 16,
+            Self::Bits256 => 32,
+        }
+    }
+
+    // The encoding of the length used in `INSTANCE`
+    fn instance_bits(&self) -> u8 {
+        match self {
+            Self::Bits64 => 0b001,
+            Self::Bits128 => 0b010,
+            Self::Bits256 => 0b100,
+        }
+    }
+}
+
+impl TuakResLength {
+    fn octets(&self) -> usize {
+        match self {
+            Self::Bits32 => 4,
+            Self::Bits64 => 8,
+            Self::Bits128 => 16,
+            Self::Bits256 => 32,
+        }
+    }
+
+    // The encoding of the length used in `INSTANCE`
+    fn instance_bits(&self) -> u8 {
+        match self {
+            Self::Bits32 => 0b000,
+            Self::Bits64 => 0b001,
+            Self::Bits128 => 0b010,
+            Self::Bits256 => 0b100,
+        }
+    }
+}
+
+impl TuakKeyLength {
+    fn octets(&self) -> usize {
+        match self {
+            Self::Bits128 => 16,
+            Self::Bits256 => 32,
+        }
+    }
+}
+
+/// TUAK Algorithm Set for a subscriber identified by the Subscriber Key and `TOPc`.
+///
+/// By default the Algorithm set generates a 64 bit `MAC`, a 32 bit `RES`, 128 bit `CK` and `IK`
+/// and uses a single iteration of Keccak. Use the `with_*` methods to change these.
+#[derive(Debug, Clone)]
+pub struct Tuak {
+    key: TuakKey,
+    topc: Top,
+    mac_length: TuakMacLength,
+    res_length: TuakResLength,
+    ck_length: TuakKeyLength,
+    ik_length: TuakKeyLength,
+    iterations: u8,
+}
+
+impl Tuak {
+    /// Create the Algorithm set using `K` and `TOP`. The `TOPc` is derived from the `TOP`.
+    pub fn from_top(key: TuakKey, top: Top) -> Self {
+        let topc = tuak_topc(&key, top, 1);
+        Self::from_topc(key, topc)
+    }
+
+    /// Create the Algorithm set using `K` and `TOPc`.
+    pub fn from_topc(key: TuakKey, topc: Top) -> Self {
+        Self {
+            key,
+            topc,
+            mac_length: TuakMacLength::Bits64,
+            res_length: TuakResLength::Bits32,
+            ck_length: TuakKeyLength::Bits128,
+            ik_length: TuakKeyLength::Bits128,
+            iterations: 1,
+        }
+    }
+
+    /// Use the given length for `MAC-A` and `MAC-S`.
+    pub fn with_mac_length(mut self, mac_length: TuakMacLength) -> Self {
+        self.mac_length = mac_length;
+        self
+    }
+
+    /// Use the given length for `RES`.
+    pub fn with_res_length(mut self, res_length: TuakResLength) -> Self {
+        self.res_length = res_length;
+        self
+    }
+
+    /// Use the given lengths for `CK` and `IK`.
+    pub fn with_ck_ik_length(mut self, ck_length: TuakKeyLength, ik_length: TuakKeyLength) -> Self {
+        self.ck_length = ck_length;
+        self.ik_length = ik_length;
+        self
+    }
+
+    /// Use the given number of Keccak iterations. Note: `TOPc` is not re-computed, if a
+    /// non-default number of iterations is to be used with `TOP`, derive the `TOPc` using
+    /// [`tuak_topc`].
+    pub fn with_iterations(mut self, iterations: u8) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Get the `TOPc` used by this Algorithm set.
+    pub fn topc(&self) -> Top {
+        self.topc
+    }
+
+    // Common for `f1` and `f1*` (Section 6.3 35.231)
+    fn out_f1(&self, instance: u8, rand: Rand, sqn: Sqn, amf: AuthMgmtField) -> [u8; 200] {
+        let instance =
+            instance | (self.mac_length.instance_bits() << 3) | self.key.is_256_bits() as u8;
+
+        let mut state = tuak_state(&self.topc, instance, &self.key);
+        push_reversed(&mut state, RAND_OFFSET, &rand);
+        push_reversed(&mut state, AMF_OFFSET, &amf);
+        push_reversed(&mut state, SQN_OFFSET, &sqn);
+
+        keccak_iterations(state, self.iterations)
+    }
+
+    // Common for `f2`, `f3`, `f4`, `f5` and `f5*` (Section 6.4 and 6.5 35.231)
+    fn out_f2345(&self, instance: u8, rand: Rand) -> [u8; 200] {
+        let mut state = tuak_state(&self.topc, instance, &self.key);
+        push_reversed(&mut state, RAND_OFFSET, &rand);
+
+        keccak_iterations(state, self.iterations)
+    }
+
+    fn instance_f2345(&self) -> u8 {
+        0b0100_0000
+            | (self.res_length.instance_bits() << 3)
+            | ((self.ck_length == TuakKeyLength::Bits256) as u8) << 2
+            | ((self.ik_length == TuakKeyLength::Bits256) as u8) << 1
+            | self.key.is_256_bits() as u8
+    }
+}
+
+impl AuthAlgorithm for Tuak {
+    fn f1(&self, rand: Rand, sqn: Sqn, amf: AuthMgmtField) -> Vec<u8> {
+        let out = self.out_f1(0b0000_0000, rand, sqn, amf);
+        pull_reversed(&out, 0, self.mac_length.octets())
+    }
+
+    fn f1_star(&self, rand: Rand, sqn: Sqn, amf: AuthMgmtField) -> Vec<u8> {
+        let out = self.out_f1(0b1000_0000, rand, sqn, amf);
+        pull_reversed(&out, 0, self.mac_length.octets())
+    }
+
+    fn f2(&self, rand: Rand) -> Vec<u8> {
+        let out = self.out_f2345(self.instance_f2345(), rand);
+        pull_reversed(&out, 0, self.res_length.octets())
+    }
+
+    fn f3(&self, rand: Rand) -> Vec<u8> {
+        let out = self.out_f2345(self.instance_f2345(), rand);
+        pull_reversed(&out, CK_OFFSET, self.ck_length.octets())
+    }
+
+    fn f4(&self, rand: Rand) -> Vec<u8> {
+        let out = self.out_f2345(self.instance_f2345(), rand);
+        pull_reversed(&out, IK_OFFSET, self.ik_length.octets())
+    }
+
+    fn f5(&self, rand: Rand) -> Ak {
+        let out = self.out_f2345(self.instance_f2345(), rand);
+        pull_reversed(&out, AK_OFFSET, 6).try_into().unwrap()
+    }
+
+    fn f5_star(&self, rand: Rand) -> Ak {
+        let instance = 0b1100_0000 | self.key.is_256_bits() as u8;
+        let out = self.out_f2345(instance, rand);
+        pull_reversed(&out, AK_OFFSET, 6).try_into().unwrap()
+    }
+}
+
+/// Derive `TOPc` from `TOP` and the Subscriber Key `K` using the given number of Keccak
+/// iterations. (Section 6.2 35.231)
+pub fn tuak_topc(key: &TuakKey, top: Top, iterations: u8) -> Top {
+    let instance = key.is_256_bits() as u8;
+    let state = tuak_state(&top, instance, key);
+
+    let out = keccak_iterations(state, iterations);
+    pull_reversed(&out, 0, 32).try_into().unwrap()
+}
+
+// Keccak State with the fields common to all the functions. `top` is either `TOP` or `TOPc`.
+fn tuak_state(top: &Top, instance: u8, key: &TuakKey) -> [u8; 200] {
+    let mut state = [0_u8; 200];
+
+    push_reversed(&mut state, TOP_OFFSET, top);
+    state[INSTANCE_OFFSET] = instance;
+    push_reversed(&mut state, ALGONAME_OFFSET, ALGONAME);
+    push_reversed(&mut state, KEY_OFFSET, &key.as_256_bits());
+    state[PADDING_OFFSET] = 0x1F;
+    state[PADDING_END_OFFSET] = 0x80;
+
+    state
+}
+
+fn push_reversed(state: &mut [u8; 200], offset: usize, data: &[u8]) {
+    for (s, d) in state[offset..offset + data.len()]
+        .iter_mut()
+        .zip(data.iter().rev())
+    {
+        *s = *d;
+    }
+}
+
+fn pull_reversed(state: &[u8; 200], offset: usize, len: usize) -> Vec<u8> {
+    state[offset..offset + len].iter().rev().copied().collect()
+}
+
+fn keccak_iterations(state: [u8; 200], iterations: u8) -> [u8; 200] {
+    let mut lanes = [0_u64; 25];
+    for (lane, chunk) in lanes.iter_mut().zip(state.chunks(8)) {
+        *lane = u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+
+    for _ in 0..iterations {
+        keccak::f1600(&mut lanes);
+    }
+
+    let mut out = [0_u8; 200];
+    for (chunk, lane) in out.chunks_mut(8).zip(lanes) {
+        chunk.copy_from_slice(&lane.to_le_bytes());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::AuthAlgorithm;
+
+    #[test]
+    fn test_35_232_test_sets() {
+        // 35.232 Section 6.3 Test Set 1
+        let key = super::TuakKey::Key128(
+            hex::decode("abababababababababababababababab")
+                .unwrap()
+                .try_into()
+                .unwrap(),
+        );
+        let top = hex::decode("5555555555555555555555555555555555555555555555555555555555555555")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let rand = hex::decode("42424242424242424242424242424242")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let sqn = hex::decode("111111111111").unwrap().try_into().unwrap();
+        let amf = [0xff, 0xff];
+
+        let tuak = super::Tuak::from_top(key, top);
+
+        assert_eq!(
+            hex::encode(tuak.topc()),
+            "bd04d9530e87513c5d837ac2ad954623a8e2330c115305a73eb45d1f40cccbff"
+        );
+        assert_eq!(hex::encode(tuak.f1(rand, sqn, amf)), "f9a54e6aeaa8618d");
+        assert_eq!(
+            hex::encode(tuak.f1_star(rand, sqn, amf)),
+            "e94b4dc6c7297df3"
+        );
+        assert_eq!(hex::encode(tuak.f2(rand)), "657acd64");
+        assert_eq!(
+            hex::encode(tuak.f3(rand)),
+            "d71a1e5c6caffe986a26f783e5c78be1"
+        );
+        assert_eq!(
+            hex::encode(tuak.f4(rand)),
+            "be849fa2564f869aecee6f62d4337e72"
+        );
+        assert_eq!(hex::encode(tuak.f5(rand)), "719f1e9b9054");
+        assert_eq!(hex::encode(tuak.f5_star(rand)), "e7af6b3d0e38");
+    }
+
+    #[test]
+    fn test_auth_algorithm_per_subscriber() {
+        let rand = [0x42; 16];
+        let sqn = [0x11; 6];
+        let amf = [0x80, 0x00];
+
+        let algorithms: Vec<Box<dyn AuthAlgorithm>> = vec![
+            Box::new(super::Tuak::from_top(
+                super::TuakKey::Key256([0xab; 32]),
+                [0x55; 32],
+            )),
+            Box::new(crate::Milenage::from_op([0xab; 16], [0x55; 16])),
+        ];
+
+        for algorithm in algorithms {
+            let av = algorithm.generate_auth_vector(rand, sqn, amf);
+            assert_eq!(av.autn.len(), 16);
+
+            let ak = algorithm.f5_star(rand);
+            let mut auts: Vec<u8> = sqn.iter().zip(ak).map(|(s, a)| s ^ a).collect();
+            auts.extend(algorithm.f1_star(rand, sqn, [0, 0]));
+            assert_eq!(algorithm.resync_sqn(rand, &auts), Some(sqn));
+        }
+    }
+
+    #[test]
+    fn test_mac_lengths() {
+        let rand = [0x42; 16];
+        let sqn = [0x11; 6];
+        let amf = [0x80, 0x00];
+
+        for (mac_length, octets) in [
+            (super::TuakMacLength::Bits64, 8),
+            (super::TuakMacLength::Bits128, 16),
+            (super::TuakMacLength::Bits256, 32),
+        ] {
+            let tuak = super::Tuak::from_top(super::TuakKey::Key128([0xab; 16]), [0x55; 32])
+                .with_mac_length(mac_length);
+
+            let av = tuak.generate_auth_vector(rand, sqn, amf);
+            assert_eq!(av.autn.len(), 8 + octets);
+
+            // The `AUTS` carries the `MAC-S` of the configured length.
+            let mut auts: Vec<u8> = sqn
+                .iter()
+                .zip(tuak.f5_star(rand))
+                .map(|(s, a)| s ^ a)
+                .collect();
+            auts.extend(tuak.f1_star(rand, sqn, [0, 0]));
+            assert_eq!(auts.len(), 6 + octets);
+            assert_eq!(tuak.resync_sqn(rand, &auts), Some(sqn));
+
+            // A 64 bit `MAC-S` is not accepted for a longer `MAC` length.
+            if octets > 8 {
+                assert_eq!(tuak.resync_sqn(rand, &auts[..14]), None);
+            }
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! Utility functions shared by the algorithm implementations.
+
+/// Clears all the bits in the `data` beyond `bitlen` bits.
+///
+/// The bit stream algorithms (eg. Snow 3G based `f8`) work on 32 bit words (and AES in counter
+/// mode on 128 bit blocks), the output bits beyond the `bitlen` are zeroed, as done by the
+/// reference implementations (and as expected by the test data).
+pub fn zero_trailing_bits(data: &mut [u8], bitlen: u32) {
+    let full_bytes = (bitlen / 8) as usize;
+    let remaining_bits = bitlen % 8;
+
+    let mut start = full_bytes;
+    if remaining_bits > 0 && full_bytes < data.len() {
+        data[full_bytes] &= 0xFF_u8 << (8 - remaining_bits);
+        start += 1;
+    }
+
+    if start < data.len() {
+        data[start..].fill(0);
+    }
+}
+
+/// Compares the MACs (or the RES) in constant time, so that the time taken does not reveal the
+/// number of matching octets. The lengths are not secret.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(&[], &[]));
+        assert!(constant_time_eq(&[1, 2, 3], &[1, 2, 3]));
+        assert!(!constant_time_eq(&[1, 2, 3], &[1, 2, 4]));
+        assert!(!constant_time_eq(&[0x80, 2, 3], &[0, 2, 3]));
+        assert!(!constant_time_eq(&[1, 2, 3], &[1, 2]));
+    }
+}
@@ -0,0 +1,456 @@
+//! ZUC based Encryption and Integrity functions.
+//!
+//! The ZUC Keystream generator and the 128-EEA3 and 128-EIA3 algorithms as specified by the
+//! ETSI/SAGE "Specification of the 3GPP Confidentiality and Integrity Algorithms 128-EEA3 &
+//! 128-EIA3" Documents 1 and 2. These are used as 128-NEA3/128-NIA3 (33.501).
+
+use crate::utils::zero_trailing_bits;
+
+pub type ZucKey = [u8; 16];
+pub type ZucIv = [u8; 16];
+pub type ZucMac = [u8; 4];
+
+// S-Box `S0` (Section 3.4.2 ZUC Specification)
+const S0: [u8; 256] = [
+    0x3e, 0x72, 0x5b, 0x47, 0xca, 0xe0, 0x00, 0x33, 0x04, 0xd1, 0x54, 0x98, 0x09, 0xb9, 0x6d, 0xcb,
+    0x7b, 0x1b, 0xf9, 0x32, 0xaf, 0x9d, 0x6a, 0xa5, 0xb8, 0x2d, 0xfc, 0x1d, 0x08, 0x53, 0x03, 0x90,
+    0x4d, 0x4e, 0x84, 0x99, 0xe4, 0xce, 0xd9, 0x91, 0xdd, 0xb6, 0x85, 0x48, 0x8b, 0x29, 0x6e, 0xac,
+    0xcd, 0xc1, 0xf8, 0x1e, 0x73, 0x43, 0x69, 0xc6, 0xb5, 0xbd, 0xfd, 0x39, 0x63, 0x20, 0xd4, 0x38,
+    0x76, 0x7d, 0xb2, 0xa7, 0xcf, 0xed, 0x57, 0xc5, 0xf3, 0x2c, 0xbb, 0x14, 0x21, 0x06, 0x55, 0x9b,
+    0xe3, 0xef, 0x5e, 0x31, 0x4f, 0x7f, 0x5a, 0xa4, 0x0d, 0x82, 0x51, 0x49, 0x5f, 0xba, 0x58, 0x1c,
+    0x4a, 0x16, 0xd5, 0x17, 0xa8, 0x92, 0x24, 0x1f, 0x8c, 0xff, 0xd8, 0xae, 0x2e, 0x01, 0xd3, 0xad,
+    0x3b, 0x4b, 0xda, 0x46, 0xeb, 0xc9, 0xde, 0x9a, 0x8f, 0x87, 0xd7, 0x3a, 0x80, 0x6f, 0x2f, 0xc8,
+    0xb1, 0xb4, 0x37, 0xf7, 0x0a, 0x22, 0x13, 0x28, 0x7c, 0xcc, 0x3c, 0x89, 0xc7, 0xc3, 0x96, 0x56,
+    0x07, 0xbf, 0x7e, 0xf0, 0x0b, 0x2b, 0x97, 0x52, 0x35, 0x41, 0x79, 0x61, 0xa6, 0x4c, 0x10, 0xfe,
+    0xbc, 0x26, 0x95, 0x88, 0x8a, 0xb0, 0xa3, 0xfb, 0xc0, 0x18, 0x94, 0xf2, 0xe1, 0xe5, 0xe9, 0x5d,
+    0xd0, 0xdc, 0x11, 0x66, 0x64, 0x5c, 0xec, 0x59, 0x42, 0x75, 0x12, 0xf5, 0x74, 0x9c, 0xaa, 0x23,
+    0x0e, 0x86, 0xab, 0xbe, 0x2a, 0x02, 0xe7, 0x67, 0xe6, 0x44, 0xa2, 0x6c, 0xc2, 0x93, 0x9f, 0xf1,
+    0xf6, 0xfa, 0x36, 0xd2, 0x50, 0x68, 0x9e, 0x62, 0x71, 0x15, 0x3d, 0xd6, 0x40, 0xc4, 0xe2, 0x0f,
+    0x8e, 0x83, 0x77, 0x6b, 0x25, 0x05, 0x3f, 0x0c, 0x30, 0xea, 0x70, 0xb7, 0xa1, 0xe8, 0xa9, 0x65,
+    0x8d, 0x27, 0x1a, 0xdb, 0x81, 0xb3, 0xa0, 0xf4, 0x45, 0x7a, 0x19, 0xdf, 0xee, 0x78, 0x34, 0x60,
+];
+
+// S-Box `S1` (Section 3.4.2 ZUC Specification)
+const S1: [u8; 256] = [
+    0x55, 0xc2, 0x63, 0x71, 0x3b, 0xc8, 0x47, 0x86, 0x9f, 0x3c, 0xda, 0x5b, 0x29, 0xaa, 0xfd, 0x77,
+    0x8c, 0xc5, 0x94, 0x0c, 0xa6, 0x1a, 0x13, 0x00, 0xe3, 0xa8, 0x16, 0x72, 0x40, 0xf9, 0xf8, 0x42,
+    0x44, 0x26, 0x68, 0x96, 0x81, 0xd9, 0x45, 0x3e, 0x10, 0x76, 0xc6, 0xa7, 0x8b, 0x39, 0x43, 0xe1,
+    0x3a, 0xb5, 0x56, 0x2a, 0xc0, 0x6d, 0xb3, 0x05, 0x22, 0x66, 0xbf, 0xdc, 0x0b, 0xfa, 0x62, 0x48,
+    0xdd, 0x20, 0x11, 0x06, 0x36, 0xc9, 0xc1, 0xcf, 0xf6, 0x27, 0x52, 0xbb, 0x69, 0xf5, 0xd4, 0x87,
+    0x7f, 0x84, 0x4c, 0xd2, 0x9c, 0x57, 0xa4, 0xbc, 0x4f, 0x9a, 0xdf, 0xfe, 0xd6, 0x8d, 0x7a, 0xeb,
+    0x2b, 0x53, 0xd8, 0x5c, 0xa1, 0x14, 0x17, 0xfb, 0x23, 0xd5, 0x7d, 0x30, 0x67, 0x73, 0x08, 0x09,
+    0xee, 0xb7, 0x70, 0x3f, 0x61, 0xb2, 0x19, 0x8e, 0x4e, 0xe5, 0x4b, 0x93, 0x8f, 0x5d, 0xdb, 0xa9,
+    0xad, 0xf1, 0xae, 0x2e, 0xcb, 0x0d, 0xfc, 0xf4, 0x2d, 0x46, 0x6e, 0x1d, 0x97, 0xe8, 0xd1, 0xe9,
+    0x4d, 0x37, 0xa5, 0x75, 0x5e, 0x83, 0x9e, 0xab, 0x82, 0x9d, 0xb9, 0x1c, 0xe0, 0xcd, 0x49, 0x89,
+    0x01, 0xb6, 0xbd, 0x58, 0x24, 0xa2, 0x5f, 0x38, 0x78, 0x99, 0x15, 0x90, 0x50, 0xb8, 0x95, 0xe4,
+    0xd0, 0x91, 0xc7, 0xce, 0xed, 0x0f, 0xb4, 0x6f, 0xa0, 0xcc, 0xf0, 0x02, 0x4a, 0x79, 0xc3, 0xde,
+    0xa3, 0xef, 0xea, 0x51, 0xe6, 0x6b, 0x18, 0xec, 0x1b, 0x2c, 0x80, 0xf7, 0x74, 0xe7, 0xff, 0x21,
+    0x5a, 0x6a, 0x54, 0x1e, 0x41, 0x31, 0x92, 0x35, 0xc4, 0x33, 0x07, 0x0a, 0xba, 0x7e, 0x0e, 0x34,
+    0x88, 0xb1, 0x98, 0x7c, 0xf3, 0x3d, 0x60, 0x6c, 0x7b, 0xca, 0xd3, 0x1f, 0x32, 0x65, 0x04, 0x28,
+    0x64, 0xbe, 0x85, 0x9b, 0x2f, 0x59, 0x8a, 0xd7, 0xb0, 0x25, 0xac, 0xaf, 0x12, 0x03, 0xe2, 0xf2,
+];
+
+// Constants `d0` to `d15` used during Key Loading (Section 3.5 ZUC Specification)
+const D: [u32; 16] = [
+    0x44D7, 0x26BC, 0x626B, 0x135E, 0x5789, 0x35E2, 0x7135, 0x09AF, 0x4D78, 0x2F13, 0x6BC4, 0x1AF1,
+    0x5E26, 0x3C4D, 0x789A, 0x47AC,
+];
+
+const MODULUS: u32 = 0x7FFFFFFF;
+
+/// ZUC Keystream Generator.
+///
+/// Initialized with a 128 bit key and a 128 bit IV, generates the keystream as 32 bit words.
+#[derive(Debug, Clone)]
+pub struct Zuc {
+    lfsr: [u32; 16],
+    r1: u32,
+    r2: u32,
+}
+
+impl Zuc {
+    /// Initialize the Generator using the `key` and `iv`. (Section 3.6.1 ZUC Specification)
+    pub fn new(key: ZucKey, iv: ZucIv) -> Self {
+        let mut lfsr = [0_u32; 16];
+        for (i, s) in lfsr.iter_mut().enumerate() {
+            *s = ((key[i] as u32) << 23) | (D[i] << 8) | iv[i] as u32;
+        }
+
+        let mut this = Self { lfsr, r1: 0, r2: 0 };
+
+        for _ in 0..32 {
+            let x = this.bit_reorganization();
+            let w = this.f(x[0], x[1], x[2]);
+            this.lfsr_with_initialization_mode(w >> 1);
+        }
+
+        // Run once in the Working Stage and discard the output. (Section 3.6.2 ZUC Specification)
+        let x = this.bit_reorganization();
+        let _ = this.f(x[0], x[1], x[2]);
+        this.lfsr_with_work_mode();
+
+        this
+    }
+
+    /// Generate the next `n` words of the Keystream.
+    pub fn keystream(&mut self, n: usize) -> Vec<u32> {
+        let mut out = Vec::with_capacity(n);
+        for _ in 0..n {
+            let x = self.bit_reorganization();
+            out.push(self.f(x[0], x[1], x[2]) ^ x[3]);
+            self.lfsr_with_work_mode();
+        }
+        out
+    }
+
+    fn lfsr_feedback(&self) -> u32 {
+        let s = &self.lfsr;
+        let mut v = s[0];
+        v = add_mod(v, mul_pow2_mod(s[0], 8));
+        v = add_mod(v, mul_pow2_mod(s[4], 20));
+        v = add_mod(v, mul_pow2_mod(s[10], 21));
+        v = add_mod(v, mul_pow2_mod(s[13], 17));
+        add_mod(v, mul_pow2_mod(s[15], 15))
+    }
+
+    fn lfsr_with_initialization_mode(&mut self, u: u32) {
+        let s16 = add_mod(self.lfsr_feedback(), u);
+        self.lfsr_shift(s16);
+    }
+
+    fn lfsr_with_work_mode(&mut self) {
+        let s16 = self.lfsr_feedback();
+        self.lfsr_shift(s16);
+    }
+
+    fn lfsr_shift(&mut self, s16: u32) {
+        self.lfsr.copy_within(1.., 0);
+        self.lfsr[15] = if s16 == 0 { MODULUS } else { s16 };
+    }
+
+    fn bit_reorganization(&self) -> [u32; 4] {
+        let s = &self.lfsr;
+        [
+            ((s[15] & 0x7FFF8000) << 1) | (s[14] & 0xFFFF),
+            ((s[11] & 0xFFFF) << 16) | (s[9] >> 15),
+            ((s[7] & 0xFFFF) << 16) | (s[5] >> 15),
+            ((s[2] & 0xFFFF) << 16) | (s[0] >> 15),
+        ]
+    }
+
+    fn f(&mut self, x0: u32, x1: u32, x2: u32) -> u32 {
+        let w = (x0 ^ self.r1).wrapping_add(self.r2);
+        let w1 = self.r1.wrapping_add(x1);
+        let w2 = self.r2 ^ x2;
+        self.r1 = s(l1((w1 << 16) | (w2 >> 16)));
+        self.r2 = s(l2((w2 << 16) | (w1 >> 16)));
+
+        w
+    }
+}
+
+// Addition modulo 2^31 - 1
+fn add_mod(a: u32, b: u32) -> u32 {
+    let c = a + b;
+    (c & MODULUS) + (c >> 31)
+}
+
+// Multiplication by 2^k modulo 2^31 - 1
+fn mul_pow2_mod(x: u32, k: u32) -> u32 {
+    ((x << k) | (x >> (31 - k))) & MODULUS
+}
+
+fn l1(x: u32) -> u32 {
+    x ^ x.rotate_left(2) ^ x.rotate_left(10) ^ x.rotate_left(18) ^ x.rotate_left(24)
+}
+
+fn l2(x: u32) -> u32 {
+    x ^ x.rotate_left(8) ^ x.rotate_left(14) ^ x.rotate_left(22) ^ x.rotate_left(30)
+}
+
+fn s(x: u32) -> u32 {
+    let [x0, x1, x2, x3] = x.to_be_bytes();
+    u32::from_be_bytes([
+        S0[x0 as usize],
+        S1[x1 as usize],
+        S0[x2 as usize],
+        S1[x3 as usize],
+    ])
+}
+
+/// 128-EEA3 Confidentiality Algorithm
+///
+/// Encrypts (or decrypts) the first `bitlen` bits of the `payload`. The bits in the output beyond
+/// `bitlen` are set to zero. A `bitlen` beyond the length of the `payload` is limited to the length
+/// of the `payload`.
+pub fn encrypt_zuc_eea3(
+    key: ZucKey,
+    count: u32,
+    bearer: u8,
+    downlink: bool,
+    payload: &[u8],
+    bitlen: u32,
+) -> Vec<u8> {
+    let mut iv = [0_u8; 16];
+    iv[0..4].copy_from_slice(&count.to_be_bytes());
+    iv[4] = (bearer << 3) | ((downlink as u8) << 2);
+    iv.copy_within(0..8, 8);
+
+    let mut zuc = Zuc::new(key, iv);
+
+    let bitlen = std::cmp::min(bitlen as u64, payload.len() as u64 * 8) as u32;
+    let bytes = (bitlen as usize).div_ceil(8);
+    let mut out = payload[..bytes].to_vec();
+    let keystream = zuc.keystream(bytes.div_ceil(4));
+    for (chunk, z) in out.chunks_mut(4).zip(keystream) {
+        for (byte, k) in chunk.iter_mut().zip(z.to_be_bytes()) {
+            *byte ^= k;
+        }
+    }
+
+    zero_trailing_bits(&mut out, bitlen);
+
+    out
+}
+
+/// 128-EIA3 Integrity Algorithm
+///
+/// Calculates the 32 bit MAC over the first `bitlen` bits of the `message`. A `bitlen` beyond the
+/// length of the `message` is limited to the length of the `message`.
+pub fn mac_zuc_eia3(
+    key: ZucKey,
+    count: u32,
+    bearer: u8,
+    downlink: bool,
+    message: &[u8],
+    bitlen: u32,
+) -> ZucMac {
+    let bitlen = std::cmp::min(bitlen as u64, message.len() as u64 * 8) as u32;
+    let direction = (downlink as u8) << 7;
+
+    let mut iv = [0_u8; 16];
+    iv[0..4].copy_from_slice(&count.to_be_bytes());
+    iv[4] = bearer << 3;
+    iv.copy_within(0..8, 8);
+    iv[8] ^= direction;
+    iv[14] ^= direction;
+
+    let mut zuc = Zuc::new(key, iv);
+    let words = (bitlen as usize + 64).div_ceil(32);
+    let z = zuc.keystream(words);
+
+    // The 32 bit word starting at bit `i` of the Keystream.
+    let word_at = |i: usize| -> u32 {
+        let (idx, offset) = (i / 32, i % 32);
+        if offset == 0 {
+            z[idx]
+        } else {
+            (z[idx] << offset) | (z[idx + 1] >> (32 - offset))
+        }
+    };
+
+    let mut t = 0_u32;
+    for i in 0..bitlen as usize {
+        if (message[i / 8] >> (7 - (i % 8))) & 0x01 == 0x01 {
+            t ^= word_at(i);
+        }
+    }
+    t ^= word_at(bitlen as usize);
+
+    (t ^ z[words - 1]).to_be_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn test_zuc_keystream() {
+        // ZUC Specification Document 3: Implementor's Test Data
+        struct TestSet<'ts> {
+            name: &'ts str,
+            key: &'ts str,
+            iv: &'ts str,
+            keystream: [u32; 2],
+        }
+
+        let testsets = vec![
+            TestSet {
+                name: "TestSet 1",
+                key: "00000000000000000000000000000000",
+                iv: "00000000000000000000000000000000",
+                keystream: [0x27bede74, 0x018082da],
+            },
+            TestSet {
+                name: "TestSet 2",
+                key: "ffffffffffffffffffffffffffffffff",
+                iv: "ffffffffffffffffffffffffffffffff",
+                keystream: [0x0657cfa0, 0x7096398b],
+            },
+            TestSet {
+                name: "TestSet 3",
+                key: "3d4c4be96a82fdaeb58f641db17b455b",
+                iv: "84319aa8de6915ca1f6bda6bfbd8c766",
+                keystream: [0x14f1c272, 0x3279c419],
+            },
+        ];
+
+        for ts in testsets {
+            let key = hex::decode(ts.key).unwrap().try_into().unwrap();
+            let iv = hex::decode(ts.iv).unwrap().try_into().unwrap();
+
+            let mut zuc = super::Zuc::new(key, iv);
+            assert_eq!(zuc.keystream(2), ts.keystream, "Failure: {}", ts.name);
+        }
+    }
+
+    #[test]
+    fn test_zuc_eea3() {
+        struct TestSet<'ts> {
+            name: &'ts str,
+            key: &'ts str,
+            count: u32,
+            bearer: u8,
+            downlink: bool,
+            payload: &'ts str,
+            bitlen: u32,
+            ciphertext: &'ts str,
+        }
+
+        // 128-EEA3 & 128-EIA3 Document 3: Implementor's Test Data
+        let testsets = vec![
+            TestSet {
+                name: "TestSet 1",
+                key: "173d14ba5003731d7a60049470f00a29",
+                count: 0x66035492,
+                bearer: 0x0f,
+                downlink: false,
+                payload: "6cf65340735552ab0c9752fa6f9025fe0bd675d9005875b200",
+                bitlen: 193,
+                ciphertext: "a6c85fc66afb8533aafc2518dfe784940ee1e4b030238cc800",
+            },
+            TestSet {
+                name: "TestSet 2",
+                key: "e5bd3ea0eb55ade866c6ac58bd54302a",
+                count: 0x00056823,
+                bearer: 0x18,
+                downlink: true,
+                payload: "14a8ef693d678507bbe7270a7f67ff5006c3525b9807e467c4e56000ba338f5d429559036751822246c80d3b38f07f4be2d8ff5805f5132229bde93bbbdcaf382bf1ee972fbf9977bada8945847a2a6c9ad34a667554e04d1f7fa2c33241bd8f01ba220d",
+                bitlen: 800,
+                ciphertext: "131d43e0dea1be5c5a1bfd971d852cbf712d7b4f57961fea3208afa8bca433f456ad09c7417e58bc69cf8866d1353f74865e80781d202dfb3ecff7fcbc3b190fe82a204ed0e350fc0f6f2613b2f2bca6df5a473a57a4a00d985ebad880d6f23864a07b01",
+            },
+            TestSet {
+                name: "TestSet 3",
+                key: "d4552a8fd6e61cc81a2009141a29c10b",
+                count: 0x76452ec1,
+                bearer: 0x02,
+                downlink: true,
+                payload: "38f07f4be2d8ff5805f5132229bde93bbbdcaf382bf1ee972fbf9977bada8945847a2a6c9ad34a667554e04d1f7fa2c33241bd8f01ba220d3ca4ec41e074595f54ae2b454fd971432043601965cca85c2417ed6cbec3bada84fc8a579aea7837b0271177242a64dc0a9de71a8edee86ca3d47d033d6bf539804eca86c584a9052de46ad3fced65543bd90207372b27afb79234f5ff43ea870820e2c2b78a8aae61cce52a0515e348d196664a3456b182a07c406e4a20791271cfeda165d535ec5ea2d4df40",
+                bitlen: 1570,
+                ciphertext: "8383b0229fcc0b9d2295ec41c977e9c2bb72e220378141f9c8318f3a270dfbcdee6411c2b3044f176dc6e00f8960f97afacd131ad6a3b49b16b7babcf2a509ebb16a75dcab14ff275dbeeea1a2b155f9d52c26452d0187c310a4ee55beaa78ab4024615ba9f5d5adc7728f73560671f013e5e550085d3291df7d5fecedded559641b6c2f585233bc71e9602bd2305855bbd25ffa7f17ecbc042daae38c1f57ad8e8ebd37346f71befdbb7432e0e0bb2cfc09bcd96570cb0c0c39df5e29294e82703a637f80",
+            },
+        ];
+
+        for ts in testsets {
+            let key = hex::decode(ts.key).unwrap().try_into().unwrap();
+            let payload = hex::decode(ts.payload).unwrap();
+
+            let result =
+                super::encrypt_zuc_eea3(key, ts.count, ts.bearer, ts.downlink, &payload, ts.bitlen);
+            assert!(
+                ts.ciphertext == hex::encode(&result),
+                "Failure:{}, Expected: {}, Computed:{}",
+                ts.name,
+                ts.ciphertext,
+                hex::encode(&result)
+            );
+
+            let result =
+                super::encrypt_zuc_eea3(key, ts.count, ts.bearer, ts.downlink, &result, ts.bitlen);
+            assert!(result == payload, "Failure:{}", ts.name);
+        }
+    }
+
+    #[test]
+    fn test_zuc_eia3() {
+        struct TestSet<'ts> {
+            name: &'ts str,
+            key: &'ts str,
+            count: u32,
+            bearer: u8,
+            downlink: bool,
+            message: &'ts str,
+            bitlen: u32,
+            mac: &'ts str,
+        }
+
+        // 128-EEA3 & 128-EIA3 Document 3: Implementor's Test Data
+        let testsets = vec![
+            TestSet {
+                name: "TestSet 1",
+                key: "00000000000000000000000000000000",
+                count: 0,
+                bearer: 0,
+                downlink: false,
+                message: "00000000",
+                bitlen: 1,
+                mac: "c8a9595e",
+            },
+            TestSet {
+                name: "TestSet 2",
+                key: "47054125561eb2dda94059da05097850",
+                count: 0x561eb2dd,
+                bearer: 0x14,
+                downlink: false,
+                message: "000000000000000000000000",
+                bitlen: 90,
+                mac: "6719a088",
+            },
+            TestSet {
+                name: "TestSet 3",
+                key: "c9e6cec4607c72db000aefa88385ab0a",
+                count: 0xa94059da,
+                bearer: 0x0a,
+                downlink: true,
+                message: "983b41d47d780c9e1ad11d7eb70391b1de0b35da2dc62f83e7b78d6306ca0ea07e941b7be91348f9fcb170e2217fecd97f9f68adb16e5d7d21e569d280ed775cebde3f4093c5388100000000",
+                bitlen: 577,
+                mac: "fae8ff0b",
+            },
+        ];
+
+        for ts in testsets {
+            let key = hex::decode(ts.key).unwrap().try_into().unwrap();
+            let message = hex::decode(ts.message).unwrap();
+
+            let result =
+                super::mac_zuc_eia3(key, ts.count, ts.bearer, ts.downlink, &message, ts.bitlen);
+            assert!(
+                ts.mac == hex::encode(result),
+                "Failure:{}, Expected: {}, Computed:{}",
+                ts.name,
+                ts.mac,
+                hex::encode(result)
+            );
+        }
+    }
+
+    #[test]
+    fn test_zuc_bitlen_beyond_input() {
+        let key = hex::decode("173d14ba5003731d7a60049470f00a29")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let payload = hex::decode("6cf65340735552ab").unwrap();
+
+        let result = super::encrypt_zuc_eea3(key, 0x66035492, 0x0f, false, &payload, 1024);
+        assert_eq!(
+            result,
+            super::encrypt_zuc_eea3(key, 0x66035492, 0x0f, false, &payload, 64)
+        );
+
+        let result = super::mac_zuc_eia3(key, 0x66035492, 0x0f, false, &payload, 1024);
+        assert_eq!(
+            result,
+            super::mac_zuc_eia3(key, 0x66035492, 0x0f, false, &payload, 64)
+        );
+    }
+}